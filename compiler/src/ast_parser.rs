@@ -4,7 +4,9 @@ pub mod ast_parser {
     use std::collections::HashMap;
 
     use super::formater::refactor;
+    use crate::grammar_check::GrammarError;
     use crate::lexer::tokenizer::*;
+    use crate::tree_walker::tree_walker::Line;
     pub fn generate_ast(source_path: &str) -> Option<(Tree, Vec<HeadParam>)> {
         use std::fs;
         let source = match fs::read_to_string(source_path) {
@@ -13,26 +15,41 @@ pub mod ast_parser {
                 return None;
             }
         };
+        match parse_ast(&source) {
+            Ok(ast) => Some(ast),
+            Err(errors) => {
+                println!("Could not parse AST {source_path}, number of errors: {errors}");
+                None
+            }
+        }
+    }
+    /// parses the grammar from memory, returns the number of errors if it fails
+    pub fn parse_ast(source: &str) -> Result<(Tree, Vec<HeadParam>), usize> {
+        parse_grammar(source).map_err(|errors| errors.len())
+    }
+    /// parses the grammar from memory, returns what is wrong with its format if it fails
+    pub fn parse_grammar(source: &str) -> Result<(Tree, Vec<HeadParam>), Vec<GrammarError>> {
         let (tokens, mut lines, mut errors) = tokenize(&source.as_bytes(), false);
-        if let Ok(mut refactored) = refactor(tokens, &mut lines, &mut errors) {
-            return Some(analize_tree(&mut refactored));
-        } else {
-            println!(
-                "Could not parse AST {source_path}, number of errors: {}",
-                errors.len()
-            );
-            return None;
+        match refactor(tokens, &mut lines, &mut errors) {
+            Ok(mut refactored) => analize_tree(&mut refactored, &mut lines).map_err(|err| vec![err]),
+            Err(_) => Err(errors
+                .iter_mut()
+                .map(|err| GrammarError::Syntax(String::from("could not read the grammar"), *err.line_mut()))
+                .collect()),
         }
     }
-    fn analize_tree(tokens: &mut Vec<Tokens>) -> (Tree, Vec<HeadParam>) {
+    fn analize_tree(
+        tokens: &mut Vec<Tokens>,
+        lines: &mut Vec<Line>,
+    ) -> Result<(Tree, Vec<HeadParam>), GrammarError> {
         let mut hash_map = HashMap::new();
         let mut idx = 0;
-        while let Some(head) = read_head(tokens, &mut idx) {
+        while let Some(head) = read_head(tokens, lines, &mut idx)? {
             hash_map.insert(head.name.to_string(), head);
             idx += 1;
         }
         let globals = get_globals(&hash_map);
-        (hash_map, globals)
+        Ok((hash_map, globals))
     }
     fn get_globals(tree: &HashMap<String, Head>) -> Vec<HeadParam> {
         let mut globals = vec![];
@@ -49,104 +66,191 @@ pub mod ast_parser {
         }
         globals
     }
-    fn read_head(tokens: &mut Vec<Tokens>, idx: &mut usize) -> Option<Head> {
-        if tokens.len() == *idx {
-            return None;
+    /// line of the token, the end of the file if there are no more tokens
+    fn line_at(lines: &[Line], idx: usize) -> Line {
+        match lines.get(idx).or(lines.last()) {
+            Some(line) => *line,
+            None => Line::from((0, 0)),
         }
-        let name = loop {
-            if let Tokens::Text(txt) = &tokens[*idx] {
-                *idx += 1;
-                break txt.to_string();
+    }
+    fn read_head(
+        tokens: &mut Vec<Tokens>,
+        lines: &mut Vec<Line>,
+        idx: &mut usize,
+    ) -> Result<Option<Head>, GrammarError> {
+        let (name, line) = loop {
+            match tokens.get(*idx) {
+                Some(Tokens::Text(txt)) => {
+                    *idx += 1;
+                    break (txt.to_string(), lines[*idx - 1]);
+                }
+                Some(_) => *idx += 1,
+                None => return Ok(None),
             }
-            *idx += 1;
         };
         let mut parameters = Vec::with_capacity(tokens.len() / 100);
-        while tokens[*idx] != Tokens::Tab {
-            match &tokens[*idx] {
-                Tokens::SquareBracket(closed) => {
-                    if !closed {
-                        if let Tokens::Text(txt) = &tokens[*idx + 1] {
-                            parameters.push(HeadParam::Array(txt.to_string()));
-                            *idx += 2;
-                        } else {
-                            return None;
-                        }
+        loop {
+            match tokens.get(*idx) {
+                Some(Tokens::Tab) => break,
+                Some(Tokens::SquareBracket(false)) => {
+                    if let Some(Tokens::Text(txt)) = tokens.get(*idx + 1) {
+                        parameters.push(HeadParam::Array(txt.to_string()));
+                        *idx += 2;
                     } else {
-                        return None;
+                        return Err(GrammarError::Syntax(
+                            format!("expected a parameter name after `[` in structure {name}"),
+                            line_at(lines, *idx),
+                        ));
                     }
                 }
-                Tokens::Text(txt) => {
+                Some(Tokens::SquareBracket(true)) => {
+                    return Err(GrammarError::Syntax(
+                        format!("unexpected `]` in parameters of structure {name}"),
+                        line_at(lines, *idx),
+                    ));
+                }
+                Some(Tokens::Text(txt)) => {
                     parameters.push(HeadParam::Value(txt.to_string()));
                     *idx += 1;
                 }
-                _ => {}
+                Some(_) => *idx += 1,
+                None => {
+                    return Err(GrammarError::Syntax(
+                        format!("structure {name} has no elements"),
+                        line,
+                    ))
+                }
             }
         }
-        Some(Head {
+        let nodes = get_nodes(tokens, lines, idx, 1)?;
+        match tokens.get(*idx) {
+            Some(Tokens::Semicolon) => (),
+            Some(Tokens::Tab) => {
+                return Err(GrammarError::Syntax(
+                    String::from("element is indented more than one level deeper than the one before it"),
+                    line_at(lines, *idx + count_tabs(tokens, *idx)),
+                ))
+            }
+            _ => {
+                return Err(GrammarError::Syntax(
+                    format!("structure {name} is not ended with `;`"),
+                    line_at(lines, *idx),
+                ))
+            }
+        }
+        Ok(Some(Head {
             name,
             parameters,
-            nodes: get_nodes(tokens, idx, 1),
-        })
+            nodes,
+            line,
+        }))
     }
-    fn get_nodes(tokens: &mut Vec<Tokens>, idx: &mut usize, tabs: usize) -> Vec<NodeType> {
+    fn get_nodes(
+        tokens: &mut Vec<Tokens>,
+        lines: &mut Vec<Line>,
+        idx: &mut usize,
+        tabs: usize,
+    ) -> Result<Vec<NodeType>, GrammarError> {
         let mut nodes: Vec<NodeType> = vec![];
         while count_tabs(&tokens, *idx) == tabs {
             *idx += tabs;
-            match tokens[*idx + 1] {
+            let line = line_at(lines, *idx);
+            let next = match tokens.get(*idx + 1) {
+                Some(next) => next,
+                None => {
+                    return Err(GrammarError::Syntax(
+                        String::from("element is not ended with `;`"),
+                        line,
+                    ))
+                }
+            };
+            match next {
                 Tokens::Optional => {
                     tokens.remove(*idx + 1);
+                    lines.remove(*idx + 1);
+                    lines.remove(*idx);
                     let node = NodeType::Maybe(Node {
                         kind: tokens.remove(*idx),
-                        arguments: get_node_args(&tokens, idx),
-                        nodes: get_nodes(tokens, idx, tabs + 1),
+                        arguments: get_node_args(&tokens, lines, idx)?,
+                        nodes: get_nodes(tokens, lines, idx, tabs + 1)?,
+                        line,
                     });
                     nodes.push(node);
                 }
                 Tokens::Operator(op) => match op {
                     Operators::Not => {
                         tokens.remove(*idx + 1);
+                        lines.remove(*idx + 1);
+                        lines.remove(*idx);
                         let node = NodeType::Command(Node {
                             kind: tokens.remove(*idx),
-                            arguments: get_node_args(&tokens, idx),
-                            nodes: get_nodes(tokens, idx, tabs + 1),
+                            arguments: get_node_args(&tokens, lines, idx)?,
+                            nodes: get_nodes(tokens, lines, idx, tabs + 1)?,
+                            line,
                         });
                         nodes.push(node);
                     }
                     Operators::Equal => {
                         let node = NodeType::ArgsCondition(ArgsCon {
-                            params: get_node_args(&tokens, idx),
-                            nodes: get_nodes(tokens, idx, tabs + 1),
+                            params: get_node_args(&tokens, lines, idx)?,
+                            nodes: get_nodes(tokens, lines, idx, tabs + 1)?,
+                            line,
                         });
                         nodes.push(node);
                     }
-                    _ => {}
+                    _ => {
+                        return Err(GrammarError::Syntax(
+                            format!("unexpected `{}` after element", deparse_token(next)),
+                            line_at(lines, *idx + 1),
+                        ))
+                    }
                 },
                 _ => {
+                    lines.remove(*idx);
                     let node = NodeType::Expect(Node {
                         kind: tokens.remove(*idx),
-                        arguments: get_node_args(&tokens, idx),
-                        nodes: get_nodes(tokens, idx, tabs + 1),
+                        arguments: get_node_args(&tokens, lines, idx)?,
+                        nodes: get_nodes(tokens, lines, idx, tabs + 1)?,
+                        line,
                     });
                     nodes.push(node);
                 }
             }
         }
-        nodes
+        Ok(nodes)
     }
-    fn get_node_args(tokens: &Vec<Tokens>, idx: &mut usize) -> NodeParameters {
+    fn get_node_args(
+        tokens: &Vec<Tokens>,
+        lines: &[Line],
+        idx: &mut usize,
+    ) -> Result<NodeParameters, GrammarError> {
         let mut args = HashMap::new();
-        while let Tokens::Text(name) = &tokens[*idx] {
+        while let Some(Tokens::Text(name)) = tokens.get(*idx) {
+            if tokens.get(*idx + 1) != Some(&Tokens::Operator(Operators::Equal)) {
+                return Err(GrammarError::Syntax(
+                    format!("expected `=` after argument {name}"),
+                    line_at(lines, *idx),
+                ));
+            }
             *idx += 2;
-            if let Tokens::String(value) = &tokens[*idx] {
-                args.insert(name.to_string(), value.to_string());
+            match tokens.get(*idx) {
+                Some(Tokens::String(value)) => {
+                    args.insert(name.to_string(), value.to_string());
+                }
+                _ => {
+                    return Err(GrammarError::Syntax(
+                        format!("value of argument {name} must be in quotes"),
+                        line_at(lines, *idx),
+                    ))
+                }
             }
             *idx += 1;
         }
-        args
+        Ok(args)
     }
     fn count_tabs(tokens: &Vec<Tokens>, idx: usize) -> usize {
         let mut count = 0;
-        while let Tokens::Tab = &tokens[idx + count] {
+        while let Some(Tokens::Tab) = tokens.get(idx + count) {
             count += 1;
         }
         count
@@ -156,6 +260,7 @@ pub mod ast_parser {
         pub name: String,
         pub parameters: Vec<HeadParam>,
         pub nodes: Vec<NodeType>,
+        pub line: Line,
     }
     #[derive(Debug)]
     pub enum HeadParam {
@@ -167,6 +272,7 @@ pub mod ast_parser {
         pub kind: Tokens,
         pub arguments: NodeParameters,
         pub nodes: Vec<NodeType>,
+        pub line: Line,
     }
     #[derive(Debug)]
     pub enum NodeType {
@@ -179,6 +285,7 @@ pub mod ast_parser {
     pub struct ArgsCon {
         pub params: NodeParameters,
         pub nodes: Vec<NodeType>,
+        pub line: Line,
     }
 }
 
@@ -191,7 +298,7 @@ mod formater {
 
     pub fn refactor(
         mut tokens: Vec<Tokens>,
-        lines: &mut Vec<(usize, usize)>,
+        lines: &mut Vec<Line>,
         errors: &mut Vec<Errors>,
     ) -> Result<Vec<Tokens>, LexingErr> {
         let mut i = 0;
@@ -203,7 +310,7 @@ mod formater {
     fn process_token(
         tokens: &mut Vec<Tokens>,
         idx: usize,
-        lines: &mut Vec<(usize, usize)>,
+        lines: &mut Vec<Line>,
         errors: &mut Vec<Errors>,
     ) -> usize {
         match &tokens[idx] {
@@ -222,6 +329,7 @@ mod formater {
                     }
                 }
                 tokens.splice(idx + 1..i + 1, []);
+                lines[idx] = lines[idx].to(&lines[i]);
                 lines.splice(idx + 1..i + 1, []);
                 tokens[idx] = Tokens::String(res);
             }
@@ -237,6 +345,13 @@ mod formater {
                     lines.remove(idx + 1);
                 }
             }
+            Tokens::Dot => {
+                if let Tokens::Dot = tokens[idx + 1] {
+                    tokens[idx] = Tokens::DoubleDot;
+                    tokens.remove(idx + 1);
+                    lines.remove(idx + 1);
+                }
+            }
             Tokens::Text(txt) => {
                 let bytes = txt.as_bytes();
                 if let Some(first) = bytes.get(0) {
@@ -248,7 +363,7 @@ mod formater {
                             } else {
                                 // syntax err: incorrect number
                                 errors.push(Errors::InvalidNumber(
-                                    Line::from(lines[idx]),
+                                    lines[idx],
                                     txt.to_string(),
                                 ));
                                 return 1;
@@ -267,7 +382,7 @@ mod formater {
                                     let mut res = txt.to_string();
                                     res.push('.');
                                     res.push_str(txt2);
-                                    errors.push(Errors::InvalidNumber(Line::from(lines[idx]), res));
+                                    errors.push(Errors::InvalidNumber(lines[idx], res));
                                     return 1;
                                 };
                             } else {
@@ -280,7 +395,7 @@ mod formater {
                                     tokens[idx] = Tokens::Number(num as f64, 'i')
                                 } else {
                                     errors.push(Errors::InvalidNumber(
-                                        Line::from(lines[idx]),
+                                        lines[idx],
                                         txt.to_string(),
                                     ));
                                     // syntax err: incorrect number
@@ -291,7 +406,7 @@ mod formater {
                                         Tokens::Number(num as f64, bytes[bytes.len() - 1] as char)
                                 } else {
                                     errors.push(Errors::InvalidNumber(
-                                        Line::from(lines[idx]),
+                                        lines[idx],
                                         txt.to_string(),
                                     ));
                                     // syntax err: incorrect number
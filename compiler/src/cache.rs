@@ -0,0 +1,490 @@
+//! Parts of a build kept for the next one
+//!
+//! Modules are cached as the dictionaries they were parsed into, keyed by the hash of their source.
+//! Libraries are cached as the declarations they registered, keyed by the size and modification
+//! time of the binary, so they are not loaded again.
+//! Every module also gets a key made of its source and the public interfaces of the modules
+//! it imports, a module whose key did not change since it passed type checking is not checked again.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::import_graph::ImportGraph;
+use crate::codegen::InnerPath;
+use crate::intermediate::dictionary::{
+    Arg, ConstValue, Dictionary, Function, GenericDecl, KindType, NestedIdent, Overload, PathPart,
+};
+use crate::intermediate::{Kind, TypeBody};
+use crate::lexer::tokenizer::Tokens;
+use crate::Dictionaries;
+
+/// change when the cached types change, old entries are never read again
+const FORMAT: u32 = 1;
+const CHECKED_FILE: &str = "checked.txt";
+
+/// what was reused and what had to be done again
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stats {
+    pub modules_parsed: usize,
+    pub modules_cached: usize,
+    pub libraries_loaded: usize,
+    pub libraries_cached: usize,
+}
+
+pub struct Cache {
+    dir: PathBuf,
+    /// mixed into every key, differs for every grammar and compiler
+    salt: String,
+    /// hashes of the sources of the modules loaded in this build
+    sources: HashMap<String, String>,
+    /// hashes of the declarations of the libraries loaded in this build
+    libraries: Vec<String>,
+    /// keys of the modules that passed type checking
+    checked: HashSet<String>,
+    pub stats: Stats,
+}
+
+impl Cache {
+    /// opens the cache in `dir`, entries made with a different `salt` are not used
+    pub fn open(dir: impl Into<PathBuf>, salt: &str) -> Self {
+        let dir = dir.into();
+        let checked = std::fs::read_to_string(dir.join(CHECKED_FILE))
+            .map(|checked| checked.lines().map(String::from).collect())
+            .unwrap_or_default();
+        Self {
+            dir,
+            salt: hash(&[&FORMAT.to_string(), salt]),
+            sources: HashMap::new(),
+            libraries: Vec::new(),
+            checked,
+            stats: Stats::default(),
+        }
+    }
+
+    fn key(&self, parts: &[&str]) -> String {
+        let mut all = vec![self.salt.as_str()];
+        all.extend(parts);
+        hash(&all)
+    }
+
+    /// dictionary of the module parsed from the same source before
+    pub(crate) fn dictionary(&mut self, module: &str, source: &str) -> Option<Dictionary> {
+        let key = self.key(&[module, source]);
+        self.sources.insert(module.to_string(), key.clone());
+        let bytes = std::fs::read(self.dir.join("modules").join(key)).ok()?;
+        let dictionary = bincode::deserialize(&bytes).ok()?;
+        self.stats.modules_cached += 1;
+        Some(dictionary)
+    }
+
+    /// keeps the dictionary of a module that was parsed without errors
+    pub(crate) fn store_dictionary(&mut self, module: &str, source: &str, dictionary: &Dictionary) {
+        self.stats.modules_parsed += 1;
+        let bytes = match bincode::serialize(dictionary) {
+            Ok(bytes) => bytes,
+            Err(_) => return,
+        };
+        // a cache that can not be written only makes the next build slower
+        let dir = self.dir.join("modules");
+        let _ = std::fs::create_dir_all(&dir);
+        let _ = std::fs::write(dir.join(self.key(&[module, source])), bytes);
+    }
+
+    fn library_key(&self, path: &Path) -> Option<String> {
+        let metadata = std::fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?;
+        Some(self.key(&[
+            &path.to_string_lossy(),
+            &metadata.len().to_string(),
+            &modified.as_nanos().to_string(),
+        ]))
+    }
+
+    /// declarations registered by the library when it was loaded before
+    pub(crate) fn register(&mut self, path: &Path) -> Option<String> {
+        let key = self.library_key(path)?;
+        let register = std::fs::read_to_string(self.dir.join("libraries").join(key)).ok()?;
+        self.stats.libraries_cached += 1;
+        self.libraries.push(hash(&[&register]));
+        Some(register)
+    }
+
+    pub(crate) fn store_register(&mut self, path: &Path, register: &str) {
+        self.stats.libraries_loaded += 1;
+        self.libraries.push(hash(&[register]));
+        let key = match self.library_key(path) {
+            Some(key) => key,
+            None => return,
+        };
+        let dir = self.dir.join("libraries");
+        let _ = std::fs::create_dir_all(&dir);
+        let _ = std::fs::write(dir.join(key), register);
+    }
+
+    /// keys of the loaded modules, a key changes with the source of the module,
+    /// the public interface of any module it imports or the declarations of any library
+    pub fn module_keys(&self, dictionaries: &Dictionaries, graph: &ImportGraph) -> HashMap<String, String> {
+        let mut libraries = self.libraries.clone();
+        libraries.sort();
+        let interfaces = dictionaries
+            .iter()
+            .map(|(module, dictionary)| (module.as_str(), interface(dictionary)))
+            .collect::<HashMap<_, _>>();
+        let mut keys = HashMap::new();
+        for module in dictionaries.keys() {
+            let source = match self.sources.get(module) {
+                Some(source) => source,
+                None => continue,
+            };
+            let mut parts = vec![source.as_str()];
+            parts.extend(libraries.iter().map(|library| library.as_str()));
+            let dependencies = graph.dependencies(module);
+            for dependency in dependencies.iter() {
+                parts.push(dependency);
+                parts.push(interfaces.get(dependency.as_str()).map(|i| i.as_str()).unwrap_or(""));
+            }
+            keys.insert(module.clone(), self.key(&parts));
+        }
+        keys
+    }
+
+    pub fn is_checked(&self, key: &str) -> bool {
+        self.checked.contains(key)
+    }
+
+    /// remembers the keys of modules that passed type checking, only they are kept
+    pub fn save_checked<'a>(&mut self, keys: impl Iterator<Item = &'a String>) {
+        self.checked = keys.cloned().collect();
+        let mut checked = self.checked.iter().cloned().collect::<Vec<_>>();
+        checked.sort();
+        let _ = std::fs::create_dir_all(&self.dir);
+        let _ = std::fs::write(self.dir.join(CHECKED_FILE), checked.join("\n"));
+    }
+}
+
+fn hash(parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.len().to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// digest of what other modules can see of the module
+///
+/// bodies of functions are left out and so are the positions,
+/// so editing a function does not change the interface
+fn interface(dictionary: &Dictionary) -> String {
+    let mut items = Vec::new();
+    for fun in dictionary.functions.iter().filter(|fun| fun.public) {
+        items.push(Item::Fun(signature(fun)));
+    }
+    for structt in dictionary.structs.iter().filter(|structt| structt.public) {
+        items.push(Item::Struct {
+            identifier: structt.identifier.clone(),
+            generics: generics(&structt.generics),
+            fields: structt.fields.iter().map(|(name, kind)| (name.clone(), kind_of(kind))).collect(),
+            traits: structt.traits.clone(),
+            constructor: structt.constructor,
+            methods: structt.functions.iter().map(signature).collect(),
+            overloads: structt.overloads.iter().map(overload).collect(),
+            impls: structt
+                .impls
+                .iter()
+                .map(|implementation| (implementation.target.clone(), implementation.functions.iter().map(signature).collect()))
+                .collect(),
+        });
+    }
+    for enumm in dictionary.enums.iter().filter(|enumm| enumm.public) {
+        items.push(Item::Enum {
+            identifier: enumm.identifier.clone(),
+            keys: enumm.keys.iter().map(|(key, value, _)| (key.clone(), *value)).collect(),
+            fields: enumm.fields.iter().map(|fields| params(fields)).collect(),
+            methods: enumm.methods.iter().map(signature).collect(),
+        });
+    }
+    for typedef in dictionary.types.iter().filter(|typedef| typedef.public) {
+        items.push(Item::Type {
+            identifier: typedef.identifier.clone(),
+            generics: generics(&typedef.generics),
+            kind: kind_of(&typedef.kind),
+            methods: typedef.methods.iter().map(signature).collect(),
+        });
+    }
+    for constant in dictionary.constants.iter().filter(|constant| constant.public) {
+        items.push(Item::Const {
+            identifier: constant.identifier.clone(),
+            value: constant.real_value.as_ref().map(value_of),
+        });
+    }
+    for traitt in dictionary.traits.iter().filter(|traitt| traitt.public) {
+        items.push(Item::Trait {
+            identifier: traitt.identifier.clone(),
+            traits: traitt.traits.clone(),
+            defaults: traitt.defaults.clone(),
+            methods: traitt.methods.iter().map(signature).collect(),
+        });
+    }
+    for error in dictionary.errors.iter() {
+        items.push(Item::Error {
+            identifier: error.identifier.clone(),
+            args: params(&error.args),
+            fields: error.fields.iter().map(|(name, _)| name.clone()).collect(),
+        });
+    }
+    for import in dictionary.imports.iter() {
+        items.push(Item::Import {
+            path: import.path.clone(),
+            alias: import.alias.clone(),
+        });
+    }
+    for usee in dictionary.uses.iter() {
+        items.push(Item::Use {
+            path: usee.path.clone(),
+            alias: usee.alias.clone(),
+        });
+    }
+    let bytes = bincode::serialize(&items).unwrap_or_default();
+    format!("{:x}", Sha256::digest(&bytes))
+}
+
+/// declaration of the interface, it holds no positions so moving code does not change it
+#[derive(Serialize)]
+enum Item {
+    Fun(Signature),
+    Struct {
+        identifier: String,
+        generics: Vec<Generic>,
+        fields: Vec<(String, PlainKind)>,
+        traits: Vec<NestedIdent>,
+        constructor: Option<usize>,
+        methods: Vec<Signature>,
+        overloads: Vec<OverloadSignature>,
+        impls: Vec<(NestedIdent, Vec<Signature>)>,
+    },
+    Enum {
+        identifier: String,
+        keys: Vec<(String, usize)>,
+        fields: Vec<Vec<Param>>,
+        methods: Vec<Signature>,
+    },
+    Type {
+        identifier: String,
+        generics: Vec<Generic>,
+        kind: PlainKind,
+        methods: Vec<Signature>,
+    },
+    Const {
+        identifier: String,
+        value: Option<Value>,
+    },
+    Trait {
+        identifier: String,
+        traits: Vec<NestedIdent>,
+        defaults: Vec<String>,
+        methods: Vec<Signature>,
+    },
+    /// the fields of errors always have the same kinds, only their names are visible
+    Error {
+        identifier: String,
+        args: Vec<Param>,
+        fields: Vec<String>,
+    },
+    Import {
+        path: String,
+        alias: String,
+    },
+    Use {
+        path: Vec<PathPart>,
+        alias: Option<String>,
+    },
+}
+
+#[derive(Serialize)]
+struct Signature {
+    identifier: Option<String>,
+    generics: Vec<Generic>,
+    args: Vec<Param>,
+    return_type: Option<PlainKind>,
+    can_yeet: bool,
+    takes_self: bool,
+    public: bool,
+}
+
+#[derive(Serialize)]
+struct OverloadSignature {
+    operator: Tokens,
+    arg: Option<Param>,
+    return_type: Option<PlainKind>,
+}
+
+#[derive(Serialize)]
+struct Param {
+    identifier: String,
+    kind: PlainKind,
+    default: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct Generic {
+    identifier: String,
+    traits: Vec<NestedIdent>,
+}
+
+/// `Kind` without the positions
+#[derive(Serialize)]
+enum PlainKind {
+    Function {
+        args: Vec<Param>,
+        return_type: Option<Box<PlainKind>>,
+        refs: usize,
+    },
+    Type {
+        refs: usize,
+        main: NestedIdent,
+        generics: Vec<PlainKind>,
+        nullable: bool,
+        kind: KindType,
+        file: Option<String>,
+    },
+    Generic {
+        identifier: String,
+        constraints: Vec<InnerPath>,
+        refs: usize,
+        nullable: bool,
+    },
+    Array {
+        type_: Box<PlainKind>,
+        size: usize,
+        refs: usize,
+        nullable: bool,
+    },
+    Tuple {
+        types: Vec<PlainKind>,
+        refs: usize,
+        nullable: bool,
+    },
+    Void,
+}
+
+/// `ConstValue` without the positions
+#[derive(Serialize)]
+enum Value {
+    Function(Signature),
+    Array(Vec<Value>),
+    Scalar(ConstValue),
+}
+
+fn signature(fun: &Function) -> Signature {
+    Signature {
+        identifier: fun.identifier.clone(),
+        generics: generics(&fun.generics),
+        args: params(&fun.args),
+        return_type: fun.return_type.as_ref().map(kind_of),
+        can_yeet: fun.can_yeet,
+        takes_self: fun.takes_self,
+        public: fun.public,
+    }
+}
+
+fn overload(overload: &Overload) -> OverloadSignature {
+    OverloadSignature {
+        operator: overload.operator.clone(),
+        arg: overload.arg.as_ref().map(param),
+        return_type: overload.return_type.as_ref().map(kind_of),
+    }
+}
+
+fn params(args: &[Arg]) -> Vec<Param> {
+    args.iter().map(param).collect()
+}
+
+fn param(arg: &Arg) -> Param {
+    Param {
+        identifier: arg.identifier.clone(),
+        kind: kind_of(&arg.kind),
+        default: arg.default.as_ref().map(value_of),
+    }
+}
+
+fn generics(decls: &[GenericDecl]) -> Vec<Generic> {
+    decls
+        .iter()
+        .map(|decl| Generic {
+            identifier: decl.identifier.clone(),
+            traits: decl.traits.clone(),
+        })
+        .collect()
+}
+
+fn kind_of(kind: &Kind) -> PlainKind {
+    match &kind.body {
+        TypeBody::Function {
+            args,
+            return_type,
+            refs,
+        } => PlainKind::Function {
+            args: params(args),
+            return_type: return_type.as_ref().as_ref().map(|kind| Box::new(kind_of(kind))),
+            refs: *refs,
+        },
+        TypeBody::Type {
+            refs,
+            main,
+            generics,
+            nullable,
+            kind: kind_type,
+        } => PlainKind::Type {
+            refs: *refs,
+            main: main.clone(),
+            generics: generics.iter().map(kind_of).collect(),
+            nullable: *nullable,
+            kind: *kind_type,
+            file: kind.file.clone(),
+        },
+        TypeBody::Generic {
+            identifier,
+            constraints,
+            refs,
+            nullable,
+        } => PlainKind::Generic {
+            identifier: identifier.clone(),
+            constraints: constraints.clone(),
+            refs: *refs,
+            nullable: *nullable,
+        },
+        TypeBody::Array {
+            type_,
+            size,
+            refs,
+            nullable,
+        } => PlainKind::Array {
+            type_: Box::new(kind_of(type_)),
+            size: *size,
+            refs: *refs,
+            nullable: *nullable,
+        },
+        TypeBody::Tuple {
+            types,
+            refs,
+            nullable,
+        } => PlainKind::Tuple {
+            types: types.iter().map(kind_of).collect(),
+            refs: *refs,
+            nullable: *nullable,
+        },
+        TypeBody::Void => PlainKind::Void,
+    }
+}
+
+fn value_of(value: &ConstValue) -> Value {
+    match value {
+        ConstValue::Function(fun) => Value::Function(signature(fun)),
+        ConstValue::Array(values) => Value::Array(values.iter().map(value_of).collect()),
+        value => Value::Scalar(value.clone()),
+    }
+}
@@ -4,11 +4,13 @@ use crate::expression_parser::*;
 use crate::intermediate::Kind;
 use crate::intermediate::dictionary::{
     get_ident, get_type, step_inside_arr, step_inside_val, get_loop_ident, get_break_ident,
+    try_get_ident,
 };
 use crate::intermediate::AnalyzationError::ErrType;
 use crate::lexer::tokenizer::*;
-use crate::tree_walker::tree_walker::Line;
+use crate::tree_walker::tree_walker::{Line, Node};
 use crate::tree_walker;
+use serde::{Deserialize, Serialize};
 
 pub fn generate_tree(
     node: &tree_walker::tree_walker::Node,
@@ -66,11 +68,23 @@ pub fn node_from_node(
             }
             "KWYeet" => {
                 let expr = step_inside_val(&node, "err");
-                let expr = try_get_variable(&expr, errors, file_name).unwrap();
-                Some(Nodes::Yeet {
-                    expr,
-                    line: node.line,
-                })
+                match try_get_value(&expr, errors, file_name) {
+                    Some(ValueType::Value(Variable {
+                        root: (Root::Identifier(ident), line),
+                        tail,
+                        ..
+                    })) => Some(Nodes::Yeet {
+                        expr: ((ident, line), tail),
+                        line: node.line,
+                    }),
+                    _ => {
+                        errors.push(ErrType::NonExistentIdentifier(
+                            String::from("yeet"),
+                            node.line,
+                        ));
+                        None
+                    }
+                }
             }
             "code_block" => Some(Nodes::Block {
                 body: generate_tree(&node, errors, file_name),
@@ -145,17 +159,66 @@ pub fn node_from_node(
                 })
             }
             "KWFor" => {
-                let ident = get_ident(&node);
+                let first = get_binding(step_inside_val(&node, "identifier"));
+                // for i, value in array
+                let value = step_inside_val(&node, "value");
+                let second = match &value.name {
+                    Tokens::Text(txt) if txt == "binding" => Some(get_binding(value)),
+                    _ => None,
+                };
+                let (ident, ident2, pattern) = match (first, second) {
+                    ((first, None), Some((ident, pattern))) => (ident, Some(first), pattern),
+                    ((ident, pattern), None) => (ident, None, pattern),
+                    ((_, Some(_)), Some(_)) => {
+                        errors.push(ErrType::InvalidPattern(
+                            "index of a loop can not be destructured".to_string(),
+                            node.line,
+                        ));
+                        return None;
+                    }
+                };
                 let expr = step_inside_val(&node, "expression");
                 let expr = expr_into_tree(&expr, errors, file_name);
+                let range = step_inside_val(&node, "range");
+                let range = match &range.name {
+                    Tokens::Text(txt) if txt == "range" => {
+                        let end = step_inside_val(&range, "end");
+                        let step = step_inside_val(&range, "step");
+                        let step = match &step.name {
+                            Tokens::Text(txt) if txt == "expression" => {
+                                Some(expr_into_tree(&step, errors, file_name))
+                            }
+                            _ => None,
+                        };
+                        Some(Range {
+                            end: expr_into_tree(&end, errors, file_name),
+                            inclusive: step_inside_val(&range, "inclusive").name
+                                == Tokens::Operator(Operators::Equal),
+                            step,
+                            line: range.line,
+                        })
+                    }
+                    _ => None,
+                };
                 let body = generate_tree(step_inside_val(&node, "code"), errors, file_name);
-                let ident2 = get_loop_ident(&node);
+                let label = match &step_inside_val(&node, "label").name {
+                    Tokens::Text(txt) if txt == "loop_ident" => {
+                        match &step_inside_val(step_inside_val(&node, "label"), "identifier").name {
+                            Tokens::String(txt) => Some(txt.to_string()),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
                 Some(Nodes::For {
                     ident,
                     expr,
+                    range,
                     body,
                     line: node.line,
                     ident2,
+                    label,
+                    pattern,
                 })
             }
             "KWTry" => {
@@ -172,7 +235,7 @@ pub fn node_from_node(
                 };
                 let mut catch = Vec::new();
                 for node in step_inside_arr(&node, "catch") {
-                    let ident = get_ident(&node);
+                    let ident = try_get_ident(&node);
                     let body = generate_tree(step_inside_val(&node, "code"), errors, file_name);
                     let mut kinds = Vec::new();
                     let kinds_path = step_inside_arr(&node, "types");
@@ -212,17 +275,20 @@ pub fn node_from_node(
                 let mut body = Vec::new();
                 let mut default = None;
                 for node in step_inside_arr(&node, "nodes") {
-                    let expr = step_inside_val(&node, "expression");
-
-                    if let Tokens::Text(txt) = &step_inside_val(&expr, "ignore").name {
-                        if txt == "_" {
-                            default = Some(generate_tree(step_inside_val(&node, "code"), errors, file_name));
-                        } else {
-                            let expr = expr_into_tree(&expr, errors, file_name);
-                            let bd = generate_tree(step_inside_val(&node, "code"), errors, file_name);
-                            body.push((expr, bd));
+                    let mut values = Vec::new();
+                    let mut is_default = false;
+                    for expr in step_inside_arr(&node, "values") {
+                        match &step_inside_val(&expr, "ignore").name {
+                            Tokens::Text(txt) if txt == "_" => is_default = true,
+                            _ => values.push(expr_into_tree(&expr, errors, file_name)),
                         }
                     }
+                    let bd = generate_tree(step_inside_val(&node, "code"), errors, file_name);
+                    if is_default {
+                        default = Some(bd);
+                    } else {
+                        body.push((values, bd));
+                    }
                 }
                 return Some(Nodes::Switch {
                     expr,
@@ -232,7 +298,7 @@ pub fn node_from_node(
                 });
             }
             "KWLet" => {
-                let ident = get_ident(&node);
+                let (ident, pattern) = get_binding(step_inside_val(&node, "identifier"));
                 let expr = step_inside_val(&node, "expression");
                 let expr = if let Tokens::Text(txt) = &expr.name {
                     if txt == "expression" {
@@ -261,6 +327,7 @@ pub fn node_from_node(
                     expr,
                     kind,
                     line: node.line,
+                    pattern,
                 })
             }
 
@@ -271,13 +338,40 @@ pub fn node_from_node(
     }
 }
 
-#[derive(Debug, Clone)]
+/// names bound to the elements of a tuple, `None` ignores the element
+pub type TuplePattern = Vec<Option<String>>;
+
+/// name or tuple pattern of a `binding`
+///
+/// elements of a pattern are bound from a hidden variable named after the position of the pattern
+fn get_binding(node: &Node) -> (String, Option<TuplePattern>) {
+    let pattern = step_inside_val(node, "pattern");
+    if let Tokens::Text(txt) = &pattern.name {
+        if txt == "tuple_pattern" {
+            let bindings = step_inside_arr(pattern, "bindings")
+                .iter()
+                .map(|binding| match &step_inside_val(binding, "ignore").name {
+                    Tokens::Text(txt) if txt == "_" => None,
+                    _ => Some(get_ident(binding)),
+                })
+                .collect();
+            let hidden = format!("#tuple:{}:{}", pattern.line.line, pattern.line.column);
+            return (hidden, Some(bindings));
+        }
+    }
+    (get_ident(node), None)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Nodes {
     Let {
+        /// hidden name of the whole tuple when a pattern is used
         ident: String,
         expr: Option<ValueType>,
         kind: Option<Kind>,
         line: Line,
+        /// `let (a, _) = pair`
+        pattern: Option<TuplePattern>,
     },
     If {
         cond: ValueType,
@@ -294,10 +388,16 @@ pub enum Nodes {
     },
     For {
         ident: String,
+        /// start of the range or the iterated value
         expr: ValueType,
+        range: Option<Range>,
         body: Vec<Nodes>,
         line: Line,
+        /// index in `for i, value in array`
         ident2: Option<String>,
+        label: Option<String>,
+        /// `for (a, b) in pairs`
+        pattern: Option<TuplePattern>,
     },
     Return {
         expr: Option<ValueType>,
@@ -337,7 +437,8 @@ pub enum Nodes {
     },
     Switch {
         expr: ValueType,
-        body: Vec<(ValueType, Vec<Nodes>)>,
+        /// cases with all of their values
+        body: Vec<(Vec<ValueType>, Vec<Nodes>)>,
         default: Option<Vec<Nodes>>,
         line: Line,
     },
@@ -349,10 +450,21 @@ pub enum Nodes {
     },
 }
 
-#[derive(Debug, Clone)]
+/// `..end`, `..=end` or `..end step n` following the start of a for loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Range {
+    pub end: ValueType,
+    pub inclusive: bool,
+    pub step: Option<ValueType>,
+    pub line: Line,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Catch {
-    ident: String,
-    kinds: Vec<Vec<String>>,
-    body: Vec<Nodes>,
+    /// variable the error is bound to
+    pub ident: Option<String>,
+    /// paths of caught errors, catches everything if empty
+    pub kinds: Vec<Vec<String>>,
+    pub body: Vec<Nodes>,
     pub line: Line,
 }
@@ -7,8 +7,10 @@ use runtime::runtime_types::{
     GENERAL_REG3, MEMORY_REG1, POINTER_REG, RETURN_REG,
 };
 
-use crate::codeblock_parser::Nodes;
-use crate::expression_parser::{self, ArrayRule, FunctionCall, Root, TailNodes, ValueType};
+use crate::codeblock_parser::{Nodes, TuplePattern};
+use crate::expression_parser::{
+    self, ArgError, ArmBody, ArrayRule, FunctionCall, InterpolationPart, Match, Root, TailNodes, ValueType,
+};
 use crate::intermediate::dictionary::{
     self, Arg, ConstValue, Function, GenericDecl, TypeComparison,
 };
@@ -16,15 +18,38 @@ use crate::intermediate::{Kind, TypeBody};
 use crate::lexer::tokenizer::{self, Operators};
 use crate::rdasm_opt;
 use crate::tree_walker::tree_walker::Line;
+use serde::{Deserialize, Serialize};
 use crate::{intermediate, prep_objects::Context};
 
 use crate::libloader::{self, MemoryTypes};
 
 const CORE_LIB: usize = 4;
+/// smallest number of dense cases for switch to use a jump table
+const JUMP_TABLE_MIN_CASES: usize = 4;
+
+/// Settings of the build profile that change the generated code
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// functions may be inlined at their call sites
+    pub inline: bool,
+    /// maximum number of statements in a function that is inlined
+    /// without being marked as `inline`
+    pub inline_threshold: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            inline: true,
+            inline_threshold: 3,
+        }
+    }
+}
 
 pub fn gen(
     objects: &mut Context,
     _main: &str,
+    options: &Options,
 ) -> Result<runtime::runtime_types::Context, CodegenError> {
     let mut vm_context = runtime::runtime_types::Context::new(Vec::new());
     // Initialize some common constants for faster lookup
@@ -51,8 +76,11 @@ pub fn gen(
     });
     let main_path = InnerPath::main();
     let fun_locs = gen_all_fun_ids(objects)?;
+    mark_inlinable(objects, &fun_locs, options)?;
+    gen_struct_types(objects, &mut vm_context);
     gen_all_funs(objects, &mut vm_context)?;
-    fix_fun_calls(objects, &mut vm_context, &fun_locs)?;
+    fix_fun_calls(objects, &mut vm_context)?;
+    fix_dyn_methods(objects, &mut vm_context)?;
     call_main(main_path.get(objects)?, &mut vm_context)?;
     Ok(vm_context)
 }
@@ -81,13 +109,26 @@ fn call_main(main: &Function, context: &mut runtime_types::Context) -> Result<()
 fn fix_fun_calls(
     objects: &Context,
     context: &mut runtime_types::Context,
-    fun_ids: &Vec<InnerPath>,
 ) -> Result<(), CodegenError> {
+    // specializations of generic functions are created during generation,
+    // so the locations are collected from all functions
+    let mut locations = HashMap::new();
+    for dictionary in objects.0.values() {
+        let functions = dictionary
+            .functions
+            .iter()
+            .chain(dictionary.structs.iter().flat_map(|structt| structt.functions.iter()));
+        for fun in functions {
+            if fun.identifier.as_ref().is_some_and(|ident| ident.starts_with('#')) {
+                continue;
+            }
+            locations.insert(fun.id, fun.location);
+        }
+    }
     for instr in context.code.data.iter_mut() {
         match instr {
             Instructions::Jump(id) => {
-                let fun = fun_ids[*id].get(objects)?;
-                *id = fun.location;
+                *id = locations[id];
             }
             _ => {}
         }
@@ -95,6 +136,100 @@ fn fix_fun_calls(
     Ok(())
 }
 
+/// Registers a runtime type for every struct, its id is stored in the header of the object
+fn gen_struct_types(objects: &Context, context: &mut runtime_types::Context) {
+    let mut files = objects.0.keys().collect::<Vec<_>>();
+    files.sort();
+    for file in files {
+        for structt in objects.0.get(file).unwrap().structs.iter() {
+            context
+                .memory
+                .non_primitives
+                .push(runtime_types::NonPrimitiveType {
+                    name: struct_type_name(file, &structt.identifier),
+                    kind: runtime_types::NonPrimitiveTypes::Struct,
+                    len: structt.fields.len() + 1,
+                    pointers: structt.ptrs,
+                    methods: HashMap::new(),
+                });
+        }
+        // every variant of an enum with data is a type of its own
+        for enumm in objects.0.get(file).unwrap().enums.iter() {
+            if !enumm.has_payload() {
+                continue;
+            }
+            for (key, fields) in enumm.keys.iter().zip(enumm.fields.iter()) {
+                context
+                    .memory
+                    .non_primitives
+                    .push(runtime_types::NonPrimitiveType {
+                        name: struct_type_name(file, &variant_ident(&enumm.identifier, &key.0)),
+                        kind: runtime_types::NonPrimitiveTypes::Struct,
+                        len: fields.len() + 1,
+                        pointers: 0,
+                        methods: HashMap::new(),
+                    });
+            }
+        }
+    }
+}
+
+/// identifier of the runtime type of an enum variant
+fn variant_ident(enumm: &str, variant: &str) -> String {
+    format!("{}.{}", enumm, variant)
+}
+
+fn struct_type_name(file: &str, ident: &str) -> String {
+    format!("{}:{}", file, ident)
+}
+
+fn struct_type_id(context: &runtime_types::Context, file: &str, ident: &str) -> Option<usize> {
+    let name = struct_type_name(file, ident);
+    context
+        .memory
+        .non_primitives
+        .iter()
+        .position(|non_primitive| non_primitive.name == name)
+}
+
+/// Fills the method tables used by `DynMethod` once all methods are generated
+///
+/// Methods of a trait are stored in the order they are declared in the trait
+fn fix_dyn_methods(
+    objects: &Context,
+    context: &mut runtime_types::Context,
+) -> Result<(), CodegenError> {
+    for (file, dictionary) in objects.0.iter() {
+        for structt in dictionary.structs.iter() {
+            let id = match struct_type_id(context, file, &structt.identifier) {
+                Some(id) => id,
+                None => continue,
+            };
+            for ident in structt.traits.iter() {
+                let (trait_file, traitt) = match resolve_trait(objects, ident, file) {
+                    Some(traitt) => traitt,
+                    None => continue,
+                };
+                let mut methods = Vec::new();
+                for method in traitt.methods.iter() {
+                    let path = InnerPath {
+                        file: file.clone(),
+                        block: Some(structt.identifier.clone()),
+                        ident: method.identifier.clone().unwrap(),
+                        kind: ImportKinds::Rd,
+                    };
+                    methods.push(path.get(objects)?.location);
+                }
+                let trait_id = trait_id(objects, &traitt.identifier, trait_file).unwrap();
+                context.memory.non_primitives[id]
+                    .methods
+                    .insert(trait_id, methods);
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Gives all functions unique ids before generating them,
 /// then if a function wants to call another function,
 /// it can just use the id, which will be replaced with
@@ -166,6 +301,125 @@ fn gen_all_fun_ids(objects: &mut Context) -> Result<Vec<InnerPath>, CodegenError
     Ok(paths)
 }
 
+/// Decides which functions will be inlined at their call sites
+///
+/// Only free functions without generics can be inlined,
+/// `inline` and `noinline` attributes take precedence over the size heuristic
+/// and disabling inlining in the profile overrides both.
+/// Recursive functions would be expanded into themselves, they are always called
+fn mark_inlinable(
+    objects: &mut Context,
+    fun_ids: &Vec<InnerPath>,
+    options: &Options,
+) -> Result<(), CodegenError> {
+    for path in fun_ids {
+        let fun = path.get_mut(objects)?;
+        let inlinable = options.inline
+            && path.block.is_none()
+            && path.ident != "main"
+            && fun.generics.is_empty()
+            && !fun.can_yeet
+            && !fun.takes_self;
+        fun.inline = match (inlinable, fun.inline) {
+            (false, _) => dictionary::InlineHint::Never,
+            (true, dictionary::InlineHint::Auto) => {
+                if statement_count(&fun.code) <= options.inline_threshold {
+                    dictionary::InlineHint::Always
+                } else {
+                    dictionary::InlineHint::Never
+                }
+            }
+            (true, hint) => hint,
+        };
+    }
+    let mut inlined = Vec::new();
+    for path in fun_ids {
+        if path.get(objects)?.inline == dictionary::InlineHint::Always {
+            inlined.push(path);
+        }
+    }
+    // functions of the same file each of them refers to, a local variable
+    // with the name of a function is counted as well
+    let mut calls = Vec::with_capacity(inlined.len());
+    for path in &inlined {
+        let mut idents = Vec::new();
+        for node in path.get(objects)?.code.iter() {
+            node_idents(node, &mut idents);
+        }
+        calls.push(
+            (0..inlined.len())
+                .filter(|&callee| {
+                    inlined[callee].file == path.file && idents.contains(&inlined[callee].ident)
+                })
+                .collect::<Vec<usize>>(),
+        );
+    }
+    for (idx, path) in inlined.iter().enumerate() {
+        if calls_itself(&calls, idx) {
+            path.get_mut(objects)?.inline = dictionary::InlineHint::Never;
+        }
+    }
+    Ok(())
+}
+
+/// whether the function reaches itself through the calls of other inlined functions
+fn calls_itself(calls: &[Vec<usize>], fun: usize) -> bool {
+    let mut visited = vec![false; calls.len()];
+    let mut stack = calls[fun].clone();
+    while let Some(callee) = stack.pop() {
+        if callee == fun {
+            return true;
+        }
+        if !std::mem::replace(&mut visited[callee], true) {
+            stack.extend(&calls[callee]);
+        }
+    }
+    false
+}
+
+/// counts statements of a block including nested blocks
+fn statement_count(block: &Vec<Nodes>) -> usize {
+    let mut count = 0;
+    for node in block {
+        count += 1;
+        match node {
+            Nodes::If {
+                body, elif, els, ..
+            } => {
+                count += statement_count(body);
+                for elif in elif {
+                    count += statement_count(&elif.1);
+                }
+                if let Some(els) = els {
+                    count += statement_count(&els.0);
+                }
+            }
+            Nodes::While { body, .. }
+            | Nodes::For { body, .. }
+            | Nodes::Block { body, .. }
+            | Nodes::Loop { body, .. } => {
+                count += statement_count(body);
+            }
+            Nodes::Try { body, finally, .. } => {
+                count += statement_count(body);
+                if let Some(finally) = finally {
+                    count += statement_count(finally);
+                }
+            }
+            Nodes::Switch { body, default, .. } => {
+                for case in body {
+                    count += statement_count(&case.1);
+                }
+                if let Some(default) = default {
+                    count += statement_count(default);
+                }
+            }
+            _ => (),
+        }
+    }
+    count
+}
+
 fn gen_all_funs(
     objects: &mut Context,
     context: &mut runtime_types::Context,
@@ -182,7 +436,15 @@ fn gen_all_funs(
                     .unwrap(),
                 kind: ImportKinds::Rd,
             };
-            gen_fun(objects, &fun, context, false, None)?;
+            // anonymous functions are generated where they are defined
+            if fun.ident.starts_with('#') {
+                continue;
+            }
+            // generic functions are specialized at their call sites
+            if !fun.get(objects)?.generics.is_empty() || fun.ident.contains('<') {
+                continue;
+            }
+            gen_fun(objects, &fun, context, false, None, None, None)?;
         }
         for structt in 0..objects.0.get(&file).unwrap().structs.len() {
             let path = InnerPath {
@@ -223,12 +485,20 @@ fn gen_all_funs(
                     .find(|struc| struc.identifier == path.block.clone().unwrap())
                     .clone()
                     .unwrap();
+                if !structt.generics.is_empty()
+                    || !fun_path.get(objects)?.generics.is_empty()
+                    || fun_path.ident.contains('<')
+                {
+                    continue;
+                }
                 gen_fun(
                     objects,
                     &fun_path,
                     context,
                     is_constructor,
                     Some(structt.generics.clone()),
+                    None,
+                    None,
                 )?;
             }
         }
@@ -292,6 +562,278 @@ pub enum CodegenError {
     CannotCast(Kind, Position, Line),
     CannotTestNullable(Line),
     CannotTestNonNullable(Kind, Line),
+    /// (kind, line)
+    CannotIterate(Kind, Line),
+    ErrorNotFound(String, Line),
+    FieldNotInError(String, Line),
+    CannotSwitch(Kind, Line),
+    /// (missing variants, line)
+    SwitchNotExhaustive(Vec<String>, Line),
+    DuplicateCase(Line),
+    /// (method, trait, line)
+    MethodNotInTrait(String, String, Line),
+    /// (generic, line)
+    CannotInferGeneric(String, Line),
+    /// (kind, trait, generic, line)
+    BoundNotSatisfied(Kind, String, String, Line),
+    /// (struct, operator, right, line)
+    OverloadNotFound(Kind, Operators, Option<Kind>, Line),
+    /// (struct, operator, right, line) | more than one overload accepts the right operand
+    AmbiguousOverload(Kind, Operators, Option<Kind>, Line),
+    CannotMatch(Kind, Line),
+    /// (missing variants, line)
+    MatchNotExhaustive(Vec<String>, Line),
+    /// (pattern, matched kind, line)
+    PatternNotOfKind(String, Kind, Line),
+    /// (variant, expected, got, line)
+    IncorrectNumberOfBindings(String, usize, usize, Line),
+    /// (line) | arm can never be chosen because the arms before it cover every value
+    UnreachableArm(Line),
+    /// (line) | block arm of a match used as a value does not leave the block
+    ArmWithoutValue(Line),
+    /// (item, module, line) | item is used from another module without being `pub`
+    NotPublic(String, String, Line),
+    /// (kind, line) | only primitive values can be embedded in a string
+    CannotInterpolate(Kind, Line),
+    /// (format, kind, line)
+    FormatNotApplicable(String, Kind, Line),
+    /// (position, line) | position or pattern used on a value that is not a tuple
+    NotATuple(Position, Line),
+    /// (tuple, index, line)
+    TupleIndexOutOfBounds(Kind, usize, Line),
+    /// (tuple, names, line) | tuple destructured into a different number of names
+    PatternLength(Kind, usize, Line),
+    /// (reason) | named arguments or default values do not fit the called function
+    InvalidArgs(ArgError),
+    /// (file, error) | error occured in a function of the file
+    InFile(String, Box<CodegenError>),
+}
+
+impl CodegenError {
+    /// remembers the file the error occured in, the innermost one is kept
+    pub fn in_file(self, file: &str) -> Self {
+        match self {
+            CodegenError::InFile(..) => self,
+            err => CodegenError::InFile(file.to_string(), Box::new(err)),
+        }
+    }
+}
+
+impl std::fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodegenError::CannotInitializeConstant => {
+                write!(f, "constant can not be initialized")
+            }
+            CodegenError::FunctionNotFound(path) => {
+                write!(f, "function {} not found in {}", path.ident, path.file)
+            }
+            CodegenError::ExpectedBool(line) => write!(f, "expected bool at {line}"),
+            CodegenError::DerefereString(depth, line) => {
+                write!(f, "string can not be dereferenced {depth} times at {line}")
+            }
+            CodegenError::ReferenceString(depth, line) => {
+                write!(f, "string can not be referenced {depth} times at {line}")
+            }
+            CodegenError::VariableTypeMismatch(expected, got, cmp, line) => {
+                write!(f, "expected {expected:?}, got {got:?} ({cmp:?}) at {line}")
+            }
+            CodegenError::NotInitializedNoType(line) => {
+                write!(f, "variable needs a type or a value at {line}")
+            }
+            CodegenError::TypeNotNullable(line) => {
+                write!(f, "type is not nullable at {line}")
+            }
+            CodegenError::VariableAlreadyDeclared(ident, line, _) => {
+                write!(f, "variable {ident} is already declared at {line}")
+            }
+            CodegenError::VariableNotFound(ident, line) => {
+                write!(f, "variable {ident} not found at {line}")
+            }
+            CodegenError::CannotIndexFile(line) => {
+                write!(f, "can not index into a module at {line}")
+            }
+            CodegenError::CannotIndexFunction(line) => {
+                write!(f, "can not index into a function at {line}")
+            }
+            CodegenError::CanCallOnlyFunctions(line) => {
+                write!(f, "only functions can be called at {line}")
+            }
+            CodegenError::ArgTypeMismatch(arg, got, cmp, line) => {
+                write!(
+                    f,
+                    "argument {} expected {:?}, got {got:?} ({cmp:?}) at {line}",
+                    arg.identifier, arg.kind
+                )
+            }
+            CodegenError::CannotAttachMethodsToFunctions(line) => {
+                write!(f, "methods can not be attached to functions at {line}")
+            }
+            CodegenError::ImportIsNotAValidValue(line) => {
+                write!(f, "module is not a valid value at {line}")
+            }
+            CodegenError::IncorrectArgs(line) => write!(f, "incorrect arguments at {line}"),
+            CodegenError::ExressionNotHandledProperly(line) => {
+                write!(f, "expression is not supported here at {line}")
+            }
+            CodegenError::InvalidOperator(left, right, op, line) => {
+                write!(
+                    f,
+                    "operator `{op}` can not be applied to {left:?} and {right:?} at {line}"
+                )
+            }
+            CodegenError::CouldNotCastTo(to, from, line) => {
+                write!(f, "{from:?} can not be cast to {to:?} at {line}")
+            }
+            CodegenError::ExpectedNumber(line) => write!(f, "expected number at {line}"),
+            CodegenError::UnaryNotApplicable(kind, op, line) => {
+                write!(f, "unary `{op}` can not be applied to {kind:?} at {line}")
+            }
+            CodegenError::CoudNotCastAnArrayToANonArray(kind, line) => {
+                write!(f, "array can not be cast to {kind:?} at {line}")
+            }
+            CodegenError::FunctionDoesNotReturn(line) => {
+                write!(f, "function does not return a value at {line}")
+            }
+            CodegenError::IncorrectNumberOfArgs(expected, got, line) => {
+                write!(f, "expected {expected} arguments, got {got} at {line}")
+            }
+            CodegenError::ImportNotFound(ident, line) => {
+                write!(f, "module {ident} not found at {line}")
+            }
+            CodegenError::KindNotFound(path, line) => {
+                write!(f, "type {} not found in {} at {line}", path.ident, path.file)
+            }
+            CodegenError::CannotRefDerefNumLiteral(line) => {
+                write!(f, "number literal can not be referenced or dereferenced at {line}")
+            }
+            CodegenError::CannotIndexNonArray(pos, line) => {
+                write!(f, "can not index into {pos:?} at {line}")
+            }
+            CodegenError::FieldNotInStruct(ident, line) => {
+                write!(f, "field {ident} not found in struct at {line}")
+            }
+            CodegenError::FieldNotInEnum(ident, line) => {
+                write!(f, "variant {ident} not found in enum at {line}")
+            }
+            CodegenError::CannotDereference(depth, kind, line) => {
+                write!(f, "{kind:?} can not be dereferenced {depth} times at {line}")
+            }
+            CodegenError::CannotReference(depth, kind, line) => {
+                write!(f, "{kind:?} can not be referenced {depth} times at {line}")
+            }
+            CodegenError::CannotGetKind(pos) => write!(f, "type of {pos:?} is not known"),
+            CodegenError::CannotCallType(kind, line) => {
+                write!(f, "{kind:?} can not be called at {line}")
+            }
+            CodegenError::CannotCastNull(kind, line) => {
+                write!(f, "null can not be cast to {kind:?} at {line}")
+            }
+            CodegenError::SwitchWithoutCases(line) => {
+                write!(f, "switch has no cases at {line}")
+            }
+            CodegenError::IncorrectNumberOfGenerics(expected, got, line) => {
+                write!(
+                    f,
+                    "expected {expected} generic arguments, got {got} at {line}"
+                )
+            }
+            CodegenError::UnresolvedInstructionStops(stops) => {
+                write!(f, "{} break or continue statements are outside of a loop", stops.len())
+            }
+            CodegenError::CannotCast(kind, pos, line) => {
+                write!(f, "{pos:?} can not be cast to {kind:?} at {line}")
+            }
+            CodegenError::CannotTestNullable(line) => {
+                write!(f, "nullable value can not be tested at {line}")
+            }
+            CodegenError::CannotTestNonNullable(kind, line) => {
+                write!(f, "{kind:?} can not be null, there is nothing to test at {line}")
+            }
+            CodegenError::CannotIterate(kind, line) => {
+                write!(f, "can not iterate over {kind:?} at {line}")
+            }
+            CodegenError::ErrorNotFound(ident, line) => {
+                write!(f, "error {ident} not found at {line}")
+            }
+            CodegenError::FieldNotInError(ident, line) => {
+                write!(f, "field {ident} not found in error at {line}")
+            }
+            CodegenError::CannotSwitch(kind, line) => {
+                write!(f, "can not switch on {kind:?} at {line}")
+            }
+            CodegenError::SwitchNotExhaustive(missing, line) => {
+                write!(f, "switch does not cover {} at {line}", missing.join(", "))
+            }
+            CodegenError::DuplicateCase(line) => write!(f, "duplicate case at {line}"),
+            CodegenError::MethodNotInTrait(method, traitt, line) => {
+                write!(f, "method {method} is not a member of trait {traitt} at {line}")
+            }
+            CodegenError::CannotInferGeneric(generic, line) => {
+                write!(f, "generic {generic} can not be inferred at {line}")
+            }
+            CodegenError::BoundNotSatisfied(kind, traitt, generic, line) => {
+                write!(
+                    f,
+                    "{kind:?} does not implement trait {traitt} required by generic {generic} at {line}"
+                )
+            }
+            CodegenError::OverloadNotFound(kind, op, Some(right), line) => {
+                write!(f, "{kind:?} has no overload of {op:?} for {right:?} at {line}")
+            }
+            CodegenError::OverloadNotFound(kind, op, None, line) => {
+                write!(f, "{kind:?} has no overload of unary {op:?} at {line}")
+            }
+            CodegenError::AmbiguousOverload(kind, op, Some(right), line) => {
+                write!(
+                    f,
+                    "more than one overload of {op:?} on {kind:?} accepts {right:?} at {line}"
+                )
+            }
+            CodegenError::AmbiguousOverload(kind, op, None, line) => {
+                write!(f, "{kind:?} has more than one overload of unary {op:?} at {line}")
+            }
+            CodegenError::CannotMatch(kind, line) => {
+                write!(f, "can not match on {kind:?}, only enums can be matched at {line}")
+            }
+            CodegenError::MatchNotExhaustive(missing, line) => {
+                write!(f, "match does not cover {} at {line}", missing.join(", "))
+            }
+            CodegenError::PatternNotOfKind(pattern, kind, line) => {
+                write!(f, "pattern {pattern} does not match {kind:?} at {line}")
+            }
+            CodegenError::IncorrectNumberOfBindings(variant, expected, got, line) => {
+                write!(
+                    f,
+                    "variant {variant} carries {expected} values, got {got} bindings at {line}"
+                )
+            }
+            CodegenError::UnreachableArm(line) => write!(f, "unreachable arm at {line}"),
+            CodegenError::ArmWithoutValue(line) => {
+                write!(f, "arm does not produce a value at {line}")
+            }
+            CodegenError::NotPublic(item, module, line) => {
+                write!(f, "{item} is not public in module {module} at {line}")
+            }
+            CodegenError::CannotInterpolate(kind, line) => {
+                write!(f, "{kind:?} can not be embedded in a string at {line}")
+            }
+            CodegenError::FormatNotApplicable(format, kind, line) => {
+                write!(f, "format {format} can not be applied to {kind:?} at {line}")
+            }
+            CodegenError::NotATuple(pos, line) => {
+                write!(f, "{pos:?} is not a tuple at {line}")
+            }
+            CodegenError::TupleIndexOutOfBounds(kind, idx, line) => {
+                write!(f, "{kind:?} has no element {idx} at {line}")
+            }
+            CodegenError::PatternLength(kind, names, line) => {
+                write!(f, "{kind:?} can not be destructured into {names} names at {line}")
+            }
+            CodegenError::InvalidArgs(err) => write!(f, "{err}"),
+            CodegenError::InFile(file, err) => write!(f, "{err} in {file}"),
+        }
+    }
 }
 
 pub fn stringify(
@@ -303,12 +845,38 @@ pub fn stringify(
 }
 
 /// Returns whether the function was successfully generated
+///
+/// Closures get the closure object as their first argument
+/// and copy `captures` out of it before the body runs
 fn gen_fun<'a>(
     objects: &'a mut Context,
     fun: &'a InnerPath,
     context: &'a mut runtime_types::Context,
     is_constructor: bool,
     self_generics: Option<Vec<GenericDecl>>,
+    captures: Option<&[(String, Kind)]>,
+    bindings: Option<&HashMap<String, Kind>>,
+) -> Result<bool, CodegenError> {
+    gen_fun_body(
+        objects,
+        fun,
+        context,
+        is_constructor,
+        self_generics,
+        captures,
+        bindings,
+    )
+    .map_err(|err| err.in_file(&fun.file))
+}
+
+fn gen_fun_body<'a>(
+    objects: &'a mut Context,
+    fun: &'a InnerPath,
+    context: &'a mut runtime_types::Context,
+    is_constructor: bool,
+    self_generics: Option<Vec<GenericDecl>>,
+    captures: Option<&[(String, Kind)]>,
+    bindings: Option<&HashMap<String, Kind>>,
 ) -> Result<bool, CodegenError> {
     let mut generics: HashMap<String, Kind> = HashMap::new();
     let this_fun = fun.get(objects)?;
@@ -329,7 +897,7 @@ fn gen_fun<'a>(
                 ident: constraint.last().unwrap().to_string(),
                 kind: ImportKinds::Rd,
             };
-            for i in 1..constraint.len() - 1 {
+            for i in 0..constraint.len() - 1 {
                 let file = match find_import(objects, &constraint[i], &path.file) {
                     Some((file, _)) => file.to_string(),
                     None => Err(CodegenError::ImportNotFound(
@@ -362,6 +930,12 @@ fn gen_fun<'a>(
             },
         );
     }
+    // specialized functions see their generics as the concrete kinds
+    if let Some(bindings) = bindings {
+        for (ident, kind) in bindings {
+            generics.insert(ident.clone(), kind.clone());
+        }
+    }
     let mut code = Code::new();
     code.add_debug(this_fun.line, fun.file.clone());
     let mut args_scope_len = 0;
@@ -369,6 +943,23 @@ fn gen_fun<'a>(
         variables: HashMap::new(),
     }];
     let this_fun = fun.get(objects)?;
+    let env = match captures {
+        Some(_) => {
+            let pos = create_var_pos(&scopes);
+            scopes[0].variables.insert(
+                "#env".to_string(),
+                Variable {
+                    kind: None,
+                    pos: pos.clone(),
+                    value: None,
+                    line: this_fun.line.clone(),
+                },
+            );
+            args_scope_len += 1;
+            Some(pos)
+        }
+        None => None,
+    };
     let takes_self = this_fun.takes_self;
     if takes_self {
         let structt = objects
@@ -380,11 +971,7 @@ fn gen_fun<'a>(
             .find(|struc| struc.identifier == fun.block.clone().unwrap())
             .clone()
             .unwrap();
-        let struct_kind = Kind::from_struct(
-            structt.identifier.clone(),
-            fun.file.clone(),
-            structt.line.clone(),
-        );
+        let struct_kind = self_kind(structt, &fun.file, &generics);
         let pos = create_var_pos(&scopes);
         scopes[0].variables.insert(
             "self".to_string(),
@@ -411,6 +998,30 @@ fn gen_fun<'a>(
         );
         args_scope_len += 1;
     }
+    if let (Some(captures), Some(env)) = (captures, &env) {
+        scopes.push(ScopeCached {
+            variables: HashMap::new(),
+        });
+        for (idx, (ident, kind)) in captures.iter().enumerate() {
+            let pos = create_var_pos(&scopes);
+            scopes[1].variables.insert(
+                ident.clone(),
+                Variable {
+                    kind: Some(kind.clone()),
+                    pos: pos.clone(),
+                    value: None,
+                    line: this_fun.line.clone(),
+                },
+            );
+            args_scope_len += 1;
+            code.read(env, POINTER_REG);
+            code.extend(&[
+                Instructions::IndexStatic(idx + 1),
+                Instructions::ReadPtr(GENERAL_REG1),
+            ]);
+            code.write(GENERAL_REG1, &pos);
+        }
+    }
     if is_constructor {
         scopes.push(ScopeCached {
             variables: HashMap::new(),
@@ -428,11 +1039,7 @@ fn gen_fun<'a>(
         scopes[1].variables.insert(
             "self".to_string(),
             Variable {
-                kind: Some(Kind::from_struct(
-                    structt.identifier.clone(),
-                    fun.file.clone(),
-                    structt.line.clone(),
-                )),
+                kind: Some(self_kind(structt, &fun.file, &generics)),
                 pos,
                 value: None,
                 line: this_fun.line.clone(),
@@ -441,6 +1048,16 @@ fn gen_fun<'a>(
         args_scope_len += 1;
         code.extend(&[Instructions::AllocateStatic(structt.fields.len() + 1)]);
         code.write(POINTER_REG, &pos);
+        // header of the object identifies its type for dynamic dispatch
+        if let Some(id) = struct_type_id(context, &fun.file, &structt.identifier) {
+            let header = context.memory.stack.data.len();
+            context.memory.stack.data.push(Types::NonPrimitive(id));
+            code.extend(&[
+                Instructions::ReadConst(header, GENERAL_REG1),
+                Instructions::IndexStatic(0),
+                Instructions::WritePtr(GENERAL_REG1),
+            ]);
+        }
     }
     let mut scope_len = {
         let (scope_len, terminator) = get_scope(
@@ -454,6 +1071,7 @@ fn gen_fun<'a>(
         )?;
         let this_fun = fun.get(objects)?;
         if terminator != ScopeTerminator::Return
+            && terminator != ScopeTerminator::Yeet
             && this_fun.return_type.is_some()
             && !is_constructor
         {
@@ -523,6 +1141,42 @@ fn gen_fun<'a>(
     Ok(true)
 }
 
+/// Kind of `self` inside of a method, generics of the struct are filled in
+fn self_kind(structt: &dictionary::Struct, file: &str, generics: &HashMap<String, Kind>) -> Kind {
+    let mut kind = Kind::from_struct(
+        structt.identifier.clone(),
+        file.to_string(),
+        structt.line.clone(),
+    );
+    if let TypeBody::Type {
+        generics: kind_generics,
+        ..
+    } = &mut kind.body
+    {
+        for generic in structt.generics.iter() {
+            if let Some(bound) = generics.get(&generic.identifier) {
+                kind_generics.push(bound.clone());
+            }
+        }
+    }
+    kind
+}
+
+/// Kind of a field with the generics of the struct replaced by the generics of the object
+fn field_kind(structt: &dictionary::Struct, field: &Kind, owner: &Kind) -> Kind {
+    let owner = match &owner.body {
+        TypeBody::Type { generics, .. } if !structt.generics.is_empty() => generics,
+        _ => return field.clone(),
+    };
+    let generics = structt
+        .generics
+        .iter()
+        .zip(owner.iter())
+        .map(|(decl, kind)| (decl.identifier.clone(), kind.clone()))
+        .collect::<HashMap<_, _>>();
+    substitute_generics(field, &generics)
+}
+
 /// evaluates expression at runtime and puts result in reg1
 fn expression(
     objects: &mut Context,
@@ -543,7 +1197,9 @@ fn expression(
         None => None,
     };
     match expr {
-        ValueType::AnonymousFunction(_) => todo!(),
+        ValueType::AnonymousFunction(lambda) => {
+            return_kind = closure(objects, lambda, scopes, code, context, fun, scope_len, &line)?;
+        }
         ValueType::Expression(expr) => {
             let left = match expr.left.as_ref() {
                 Some(left) => left,
@@ -556,19 +1212,13 @@ fn expression(
             let left_kind = expression(
                 objects, left, scopes, code, context, &fun, scope_len, None, line, generics,
             )?;
-            *scope_len += 1;
-            let var = create_var_pos(scopes);
-            let len = scopes.len();
-            scopes[len - 1].variables.insert(
-                scope_len.to_string(),
-                Variable {
-                    kind: Some(left_kind.clone()),
-                    pos: var.clone(),
-                    value: None,
-                    line: expr.line.clone(),
-                },
-            );
+            let var = temp_var(scopes, scope_len, Some(left_kind.clone()), &expr.line);
             code.write(GENERAL_REG1, &var);
+            // overloads of structs pick the kind of the right operand themselves
+            let right_expected = match left_kind.check_type(&dictionary::KindType::Struct) {
+                true => None,
+                false => Some(left_kind.clone()),
+            };
             let right_kind = expression(
                 objects,
                 right,
@@ -577,7 +1227,7 @@ fn expression(
                 context,
                 &fun,
                 scope_len,
-                Some(left_kind.clone()),
+                right_expected,
                 line,
                 generics,
             )?;
@@ -587,7 +1237,7 @@ fn expression(
                 Some(op) => op,
                 None => Err(CodegenError::ExressionNotHandledProperly(expr.line.clone()))?,
             };
-            let kind = match native_operand(
+            return_kind = operand(
                 objects,
                 &op,
                 &left_kind,
@@ -597,13 +1247,7 @@ fn expression(
                 &fun,
                 &expr.line,
                 generics,
-            ) {
-                Some(kind) => kind,
-                None => {
-                    todo!("non native operand");
-                }
-            };
-            return_kind = kind;
+            )?;
         }
         ValueType::Operator(_, line) => unreachable!(
             "operator not handled properly by the compiler at {line}, please report this bug"
@@ -676,7 +1320,7 @@ fn find_var<'a>(scopes: &'a Vec<ScopeCached>, ident: &'a str) -> Option<&'a Vari
     None
 }
 
-fn find_import<'a>(
+pub(crate) fn find_import<'a>(
     objects: &'a Context,
     ident: &'a str,
     file_name: &'a str,
@@ -694,28 +1338,125 @@ fn find_import<'a>(
     None
 }
 
-fn find_fun<'a>(objects: &'a Context, ident: &'a str, file_name: &'a str) -> Option<FunctionKind> {
-    match objects.0.get(file_name) {
-        Some(dictionary) => {
-            for fun in dictionary.functions.iter() {
-                if fun.identifier.clone().unwrap() == ident {
-                    return Some(FunctionKind::Fun(InnerPath {
-                        file: file_name.to_string(),
-                        block: None,
-                        ident: ident.to_string(),
-                        kind: ImportKinds::Rd,
-                    }));
-                }
-            }
-        }
-        None => (),
+/// Whether an item of the module can be used from other modules,
+/// items of binary libraries, imports and errors are always public
+pub(crate) fn is_public(objects: &Context, ident: &str, file_name: &str) -> bool {
+    let dictionary = match objects.0.get(file_name) {
+        Some(dictionary) => dictionary,
+        None => return true,
     };
-    match objects.1.get(file_name) {
-        Some(dictionary) => {
-            for fun in dictionary.functions.iter() {
-                if fun.name.clone() == ident {
-                    return Some(FunctionKind::Binary(InnerPath {
-                        file: file_name.to_string(),
+    if let Some(fun) = dictionary
+        .functions
+        .iter()
+        .find(|fun| fun.identifier.as_deref() == Some(ident))
+    {
+        return fun.public;
+    }
+    if let Some(structt) = dictionary.structs.iter().find(|s| s.identifier == ident) {
+        return structt.public;
+    }
+    if let Some(enumm) = dictionary.enums.iter().find(|e| e.identifier == ident) {
+        return enumm.public;
+    }
+    if let Some(traitt) = dictionary.traits.iter().find(|t| t.identifier == ident) {
+        return traitt.public;
+    }
+    if let Some(typedef) = dictionary.types.iter().find(|t| t.identifier == ident) {
+        return typedef.public;
+    }
+    if let Some(constant) = dictionary.find_const(ident) {
+        return constant.public;
+    }
+    true
+}
+
+/// Whether the module declares an item with the identifier
+pub(crate) fn module_has(objects: &Context, ident: &str, file_name: &str) -> bool {
+    if let Some(dictionary) = objects.0.get(file_name) {
+        return dictionary.identifiers.iter().any(|(name, _)| name == ident);
+    }
+    find_fun(objects, ident, file_name).is_some()
+        || find_userdata(objects, ident, file_name).is_some()
+        || find_benum(objects, ident, file_name).is_some()
+}
+
+/// Module that the path of `use` leads to, the last part of the path is left out
+pub(crate) fn use_module<'a>(
+    objects: &'a Context,
+    usee: &'a dictionary::Use,
+    file_name: &'a str,
+) -> Option<(&'a str, ImportKinds)> {
+    if usee.path.len() < 2 {
+        return None;
+    }
+    let mut module = (file_name, ImportKinds::Rd);
+    for part in usee.path.iter().take(usee.path.len() - 1) {
+        match part {
+            dictionary::PathPart::Identifier(ident) => {
+                module = find_import(objects, ident, module.0)?;
+            }
+            dictionary::PathPart::Asterisk => None?,
+        }
+    }
+    Some(module)
+}
+
+/// Finds an identifier brought into the file by `use`,
+/// returns the module and the identifier of the item inside of it
+///
+/// `use` only affects the file it is written in, so the items are not visible
+/// to modules importing the file
+pub(crate) fn find_use<'a>(
+    objects: &'a Context,
+    ident: &'a str,
+    file_name: &'a str,
+) -> Option<(&'a str, &'a str)> {
+    let dictionary = objects.0.get(file_name)?;
+    for usee in dictionary.uses.iter() {
+        let module = match use_module(objects, usee, file_name) {
+            Some((module, _)) => module,
+            None => continue,
+        };
+        match usee.path.last() {
+            Some(dictionary::PathPart::Identifier(item)) => {
+                if usee.alias.as_deref().unwrap_or(item) == ident {
+                    return Some((module, item));
+                }
+            }
+            // only public items are brought in by `*`
+            Some(dictionary::PathPart::Asterisk) => {
+                if module_has(objects, ident, module) && is_public(objects, ident, module) {
+                    return Some((module, ident));
+                }
+            }
+            None => (),
+        }
+    }
+    None
+}
+
+pub(crate) fn find_fun<'a>(objects: &'a Context, ident: &'a str, file_name: &'a str) -> Option<FunctionKind> {
+    match objects.0.get(file_name) {
+        Some(dictionary) => {
+            for fun in dictionary.functions.iter() {
+                if fun.identifier.clone().unwrap() == ident {
+                    return Some(FunctionKind::Fun(InnerPath {
+                        file: file_name.to_string(),
+                        block: None,
+                        ident: ident.to_string(),
+                        kind: ImportKinds::Rd,
+                    }));
+                }
+            }
+        }
+        None => (),
+    };
+    match objects.1.get(file_name) {
+        Some(dictionary) => {
+            for fun in dictionary.functions.iter() {
+                if fun.name.clone() == ident {
+                    return Some(FunctionKind::Binary(InnerPath {
+                        file: file_name.to_string(),
                         block: None,
                         ident: ident.to_string(),
                         kind: ImportKinds::Dll,
@@ -728,7 +1469,7 @@ fn find_fun<'a>(objects: &'a Context, ident: &'a str, file_name: &'a str) -> Opt
     None
 }
 
-fn find_struct<'a>(
+pub(crate) fn find_struct<'a>(
     objects: &'a Context,
     ident: &'a str,
     file_name: &'a str,
@@ -746,7 +1487,7 @@ fn find_struct<'a>(
     None
 }
 
-fn find_trait<'a>(
+pub(crate) fn find_trait<'a>(
     objects: &'a Context,
     ident: &'a str,
     file_name: &'a str,
@@ -764,6 +1505,122 @@ fn find_trait<'a>(
     None
 }
 
+/// Position of a method called on a trait object, the object has to be in reg1
+fn trait_method(
+    objects: &Context,
+    kind: &Kind,
+    ident: &str,
+    line: &Line,
+) -> Result<Position, CodegenError> {
+    let main = match &kind.body {
+        TypeBody::Type { main, .. } => main,
+        _ => unreachable!("kind not handled properly by the compiler, please report this bug"),
+    };
+    let (file, traitt) = match find_trait(
+        objects,
+        main.last().unwrap(),
+        kind.file.as_deref().unwrap_or_default(),
+    ) {
+        Some(traitt) => traitt,
+        None => Err(CodegenError::KindNotFound(
+            InnerPath {
+                file: kind.file.clone().unwrap_or_default(),
+                block: None,
+                ident: main.last().unwrap().clone(),
+                kind: ImportKinds::Rd,
+            },
+            line.clone(),
+        ))?,
+    };
+    if !traitt
+        .methods
+        .iter()
+        .any(|method| method.identifier.as_deref() == Some(ident))
+    {
+        Err(CodegenError::MethodNotInTrait(
+            ident.to_string(),
+            traitt.identifier.clone(),
+            line.clone(),
+        ))?;
+    }
+    Ok(Position::CompoundField(
+        InnerPath {
+            file: file.to_string(),
+            block: Some(traitt.identifier.clone()),
+            ident: ident.to_string(),
+            kind: ImportKinds::Rd,
+        },
+        CompoundField::TraitMethod(traitt.identifier.clone(), ident.to_string()),
+        kind.clone(),
+    ))
+}
+
+/// Finds a trait written as `Trait` or `module.Trait` in the file
+pub(crate) fn resolve_trait<'a>(
+    objects: &'a Context,
+    ident: &'a dictionary::NestedIdent,
+    file_name: &'a str,
+) -> Option<(&'a str, &'a dictionary::Trait)> {
+    let mut file = file_name;
+    for module in ident.iter().take(ident.len().saturating_sub(1)) {
+        file = find_import(objects, module, file)?.0;
+    }
+    match find_trait(objects, ident.last()?, file) {
+        None if ident.len() == 1 => {
+            let (module, item) = find_use(objects, ident.last()?, file)?;
+            find_trait(objects, item, module)
+        }
+        found => found,
+    }
+}
+
+/// Id of the trait used by `DynMethod`, traits are numbered in the order of files
+fn trait_id(objects: &Context, ident: &str, file_name: &str) -> Option<usize> {
+    let mut files = objects.0.keys().collect::<Vec<_>>();
+    files.sort();
+    let mut id = 0;
+    for file in files {
+        for traitt in objects.0.get(file).unwrap().traits.iter() {
+            if file == file_name && traitt.identifier == ident {
+                return Some(id);
+            }
+            id += 1;
+        }
+    }
+    None
+}
+
+/// Returns whether a value of kind `from` can be used where trait `to` is expected
+pub(crate) fn implements_trait(objects: &Context, from: &Kind, to: &Kind) -> bool {
+    let (traitt, trait_file) = match &to.body {
+        TypeBody::Type {
+            main,
+            kind: dictionary::KindType::Trait,
+            ..
+        } => (main.last().unwrap(), to.file.as_deref().unwrap_or_default()),
+        _ => return false,
+    };
+    let (structt, struct_file) = match &from.body {
+        TypeBody::Type {
+            main,
+            kind: dictionary::KindType::Struct,
+            ..
+        } => (main.last().unwrap(), from.file.as_deref().unwrap_or_default()),
+        _ => return false,
+    };
+    if from.get_refs() != to.get_refs() {
+        return false;
+    }
+    let structt = match find_struct(objects, structt, struct_file) {
+        Some((_, structt)) => structt,
+        None => return false,
+    };
+    structt.traits.iter().any(|ident| {
+        resolve_trait(objects, ident, struct_file)
+            .is_some_and(|(file, found)| file == trait_file && &found.identifier == traitt)
+    })
+}
+
 fn find_userdata<'a>(
     objects: &'a Context,
     ident: &'a str,
@@ -782,7 +1639,80 @@ fn find_userdata<'a>(
     None
 }
 
-fn find_enum<'a>(
+/// returns the id of the error and its declaration
+///
+/// id 0 belongs to the built-in `Error` which is also used for errors raised by the runtime,
+/// declared errors are numbered in order of declaration with files sorted by name
+pub(crate) fn find_error<'a>(
+    objects: &'a Context,
+    ident: &str,
+    file_name: &str,
+) -> Option<(usize, Option<&'a dictionary::Error>)> {
+    let mut files = objects.0.keys().collect::<Vec<_>>();
+    files.sort();
+    let mut id = 1;
+    for file in files {
+        for error in objects.0.get(file).unwrap().errors.iter() {
+            if file == file_name && error.identifier == ident {
+                return Some((id, Some(error)));
+            }
+            id += 1;
+        }
+    }
+    if ident == "Error" {
+        return Some((0, None));
+    }
+    None
+}
+
+/// resolves `Error` or `import.Error` into the file and identifier of the error
+fn error_path(
+    objects: &Context,
+    path: &[String],
+    file_name: &str,
+    line: &Line,
+) -> Result<(String, String), CodegenError> {
+    match path {
+        [ident] => match find_use(objects, ident, file_name) {
+            Some((module, item)) if find_error(objects, ident, file_name).is_none() => {
+                Ok((module.to_string(), item.to_string()))
+            }
+            _ => Ok((file_name.to_string(), ident.clone())),
+        },
+        [import, ident] => match find_import(objects, import, file_name) {
+            Some((file, _)) => Ok((file.to_string(), ident.clone())),
+            None => Err(CodegenError::ImportNotFound(import.clone(), line.clone())),
+        },
+        _ => Err(CodegenError::ErrorNotFound(path.join("."), line.clone())),
+    }
+}
+
+/// returns the index and kind of a field of an error object
+///
+/// error objects are laid out as [id, message, code]
+fn error_field(ident: &str, file: &str, line: &Line) -> Result<(usize, Kind), CodegenError> {
+    let (idx, main) = match ident {
+        "message" => (1, "string"),
+        "code" => (2, "int"),
+        _ => Err(CodegenError::FieldNotInError(ident.to_string(), line.clone()))?,
+    };
+    Ok((
+        idx,
+        Kind {
+            body: TypeBody::Type {
+                refs: 0,
+                main: vec![main.to_string()],
+                generics: vec![],
+                nullable: false,
+                kind: dictionary::KindType::Primitive,
+            },
+            line: line.clone(),
+            file: Some(file.to_string()),
+        },
+    ))
+}
+
+pub(crate) fn find_enum<'a>(
     objects: &'a Context,
     ident: &'a str,
     file_name: &'a str,
@@ -800,6 +1730,25 @@ fn find_enum<'a>(
     None
 }
 
+/// Whether values of the kind are numbers of an enum, enums with data are objects
+pub(crate) fn is_number_enum(objects: &Context, kind: &Kind) -> bool {
+    match &kind.body {
+        TypeBody::Type {
+            main,
+            kind: dictionary::KindType::Enum,
+            ..
+        } => match find_enum(objects, main.last().unwrap(), kind.file.as_ref().unwrap()) {
+            Some((_, enumm)) => !enumm.has_payload(),
+            None => true,
+        },
+        TypeBody::Type {
+            kind: dictionary::KindType::BEnum,
+            ..
+        } => true,
+        _ => false,
+    }
+}
+
 fn find_benum<'a>(
     objects: &'a Context,
     ident: &'a str,
@@ -871,13 +1820,16 @@ fn gen_value(
             let structt = find_struct(objects, &path.ident, kind.file.as_ref().unwrap())
                 .unwrap()
                 .1;
-            let kind = structt
-                .fields
-                .iter()
-                .find(|f| f.0 == field.get_ident())
-                .unwrap()
-                .1
-                .clone();
+            let kind = field_kind(
+                structt,
+                &structt
+                    .fields
+                    .iter()
+                    .find(|f| f.0 == field.get_ident())
+                    .unwrap()
+                    .1,
+                kind,
+            );
             match value.refs {
                 expression_parser::Ref::Dereferencing(depth) => {
                     if depth > kind.get_refs() {
@@ -1175,6 +2127,32 @@ fn identify_root(
                     line.clone(),
                 )));
             }
+            // `use` only applies to the file the code is written in
+            if file == fun.file {
+                if let Some((module, item)) = find_use(objects, &ident, &file) {
+                    if !is_public(objects, item, module) {
+                        Err(CodegenError::NotPublic(
+                            item.to_string(),
+                            module.to_string(),
+                            line.clone(),
+                        ))?;
+                    }
+                    let (module, item) = (module.to_string(), item.to_string());
+                    return identify_root(
+                        objects,
+                        &Root::Identifier(item),
+                        None,
+                        &module,
+                        line,
+                        context,
+                        code,
+                        scope_len,
+                        expected_type,
+                        fun,
+                        generics,
+                    );
+                }
+            }
             Err(CodegenError::VariableNotFound(
                 ident.to_string(),
                 line.clone(),
@@ -1182,6 +2160,21 @@ fn identify_root(
         }
         Root::Literal(lit) => {
             match lit {
+                expression_parser::Literals::Tuple(values) => {
+                    let kind = construct_tuple(
+                        objects,
+                        values,
+                        scopes.unwrap(),
+                        code,
+                        context,
+                        fun,
+                        scope_len,
+                        expected_type,
+                        line,
+                        generics,
+                    )?;
+                    return Ok(Position::Value(kind));
+                }
                 expression_parser::Literals::Number(n) => {
                     let const_num = match n.into_const_number(line.clone()) {
                         Some(num) => num,
@@ -1210,18 +2203,7 @@ fn identify_root(
                         };
                         if let Some(scopes) = scopes {
                             code.extend(&[AllocateStatic(arr.len())]);
-                            *scope_len += 1;
-                            let obj = create_var_pos(scopes);
-                            let scopes_len = scopes.len();
-                            scopes[scopes_len - 1].variables.insert(
-                                scope_len.to_string(),
-                                Variable {
-                                    kind: Some(Kind::void()),
-                                    pos: obj.clone(),
-                                    value: None,
-                                    line: line.clone(),
-                                },
-                            );
+                            let obj = temp_var(scopes, scope_len, Some(Kind::void()), line);
                             code.write(POINTER_REG, &obj);
                             for (idx, value) in arr.iter().enumerate() {
                                 let temp_kind = Some(expression(
@@ -1313,18 +2295,7 @@ fn identify_root(
                                 *line,
                                 generics,
                             )?;
-                            *scope_len += 1;
-                            let value_var = create_var_pos(scopes);
-                            let scopes_len = scopes.len();
-                            scopes[scopes_len - 1].variables.insert(
-                                scope_len.to_string(),
-                                Variable {
-                                    kind: Some(value.clone()),
-                                    pos: value_var.clone(),
-                                    value: None,
-                                    line: line.clone(),
-                                },
-                            );
+                            let value_var = temp_var(scopes, scope_len, Some(value.clone()), line);
                             code.write(GENERAL_REG1, &value_var);
                             expression(
                                 objects,
@@ -1423,6 +2394,35 @@ fn identify_root(
             )?;
             return Ok(Position::Value(kind));
         }
+        Root::Match(matchh) => {
+            let (kind, _) = gen_match(
+                objects,
+                matchh,
+                scopes.unwrap(),
+                code,
+                context,
+                fun,
+                scope_len,
+                expected_type,
+                false,
+                generics,
+            )?;
+            return Ok(Position::Value(kind));
+        }
+        Root::Interpolation(parts) => {
+            let kind = gen_interpolation(
+                objects,
+                parts,
+                scopes.unwrap(),
+                code,
+                context,
+                fun,
+                scope_len,
+                line,
+                generics,
+            )?;
+            return Ok(Position::Value(kind));
+        }
     }
 }
 
@@ -1443,10 +2443,17 @@ fn traverse_tail(
         Some(node) => match &node.0 {
             expression_parser::TailNodes::Nested(ident) => match &pos {
                 Position::Import(fname) => {
+                    if !is_public(objects, ident, fname) {
+                        Err(CodegenError::NotPublic(
+                            ident.to_string(),
+                            fname.to_string(),
+                            node.1.clone(),
+                        ))?;
+                    }
                     let root = identify_root(
                         objects,
                         &Root::Identifier(ident.to_string()),
-                        Some(scopes),
+                        None,
                         &fname,
                         &node.1,
                         context,
@@ -1464,7 +2471,7 @@ fn traverse_tail(
                     let root = identify_root(
                         objects,
                         &Root::Identifier(ident.to_string()),
-                        Some(scopes),
+                        None,
                         &fname,
                         &node.1,
                         context,
@@ -1488,6 +2495,12 @@ fn traverse_tail(
                     };
                     let pos_cloned = var.pos.clone();
                     let kind = var.kind.as_ref().unwrap().clone();
+                    // loop variables keep the written type of the array elements
+                    let kind = if kind.check_type(&dictionary::KindType::None) {
+                        correct_kind(objects, &kind, fun, &node.1, generics)?
+                    } else {
+                        kind
+                    };
 
                     match &kind.body {
                         TypeBody::Array { type_, .. } => {
@@ -1535,7 +2548,7 @@ fn traverse_tail(
                                                     IndexStatic(idx + 1),
                                                     Move(POINTER_REG, GENERAL_REG1),
                                                 ]);
-                                                return_kind = structt.fields[idx].1.clone();
+                                                return_kind = field_kind(structt, &structt.fields[idx].1, &kind);
                                                 *return_kind.refs_mut() += 1;
                                                 let pos = Position::CompoundField(
                                                     InnerPath {
@@ -1657,11 +2670,37 @@ fn traverse_tail(
                                         generics,
                                     );
                                 }
-                                dictionary::KindType::Enum => todo!(),
-                                dictionary::KindType::BEnum => todo!(),
-                                dictionary::KindType::Trait => todo!(),
+                                dictionary::KindType::Enum | dictionary::KindType::BEnum => {
+                                    Err(CodegenError::FieldNotInEnum(
+                                        ident.clone(),
+                                        node.1.clone(),
+                                    ))?
+                                }
+                                dictionary::KindType::Trait => {
+                                    code.read(&pos_cloned, GENERAL_REG1);
+                                    let pos = trait_method(objects, &kind, &ident, &node.1)?;
+                                    return traverse_tail(
+                                        objects, tail, context, scopes, code, fun, pos,
+                                        scope_len, generics,
+                                    );
+                                }
                                 dictionary::KindType::Fun => todo!(),
-                                dictionary::KindType::Error => todo!(),
+                                dictionary::KindType::Error => {
+                                    let (idx, field_kind) = error_field(&ident, &fun.file, &node.1)?;
+                                    code.read(&pos_cloned, POINTER_REG);
+                                    code.extend(&[IndexStatic(idx), Move(POINTER_REG, GENERAL_REG1)]);
+                                    return traverse_tail(
+                                        objects,
+                                        tail,
+                                        context,
+                                        scopes,
+                                        code,
+                                        fun,
+                                        Position::Pointer(field_kind),
+                                        scope_len,
+                                        generics,
+                                    );
+                                }
                                 dictionary::KindType::BinFun => todo!(),
                                 dictionary::KindType::SelfRef => todo!(),
                                 dictionary::KindType::None => todo!(),
@@ -1692,16 +2731,25 @@ fn traverse_tail(
                     match field {
                         // ident is the field from last iteration
                         CompoundField::Field(ident) => {
-                            let field = structt
-                                .unwrap()
-                                .1
-                                .fields
-                                .iter()
-                                .find(|f| &f.0 == ident)
-                                .unwrap();
-                            let kind = field.1.clone();
+                            let structt = structt.unwrap().1;
+                            let field = structt.fields.iter().find(|f| &f.0 == ident).unwrap();
+                            let kind = field_kind(structt, &field.1, kind);
                             let main = match &kind.body {
                                 TypeBody::Type { main, .. } => main,
+                                // methods of an array stored in the field
+                                TypeBody::Array { type_, .. } => {
+                                    let path = find_array_method(objects, &_ident, &node.1)?;
+                                    code.push(ReadPtr(RETURN_REG));
+                                    let pos = Position::Function(
+                                        FunctionKind::Binary(path),
+                                        *type_.clone(),
+                                        kind.clone(),
+                                    );
+                                    return traverse_tail(
+                                        objects, tail, context, scopes, code, fun, pos, scope_len,
+                                        generics,
+                                    );
+                                }
                                 _ => Err(CodegenError::FieldNotInStruct(
                                     ident.clone(),
                                     node.1.clone(),
@@ -1782,7 +2830,18 @@ fn traverse_tail(
                                         ))?,
                                     }
                                 }
-                                None => {}
+                                None => {
+                                    // the field holds a trait object
+                                    let kind = correct_kind(objects, &kind, fun, &node.1, generics)?;
+                                    if kind.check_type(&dictionary::KindType::Trait) {
+                                        code.push(ReadPtr(GENERAL_REG1));
+                                        let pos = trait_method(objects, &kind, _ident, &node.1)?;
+                                        return traverse_tail(
+                                            objects, tail, context, scopes, code, fun, pos,
+                                            scope_len, generics,
+                                        );
+                                    }
+                                }
                             }
                         }
                         CompoundField::Method(method) => {
@@ -1815,8 +2874,9 @@ fn traverse_tail(
                                 generics,
                             );
                         }
-                        CompoundField::OverloadedOperator(_) => todo!(),
-                        CompoundField::TraitMethod(_, _) => todo!(),
+                        CompoundField::TraitMethod(_, _) => {
+                            Err(CodegenError::CannotIndexFunction(node.1.clone()))?
+                        }
                     }
                 }
                 Position::Function(_, _, _) => {
@@ -1824,7 +2884,8 @@ fn traverse_tail(
                 }
                 Position::Pointer(ptr) => {
                     let kind = ptr.clone();
-                    code.extend(&[ReadPtr(GENERAL_REG1)]);
+                    // fields are read from the object the pointer leads to
+                    code.extend(&[ReadPtr(GENERAL_REG1), Move(GENERAL_REG1, POINTER_REG)]);
                     let kind = correct_kind(objects, &kind, fun, &node.1, generics)?;
                     match &kind.body {
                         TypeBody::Array { type_, .. } => {
@@ -1872,7 +2933,7 @@ fn traverse_tail(
                                                     IndexStatic(idx + 1),
                                                     Move(POINTER_REG, GENERAL_REG1),
                                                 ]);
-                                                return_kind = structt.fields[idx].1.clone();
+                                                return_kind = field_kind(structt, &structt.fields[idx].1, &kind);
                                                 *return_kind.refs_mut() += 1;
                                                 let pos = traverse_tail(
                                                     objects,
@@ -2000,11 +3061,36 @@ fn traverse_tail(
                                         generics,
                                     );
                                 }
-                                dictionary::KindType::Enum => todo!(),
-                                dictionary::KindType::BEnum => todo!(),
-                                dictionary::KindType::Trait => todo!(),
+                                dictionary::KindType::Enum | dictionary::KindType::BEnum => {
+                                    Err(CodegenError::FieldNotInEnum(
+                                        ident.clone(),
+                                        node.1.clone(),
+                                    ))?
+                                }
+                                dictionary::KindType::Trait => {
+                                    let pos = trait_method(objects, &kind, &ident, &node.1)?;
+                                    return traverse_tail(
+                                        objects, tail, context, scopes, code, fun, pos,
+                                        scope_len, generics,
+                                    );
+                                }
                                 dictionary::KindType::Fun => todo!(),
-                                dictionary::KindType::Error => todo!(),
+                                dictionary::KindType::Error => {
+                                    let (idx, field_kind) = error_field(&ident, &fun.file, &node.1)?;
+                                    code.push(Move(GENERAL_REG1, POINTER_REG));
+                                    code.extend(&[IndexStatic(idx), Move(POINTER_REG, GENERAL_REG1)]);
+                                    return traverse_tail(
+                                        objects,
+                                        tail,
+                                        context,
+                                        scopes,
+                                        code,
+                                        fun,
+                                        Position::Pointer(field_kind),
+                                        scope_len,
+                                        generics,
+                                    );
+                                }
                                 dictionary::KindType::BinFun => todo!(),
                                 dictionary::KindType::SelfRef => todo!(),
                                 dictionary::KindType::None => todo!(),
@@ -2039,20 +3125,57 @@ fn traverse_tail(
                         &kind_main.first().unwrap(),
                         &_kind.file.as_ref().unwrap(),
                     ) {
-                        let (ident, key_code, line) =
-                            match enumm.keys.iter().find(|field| &field.0 == ident) {
-                                Some(field) => field,
-                                None => Err(CodegenError::FieldNotInEnum(
-                                    ident.clone(),
-                                    node.1.clone(),
-                                ))?,
-                            };
-                        let pos = new_const(context, &ConstValue::Uint(*key_code))?;
-                        code.push(ReadConst(pos, GENERAL_REG1));
-                        return Ok(Position::Value(Kind::from_enum(
-                            enumm,
-                            _kind.file.as_ref().unwrap().to_string(),
-                        )));
+                        let idx = match enumm.keys.iter().position(|field| &field.0 == ident) {
+                            Some(idx) => idx,
+                            None => Err(CodegenError::FieldNotInEnum(
+                                ident.clone(),
+                                node.1.clone(),
+                            ))?,
+                        };
+                        let enum_file = _kind.file.as_ref().unwrap().to_string();
+                        let kind = Kind::from_enum(enumm, enum_file.clone());
+                        if !enumm.has_payload() {
+                            let pos = new_const(context, &ConstValue::Uint(enumm.keys[idx].1))?;
+                            code.push(ReadConst(pos, GENERAL_REG1));
+                            return Ok(Position::Value(kind));
+                        }
+                        let fields = enumm.fields[idx].clone();
+                        let type_ident = variant_ident(&enumm.identifier, ident);
+                        // variants without data can be written with or without parentheses
+                        let args = match tail.clone().next() {
+                            Some((TailNodes::Call(call), line)) => {
+                                tail.next();
+                                call.resolve(&fields, line)
+                                    .map_err(CodegenError::InvalidArgs)?
+                                    .args
+                            }
+                            _ => Vec::new(),
+                        };
+                        construct_variant(
+                            objects,
+                            &enum_file,
+                            &type_ident,
+                            &fields,
+                            &args,
+                            scopes,
+                            code,
+                            context,
+                            fun,
+                            scope_len,
+                            &node.1,
+                            generics,
+                        )?;
+                        return traverse_tail(
+                            objects,
+                            tail,
+                            context,
+                            scopes,
+                            code,
+                            fun,
+                            Position::Value(kind),
+                            scope_len,
+                            generics,
+                        );
                     }
                     if let Some((_, enumm)) = find_benum(
                         objects,
@@ -2160,7 +3283,7 @@ fn traverse_tail(
                                                     IndexStatic(idx + 1),
                                                     Move(POINTER_REG, GENERAL_REG1),
                                                 ]);
-                                                return_kind = structt.fields[idx].1.clone();
+                                                return_kind = field_kind(structt, &structt.fields[idx].1, &kind);
                                                 *return_kind.refs_mut() += 1;
                                                 let pos = traverse_tail(
                                                     objects,
@@ -2288,11 +3411,36 @@ fn traverse_tail(
                                         generics,
                                     );
                                 }
-                                dictionary::KindType::Enum => todo!(),
-                                dictionary::KindType::BEnum => todo!(),
-                                dictionary::KindType::Trait => todo!(),
+                                dictionary::KindType::Enum | dictionary::KindType::BEnum => {
+                                    Err(CodegenError::FieldNotInEnum(
+                                        ident.clone(),
+                                        node.1.clone(),
+                                    ))?
+                                }
+                                dictionary::KindType::Trait => {
+                                    let pos = trait_method(objects, &kind, &ident, &node.1)?;
+                                    return traverse_tail(
+                                        objects, tail, context, scopes, code, fun, pos,
+                                        scope_len, generics,
+                                    );
+                                }
                                 dictionary::KindType::Fun => todo!(),
-                                dictionary::KindType::Error => todo!(),
+                                dictionary::KindType::Error => {
+                                    let (idx, field_kind) = error_field(&ident, &fun.file, &node.1)?;
+                                    code.push(Move(GENERAL_REG1, POINTER_REG));
+                                    code.extend(&[IndexStatic(idx), Move(POINTER_REG, GENERAL_REG1)]);
+                                    return traverse_tail(
+                                        objects,
+                                        tail,
+                                        context,
+                                        scopes,
+                                        code,
+                                        fun,
+                                        Position::Pointer(field_kind),
+                                        scope_len,
+                                        generics,
+                                    );
+                                }
                                 dictionary::KindType::BinFun => todo!(),
                                 dictionary::KindType::SelfRef => todo!(),
                                 dictionary::KindType::None => todo!(),
@@ -2307,7 +3455,7 @@ fn traverse_tail(
                 Position::Function(_, _, _) => {
                     Err(CodegenError::CannotIndexFunction(node.1.clone()))?
                 }
-                Position::CompoundField(path, field, _) => {
+                Position::CompoundField(path, field, owner) => {
                     let ident = if let CompoundField::Field(ident) = field {
                         ident
                     } else {
@@ -2326,7 +3474,7 @@ fn traverse_tail(
                                         node.1.clone(),
                                     ))?,
                                 };
-                            field.1.clone()
+                            field_kind(structt.1, &field.1, owner)
                         } else {
                             Err(CodegenError::FieldNotInStruct(
                                 ident.clone(),
@@ -2346,18 +3494,7 @@ fn traverse_tail(
                     };
 
                     // save the pointer from GENERAL_REG1 to stack
-                    *scope_len += 1;
-                    let obj = create_var_pos(scopes);
-                    let scopes_len = scopes.len();
-                    scopes[scopes_len - 1].variables.insert(
-                        scope_len.to_string(),
-                        Variable {
-                            kind: Some(return_kind.clone()),
-                            pos: obj.clone(),
-                            value: None,
-                            line: node.1.clone(),
-                        },
-                    );
+                    let obj = temp_var(scopes, scope_len, Some(return_kind.clone()), &node.1);
                     code.write(GENERAL_REG1, &obj);
 
                     expression(
@@ -2451,18 +3588,7 @@ fn traverse_tail(
                     code.push(ReadPtr(GENERAL_REG1));
 
                     // save the pointer from GENERAL_REG1 to stack
-                    *scope_len += 1;
-                    let obj = create_var_pos(scopes);
-                    let scopes_len = scopes.len();
-                    scopes[scopes_len - 1].variables.insert(
-                        scope_len.to_string(),
-                        Variable {
-                            kind: Some(return_kind.clone()),
-                            pos: obj.clone(),
-                            value: None,
-                            line: node.1.clone(),
-                        },
-                    );
+                    let obj = temp_var(scopes, scope_len, Some(return_kind.clone()), &node.1);
                     code.write(GENERAL_REG1, &obj);
 
                     expression(
@@ -2510,18 +3636,7 @@ fn traverse_tail(
                     };
 
                     // save the pointer from GENERAL_REG1 to stack
-                    *scope_len += 1;
-                    let obj = create_var_pos(scopes);
-                    let scopes_len = scopes.len();
-                    scopes[scopes_len - 1].variables.insert(
-                        scope_len.to_string(),
-                        Variable {
-                            kind: Some(return_kind.clone()),
-                            pos: obj.clone(),
-                            value: None,
-                            line: node.1.clone(),
-                        },
-                    );
+                    let obj = temp_var(scopes, scope_len, Some(return_kind.clone()), &node.1);
                     code.write(GENERAL_REG1, &obj);
 
                     expression(
@@ -2570,6 +3685,8 @@ fn traverse_tail(
                                 call_params,
                                 &node.1,
                                 fun,
+                                this,
+                                generics,
                             )?;
                             fun_kind.file = Some(fun_path.file.clone());
                             let pos = Position::Value(fun_kind);
@@ -2596,9 +3713,60 @@ fn traverse_tail(
                                 objects, tail, context, scopes, code, fun, pos, scope_len, generics,
                             );
                         }
-                        FunctionKind::Dynamic(fun) => todo!("dynamic function call"),
+                        FunctionKind::Dynamic(fun_kind) => {
+                            let pos = Position::Value(call_dynamic(
+                                objects,
+                                fun_kind,
+                                context,
+                                scopes,
+                                code,
+                                scope_len,
+                                call_params,
+                                &node.1,
+                                fun,
+                                generics,
+                            )?);
+                            return traverse_tail(
+                                objects, tail, context, scopes, code, fun, pos, scope_len, generics,
+                            );
+                        }
                     };
                 }
+                // closures stored in variables, arrays or returned from calls
+                Position::Variable(_, kind) | Position::Value(kind) | Position::Pointer(kind)
+                    if kind.is_fun() =>
+                {
+                    match &pos {
+                        Position::Variable(var, _) => {
+                            let var = match find_var(scopes, var) {
+                                Some(var) => var,
+                                None => Err(CodegenError::VariableNotFound(
+                                    var.clone(),
+                                    node.1.clone(),
+                                ))?,
+                            };
+                            let var_pos = var.pos.clone();
+                            code.read(&var_pos, GENERAL_REG1);
+                        }
+                        Position::Pointer(_) => code.push(ReadPtr(GENERAL_REG1)),
+                        _ => (),
+                    }
+                    let pos = Position::Value(call_dynamic(
+                        objects,
+                        kind,
+                        context,
+                        scopes,
+                        code,
+                        scope_len,
+                        call_params,
+                        &node.1,
+                        fun,
+                        generics,
+                    )?);
+                    return traverse_tail(
+                        objects, tail, context, scopes, code, fun, pos, scope_len, generics,
+                    );
+                }
                 Position::Compound(kind) => {
                     let (constructor, kind_kind) = match &kind.body {
                         TypeBody::Type {
@@ -2631,6 +3799,7 @@ fn traverse_tail(
                         &node.1,
                         fun,
                         &Kind::void(),
+                        generics,
                     )?;
                     kind.file = Some(path.file.clone());
                     let pos = Position::Value(kind);
@@ -2638,9 +3807,56 @@ fn traverse_tail(
                         objects, tail, context, scopes, code, fun, pos, scope_len, generics,
                     );
                 }
-                Position::CompoundField(path, field, _) => {
+                Position::CompoundField(path, field, owner) => {
                     match field {
                         CompoundField::Method(ident) => ident,
+                        // closure stored in a field, pointer to the field is in reg1
+                        CompoundField::Field(ident) => {
+                            let kind = match find_struct(objects, &path.ident, &path.file)
+                                .and_then(|(_, structt)| {
+                                    let field =
+                                        structt.fields.iter().find(|field| &field.0 == ident)?;
+                                    Some(field_kind(structt, &field.1, owner))
+                                }) {
+                                Some(kind) if kind.is_fun() => kind,
+                                _ => Err(CodegenError::CanCallOnlyFunctions(node.1.clone()))?,
+                            };
+                            code.push(ReadPtr(GENERAL_REG1));
+                            let pos = Position::Value(call_dynamic(
+                                objects,
+                                &kind,
+                                context,
+                                scopes,
+                                code,
+                                scope_len,
+                                call_params,
+                                &node.1,
+                                fun,
+                                generics,
+                            )?);
+                            return traverse_tail(
+                                objects, tail, context, scopes, code, fun, pos, scope_len,
+                                generics,
+                            );
+                        }
+                        CompoundField::TraitMethod(..) => {
+                            let pos = Position::Value(call_trait_method(
+                                objects,
+                                path,
+                                context,
+                                scopes,
+                                code,
+                                scope_len,
+                                call_params,
+                                &node.1,
+                                fun,
+                                generics,
+                            )?);
+                            return traverse_tail(
+                                objects, tail, context, scopes, code, fun, pos, scope_len,
+                                generics,
+                            );
+                        }
                         _ => todo!("trying to call a non-method field: {:?}", field),
                     };
                     code.push(Move(GENERAL_REG1, RETURN_REG));
@@ -2654,7 +3870,8 @@ fn traverse_tail(
                         call_params,
                         &node.1,
                         fun,
-                        &Kind::void(),
+                        owner,
+                        generics,
                     )?;
                     kind.file = Some(path.file.clone());
                     let pos = Position::Value(kind);
@@ -2664,7 +3881,76 @@ fn traverse_tail(
                 }
                 _ => Err(CodegenError::CanCallOnlyFunctions(node.1.clone()))?,
             },
+            expression_parser::TailNodes::TupleIndex(idx) => {
+                let tuple = match &pos {
+                    Position::Variable(var, _) => {
+                        let var = match find_var(scopes, &var) {
+                            Some(var) => var,
+                            None => {
+                                Err(CodegenError::VariableNotFound(var.clone(), node.1.clone()))?
+                            }
+                        };
+                        code.read(&var.pos, GENERAL_REG1);
+                        var.kind.clone().unwrap()
+                    }
+                    Position::Value(kind) => kind.clone(),
+                    Position::Pointer(kind) => {
+                        code.push(ReadPtr(GENERAL_REG1));
+                        kind.clone()
+                    }
+                    Position::CompoundField(path, CompoundField::Field(ident), owner) => {
+                        let structt = match find_struct(objects, &path.ident, &path.file) {
+                            Some(structt) => structt.1,
+                            None => Err(CodegenError::FieldNotInStruct(
+                                ident.clone(),
+                                node.1.clone(),
+                            ))?,
+                        };
+                        let field = match structt.fields.iter().find(|field| &field.0 == ident) {
+                            Some(field) => field,
+                            None => Err(CodegenError::FieldNotInStruct(
+                                ident.clone(),
+                                node.1.clone(),
+                            ))?,
+                        };
+                        let kind = field_kind(structt, &field.1, owner);
+                        code.push(ReadPtr(GENERAL_REG1));
+                        kind
+                    }
+                    _ => Err(CodegenError::NotATuple(pos.clone(), node.1.clone()))?,
+                };
+                let tuple = correct_kind(objects, &tuple, fun, &node.1, generics)?;
+                let element = match &tuple.body {
+                    TypeBody::Tuple { types, .. } => match types.get(*idx) {
+                        Some(element) => element.clone(),
+                        None => Err(CodegenError::TupleIndexOutOfBounds(
+                            tuple.clone(),
+                            *idx,
+                            node.1.clone(),
+                        ))?,
+                    },
+                    _ => Err(CodegenError::NotATuple(pos.clone(), node.1.clone()))?,
+                };
+                code.extend(&[
+                    Move(GENERAL_REG1, POINTER_REG),
+                    IndexStatic(*idx),
+                    Move(POINTER_REG, GENERAL_REG1),
+                ]);
+                return traverse_tail(
+                    objects,
+                    tail,
+                    context,
+                    scopes,
+                    code,
+                    fun,
+                    Position::Pointer(element),
+                    scope_len,
+                    generics,
+                );
+            }
             expression_parser::TailNodes::Cast(_) => (), // this will be handled later
+            // uncaught errors are always forwarded to the caller
+            TailNodes::Propagate => (),
             TailNodes::Nullable => {
                 let kind = match &pos {
                     Position::Value(kind) => kind,
@@ -2836,6 +4122,10 @@ fn call_binary(
     // setup arguments (stack is not needed)
     let mut args = Vec::new();
     let called_fun = fun.get_bin(objects)?;
+    let params = called_fun.args.iter().map(Arg::from_barg).collect::<Vec<_>>();
+    let call_params = &call_params
+        .resolve(&params, line)
+        .map_err(CodegenError::InvalidArgs)?;
     let args_len = called_fun.args.len();
     let takes_self = called_fun.takes_self;
     temp_code.extend(&[OpenArgs, Freeze]);
@@ -2916,14 +4206,8 @@ fn call_binary(
         }
         let cmp = fun.get_bin(objects)?.args[idx].1.cmp(&arg, &generics_map);
         if cmp.is_not_equal() {
-            let expected = fun.get_bin(objects)?.args[idx].1.clone();
-            let ident = fun.get_bin(objects)?.args[idx].0.clone();
             return Err(CodegenError::ArgTypeMismatch(
-                Arg {
-                    identifier: ident,
-                    kind: expected,
-                    line: fun.get_bin(objects)?.args[idx].3.clone(),
-                },
+                Arg::from_barg(&fun.get_bin(objects)?.args[idx]),
                 arg.clone(),
                 cmp,
                 line.clone(),
@@ -2962,26 +4246,56 @@ fn call_fun(
     call_params: &FunctionCall,
     line: &Line,
     _this: &InnerPath,
+    this_kind: &Kind,
+    generics: &HashMap<String, Kind>,
 ) -> Result<Kind, CodegenError> {
     use Instructions::*;
-    let mut generics_map: HashMap<String, Kind> = HashMap::new();
-    let called_fun = fun.get(objects)?;
-    if call_params.generic.len() > called_fun.generics.len() {
+    let decls = generic_decls(objects, fun)?;
+    let names = decls
+        .iter()
+        .map(|decl| decl.identifier.clone())
+        .collect::<Vec<_>>();
+    let is_generic = !names.is_empty();
+    let mut bindings: HashMap<String, Kind> = HashMap::new();
+    let is_constructor = fun.block.as_ref() == Some(&fun.ident);
+    // generics written after the name of a struct belong to the struct
+    let explicit = match is_constructor {
+        true => decls.clone(),
+        false => fun.get(objects)?.generics.clone(),
+    };
+    if call_params.generic.len() > explicit.len() {
         Err(CodegenError::IncorrectNumberOfGenerics(
-            called_fun.generics.len(),
+            explicit.len(),
             call_params.generic.len(),
             line.clone(),
         ))?;
     }
-    for (idx, generic) in call_params.generic.iter().enumerate() {
-        generics_map.insert(
-            called_fun.generics[idx].identifier.to_string(),
-            generic.clone(),
-        );
+    for (decl, generic) in explicit.iter().zip(call_params.generic.iter()) {
+        let kind = correct_kind(objects, generic, _this, line, generics)?;
+        bindings.insert(decl.identifier.clone(), kind);
+    }
+    // methods of a generic struct use the generics of the object they are called on
+    if let (Some(block), TypeBody::Type { generics: owner, .. }) = (&fun.block, &this_kind.body) {
+        if let Some((_, structt)) = find_struct(objects, block, &fun.file) {
+            for (decl, kind) in structt.generics.iter().zip(owner.iter()) {
+                if !matches!(kind.body, TypeBody::Generic { .. }) {
+                    bindings
+                        .entry(decl.identifier.clone())
+                        .or_insert_with(|| kind.clone());
+                }
+            }
+        }
     }
+    let called_fun = fun.get(objects)?;
+    let call_params = &call_params
+        .resolve(&called_fun.args, line)
+        .map_err(CodegenError::InvalidArgs)?;
     let mut temp_code = Code::new();
     let takes_self = called_fun.takes_self;
-    temp_code.extend(&[OpenArgs, Freeze]);
+    let inline = called_fun.inline == dictionary::InlineHint::Always && !called_fun.inlining;
+    if !inline {
+        temp_code.extend(&[OpenArgs, Freeze]);
+    }
     // setup args
     if called_fun.args.len() != call_params.args.len() {
         Err(CodegenError::IncorrectNumberOfArgs(
@@ -2994,75 +4308,69 @@ fn call_fun(
         temp_code.push(WriteArg(0, RETURN_REG));
     }
     let mut args = Vec::new();
+    let mut arg_positions = Vec::new();
     for (idx, arg) in call_params
         .args
         .iter()
         .zip(called_fun.args.clone())
         .enumerate()
     {
-        let kind = match arg.1.kind.body {
-            TypeBody::Generic {
-                identifier,
-                constraints,
-                ..
-            } => {
-                if let Some(kind) = generics_map.get(&identifier) {
-                    expression(
-                        objects,
-                        arg.0,
-                        scopes,
-                        &mut temp_code,
-                        context,
-                        &_this,
-                        scope_len,
-                        Some(kind.clone()),
-                        *line,
-                        &generics_map,
-                    )?;
-                    kind.clone()
-                } else {
-                    let kind = expression(
-                        objects,
-                        arg.0,
-                        scopes,
-                        &mut temp_code,
-                        context,
-                        &_this,
-                        scope_len,
-                        None,
-                        *line,
-                        &generics_map,
-                    )?;
-                    generics_map.insert(identifier, kind.clone());
-                    kind
-                }
-            }
-            _ => expression(
-                objects,
-                arg.0,
-                scopes,
-                &mut temp_code,
-                context,
-                &_this,
-                scope_len,
-                Some(arg.1.clone().kind),
-                *line,
-                &generics_map,
-            )?,
+        // generics that are not known yet are inferred from the argument
+        let expected = if !is_generic {
+            Some(arg.1.kind.clone())
+        } else if has_unbound_generics(&arg.1.kind, &names, &bindings) {
+            None
+        } else {
+            Some(correct_kind(objects, &arg.1.kind, fun, line, &bindings)?)
+        };
+        let kind = expression(
+            objects,
+            arg.0,
+            scopes,
+            &mut temp_code,
+            context,
+            &_this,
+            scope_len,
+            expected,
+            *line,
+            generics,
+        )?;
+        let kind = if is_generic {
+            let kind = correct_kind(objects, &kind, _this, line, &HashMap::new()).unwrap_or(kind);
+            infer_generics(&arg.1.kind, &kind, &names, &mut bindings);
+            kind
+        } else {
+            kind
         };
         args.push(kind);
-        temp_code.push(WriteArg(idx + takes_self as usize, GENERAL_REG1));
+        if inline {
+            // inlined arguments live on the stack of the caller
+            let pos = temp_var(scopes, scope_len, None, line);
+            temp_code.write(GENERAL_REG1, &pos);
+            arg_positions.push(pos);
+        } else {
+            temp_code.push(WriteArg(idx + takes_self as usize, GENERAL_REG1));
+        }
     }
+    // generic functions are called through a copy made for the concrete kinds
+    let fun = &match is_generic {
+        true => specialize(objects, fun, context, &bindings, line)?,
+        false => fun.clone(),
+    };
     // type check
-    let called_fun = fun.get(objects)?;
+    let called_fun = fun.get(objects)?.clone();
     for (idx, arg) in args.iter().enumerate() {
         if idx >= called_fun.args.len() {
             Err(CodegenError::IncorrectArgs(line.clone()))?;
         }
-        let cmp = called_fun.args[idx].kind.cmp(&arg, &generics_map);
+        let mut expected = called_fun.args[idx].clone();
+        if is_generic {
+            expected.kind = correct_kind(objects, &expected.kind, fun, line, &bindings)?;
+        }
+        let cmp = expected.kind.cmp(&arg, &bindings);
         if cmp.is_not_equal() {
             return Err(CodegenError::ArgTypeMismatch(
-                called_fun.args[idx].clone(),
+                expected,
                 arg.clone(),
                 cmp,
                 line.clone(),
@@ -3070,26 +4378,38 @@ fn call_fun(
         }
     }
     // call
-    temp_code.extend(&[
-        // The function may not be generated yet, so we need to jump to its id
-        // which will be replaced with the actual location later
-        Jump(called_fun.id),
-        Unfreeze,
-        Move(RETURN_REG, GENERAL_REG1),
-    ]);
+    if inline {
+        inline_fun(
+            objects,
+            fun,
+            context,
+            scopes,
+            &mut temp_code,
+            scope_len,
+            &arg_positions,
+            line,
+            _this,
+        )?;
+    } else {
+        temp_code.extend(&[
+            // The function may not be generated yet, so we need to jump to its id
+            // which will be replaced with the actual location later
+            Jump(called_fun.id),
+            Unfreeze,
+            Move(RETURN_REG, GENERAL_REG1),
+        ]);
+    }
     merge_code(code, &temp_code, *scope_len);
-    let return_kind = match called_fun.return_type.clone() {
-        Some(kind) => {
-            if let TypeBody::Generic { identifier, .. } = &kind.body {
-                if let Some(kind) = generics_map.get(identifier) {
-                    kind.clone()
-                } else {
-                    kind
-                }
-            } else {
-                kind
-            }
+    // constructors of generic structs return the struct with the concrete generics
+    if is_generic && is_constructor {
+        let block = fun.block.as_ref().unwrap();
+        if let Some((file, structt)) = find_struct(objects, block, &fun.file) {
+            return Ok(self_kind(structt, file, &bindings));
         }
+    }
+    let return_kind = match called_fun.return_type.clone() {
+        Some(kind) if is_generic => correct_kind(objects, &kind, fun, line, &bindings)?,
+        Some(kind) => kind,
         None => Kind {
             body: TypeBody::Type {
                 refs: 0,
@@ -3105,7 +4425,1317 @@ fn call_fun(
     Ok(return_kind)
 }
 
-fn call_whichever(
+/// Generics of a function followed by the generics of the struct it belongs to
+fn generic_decls(objects: &Context, fun: &InnerPath) -> Result<Vec<GenericDecl>, CodegenError> {
+    let mut decls = Vec::new();
+    if let Some(block) = &fun.block {
+        if let Some((_, structt)) = find_struct(objects, block, &fun.file) {
+            decls.extend(structt.generics.iter().cloned());
+        }
+    }
+    decls.extend(fun.get(objects)?.generics.iter().cloned());
+    Ok(decls)
+}
+
+/// Checks that the kind bound to a generic implements all traits the generic requires
+fn check_bounds(
+    objects: &Context,
+    decl: &GenericDecl,
+    kind: &Kind,
+    file: &str,
+    line: &Line,
+) -> Result<(), CodegenError> {
+    for constraint in decl.traits.iter() {
+        let (trait_file, traitt) = match resolve_trait(objects, constraint, file) {
+            Some(traitt) => traitt,
+            None => Err(CodegenError::KindNotFound(
+                InnerPath {
+                    file: file.to_string(),
+                    block: None,
+                    ident: constraint.last().unwrap().to_string(),
+                    kind: ImportKinds::Rd,
+                },
+                decl.line.clone(),
+            ))?,
+        };
+        let trait_kind = Kind::from_trait(traitt, trait_file.to_string());
+        if !implements_trait(objects, kind, &trait_kind) {
+            Err(CodegenError::BoundNotSatisfied(
+                kind.clone(),
+                traitt.identifier.clone(),
+                decl.identifier.clone(),
+                line.clone(),
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns a copy of the generic function made for the concrete kinds of its generics
+///
+/// Each combination of kinds is generated only once,
+/// the copy gets its own id so calls to it are resolved like any other call
+fn specialize(
+    objects: &mut Context,
+    fun: &InnerPath,
+    context: &mut runtime_types::Context,
+    bindings: &HashMap<String, Kind>,
+    line: &Line,
+) -> Result<InnerPath, CodegenError> {
+    let decls = generic_decls(objects, fun)?;
+    let mut kinds = Vec::new();
+    for decl in decls.iter() {
+        let kind = match bindings.get(&decl.identifier) {
+            Some(kind) => kind,
+            None => Err(CodegenError::CannotInferGeneric(
+                decl.identifier.clone(),
+                line.clone(),
+            ))?,
+        };
+        check_bounds(objects, decl, kind, &fun.file, line)?;
+        kinds.push(format!("{:?}", kind));
+    }
+    let mut path = fun.clone();
+    path.ident = format!("{}<{}>", fun.ident, kinds.join(", "));
+    if path.get(objects).is_ok() {
+        return Ok(path);
+    }
+    let is_constructor = fun.block.as_ref() == Some(&fun.ident);
+    let mut specialized = fun.get(objects)?.clone();
+    specialized.identifier = Some(path.ident.clone());
+    specialized.id = next_fun_id(objects);
+    specialized.inline = dictionary::InlineHint::Never;
+    let file = objects.0.get_mut(&fun.file).unwrap();
+    let self_generics = match &fun.block {
+        Some(block) => {
+            let structt = file
+                .structs
+                .iter_mut()
+                .find(|structt| &structt.identifier == block)
+                .unwrap();
+            structt.functions.push(specialized);
+            Some(structt.generics.clone())
+        }
+        None => {
+            file.functions.push(specialized);
+            None
+        }
+    };
+    gen_fun(
+        objects,
+        &path,
+        context,
+        is_constructor,
+        self_generics,
+        None,
+        Some(bindings),
+    )?;
+    Ok(path)
+}
+
+/// Id that is not used by any function yet
+fn next_fun_id(objects: &Context) -> usize {
+    objects
+        .0
+        .values()
+        .flat_map(|dictionary| {
+            dictionary
+                .functions
+                .iter()
+                .chain(dictionary.structs.iter().flat_map(|structt| structt.functions.iter()))
+        })
+        .map(|fun| fun.id + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Calls the closure in reg1
+///
+/// The closure object is passed as the first argument,
+/// so the called function can read its captured variables
+fn call_dynamic(
+    objects: &mut Context,
+    fun_kind: &Kind,
+    context: &mut runtime_types::Context,
+    scopes: &mut Vec<ScopeCached>,
+    code: &mut Code,
+    scope_len: &mut usize,
+    call_params: &FunctionCall,
+    line: &Line,
+    this: &InnerPath,
+    generics: &HashMap<String, Kind>,
+) -> Result<Kind, CodegenError> {
+    use Instructions::*;
+    let (args, return_type) = match &fun_kind.body {
+        TypeBody::Function {
+            args, return_type, ..
+        } => (args.clone(), (**return_type).clone()),
+        _ => Err(CodegenError::CanCallOnlyFunctions(line.clone()))?,
+    };
+    let call_params = &call_params
+        .resolve(&args, line)
+        .map_err(CodegenError::InvalidArgs)?;
+    if args.len() != call_params.args.len() {
+        Err(CodegenError::IncorrectNumberOfArgs(
+            args.len(),
+            call_params.args.len(),
+            line.clone(),
+        ))?;
+    }
+    let mut temp_code = Code::new();
+    // the closure has to survive evaluation of the arguments
+    let closure = temp_var(scopes, scope_len, None, line);
+    temp_code.write(GENERAL_REG1, &closure);
+    temp_code.extend(&[OpenArgs, Freeze]);
+    temp_code.read(&closure, GENERAL_REG1);
+    temp_code.push(WriteArg(0, GENERAL_REG1));
+    for (idx, (arg, decl)) in call_params.args.iter().zip(args.iter()).enumerate() {
+        let expected = correct_kind(objects, &decl.kind, this, line, generics)?;
+        let kind = expression(
+            objects,
+            arg,
+            scopes,
+            &mut temp_code,
+            context,
+            this,
+            scope_len,
+            Some(expected.clone()),
+            *line,
+            generics,
+        )?;
+        let cmp = expected.cmp(&kind, generics);
+        if cmp.is_not_equal() {
+            Err(CodegenError::ArgTypeMismatch(
+                decl.clone(),
+                kind,
+                cmp,
+                line.clone(),
+            ))?;
+        }
+        temp_code.push(WriteArg(idx + 1, GENERAL_REG1));
+    }
+    temp_code.read(&closure, POINTER_REG);
+    temp_code.extend(&[
+        IndexStatic(0),
+        ReadPtr(CODE_PTR_REG),
+        GotoPtr,
+        Unfreeze,
+        Move(RETURN_REG, GENERAL_REG1),
+    ]);
+    merge_code(code, &temp_code, *scope_len);
+    Ok(return_type.unwrap_or(Kind {
+        body: TypeBody::Type {
+            refs: 0,
+            main: vec!["null".to_string()],
+            generics: vec![],
+            nullable: false,
+            kind: dictionary::KindType::Primitive,
+        },
+        line: line.clone(),
+        file: Some(this.file.clone()),
+    }))
+}
+
+/// Calls a trait method on the trait object in reg1 using `DynMethod`
+///
+/// The method is looked up at runtime in the method table of the object's type
+fn call_trait_method(
+    objects: &mut Context,
+    method: &InnerPath,
+    context: &mut runtime_types::Context,
+    scopes: &mut Vec<ScopeCached>,
+    code: &mut Code,
+    scope_len: &mut usize,
+    call_params: &FunctionCall,
+    line: &Line,
+    this: &InnerPath,
+    generics: &HashMap<String, Kind>,
+) -> Result<Kind, CodegenError> {
+    use Instructions::*;
+    let traitt = method.block.clone().unwrap();
+    let trait_id = match trait_id(objects, &traitt, &method.file) {
+        Some(id) => id,
+        None => Err(CodegenError::FunctionNotFound(method.clone()))?,
+    };
+    let method_id = match find_trait(objects, &traitt, &method.file).and_then(|(_, traitt)| {
+        traitt
+            .methods
+            .iter()
+            .position(|fun| fun.identifier.as_ref() == Some(&method.ident))
+    }) {
+        Some(id) => id,
+        None => Err(CodegenError::FunctionNotFound(method.clone()))?,
+    };
+    let called_fun = method.get(objects)?.clone();
+    let call_params = &call_params
+        .resolve(&called_fun.args, line)
+        .map_err(CodegenError::InvalidArgs)?;
+    if called_fun.args.len() != call_params.args.len() {
+        Err(CodegenError::IncorrectNumberOfArgs(
+            called_fun.args.len(),
+            call_params.args.len(),
+            line.clone(),
+        ))?;
+    }
+    let mut temp_code = Code::new();
+    // the object has to survive evaluation of the arguments
+    let obj = temp_var(scopes, scope_len, None, line);
+    temp_code.write(GENERAL_REG1, &obj);
+    temp_code.extend(&[OpenArgs, Freeze]);
+    let takes_self = called_fun.takes_self as usize;
+    if called_fun.takes_self {
+        temp_code.read(&obj, GENERAL_REG1);
+        temp_code.push(WriteArg(0, GENERAL_REG1));
+    }
+    for (idx, (arg, decl)) in call_params
+        .args
+        .iter()
+        .zip(called_fun.args.iter())
+        .enumerate()
+    {
+        let expected = correct_kind(objects, &decl.kind, method, line, generics)?;
+        let kind = expression(
+            objects,
+            arg,
+            scopes,
+            &mut temp_code,
+            context,
+            this,
+            scope_len,
+            Some(expected.clone()),
+            *line,
+            generics,
+        )?;
+        let cmp = expected.cmp(&kind, generics);
+        if cmp.is_not_equal() {
+            Err(CodegenError::ArgTypeMismatch(
+                decl.clone(),
+                kind,
+                cmp,
+                line.clone(),
+            ))?;
+        }
+        temp_code.push(WriteArg(idx + takes_self, GENERAL_REG1));
+    }
+    temp_code.read(&obj, POINTER_REG);
+    temp_code.extend(&[
+        IndexStatic(0),
+        ReadPtr(GENERAL_REG1),
+        DynMethod(GENERAL_REG1, trait_id, method_id),
+        Unfreeze,
+        Move(RETURN_REG, GENERAL_REG1),
+    ]);
+    merge_code(code, &temp_code, *scope_len);
+    match called_fun.return_type {
+        Some(kind) => correct_kind(objects, &kind, method, line, generics),
+        None => Ok(Kind {
+            body: TypeBody::Type {
+                refs: 0,
+                main: vec!["null".to_string()],
+                generics: vec![],
+                nullable: false,
+                kind: dictionary::KindType::Primitive,
+            },
+            line: line.clone(),
+            file: Some(this.file.clone()),
+        }),
+    }
+}
+
+/// Generates the anonymous function and creates its closure in reg1
+///
+/// The closure is an object [function, captured values..] on heap,
+/// variables used by the function are copied into it when it is created
+fn closure(
+    objects: &mut Context,
+    lambda: &Function,
+    scopes: &mut Vec<ScopeCached>,
+    code: &mut Code,
+    context: &mut runtime_types::Context,
+    fun: &InnerPath,
+    scope_len: &mut usize,
+    line: &Line,
+) -> Result<Kind, CodegenError> {
+    use Instructions::*;
+    let mut idents = Vec::new();
+    for node in lambda.code.iter() {
+        node_idents(node, &mut idents);
+    }
+    let mut captures: Vec<(String, Kind, MemoryTypes)> = Vec::new();
+    for ident in idents {
+        if lambda.args.iter().any(|arg| arg.identifier == ident)
+            || captures.iter().any(|capture| capture.0 == ident)
+        {
+            continue;
+        }
+        if let Some(Variable {
+            kind: Some(kind),
+            pos,
+            ..
+        }) = find_var(scopes, &ident)
+        {
+            captures.push((ident.clone(), kind.clone(), pos.clone()));
+        }
+    }
+    // anonymous functions are generated as hidden functions of the file
+    let id = context.memory.fun_table.len();
+    let path = InnerPath {
+        file: fun.file.clone(),
+        block: None,
+        ident: format!("#fun{id}"),
+        kind: ImportKinds::Rd,
+    };
+    let mut lambda = lambda.clone();
+    lambda.identifier = Some(path.ident.clone());
+    lambda.inline = dictionary::InlineHint::Never;
+    match objects.0.get_mut(&fun.file) {
+        Some(file) => file.functions.push(lambda),
+        None => Err(CodegenError::FunctionNotFound(path.clone()))?,
+    }
+    // reserved before generating, nested closures get the following ids
+    context.memory.fun_table.push(runtime_types::FunSpec {
+        name: path.ident.clone(),
+        params: vec![],
+        stack_size: None,
+        loc: 0,
+    });
+    let env = captures
+        .iter()
+        .map(|(ident, kind, _)| (ident.clone(), kind.clone()))
+        .collect::<Vec<_>>();
+    gen_fun(objects, &path, context, false, None, Some(&env), None)?;
+    let lambda = path.get(objects)?;
+    context.memory.fun_table[id].loc = lambda.location;
+    let kind = Kind {
+        body: TypeBody::Function {
+            args: lambda.args.clone(),
+            return_type: Box::new(lambda.return_type.clone()),
+            refs: 0,
+        },
+        line: line.clone(),
+        file: Some(fun.file.clone()),
+    };
+    let fun_const = context.memory.stack.data.len();
+    context.memory.stack.data.push(Types::Function(id));
+    let obj = temp_var(scopes, scope_len, None, line);
+    code.push(AllocateStatic(captures.len() + 1));
+    code.write(POINTER_REG, &obj);
+    code.extend(&[
+        ReadConst(fun_const, GENERAL_REG1),
+        IndexStatic(0),
+        WritePtr(GENERAL_REG1),
+    ]);
+    for (idx, (_, _, pos)) in captures.iter().enumerate() {
+        code.read(pos, GENERAL_REG1);
+        code.read(&obj, POINTER_REG);
+        code.extend(&[IndexStatic(idx + 1), WritePtr(GENERAL_REG1)]);
+    }
+    code.read(&obj, GENERAL_REG1);
+    Ok(kind)
+}
+
+/// Declares a hidden variable in the last scope and returns its position
+fn temp_var(
+    scopes: &mut Vec<ScopeCached>,
+    scope_len: &mut usize,
+    kind: Option<Kind>,
+    line: &Line,
+) -> MemoryTypes {
+    *scope_len += 1;
+    let pos = create_var_pos(scopes);
+    let len = scopes.len();
+    scopes[len - 1].variables.insert(
+        scope_len.to_string(),
+        Variable {
+            kind,
+            pos: pos.clone(),
+            value: None,
+            line: line.clone(),
+        },
+    );
+    pos
+}
+
+/// Allocates an object of a variant of an enum with data and leaves it in GENERAL_REG1
+///
+/// Header of the object is the type of the variant, fields follow in the order they are declared
+fn construct_variant(
+    objects: &mut Context,
+    enum_file: &str,
+    type_ident: &str,
+    fields: &[Arg],
+    args: &[ValueType],
+    scopes: &mut Vec<ScopeCached>,
+    code: &mut Code,
+    context: &mut runtime_types::Context,
+    fun: &InnerPath,
+    scope_len: &mut usize,
+    line: &Line,
+    generics: &HashMap<String, Kind>,
+) -> Result<(), CodegenError> {
+    use Instructions::*;
+    if args.len() != fields.len() {
+        Err(CodegenError::IncorrectNumberOfArgs(
+            fields.len(),
+            args.len(),
+            line.clone(),
+        ))?;
+    }
+    // kinds of the fields are written in the file of the enum
+    let enum_path = InnerPath {
+        file: enum_file.to_string(),
+        block: None,
+        ident: "".to_string(),
+        kind: ImportKinds::Rd,
+    };
+    let mut values = Vec::new();
+    for (arg, field) in args.iter().zip(fields.iter()) {
+        let expected = correct_kind(objects, &field.kind, &enum_path, line, &HashMap::new())?;
+        let kind = expression(
+            objects,
+            arg,
+            scopes,
+            code,
+            context,
+            fun,
+            scope_len,
+            Some(expected.clone()),
+            *line,
+            generics,
+        )?;
+        let cmp = expected.cmp(&kind, generics);
+        if cmp.is_not_equal() {
+            Err(CodegenError::ArgTypeMismatch(
+                field.clone(),
+                kind,
+                cmp,
+                line.clone(),
+            ))?;
+        }
+        let value = temp_var(scopes, scope_len, Some(expected), line);
+        code.write(GENERAL_REG1, &value);
+        values.push(value);
+    }
+    let id = struct_type_id(context, enum_file, type_ident).unwrap();
+    let header = context.memory.stack.data.len();
+    context.memory.stack.data.push(Types::NonPrimitive(id));
+    let obj = temp_var(scopes, scope_len, None, line);
+    code.push(AllocateStatic(fields.len() + 1));
+    code.write(POINTER_REG, &obj);
+    code.extend(&[
+        ReadConst(header, GENERAL_REG1),
+        IndexStatic(0),
+        WritePtr(GENERAL_REG1),
+    ]);
+    for (idx, value) in values.iter().enumerate() {
+        code.read(value, GENERAL_REG1);
+        code.read(&obj, POINTER_REG);
+        code.extend(&[IndexStatic(idx + 1), WritePtr(GENERAL_REG1)]);
+    }
+    code.read(&obj, GENERAL_REG1);
+    Ok(())
+}
+
+/// Declares the names of a tuple pattern and copies the elements of the tuple at `tuple` into them
+fn destructure(
+    objects: &mut Context,
+    pattern: &TuplePattern,
+    tuple: &MemoryTypes,
+    kind: &Kind,
+    scopes: &mut Vec<ScopeCached>,
+    code: &mut Code,
+    fun: &InnerPath,
+    scope_len: &mut usize,
+    line: &Line,
+    generics: &HashMap<String, Kind>,
+) -> Result<(), CodegenError> {
+    use Instructions::*;
+    let kind = correct_kind(objects, kind, fun, line, generics)?;
+    let types = match &kind.body {
+        TypeBody::Tuple { types, .. } if types.len() == pattern.len() => types,
+        TypeBody::Tuple { .. } => Err(CodegenError::PatternLength(
+            kind.clone(),
+            pattern.len(),
+            line.clone(),
+        ))?,
+        _ => Err(CodegenError::NotATuple(
+            Position::Value(kind.clone()),
+            line.clone(),
+        ))?,
+    };
+    for (idx, (name, element)) in pattern.iter().zip(types.iter()).enumerate() {
+        let name = match name {
+            Some(name) => name,
+            None => continue,
+        };
+        if let Some(var) = find_var(scopes, name) {
+            Err(CodegenError::VariableAlreadyDeclared(
+                name.clone(),
+                line.clone(),
+                var.line.clone(),
+            ))?;
+        }
+        *scope_len += 1;
+        let pos = create_var_pos(scopes);
+        let len = scopes.len();
+        scopes[len - 1].variables.insert(
+            name.clone(),
+            Variable {
+                kind: Some(element.clone()),
+                pos: pos.clone(),
+                value: None,
+                line: line.clone(),
+            },
+        );
+        code.read(tuple, POINTER_REG);
+        code.extend(&[IndexStatic(idx), ReadPtr(GENERAL_REG1)]);
+        code.write(GENERAL_REG1, &pos);
+    }
+    Ok(())
+}
+
+/// Allocates a tuple and leaves it in GENERAL_REG1
+///
+/// Elements are stored in order without a header, the same way as arrays
+fn construct_tuple(
+    objects: &mut Context,
+    values: &[ValueType],
+    scopes: &mut Vec<ScopeCached>,
+    code: &mut Code,
+    context: &mut runtime_types::Context,
+    fun: &InnerPath,
+    scope_len: &mut usize,
+    expected_type: Option<Kind>,
+    line: &Line,
+    generics: &HashMap<String, Kind>,
+) -> Result<Kind, CodegenError> {
+    use Instructions::*;
+    let expected = match &expected_type {
+        Some(Kind {
+            body: TypeBody::Tuple { types, .. },
+            ..
+        }) if types.len() == values.len() => Some(types.clone()),
+        _ => None,
+    };
+    let mut types = Vec::new();
+    let mut elements = Vec::new();
+    for (idx, value) in values.iter().enumerate() {
+        let expected = match &expected {
+            Some(expected) => Some(correct_kind(objects, &expected[idx], fun, line, generics)?),
+            None => None,
+        };
+        let kind = expression(
+            objects,
+            value,
+            scopes,
+            code,
+            context,
+            fun,
+            scope_len,
+            expected.clone(),
+            *line,
+            generics,
+        )?;
+        let kind = match expected {
+            Some(expected) => {
+                if cast(
+                    objects, &kind, &expected, code, context, fun, line, GENERAL_REG1, generics,
+                )
+                .is_none()
+                {
+                    Err(CodegenError::CouldNotCastTo(kind, expected.clone(), line.clone()))?;
+                }
+                expected
+            }
+            None => kind,
+        };
+        let element = temp_var(scopes, scope_len, Some(kind.clone()), line);
+        code.write(GENERAL_REG1, &element);
+        elements.push(element);
+        types.push(kind);
+    }
+    let obj = temp_var(scopes, scope_len, None, line);
+    code.push(AllocateStatic(values.len()));
+    code.write(POINTER_REG, &obj);
+    for (idx, element) in elements.iter().enumerate() {
+        code.read(element, GENERAL_REG1);
+        code.read(&obj, POINTER_REG);
+        code.extend(&[IndexStatic(idx), WritePtr(GENERAL_REG1)]);
+    }
+    code.read(&obj, GENERAL_REG1);
+    Ok(Kind {
+        body: TypeBody::Tuple {
+            types,
+            refs: 0,
+            nullable: false,
+        },
+        line: line.clone(),
+        file: Some(fun.file.clone()),
+    })
+}
+
+/// Builds an interpolated string and leaves it in GENERAL_REG1
+///
+/// Every part is turned into a string in GENERAL_REG2 and appended to the string built so far
+fn gen_interpolation(
+    objects: &mut Context,
+    parts: &[InterpolationPart],
+    scopes: &mut Vec<ScopeCached>,
+    code: &mut Code,
+    context: &mut runtime_types::Context,
+    fun: &InnerPath,
+    scope_len: &mut usize,
+    line: &Line,
+    generics: &HashMap<String, Kind>,
+) -> Result<Kind, CodegenError> {
+    use Instructions::*;
+    let string = Kind {
+        body: TypeBody::Type {
+            refs: 0,
+            main: vec!["string".to_string()],
+            generics: vec![],
+            nullable: false,
+            kind: dictionary::KindType::Primitive,
+        },
+        line: line.clone(),
+        file: Some(fun.file.clone()),
+    };
+    let result = temp_var(scopes, scope_len, Some(string.clone()), line);
+    for (i, part) in parts.iter().enumerate() {
+        match part {
+            InterpolationPart::Text(text) => {
+                let pos = new_const(context, &ConstValue::String(text.clone()))?;
+                code.push(ReadConst(pos, GENERAL_REG2));
+            }
+            InterpolationPart::Value(embedded) => {
+                let kind = expression(
+                    objects,
+                    &embedded.value,
+                    scopes,
+                    code,
+                    context,
+                    fun,
+                    scope_len,
+                    None,
+                    embedded.line.clone(),
+                    generics,
+                )?;
+                if !kind.is_primitive_simple() {
+                    return Err(CodegenError::CannotInterpolate(kind, embedded.line.clone()));
+                }
+                if !embedded.format_fits(&kind) {
+                    let format = embedded.format.as_ref().unwrap().0.clone();
+                    return Err(CodegenError::FormatNotApplicable(
+                        format,
+                        kind,
+                        embedded.line.clone(),
+                    ));
+                }
+                match &embedded.format {
+                    Some((format, _)) => {
+                        let pos = new_const(context, &ConstValue::String(format.clone()))?;
+                        code.extend(&[
+                            ReadConst(pos, GENERAL_REG2),
+                            Cal(CORE_LIB, 12),
+                            Move(RETURN_REG, GENERAL_REG2),
+                        ]);
+                    }
+                    None => {
+                        code.push(Move(GENERAL_REG1, GENERAL_REG2));
+                        if !kind.is_string() {
+                            cast(
+                                objects,
+                                &kind,
+                                &string,
+                                code,
+                                context,
+                                fun,
+                                &embedded.line,
+                                GENERAL_REG2,
+                                generics,
+                            )
+                            .ok_or(CodegenError::CannotInterpolate(
+                                kind.clone(),
+                                embedded.line.clone(),
+                            ))?;
+                        }
+                    }
+                }
+            }
+        }
+        if i == 0 {
+            code.write(GENERAL_REG2, &result);
+        } else {
+            code.read(&result, GENERAL_REG1);
+            code.extend(&[Cal(CORE_LIB, 2), Move(RETURN_REG, GENERAL_REG1)]);
+            code.write(GENERAL_REG1, &result);
+        }
+    }
+    code.read(&result, GENERAL_REG1);
+    Ok(string)
+}
+
+/// Generates a match, value of the chosen arm is left in GENERAL_REG1
+///
+/// Arms of a match used as a statement do not have to agree on a kind
+/// and the terminator tells whether every arm leaves the scope
+fn gen_match(
+    objects: &mut Context,
+    matchh: &Match,
+    scopes: &mut Vec<ScopeCached>,
+    code: &mut Code,
+    context: &mut runtime_types::Context,
+    fun: &InnerPath,
+    scope_len: &mut usize,
+    expected_type: Option<Kind>,
+    is_statement: bool,
+    generics: &HashMap<String, Kind>,
+) -> Result<(Kind, ScopeTerminator), CodegenError> {
+    use Instructions::*;
+    let line = &matchh.line;
+    let returns = |terminator: &ScopeTerminator| {
+        *terminator == ScopeTerminator::Return || *terminator == ScopeTerminator::Yeet
+    };
+    scopes.push(ScopeCached {
+        variables: HashMap::new(),
+    });
+    let mut buffer = Code::new();
+    let kind = expression(
+        objects,
+        &matchh.expr,
+        scopes,
+        &mut buffer,
+        context,
+        fun,
+        scope_len,
+        None,
+        *line,
+        generics,
+    )?;
+    let kind = correct_kind(objects, &kind, fun, line, generics)?;
+    let value = temp_var(scopes, scope_len, Some(kind.clone()), line);
+    buffer.write(GENERAL_REG1, &value);
+    // (name, number, fields) of every variant
+    let (enum_file, enum_ident, variants, has_payload) = match &kind.body {
+        TypeBody::Type {
+            main,
+            kind: dictionary::KindType::Enum,
+            ..
+        } => find_enum(objects, main.last().unwrap(), kind.file.as_ref().unwrap()).map(
+            |(file, enumm)| {
+                let variants = enumm
+                    .keys
+                    .iter()
+                    .zip(enumm.fields.iter())
+                    .map(|(key, fields)| (key.0.clone(), key.1, fields.clone()))
+                    .collect::<Vec<_>>();
+                (file.to_string(), enumm.identifier.clone(), variants, enumm.has_payload())
+            },
+        ),
+        TypeBody::Type {
+            main,
+            kind: dictionary::KindType::BEnum,
+            ..
+        } => find_benum(objects, main.last().unwrap(), kind.file.as_ref().unwrap()).map(
+            |(file, enumm)| {
+                let variants = enumm
+                    .variants
+                    .iter()
+                    .map(|variant| (variant.0.clone(), variant.1, Vec::new()))
+                    .collect::<Vec<_>>();
+                (file.to_string(), enumm.name.clone(), variants, false)
+            },
+        ),
+        _ => None,
+    }
+    .ok_or_else(|| CodegenError::CannotMatch(kind.clone(), line.clone()))?;
+    let enum_path = InnerPath {
+        file: enum_file.clone(),
+        block: None,
+        ident: "".to_string(),
+        kind: ImportKinds::Rd,
+    };
+    let mut covered = Vec::new();
+    let mut wildcard = false;
+    let mut arm_kind: Option<Kind> = None;
+    let mut all_return = true;
+    let mut to_end = Vec::new();
+    for arm in matchh.arms.iter() {
+        if wildcard || covered.len() == variants.len() {
+            Err(CodegenError::UnreachableArm(arm.line.clone()))?;
+        }
+        // jumps to the next arm when the value does not fit
+        let mut to_next = Vec::new();
+        let variant = match &arm.path {
+            Some(path) => {
+                let (ident, owner) = match path.as_slice() {
+                    [.., owner, ident] => (ident, owner),
+                    _ => Err(CodegenError::PatternNotOfKind(
+                        path.join("."),
+                        kind.clone(),
+                        arm.line.clone(),
+                    ))?,
+                };
+                if *owner != enum_ident {
+                    Err(CodegenError::PatternNotOfKind(
+                        path.join("."),
+                        kind.clone(),
+                        arm.line.clone(),
+                    ))?;
+                }
+                let idx = match variants.iter().position(|variant| &variant.0 == ident) {
+                    Some(idx) => idx,
+                    None => Err(CodegenError::FieldNotInEnum(
+                        ident.clone(),
+                        arm.line.clone(),
+                    ))?,
+                };
+                if covered.contains(&idx) {
+                    Err(CodegenError::UnreachableArm(arm.line.clone()))?;
+                }
+                if !arm.bindings.is_empty() && arm.bindings.len() != variants[idx].2.len() {
+                    Err(CodegenError::IncorrectNumberOfBindings(
+                        ident.clone(),
+                        variants[idx].2.len(),
+                        arm.bindings.len(),
+                        arm.line.clone(),
+                    ))?;
+                }
+                if has_payload {
+                    let id = struct_type_id(context, &enum_file, &variant_ident(&enum_ident, ident))
+                        .unwrap();
+                    buffer.read(&value, POINTER_REG);
+                    buffer.extend(&[
+                        IndexStatic(0),
+                        ReadPtr(GENERAL_REG1),
+                        NonPrimitiveType(GENERAL_REG1, id),
+                        Move(GENERAL_REG3, GENERAL_REG1),
+                    ]);
+                } else {
+                    let key = new_const(context, &ConstValue::Uint(variants[idx].1))?;
+                    buffer.read(&value, GENERAL_REG1);
+                    buffer.extend(&[
+                        ReadConst(key, GENERAL_REG2),
+                        Equ(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1),
+                    ]);
+                }
+                to_next.push(buffer.code.len());
+                buffer.push(Branch(buffer.code.len() + 1, 0));
+                Some(idx)
+            }
+            None => None,
+        };
+        scopes.push(ScopeCached {
+            variables: HashMap::new(),
+        });
+        if let Some(idx) = variant {
+            for (field_idx, binding) in arm.bindings.iter().enumerate() {
+                let binding = match binding {
+                    Some(binding) => binding,
+                    None => continue,
+                };
+                if let Some(var) = find_var(scopes, binding) {
+                    Err(CodegenError::VariableAlreadyDeclared(
+                        binding.clone(),
+                        arm.line.clone(),
+                        var.line.clone(),
+                    ))?;
+                }
+                let field = &variants[idx].2[field_idx];
+                let field_kind =
+                    correct_kind(objects, &field.kind, &enum_path, &arm.line, &HashMap::new())?;
+                *scope_len += 1;
+                let pos = create_var_pos(scopes);
+                let len = scopes.len();
+                scopes[len - 1].variables.insert(
+                    binding.clone(),
+                    Variable {
+                        kind: Some(field_kind),
+                        pos: pos.clone(),
+                        value: None,
+                        line: arm.line.clone(),
+                    },
+                );
+                buffer.read(&value, POINTER_REG);
+                buffer.extend(&[IndexStatic(field_idx + 1), ReadPtr(GENERAL_REG1)]);
+                buffer.write(GENERAL_REG1, &pos);
+            }
+        }
+        match &arm.guard {
+            Some(guard) => {
+                let guard_kind = expression(
+                    objects,
+                    guard,
+                    scopes,
+                    &mut buffer,
+                    context,
+                    fun,
+                    scope_len,
+                    None,
+                    arm.line,
+                    generics,
+                )?;
+                if !guard_kind.is_bool() {
+                    Err(CodegenError::ExpectedBool(arm.line.clone()))?;
+                }
+                to_next.push(buffer.code.len());
+                buffer.push(Branch(buffer.code.len() + 1, 0));
+            }
+            // only arms without a guard cover their variant
+            None => match variant {
+                Some(idx) => covered.push(idx),
+                None => wildcard = true,
+            },
+        }
+        match &arm.body {
+            ArmBody::Value(body) => {
+                all_return = false;
+                let expected = match is_statement {
+                    true => None,
+                    false => expected_type.clone().or(arm_kind.clone()),
+                };
+                let body_kind = expression(
+                    objects,
+                    body,
+                    scopes,
+                    &mut buffer,
+                    context,
+                    fun,
+                    scope_len,
+                    expected,
+                    arm.line,
+                    generics,
+                )?;
+                if !is_statement {
+                    match &arm_kind {
+                        Some(arm_kind) => {
+                            let cmp = arm_kind.cmp(&body_kind, generics);
+                            if cmp.is_not_equal() {
+                                Err(CodegenError::VariableTypeMismatch(
+                                    arm_kind.clone(),
+                                    body_kind,
+                                    cmp,
+                                    arm.line.clone(),
+                                ))?;
+                            }
+                        }
+                        None => arm_kind = Some(expected_type.clone().unwrap_or(body_kind)),
+                    }
+                }
+            }
+            ArmBody::Block(body) => {
+                let (len, terminator) =
+                    get_scope(objects, body, context, scopes, &mut buffer, fun, generics)?;
+                scopes.pop();
+                *scope_len += len;
+                if !returns(&terminator) {
+                    all_return = false;
+                }
+                if terminator == ScopeTerminator::None && !is_statement {
+                    Err(CodegenError::ArmWithoutValue(arm.line.clone()))?;
+                }
+            }
+        }
+        scopes.pop();
+        to_end.push(buffer.code.len());
+        buffer.push(Goto(0));
+        let next = buffer.code.len();
+        for pos in to_next {
+            if let Branch(pos1, _) = buffer.code[pos] {
+                buffer.code[pos] = Branch(pos1, next);
+            }
+        }
+    }
+    if !wildcard && covered.len() < variants.len() {
+        let missing = variants
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !covered.contains(idx))
+            .map(|(_, variant)| variant.0.clone())
+            .collect::<Vec<_>>();
+        Err(CodegenError::MatchNotExhaustive(missing, line.clone()))?;
+    }
+    let end = buffer.code.len();
+    for pos in to_end {
+        buffer.code[pos] = Goto(end);
+    }
+    scopes.pop();
+    merge_code(code, &buffer, 0);
+    let terminator = match all_return && !matchh.arms.is_empty() {
+        true => ScopeTerminator::Return,
+        false => ScopeTerminator::None,
+    };
+    Ok((arm_kind.unwrap_or(Kind::void()), terminator))
+}
+
+/// Collects identifiers that may refer to variables of an enclosing function
+fn node_idents(node: &Nodes, idents: &mut Vec<String>) {
+    let block = |nodes: &Vec<Nodes>, idents: &mut Vec<String>| {
+        for node in nodes {
+            node_idents(node, idents);
+        }
+    };
+    match node {
+        Nodes::Let { expr, .. } => {
+            if let Some(expr) = expr {
+                value_idents(expr, idents);
+            }
+        }
+        Nodes::If {
+            cond,
+            body,
+            elif,
+            els,
+            ..
+        } => {
+            value_idents(cond, idents);
+            block(body, idents);
+            for (cond, body, _) in elif {
+                value_idents(cond, idents);
+                block(body, idents);
+            }
+            if let Some((body, _)) = els {
+                block(body, idents);
+            }
+        }
+        Nodes::While { cond, body, .. } => {
+            value_idents(cond, idents);
+            block(body, idents);
+        }
+        Nodes::For {
+            expr, range, body, ..
+        } => {
+            value_idents(expr, idents);
+            if let Some(range) = range {
+                value_idents(&range.end, idents);
+                if let Some(step) = &range.step {
+                    value_idents(step, idents);
+                }
+            }
+            block(body, idents);
+        }
+        Nodes::Return { expr, .. } => {
+            if let Some(expr) = expr {
+                value_idents(expr, idents);
+            }
+        }
+        Nodes::Expr { expr, .. } => value_idents(expr, idents),
+        Nodes::Block { body, .. } | Nodes::Loop { body, .. } => block(body, idents),
+        Nodes::Yeet { expr, .. } => tail_idents(&expr.1, idents),
+        Nodes::Try {
+            body,
+            catch,
+            finally,
+            ..
+        } => {
+            block(body, idents);
+            for catch in catch {
+                block(&catch.body, idents);
+            }
+            if let Some(finally) = finally {
+                block(finally, idents);
+            }
+        }
+        Nodes::Switch {
+            expr,
+            body,
+            default,
+            ..
+        } => {
+            value_idents(expr, idents);
+            for (values, body) in body {
+                for value in values {
+                    value_idents(value, idents);
+                }
+                block(body, idents);
+            }
+            if let Some(default) = default {
+                block(default, idents);
+            }
+        }
+        Nodes::Set { target, expr, .. } => {
+            value_idents(target, idents);
+            value_idents(expr, idents);
+        }
+        Nodes::Break { .. } | Nodes::Continue { .. } => (),
+    }
+}
+
+fn value_idents(value: &ValueType, idents: &mut Vec<String>) {
+    match value {
+        ValueType::AnonymousFunction(lambda) => {
+            for node in lambda.code.iter() {
+                node_idents(node, idents);
+            }
+        }
+        ValueType::Expression(expr) => {
+            for side in [&expr.left, &expr.right].into_iter().flatten() {
+                value_idents(side, idents);
+            }
+        }
+        ValueType::Value(value) => {
+            match &value.root.0 {
+                Root::Identifier(ident) => idents.push(ident.clone()),
+                Root::Parenthesis(value) => value_idents(value, idents),
+                Root::Literal(expression_parser::Literals::Array(ArrayRule::Fill {
+                    value,
+                    size,
+                })) => {
+                    value_idents(value, idents);
+                    value_idents(size, idents);
+                }
+                Root::Literal(expression_parser::Literals::Array(ArrayRule::Explicit(
+                    values,
+                ))) => {
+                    for value in values {
+                        value_idents(value, idents);
+                    }
+                }
+                Root::Literal(expression_parser::Literals::Tuple(values)) => {
+                    for value in values {
+                        value_idents(value, idents);
+                    }
+                }
+                Root::Literal(_) => (),
+                Root::Interpolation(parts) => {
+                    for part in parts {
+                        if let InterpolationPart::Value(embedded) = part {
+                            value_idents(&embedded.value, idents);
+                        }
+                    }
+                }
+                Root::Match(matchh) => {
+                    value_idents(&matchh.expr, idents);
+                    for arm in matchh.arms.iter() {
+                        if let Some(guard) = &arm.guard {
+                            value_idents(guard, idents);
+                        }
+                        match &arm.body {
+                            ArmBody::Value(value) => value_idents(value, idents),
+                            ArmBody::Block(body) => {
+                                for node in body {
+                                    node_idents(node, idents);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            tail_idents(&value.tail, idents);
+        }
+        ValueType::Operator(_, _) | ValueType::Blank => (),
+    }
+}
+
+fn tail_idents(tail: &[(TailNodes, Line)], idents: &mut Vec<String>) {
+    for (node, _) in tail {
+        match node {
+            TailNodes::Index(value) => value_idents(value, idents),
+            TailNodes::Call(call) => {
+                for arg in call.args.iter().chain(call.named.iter().map(|named| &named.1)) {
+                    value_idents(arg, idents);
+                }
+            }
+            TailNodes::Nested(_)
+            | TailNodes::TupleIndex(_)
+            | TailNodes::Nullable
+            | TailNodes::Propagate
+            | TailNodes::Cast(_) => (),
+        }
+    }
+}
+
+/// Generates body of the called function in place of the call
+///
+/// Arguments are expected to be already written at `args` on the stack of the caller.
+/// Returns of the inlined function jump to the end of its body
+/// and its result is moved to reg1 like it would be after a call
+fn inline_fun(
+    objects: &mut Context,
+    fun: &InnerPath,
+    context: &mut runtime_types::Context,
+    scopes: &Vec<ScopeCached>,
+    code: &mut Code,
+    scope_len: &mut usize,
+    args: &Vec<MemoryTypes>,
+    line: &Line,
+    this: &InnerPath,
+) -> Result<(), CodegenError> {
+    use Instructions::*;
+    let generics = HashMap::new();
+    // variables of the caller are hidden from the inlined body,
+    // but their stack positions stay occupied
+    let occupied = scopes.iter().map(|s| s.variables.len()).sum::<usize>() - args.len();
+    let mut inline_scopes = vec![
+        ScopeCached {
+            variables: HashMap::new(),
+        },
+        ScopeCached {
+            variables: HashMap::new(),
+        },
+    ];
+    for idx in 0..occupied {
+        inline_scopes[0].variables.insert(
+            format!("#{idx}"),
+            Variable {
+                kind: None,
+                pos: MemoryTypes::Stack(idx),
+                value: None,
+                line: line.clone(),
+            },
+        );
+    }
+    let called_fun = fun.get(objects)?;
+    for (arg, pos) in called_fun.args.clone().iter().zip(args) {
+        let kind = correct_kind(objects, &arg.kind, fun, &arg.line, &generics)?;
+        inline_scopes[1].variables.insert(
+            arg.identifier.clone(),
+            Variable {
+                kind: Some(kind),
+                pos: pos.clone(),
+                value: None,
+                line: arg.line.clone(),
+            },
+        );
+    }
+    let called_fun = fun.get_mut(objects)?;
+    called_fun.inlining = true;
+    let body = called_fun.code.clone();
+    let fun_line = called_fun.line.clone();
+    let mut body_code = Code::new();
+    body_code.add_debug(fun_line, fun.file.clone());
+    let scope = get_scope(
+        objects,
+        &body,
+        context,
+        &mut inline_scopes,
+        &mut body_code,
+        fun,
+        &generics,
+    );
+    fun.get_mut(objects)?.inlining = false;
+    let (len, terminator) = scope?;
+    *scope_len += len;
+    let called_fun = fun.get(objects)?;
+    if terminator != ScopeTerminator::Return {
+        if called_fun.return_type.is_some() && terminator != ScopeTerminator::Yeet {
+            Err(CodegenError::FunctionDoesNotReturn(called_fun.line.clone()))?;
+        }
+        body_code.push(ReadConst(0, RETURN_REG));
+    }
+    let end = body_code.code.len();
+    for instr in body_code.code.iter_mut() {
+        if let Return = instr {
+            *instr = Goto(end);
+        }
+    }
+    body_code.add_debug(line.clone(), this.file.clone());
+    body_code.push(Move(RETURN_REG, GENERAL_REG1));
+    merge_code(code, &body_code, *scope_len);
+    Ok(())
+}
+
+fn call_whichever(
     objects: &mut Context,
     fun: &InnerPath,
     context: &mut runtime_types::Context,
@@ -3116,6 +5746,7 @@ fn call_whichever(
     line: &Line,
     this: &InnerPath,
     this_kind: &Kind,
+    generics: &HashMap<String, Kind>,
 ) -> Result<Kind, CodegenError> {
     match fun.kind {
         ImportKinds::Dll => {
@@ -3144,6 +5775,8 @@ fn call_whichever(
                 call_params,
                 line,
                 this,
+                this_kind,
+                generics,
             )?;
             return Ok(kind);
         }
@@ -3180,6 +5813,24 @@ fn get_scope(
         }};
     }
 
+    // declares a variable in the last scope and returns its position
+    macro_rules! declare {
+        ($ident: expr, $kind: expr, $line: expr) => {{
+            max_scope_len += 1;
+            let pos = create_var_pos(&other_scopes);
+            last!(other_scopes).variables.insert(
+                $ident,
+                Variable {
+                    kind: Some($kind),
+                    pos: pos.clone(),
+                    value: None,
+                    line: $line.clone(),
+                },
+            );
+            pos
+        }};
+    }
+
     let bool_type = Kind {
         body: TypeBody::Type {
             refs: 0,
@@ -3188,7 +5839,7 @@ fn get_scope(
             nullable: false,
             kind: dictionary::KindType::Primitive,
         },
-        line: Line { line: 0, column: 0 },
+        line: Line { line: 0, column: 0, start: 0, end: 0 },
         file: Some(fun.file.clone()),
     };
 
@@ -3199,6 +5850,7 @@ fn get_scope(
                 expr,
                 kind,
                 line,
+                pattern,
             } => {
                 if let Some(var) = find_var(other_scopes, ident) {
                     Err(CodegenError::VariableAlreadyDeclared(
@@ -3305,7 +5957,21 @@ fn get_scope(
                     }
                 }
                 let cache = last!(other_scopes);
-                cache.variables.get_mut(ident).unwrap().kind = Some(kind);
+                cache.variables.get_mut(ident).unwrap().kind = Some(kind.clone());
+                if let Some(pattern) = pattern {
+                    destructure(
+                        objects,
+                        pattern,
+                        &pos,
+                        &kind,
+                        other_scopes,
+                        code,
+                        &fun,
+                        &mut max_scope_len,
+                        line,
+                        generics,
+                    )?;
+                }
             }
             crate::codeblock_parser::Nodes::If {
                 cond,
@@ -3396,92 +6062,352 @@ fn get_scope(
                 for goto in gotos {
                     buffer.code[goto] = Goto(buffer.code.len());
                 }
-                // append
-                merge_code(code, &buffer, scope);
-                if all_end_with_return && els.is_some() {
-                    return Ok((max_scope_len, ScopeTerminator::Return));
+                // append
+                merge_code(code, &buffer, scope);
+                if all_end_with_return && els.is_some() {
+                    return Ok((max_scope_len, ScopeTerminator::Return));
+                }
+            }
+            crate::codeblock_parser::Nodes::While {
+                cond,
+                body,
+                line,
+                ident,
+            } => {
+                use Instructions::*;
+                let mut expr_code = Code::new();
+                let mut block_code = Code::new();
+                let kind = expression(
+                    objects,
+                    cond,
+                    other_scopes,
+                    &mut expr_code,
+                    context,
+                    &fun,
+                    &mut max_scope_len,
+                    Some(bool_type.clone()),
+                    *line,
+                    generics,
+                )?;
+                if kind.cmp(&bool_type, generics).is_not_equal() {
+                    return Err(CodegenError::ExpectedBool(line.clone()));
+                }
+                let (scope, terminator) = open_scope!(body, &mut block_code);
+                expr_code.push(Branch(
+                    expr_code.code.len() + 1,
+                    expr_code.code.len() + block_code.code.len() + 2,
+                ));
+                let mut buffer = Code::new();
+                merge_code(&mut buffer, &expr_code, scope);
+                merge_code(&mut buffer, &block_code, scope);
+                buffer.push(Goto(0));
+                // resolve breaks
+                let mut i = 0;
+                let mut breaks = false;
+                while i < buffer.stops.len() {
+                    let identifier = match &buffer.stops[i].kind {
+                        CodeStops::Break(ident) => ident,
+                        _ => {
+                            i += 1;
+                            continue;
+                        }
+                    };
+                    if identifier != ident {
+                        i += 1;
+                        continue;
+                    }
+                    buffer.code[buffer.stops[i].pos] = Goto(buffer.code.len());
+                    buffer.stops.remove(i);
+                    breaks = true;
+                }
+                // resolve continues
+                i = 0;
+                while i < buffer.stops.len() {
+                    let identifier = match &buffer.stops[i].kind {
+                        CodeStops::Continue(ident) => ident,
+                        _ => {
+                            i += 1;
+                            continue;
+                        }
+                    };
+                    if identifier != ident {
+                        i += 1;
+                        continue;
+                    }
+                    buffer.code[buffer.stops[i].pos] = Goto(0);
+                    buffer.stops.remove(i);
+                }
+
+                merge_code(code, &buffer, scope);
+                // the code after the loop is reachable through a break
+                if !breaks
+                    && (terminator == ScopeTerminator::Return || terminator == ScopeTerminator::Yeet)
+                {
+                    return Ok((max_scope_len, ScopeTerminator::Return));
+                }
+            }
+            crate::codeblock_parser::Nodes::For {
+                ident,
+                expr,
+                range,
+                body,
+                line,
+                ident2,
+                label,
+                pattern,
+            } => {
+                use Instructions::*;
+                // loop variables live in their own scope so they do not leak out of the loop
+                other_scopes.push(ScopeCached {
+                    variables: HashMap::new(),
+                });
+                let uint_type = Kind {
+                    body: TypeBody::Type {
+                        refs: 0,
+                        main: vec!["uint".to_string()],
+                        generics: vec![],
+                        nullable: false,
+                        kind: dictionary::KindType::Primitive,
+                    },
+                    line: line.clone(),
+                    file: Some(fun.file.clone()),
+                };
+                // runs once before the loop
+                let mut header = Code::new();
+                // leaves bool in GENERAL_REG1
+                let mut cond_code = Code::new();
+                // runs at the start of each iteration
+                let mut prologue = Code::new();
+                // advances the loop, continue jumps here
+                let mut next_code = Code::new();
+                let kind = expression(
+                    objects,
+                    expr,
+                    other_scopes,
+                    &mut header,
+                    context,
+                    &fun,
+                    &mut max_scope_len,
+                    None,
+                    *line,
+                    generics,
+                )?;
+                let kind = correct_kind(objects, &kind, &fun, line, generics)?;
+                let uint_zero = new_const(context, &ConstValue::Uint(0))?;
+                let uint_one = new_const(context, &ConstValue::Uint(1))?;
+                // index of the current iteration
+                let idx = match (ident2, range) {
+                    (Some(ident2), _) => Some(declare!(ident2.clone(), uint_type.clone(), line)),
+                    (None, None) => Some(declare!("#idx".to_string(), uint_type.clone(), line)),
+                    (None, Some(_)) => None,
+                };
+                match range {
+                    Some(range) => {
+                        if !kind.is_number() {
+                            Err(CodegenError::ExpectedNumber(line.clone()))?;
+                        }
+                        if pattern.is_some() {
+                            Err(CodegenError::NotATuple(
+                                Position::Value(kind.clone()),
+                                line.clone(),
+                            ))?;
+                        }
+                        let var = declare!(ident.clone(), kind.clone(), line);
+                        header.write(GENERAL_REG1, &var);
+                        let (zero, one) = match kind.into_const() {
+                            Some(ConstValue::Int(_)) => (ConstValue::Int(0), ConstValue::Int(1)),
+                            Some(ConstValue::Float(_)) => (ConstValue::Float(0.0), ConstValue::Float(1.0)),
+                            Some(ConstValue::Char(_)) => (ConstValue::Char(0 as char), ConstValue::Char(1 as char)),
+                            _ => (ConstValue::Uint(0), ConstValue::Uint(1)),
+                        };
+                        let mut bound = |value: &ValueType,
+                                         header: &mut Code,
+                                         other_scopes: &mut Vec<ScopeCached>,
+                                         max_scope_len: &mut usize|
+                         -> Result<MemoryTypes, CodegenError> {
+                            let value_kind = expression(
+                                objects,
+                                value,
+                                other_scopes,
+                                header,
+                                context,
+                                &fun,
+                                max_scope_len,
+                                Some(kind.clone()),
+                                range.line,
+                                generics,
+                            )?;
+                            if cast(
+                                objects,
+                                &value_kind,
+                                &kind,
+                                header,
+                                context,
+                                &fun,
+                                &range.line,
+                                GENERAL_REG1,
+                                generics,
+                            )
+                            .is_none()
+                            {
+                                Err(CodegenError::CouldNotCastTo(
+                                    value_kind,
+                                    kind.clone(),
+                                    range.line.clone(),
+                                ))?;
+                            }
+                            let pos = temp_var(
+                                other_scopes,
+                                max_scope_len,
+                                Some(kind.clone()),
+                                &range.line,
+                            );
+                            header.write(GENERAL_REG1, &pos);
+                            Ok(pos)
+                        };
+                        let end = bound(&range.end, &mut header, other_scopes, &mut max_scope_len)?;
+                        let step = match &range.step {
+                            Some(step) => {
+                                Some(bound(step, &mut header, other_scopes, &mut max_scope_len)?)
+                            }
+                            None => None,
+                        };
+                        // compares the loop variable with the end of the range
+                        let compare = |code: &mut Code, ascending: bool| {
+                            code.read(&var, GENERAL_REG1);
+                            code.read(&end, GENERAL_REG2);
+                            match (ascending, range.inclusive) {
+                                (true, false) => code.push(Less(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1)),
+                                (false, false) => code.push(Grt(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1)),
+                                (true, true) => code.extend(&[
+                                    Grt(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1),
+                                    Not(GENERAL_REG1, GENERAL_REG1),
+                                ]),
+                                (false, true) => code.extend(&[
+                                    Less(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1),
+                                    Not(GENERAL_REG1, GENERAL_REG1),
+                                ]),
+                            }
+                        };
+                        match &step {
+                            // negative step counts down
+                            Some(step) if !matches!(zero, ConstValue::Uint(_)) => {
+                                let zero = new_const(context, &zero)?;
+                                cond_code.read(step, GENERAL_REG1);
+                                cond_code.extend(&[
+                                    ReadConst(zero, GENERAL_REG2),
+                                    Less(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1),
+                                ]);
+                                let branch = cond_code.code.len();
+                                cond_code.push(Branch(0, 0));
+                                let descending = cond_code.code.len();
+                                compare(&mut cond_code, false);
+                                let goto = cond_code.code.len();
+                                cond_code.push(Goto(0));
+                                let ascending = cond_code.code.len();
+                                compare(&mut cond_code, true);
+                                cond_code.code[branch] = Branch(descending, ascending);
+                                cond_code.code[goto] = Goto(cond_code.code.len());
+                            }
+                            _ => compare(&mut cond_code, true),
+                        }
+                        next_code.read(&var, GENERAL_REG1);
+                        match &step {
+                            Some(step) => next_code.read(step, GENERAL_REG2),
+                            None => {
+                                let one = new_const(context, &one)?;
+                                next_code.push(ReadConst(one, GENERAL_REG2));
+                            }
+                        }
+                        next_code.push(Add(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1));
+                        next_code.write(GENERAL_REG1, &var);
+                    }
+                    None => {
+                        let element = match &kind.body {
+                            TypeBody::Array { type_, .. } => *type_.clone(),
+                            _ if kind.is_string() => Kind {
+                                body: TypeBody::Type {
+                                    refs: 0,
+                                    main: vec!["char".to_string()],
+                                    generics: vec![],
+                                    nullable: false,
+                                    kind: dictionary::KindType::Primitive,
+                                },
+                                line: line.clone(),
+                                file: Some(fun.file.clone()),
+                            },
+                            _ => Err(CodegenError::CannotIterate(kind.clone(), line.clone()))?,
+                        };
+                        let iter = declare!("#iter".to_string(), kind.clone(), line);
+                        header.write(GENERAL_REG1, &iter);
+                        let var = declare!(ident.clone(), element.clone(), line);
+                        let idx = idx.as_ref().unwrap();
+                        cond_code.read(&iter, GENERAL_REG2);
+                        cond_code.push(Len(GENERAL_REG2));
+                        cond_code.read(idx, GENERAL_REG1);
+                        cond_code.push(Less(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1));
+                        prologue.read(&iter, POINTER_REG);
+                        prologue.read(idx, GENERAL_REG1);
+                        prologue.extend(&[Index(GENERAL_REG1), ReadPtr(GENERAL_REG1)]);
+                        prologue.write(GENERAL_REG1, &var);
+                        if let Some(pattern) = pattern {
+                            destructure(
+                                objects,
+                                pattern,
+                                &var,
+                                &element,
+                                other_scopes,
+                                &mut prologue,
+                                &fun,
+                                &mut max_scope_len,
+                                line,
+                                generics,
+                            )?;
+                        }
+                    }
+                }
+                if let Some(idx) = &idx {
+                    header.push(ReadConst(uint_zero, GENERAL_REG1));
+                    header.write(GENERAL_REG1, idx);
+                    next_code.read(idx, GENERAL_REG1);
+                    next_code.extend(&[
+                        ReadConst(uint_one, GENERAL_REG2),
+                        Add(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1),
+                    ]);
+                    next_code.write(GENERAL_REG1, idx);
                 }
-            }
-            crate::codeblock_parser::Nodes::While {
-                cond,
-                body,
-                line,
-                ident,
-            } => {
-                use Instructions::*;
-                let mut expr_code = Code::new();
                 let mut block_code = Code::new();
-                let kind = expression(
-                    objects,
-                    cond,
-                    other_scopes,
-                    &mut expr_code,
-                    context,
-                    &fun,
-                    &mut max_scope_len,
-                    Some(bool_type.clone()),
-                    *line,
-                    generics,
-                )?;
-                if kind.cmp(&bool_type, generics).is_not_equal() {
-                    return Err(CodegenError::ExpectedBool(line.clone()));
-                }
-                let (scope, terminator) = open_scope!(body, &mut block_code);
-                expr_code.push(Branch(
-                    expr_code.code.len() + 1,
-                    expr_code.code.len() + block_code.code.len() + 2,
-                ));
+                let (scope, _) = open_scope!(body, &mut block_code);
+                // merge
                 let mut buffer = Code::new();
-                merge_code(&mut buffer, &expr_code, scope);
+                merge_code(&mut buffer, &header, scope);
+                let cond_start = buffer.code.len();
+                merge_code(&mut buffer, &cond_code, scope);
+                let branch = buffer.code.len();
+                buffer.push(Branch(0, 0));
+                merge_code(&mut buffer, &prologue, scope);
                 merge_code(&mut buffer, &block_code, scope);
-                buffer.push(Goto(0));
-                // resolve breaks
+                let next_start = buffer.code.len();
+                merge_code(&mut buffer, &next_code, scope);
+                buffer.push(Goto(cond_start));
+                buffer.code[branch] = Branch(branch + 1, buffer.code.len());
+                // resolve breaks and continues
                 let mut i = 0;
                 while i < buffer.stops.len() {
-                    let identifier = match &buffer.stops[i].kind {
-                        CodeStops::Break(ident) => ident,
-                        _ => {
-                            i += 1;
-                            continue;
-                        }
-                    };
-                    if identifier != ident {
-                        i += 1;
-                        continue;
-                    }
-                    buffer.code[buffer.stops[i].pos] = Goto(buffer.code.len());
-                    buffer.stops.remove(i);
-                }
-                // resolve continues
-                i = 0;
-                while i < buffer.stops.len() {
-                    let identifier = match &buffer.stops[i].kind {
-                        CodeStops::Continue(ident) => ident,
+                    let target = match &buffer.stops[i].kind {
+                        CodeStops::Break(ident) if ident == label => buffer.code.len(),
+                        CodeStops::Continue(ident) if ident == label => next_start,
                         _ => {
                             i += 1;
                             continue;
                         }
                     };
-                    if identifier != ident {
-                        i += 1;
-                        continue;
-                    }
-                    buffer.code[buffer.stops[i].pos] = Goto(0);
+                    buffer.code[buffer.stops[i].pos] = Goto(target);
                     buffer.stops.remove(i);
                 }
-
+                other_scopes.pop();
                 merge_code(code, &buffer, scope);
-                if terminator != ScopeTerminator::None {
-                    return Ok((max_scope_len, ScopeTerminator::Return));
-                }
             }
-            crate::codeblock_parser::Nodes::For {
-                ident,
-                expr,
-                body,
-                line,
-                ident2,
-            } => todo!(),
             crate::codeblock_parser::Nodes::Return { expr, line } => {
                 use Instructions::*;
                 let mut expr_code = Code::new();
@@ -3519,6 +6445,7 @@ fn get_scope(
                 };
                 let this_fun = fun.get(objects)?;
                 if let Some(ret_type) = &this_fun.return_type {
+                    let ret_type = &substitute_generics(ret_type, generics);
                     let cmp = ret_type.cmp(&kind, generics);
                     if cmp.is_not_equal() {
                         return Err(CodegenError::VariableTypeMismatch(
@@ -3551,6 +6478,27 @@ fn get_scope(
                 return Ok((max_scope_len, ScopeTerminator::Return));
             }
             crate::codeblock_parser::Nodes::Expr { expr, line } => {
+                // arms of a match on its own may leave the function
+                if let ValueType::Value(value) = expr {
+                    if let (Root::Match(matchh), true) = (&value.root.0, value.is_true_simple()) {
+                        let (_, terminator) = gen_match(
+                            objects,
+                            matchh,
+                            other_scopes,
+                            code,
+                            context,
+                            &fun,
+                            &mut max_scope_len,
+                            None,
+                            true,
+                            generics,
+                        )?;
+                        if terminator != ScopeTerminator::None {
+                            return Ok((max_scope_len, terminator));
+                        }
+                        continue;
+                    }
+                }
                 expression(
                     objects,
                     expr,
@@ -3604,6 +6552,7 @@ fn get_scope(
                 let scope = open_scope!(body, &mut temp_code);
                 temp_code.push(Goto(0));
                 let mut i = 0;
+                let mut breaks = false;
                 // resolve breaks
                 while i < temp_code.stops.len() {
                     let identifier = match &temp_code.stops[i].kind {
@@ -3619,6 +6568,7 @@ fn get_scope(
                     }
                     temp_code.code[temp_code.stops[i].pos] = Goto(temp_code.code.len());
                     temp_code.stops.remove(i);
+                    breaks = true;
                 }
                 // resolve continues
                 i = 0;
@@ -3638,32 +6588,414 @@ fn get_scope(
                     temp_code.stops.remove(i);
                 }
                 merge_code(code, &temp_code, scope.0);
-                if scope.1 != ScopeTerminator::None {
+                // the code after the loop is reachable through a break
+                if !breaks && (scope.1 == ScopeTerminator::Return || scope.1 == ScopeTerminator::Yeet) {
                     return Ok((max_scope_len, ScopeTerminator::Return));
                 }
             }
-            crate::codeblock_parser::Nodes::Yeet { expr, line } => todo!(),
+            crate::codeblock_parser::Nodes::Yeet { expr, line } => {
+                use Instructions::*;
+                // `yeet Error`, `yeet Error(args)` or the same prefixed with an import
+                let mut path = vec![expr.0 .0.clone()];
+                let mut args = None;
+                for (node, _) in &expr.1 {
+                    match node {
+                        TailNodes::Nested(ident) if args.is_none() => path.push(ident.clone()),
+                        TailNodes::Call(call) if args.is_none() => args = Some(call.clone()),
+                        _ => Err(CodegenError::ErrorNotFound(path.join("."), line.clone()))?,
+                    }
+                }
+                let (file, ident) = error_path(objects, &path, &fun.file, line)?;
+                let field = |error: &dictionary::Error, ident: &str| {
+                    error.fields.iter().find_map(|(name, field)| match field {
+                        dictionary::ErrorField::Expression(expr) if name == ident => {
+                            Some(expr.clone())
+                        }
+                        _ => None,
+                    })
+                };
+                let (id, decl_args, args, message, code_field) =
+                    match find_error(objects, &ident, &file) {
+                        Some((id, Some(error))) => (
+                            id,
+                            error.args.clone(),
+                            match args {
+                                Some(call) => call
+                                    .resolve(&error.args, line)
+                                    .map_err(CodegenError::InvalidArgs)?
+                                    .args,
+                                None => vec![],
+                            },
+                            field(error, "message"),
+                            field(error, "code"),
+                        ),
+                        // built-in Error takes an optional message
+                        Some((id, None)) => {
+                            if let Some((name, _, line)) = args.as_ref().and_then(|call| call.named.first()) {
+                                Err(CodegenError::InvalidArgs(ArgError::UnknownName(
+                                    name.clone(),
+                                    *line,
+                                )))?;
+                            }
+                            let args = args.map(|call| call.args).unwrap_or_default();
+                            if args.len() > 1 {
+                                Err(CodegenError::IncorrectNumberOfArgs(
+                                    1,
+                                    args.len(),
+                                    line.clone(),
+                                ))?;
+                            }
+                            (id, vec![], vec![], args.first().cloned(), None)
+                        }
+                        None => Err(CodegenError::ErrorNotFound(path.join("."), line.clone()))?,
+                    };
+                let string_kind = Kind {
+                    body: TypeBody::Type {
+                        refs: 0,
+                        main: vec!["string".to_string()],
+                        generics: vec![],
+                        nullable: false,
+                        kind: dictionary::KindType::Primitive,
+                    },
+                    line: line.clone(),
+                    file: Some(fun.file.clone()),
+                };
+                let int_kind = Kind {
+                    body: TypeBody::Type {
+                        refs: 0,
+                        main: vec!["int".to_string()],
+                        generics: vec![],
+                        nullable: false,
+                        kind: dictionary::KindType::Primitive,
+                    },
+                    line: line.clone(),
+                    file: Some(fun.file.clone()),
+                };
+                // arguments are visible to the fields of the error
+                other_scopes.push(ScopeCached {
+                    variables: HashMap::new(),
+                });
+                let mut temp_code = Code::new();
+                if decl_args.len() != args.len() {
+                    Err(CodegenError::IncorrectNumberOfArgs(
+                        decl_args.len(),
+                        args.len(),
+                        line.clone(),
+                    ))?;
+                }
+                for (arg, decl) in args.iter().zip(decl_args.iter()) {
+                    let expected = correct_kind(objects, &decl.kind, &fun, line, generics)?;
+                    let kind = expression(
+                        objects,
+                        arg,
+                        other_scopes,
+                        &mut temp_code,
+                        context,
+                        &fun,
+                        &mut max_scope_len,
+                        Some(expected.clone()),
+                        *line,
+                        generics,
+                    )?;
+                    let cmp = expected.cmp(&kind, generics);
+                    if cmp.is_not_equal() {
+                        Err(CodegenError::ArgTypeMismatch(
+                            decl.clone(),
+                            kind,
+                            cmp,
+                            line.clone(),
+                        ))?;
+                    }
+                    let pos = declare!(decl.identifier.clone(), expected, line);
+                    temp_code.write(GENERAL_REG1, &pos);
+                }
+                let mut field_value = |value: Option<ValueType>,
+                                       expected: &Kind,
+                                       default: ConstValue,
+                                       temp_code: &mut Code,
+                                       other_scopes: &mut Vec<ScopeCached>,
+                                       max_scope_len: &mut usize|
+                 -> Result<(), CodegenError> {
+                    match value {
+                        Some(value) => {
+                            let kind = expression(
+                                objects,
+                                &value,
+                                other_scopes,
+                                temp_code,
+                                context,
+                                &fun,
+                                max_scope_len,
+                                Some(expected.clone()),
+                                *line,
+                                generics,
+                            )?;
+                            let cmp = expected.cmp(&kind, generics);
+                            if cmp.is_not_equal() {
+                                Err(CodegenError::VariableTypeMismatch(
+                                    expected.clone(),
+                                    kind,
+                                    cmp,
+                                    line.clone(),
+                                ))?;
+                            }
+                        }
+                        None => {
+                            let default = new_const(context, &default)?;
+                            temp_code.push(ReadConst(default, GENERAL_REG1));
+                        }
+                    }
+                    Ok(())
+                };
+                field_value(
+                    message,
+                    &string_kind,
+                    ConstValue::String(ident.clone()),
+                    &mut temp_code,
+                    other_scopes,
+                    &mut max_scope_len,
+                )?;
+                let msg = declare!("#msg".to_string(), string_kind.clone(), line);
+                temp_code.write(GENERAL_REG1, &msg);
+                field_value(
+                    code_field,
+                    &int_kind,
+                    ConstValue::Int(1),
+                    &mut temp_code,
+                    other_scopes,
+                    &mut max_scope_len,
+                )?;
+                let err_code = declare!("#code".to_string(), int_kind.clone(), line);
+                temp_code.write(GENERAL_REG1, &err_code);
+                // error object [id, message, code] is passed in RETURN_REG
+                let id = new_const(context, &ConstValue::Uint(id))?;
+                temp_code.extend(&[
+                    AllocateStatic(3),
+                    Move(POINTER_REG, RETURN_REG),
+                    ReadConst(id, GENERAL_REG1),
+                    IndexStatic(0),
+                    WritePtr(GENERAL_REG1),
+                    Move(RETURN_REG, POINTER_REG),
+                    IndexStatic(1),
+                ]);
+                temp_code.read(&msg, GENERAL_REG1);
+                temp_code.extend(&[
+                    WritePtr(GENERAL_REG1),
+                    Move(RETURN_REG, POINTER_REG),
+                    IndexStatic(2),
+                ]);
+                temp_code.read(&err_code, GENERAL_REG1);
+                temp_code.extend(&[WritePtr(GENERAL_REG1), Panic]);
+                other_scopes.pop();
+                merge_code(code, &temp_code, 0);
+                return Ok((max_scope_len, ScopeTerminator::Yeet));
+            }
             crate::codeblock_parser::Nodes::Try {
                 body,
                 catch,
                 finally,
                 line,
-            } => todo!(),
+            } => {
+                use Instructions::*;
+                let diverges = |terminator: &ScopeTerminator| {
+                    *terminator == ScopeTerminator::Return || *terminator == ScopeTerminator::Yeet
+                };
+                // the error and the return value are kept outside of the try body
+                other_scopes.push(ScopeCached {
+                    variables: HashMap::new(),
+                });
+                let error_kind = Kind::from_error("Error".to_string(), fun.file.clone(), *line);
+                let err = declare!("#err".to_string(), error_kind.clone(), line);
+                let ret_kind = fun.get(objects)?.return_type.clone().unwrap_or(Kind::void());
+                let ret = declare!("#ret".to_string(), ret_kind, line);
+                // finally is copied to every way out of the try
+                let mut finally_code = Code::new();
+                let finally_terminator = match finally {
+                    Some(finally) => open_scope!(finally, &mut finally_code).1,
+                    None => ScopeTerminator::None,
+                };
+                let mut body_code = Code::new();
+                let (_, body_terminator) = open_scope!(body, &mut body_code);
+                let mut buffer = Code::new();
+                // the instruction following the catch is the entry of the handler
+                buffer.extend(&[Catch, Goto(0)]);
+                merge_code(&mut buffer, &body_code, 0);
+                // (start, end, removes catch)
+                let mut regions = vec![(2, buffer.code.len(), true)];
+                buffer.push(DeleteCatch(1));
+                merge_code(&mut buffer, &finally_code, 0);
+                let mut ends = vec![buffer.code.len()];
+                buffer.push(Goto(0));
+                // handler
+                buffer.code[1] = Goto(buffer.code.len());
+                buffer.write(RETURN_REG, &err);
+                // (position of the jump, catch)
+                let mut branches = Vec::new();
+                let mut catch_all = false;
+                for (idx, catch) in catch.iter().enumerate() {
+                    if catch.kinds.is_empty() {
+                        branches.push((buffer.code.len(), idx));
+                        buffer.push(Goto(0));
+                        catch_all = true;
+                        break;
+                    }
+                    for path in &catch.kinds {
+                        let (file, ident) = error_path(objects, path, &fun.file, &catch.line)?;
+                        let id = match find_error(objects, &ident, &file) {
+                            Some((id, _)) => id,
+                            None => Err(CodegenError::ErrorNotFound(
+                                path.join("."),
+                                catch.line.clone(),
+                            ))?,
+                        };
+                        let id = new_const(context, &ConstValue::Uint(id))?;
+                        buffer.read(&err, POINTER_REG);
+                        buffer.extend(&[
+                            IndexStatic(0),
+                            ReadPtr(GENERAL_REG1),
+                            ReadConst(id, GENERAL_REG2),
+                            Equ(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1),
+                        ]);
+                        branches.push((buffer.code.len(), idx));
+                        buffer.push(Branch(0, buffer.code.len() + 1));
+                    }
+                }
+                // nothing matched, pass the error to the outer catch
+                if !catch_all {
+                    merge_code(&mut buffer, &finally_code, 0);
+                    buffer.read(&err, RETURN_REG);
+                    buffer.push(Panic);
+                }
+                let mut catches_diverge = true;
+                let mut starts = Vec::new();
+                // errors leaving a handler run finally too, (position of the jump to the rethrow)
+                let protects = !finally_code.code.is_empty();
+                let mut guards = Vec::new();
+                for catch in catch.iter() {
+                    let start = buffer.code.len();
+                    starts.push(start);
+                    if protects {
+                        guards.push(buffer.code.len() + 1);
+                        buffer.extend(&[Catch, Goto(0)]);
+                    }
+                    other_scopes.push(ScopeCached {
+                        variables: HashMap::new(),
+                    });
+                    if let Some(ident) = &catch.ident {
+                        let kind = match catch.kinds.as_slice() {
+                            [path] => {
+                                let (file, ident) =
+                                    error_path(objects, path, &fun.file, &catch.line)?;
+                                Kind::from_error(ident, file, catch.line.clone())
+                            }
+                            _ => error_kind.clone(),
+                        };
+                        let var = declare!(ident.clone(), kind, catch.line);
+                        buffer.read(&err, GENERAL_REG1);
+                        buffer.write(GENERAL_REG1, &var);
+                    }
+                    let mut catch_code = Code::new();
+                    let (_, terminator) = open_scope!(&catch.body, &mut catch_code);
+                    other_scopes.pop();
+                    merge_code(&mut buffer, &catch_code, 0);
+                    regions.push((start, buffer.code.len(), protects));
+                    if !diverges(&terminator) {
+                        catches_diverge = false;
+                    }
+                    if protects {
+                        buffer.push(DeleteCatch(1));
+                    }
+                    merge_code(&mut buffer, &finally_code, 0);
+                    ends.push(buffer.code.len());
+                    buffer.push(Goto(0));
+                }
+                if protects {
+                    let rethrow = buffer.code.len();
+                    buffer.write(RETURN_REG, &err);
+                    merge_code(&mut buffer, &finally_code, 0);
+                    buffer.read(&err, RETURN_REG);
+                    buffer.push(Panic);
+                    for pos in guards {
+                        buffer.code[pos] = Goto(rethrow);
+                    }
+                }
+                for (pos, idx) in branches {
+                    buffer.code[pos] = match buffer.code[pos] {
+                        Branch(_, next) => Branch(starts[idx], next),
+                        _ => Goto(starts[idx]),
+                    };
+                }
+                // returns, breaks and continues leaving the try remove the catch and run finally
+                for (start, end, removes_catch) in regions {
+                    if !removes_catch && finally_code.code.is_empty() {
+                        continue;
+                    }
+                    for pos in start..end {
+                        if let Return = buffer.code[pos] {
+                            let trampoline = buffer.code.len();
+                            buffer.write(RETURN_REG, &ret);
+                            if removes_catch {
+                                buffer.push(DeleteCatch(1));
+                            }
+                            merge_code(&mut buffer, &finally_code, 0);
+                            buffer.read(&ret, RETURN_REG);
+                            buffer.push(Return);
+                            buffer.code[pos] = Goto(trampoline);
+                        }
+                    }
+                    let mut i = 0;
+                    while i < buffer.stops.len() {
+                        let pos = buffer.stops[i].pos;
+                        if pos < start || pos >= end {
+                            i += 1;
+                            continue;
+                        }
+                        let stop = buffer.stops.remove(i);
+                        let trampoline = buffer.code.len();
+                        if removes_catch {
+                            buffer.push(DeleteCatch(1));
+                        }
+                        merge_code(&mut buffer, &finally_code, 0);
+                        buffer.stops.push(CodeStop {
+                            pos: buffer.code.len(),
+                            ..stop
+                        });
+                        buffer.push(Goto(0));
+                        buffer.code[pos] = Goto(trampoline);
+                    }
+                }
+                let end = buffer.code.len();
+                for pos in ends {
+                    buffer.code[pos] = Goto(end);
+                }
+                other_scopes.pop();
+                merge_code(code, &buffer, 0);
+                if diverges(&finally_terminator)
+                    || (diverges(&body_terminator) && catches_diverge)
+                {
+                    return Ok((max_scope_len, ScopeTerminator::Return));
+                }
+            }
             crate::codeblock_parser::Nodes::Switch {
                 expr,
                 body,
                 default,
                 line,
             } => {
-                todo!("stack offset is not correct, this needs fix");
                 use Instructions::*;
-                let mut expr_code = Code::new();
-                let mut all_return = true;
-                expression(
+                if body.is_empty() && default.is_none() {
+                    Err(CodegenError::SwitchWithoutCases(line.clone()))?;
+                }
+                // the switched value lives in its own scope
+                other_scopes.push(ScopeCached {
+                    variables: HashMap::new(),
+                });
+                let mut buffer = Code::new();
+                let kind = expression(
                     objects,
                     expr,
                     other_scopes,
-                    &mut expr_code,
+                    &mut buffer,
                     context,
                     &fun,
                     &mut max_scope_len,
@@ -3671,97 +7003,209 @@ fn get_scope(
                     *line,
                     generics,
                 )?;
-                // save expr to stack
-                let pos = create_var_pos(&other_scopes);
-                let cache = last!(other_scopes);
-                cache.variables.insert(
-                    max_scope_len.to_string(),
-                    Variable {
-                        kind: None,
-                        pos: pos.clone(),
-                        value: None,
-                        line: line.clone(),
-                    },
-                );
-                max_scope_len += 1;
-                expr_code.write(GENERAL_REG1, &pos);
-                if body.len() == 0 {
-                    match default {
-                        Some(default) => {
-                            let mut block_code = Code::new();
-                            let (_, terminator) = open_scope!(default, &mut block_code);
-                            merge_code(&mut code, &expr_code, 0);
-                            merge_code(&mut code, &block_code, 0);
-                            return Ok((max_scope_len, terminator));
+                let kind = correct_kind(objects, &kind, &fun, line, generics)?;
+                let value = declare!("#switch".to_string(), kind.clone(), line);
+                buffer.write(GENERAL_REG1, &value);
+                // variants of an enum as (name, value)
+                let variants = match &kind.body {
+                    TypeBody::Type {
+                        main,
+                        kind: dictionary::KindType::Enum,
+                        ..
+                    } => find_enum(objects, main.last().unwrap(), kind.file.as_ref().unwrap())
+                        .filter(|(_, enumm)| !enumm.has_payload())
+                        .map(|(_, enumm)| {
+                            enumm
+                                .keys
+                                .iter()
+                                .map(|key| (key.0.clone(), key.1))
+                                .collect::<Vec<_>>()
+                        }),
+                    TypeBody::Type {
+                        main,
+                        kind: dictionary::KindType::BEnum,
+                        ..
+                    } => find_benum(objects, main.last().unwrap(), kind.file.as_ref().unwrap())
+                        .map(|(_, enumm)| enumm.variants.clone()),
+                    _ => None,
+                };
+                let primitive = kind.into_const();
+                let is_string = kind.is_string();
+                if !is_string
+                    && variants.is_none()
+                    && !matches!(
+                        primitive,
+                        Some(ConstValue::Int(_) | ConstValue::Uint(_) | ConstValue::Char(_))
+                    )
+                {
+                    Err(CodegenError::CannotSwitch(kind.clone(), line.clone()))?;
+                }
+                // (values with their compile time value, body, terminator)
+                let mut cases = Vec::new();
+                let mut covered = Vec::new();
+                for (values, case_body) in body {
+                    let mut checks = Vec::new();
+                    for case in values {
+                        let mut case_code = Code::new();
+                        let case_kind = expression(
+                            objects,
+                            case,
+                            other_scopes,
+                            &mut case_code,
+                            context,
+                            &fun,
+                            &mut max_scope_len,
+                            Some(kind.clone()),
+                            *line,
+                            generics,
+                        )?;
+                        let cmp = kind.cmp(&case_kind, generics);
+                        if cmp.is_not_equal() {
+                            Err(CodegenError::VariableTypeMismatch(
+                                kind.clone(),
+                                case_kind,
+                                cmp,
+                                line.clone(),
+                            ))?;
+                        }
+                        let key = case_key(case, variants.as_ref());
+                        if let Some(key) = key {
+                            if covered.contains(&key) {
+                                Err(CodegenError::DuplicateCase(line.clone()))?;
+                            }
+                            covered.push(key);
+                        }
+                        checks.push((case_code, key));
+                    }
+                    let mut block_code = Code::new();
+                    let (_, terminator) = open_scope!(case_body, &mut block_code);
+                    cases.push((checks, block_code, terminator));
+                }
+                if let (Some(variants), None) = (&variants, default) {
+                    let missing = variants
+                        .iter()
+                        .filter(|variant| !covered.contains(&(variant.1 as i64)))
+                        .map(|variant| variant.0.clone())
+                        .collect::<Vec<_>>();
+                    if !missing.is_empty() {
+                        Err(CodegenError::SwitchNotExhaustive(missing, line.clone()))?;
+                    }
+                }
+                let mut default_code = Code::new();
+                let default_terminator = match default {
+                    Some(default) => open_scope!(default, &mut default_code).1,
+                    None => ScopeTerminator::None,
+                };
+                // (position of the jump, case)
+                let mut jumps = Vec::new();
+                let mut to_default = Vec::new();
+                let keys = cases
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(idx, case)| case.0.iter().map(move |check| (check.1, idx)))
+                    .collect::<Vec<_>>();
+                let min = keys.iter().filter_map(|key| key.0).min().unwrap_or(0);
+                let max = keys.iter().filter_map(|key| key.0).max().unwrap_or(0);
+                // a span that overflows is far too sparse for a table anyway
+                let span = max
+                    .checked_sub(min)
+                    .and_then(|span| span.checked_add(1))
+                    .and_then(|span| usize::try_from(span).ok());
+                let dense = !matches!(primitive, Some(ConstValue::Char(_)))
+                    && !is_string
+                    && keys.len() >= JUMP_TABLE_MIN_CASES
+                    && keys.iter().all(|key| key.0.is_some())
+                    && span.is_some_and(|span| span <= keys.len() * 2);
+                if dense {
+                    // jump table indexed by the value minus the smallest case
+                    let (min_const, max_const) = match primitive {
+                        Some(ConstValue::Int(_)) => (ConstValue::Int(min), ConstValue::Int(max)),
+                        _ => (ConstValue::Uint(min as usize), ConstValue::Uint(max as usize)),
+                    };
+                    let min_const = new_const(context, &min_const)?;
+                    let max_const = new_const(context, &max_const)?;
+                    for (bound, compare) in [
+                        (min_const, Less(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1)),
+                        (max_const, Grt(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1)),
+                    ] {
+                        buffer.read(&value, GENERAL_REG1);
+                        buffer.extend(&[ReadConst(bound, GENERAL_REG2), compare]);
+                        to_default.push(buffer.code.len());
+                        buffer.push(Branch(0, buffer.code.len() + 1));
+                    }
+                    buffer.read(&value, GENERAL_REG1);
+                    buffer.extend(&[
+                        ReadConst(min_const, GENERAL_REG2),
+                        Sub(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1),
+                        GotoOffset(GENERAL_REG1),
+                    ]);
+                    for key in min..=max {
+                        match keys.iter().find(|case| case.0 == Some(key)) {
+                            Some((_, idx)) => jumps.push((buffer.code.len(), *idx)),
+                            None => to_default.push(buffer.code.len()),
+                        }
+                        buffer.push(Goto(0));
+                    }
+                } else {
+                    for (idx, (checks, _, _)) in cases.iter().enumerate() {
+                        for (case_code, _) in checks {
+                            merge_code(&mut buffer, case_code, 0);
+                            buffer.read(&value, GENERAL_REG2);
+                            if is_string {
+                                buffer.extend(&[Cal(CORE_LIB, 3), Move(RETURN_REG, GENERAL_REG1)]);
+                            } else {
+                                buffer.push(Equ(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1));
+                            }
+                            jumps.push((buffer.code.len(), idx));
+                            buffer.push(Branch(0, buffer.code.len() + 1));
                         }
-                        None => Err(CodegenError::SwitchWithoutCases(line.clone()))?,
                     }
+                    to_default.push(buffer.code.len());
+                    buffer.push(Goto(0));
                 }
-                // after this point we know that there are cases
-                // body
-                let mut cases = Vec::new();
-                for case in body {
-                    let mut expr_code = Code::new();
-                    let mut block_code = Code::new();
-                    expression(
-                        objects,
-                        &case.0,
-                        other_scopes,
-                        &mut expr_code,
-                        context,
-                        &fun,
-                        &mut max_scope_len,
-                        None,
-                        *line,
-                        generics,
-                    )?;
-                    let (scope, terminator) = open_scope!(&case.1, &mut block_code);
-                    if terminator != ScopeTerminator::Return {
+                // bodies
+                let mut all_return = true;
+                let mut starts = Vec::new();
+                let mut ends = Vec::new();
+                for (_, block_code, terminator) in cases.iter() {
+                    starts.push(buffer.code.len());
+                    merge_code(&mut buffer, block_code, 0);
+                    ends.push(buffer.code.len());
+                    buffer.push(Goto(0));
+                    if *terminator != ScopeTerminator::Return && *terminator != ScopeTerminator::Yeet
+                    {
                         all_return = false;
                     }
-                    // compare
-                    expr_code.read(&pos, GENERAL_REG2);
-                    expr_code.extend(&[
-                        Equ(GENERAL_REG1, GENERAL_REG2, GENERAL_REG1),
-                        Branch(
-                            expr_code.code.len() + 2, // +2 because of the branch itself
-                            expr_code.code.len() + block_code.code.len() + 3, // +3 because of the branch itself and the goto at the end
-                        ),
-                    ]);
-                    block_code.push(End); // placeholder for goto
-                    cases.push((expr_code, block_code, scope));
                 }
-                let largest_scope = *cases.iter().map(|(_, _, scope)| scope).max().unwrap();
-                let mut total_len = cases
-                    .iter()
-                    .map(|(a, b, _)| a.code.len() + b.code.len())
-                    .sum::<usize>()
-                    + expr_code.code.len();
-                // default
-                let defualt = if let Some(default) = default {
-                    let mut block_code = Code::new();
-                    let (_, terminator) = open_scope!(default, &mut block_code);
-                    if terminator != ScopeTerminator::Return {
-                        all_return = false;
+                let default_start = buffer.code.len();
+                merge_code(&mut buffer, &default_code, 0);
+                let jumps = jumps
+                    .into_iter()
+                    .map(|(pos, idx)| (pos, starts[idx]))
+                    .chain(to_default.into_iter().map(|pos| (pos, default_start)))
+                    .chain(ends.into_iter().map(|pos| (pos, usize::MAX)))
+                    .collect::<Vec<_>>();
+                let end = buffer.code.len();
+                for (pos, target) in jumps {
+                    let target = if target == usize::MAX { end } else { target };
+                    buffer.code[pos] = match buffer.code[pos] {
+                        Branch(_, next) => Branch(target, next),
+                        _ => Goto(target),
+                    };
+                }
+                other_scopes.pop();
+                merge_code(code, &buffer, 0);
+                // without default every variant of the enum is covered
+                let default_returns = match default {
+                    Some(_) => {
+                        default_terminator == ScopeTerminator::Return
+                            || default_terminator == ScopeTerminator::Yeet
                     }
-                    total_len += block_code.code.len();
-                    block_code
-                } else {
-                    Code::new()
+                    None => variants.is_some(),
                 };
-                // merge
-                let mut buffer = Code::new();
-                for (expr_code, block_code, scope) in cases.iter_mut() {
-                    block_code.push(Goto(total_len + 1)); // +1 because of the goto itself
-                    merge_code(&mut buffer, &expr_code, *scope);
-                    merge_code(&mut buffer, &block_code, *scope);
-                }
-                merge_code(&mut buffer, &defualt, largest_scope);
-                merge_code(&mut code, &buffer, 0);
-                if all_return {
+                if all_return && default_returns {
                     return Ok((max_scope_len, ScopeTerminator::Return));
                 }
-                return Ok((max_scope_len, ScopeTerminator::None));
             }
             crate::codeblock_parser::Nodes::Set {
                 target,
@@ -3804,7 +7248,7 @@ fn get_scope(
                                     context,
                                     &fun,
                                     &mut max_scope_len,
-                                    var.kind.clone(),
+                                    set_expected(op, var.kind.clone()),
                                     *line,
                                     generics,
                                 )?;
@@ -3813,7 +7257,7 @@ fn get_scope(
                                 } else {
                                     conclusion_code.read(&var.pos, GENERAL_REG2);
                                     conclusion_code.push(Swap(GENERAL_REG1, GENERAL_REG2));
-                                    match native_operand(
+                                    operand(
                                         objects,
                                         &op,
                                         &var.kind.clone().unwrap(),
@@ -3823,12 +7267,7 @@ fn get_scope(
                                         &fun,
                                         &line,
                                         generics,
-                                    ) {
-                                        Some(_) => (),
-                                        None => {
-                                            unreachable!("non native operand, this is a bug in the compiler, please report it");
-                                        }
-                                    };
+                                    )?;
                                     conclusion_code.write(GENERAL_REG1, &var.pos);
                                 }
                                 merge_code(code, &target_code, 0);
@@ -3837,18 +7276,12 @@ fn get_scope(
                             }
                             Position::Pointer(kind) => {
                                 // save pointer to temp var
-                                let temp_var = create_var_pos(other_scopes);
-                                let cache = last!(other_scopes);
-                                cache.variables.insert(
-                                    max_scope_len.to_string(),
-                                    Variable {
-                                        kind: Some(kind.clone()),
-                                        pos: temp_var.clone(),
-                                        value: None,
-                                        line: val.root.1.clone(),
-                                    },
+                                let temp_var = temp_var(
+                                    other_scopes,
+                                    &mut max_scope_len,
+                                    Some(kind.clone()),
+                                    &val.root.1,
                                 );
-                                max_scope_len += 1;
                                 target_code.write(POINTER_REG, &temp_var);
                                 let expr = expression(
                                     objects,
@@ -3858,7 +7291,7 @@ fn get_scope(
                                     context,
                                     &fun,
                                     &mut max_scope_len,
-                                    Some(kind.clone()),
+                                    set_expected(op, Some(kind.clone())),
                                     *line,
                                     generics,
                                 )?;
@@ -3869,7 +7302,7 @@ fn get_scope(
                                     conclusion_code.read(&temp_var, POINTER_REG);
                                     conclusion_code.push(ReadPtr(GENERAL_REG2));
                                     conclusion_code.push(Swap(GENERAL_REG1, GENERAL_REG2));
-                                    match native_operand(
+                                    operand(
                                         objects,
                                         &op,
                                         &kind.clone(),
@@ -3879,12 +7312,7 @@ fn get_scope(
                                         &fun,
                                         &line,
                                         generics,
-                                    ) {
-                                        Some(_) => (),
-                                        None => {
-                                            unreachable!("non native operand, this is a bug in the compiler, please report it");
-                                        }
-                                    };
+                                    )?;
                                     conclusion_code.read(&temp_var, POINTER_REG);
                                     conclusion_code.extend(&[WritePtr(GENERAL_REG1)]);
                                 }
@@ -3908,6 +7336,17 @@ fn get_scope(
     Ok((max_scope_len, ScopeTerminator::None))
 }
 
+/// Kind the value of an assignment is evaluated as
+///
+/// Compound assignments to structs go through overloads that pick the kind of the value themselves
+fn set_expected(op: &Operators, target: Option<Kind>) -> Option<Kind> {
+    match (op, target) {
+        (Operators::Equal, target) => target,
+        (_, Some(target)) if target.check_type(&dictionary::KindType::Struct) => None,
+        (_, target) => target,
+    }
+}
+
 fn create_var_pos(scopes: &Vec<ScopeCached>) -> MemoryTypes {
     let len = {
         let mut len = 0;
@@ -3919,6 +7358,35 @@ fn create_var_pos(scopes: &Vec<ScopeCached>) -> MemoryTypes {
     MemoryTypes::Stack(len + 0)
 }
 
+/// returns the value of a switch case if it is known at compile time
+fn case_key(value: &ValueType, variants: Option<&Vec<(String, usize)>>) -> Option<i64> {
+    let value = match value {
+        ValueType::Expression(expr) if expr.operator.is_none() && expr.right.is_none() => {
+            return case_key(expr.left.as_ref()?, variants)
+        }
+        ValueType::Value(value) => value,
+        _ => return None,
+    };
+    let sign = match value.unary.as_slice() {
+        [] => 1,
+        [(Operators::Minus, _)] => -1,
+        _ => return None,
+    };
+    match (&value.root.0, value.tail.as_slice(), variants) {
+        (
+            Root::Literal(expression_parser::Literals::Number(tokenizer::Tokens::Number(num, _))),
+            [],
+            None,
+        ) => Some(sign * *num as i64),
+        (Root::Literal(expression_parser::Literals::Char(char)), [], None) => Some(*char as i64),
+        (Root::Identifier(_), [.., (TailNodes::Nested(variant), _)], Some(variants)) => variants
+            .iter()
+            .find(|(name, _)| name == variant)
+            .map(|(_, value)| *value as i64),
+        _ => None,
+    }
+}
+
 /// TODO: todo xd
 fn try_get_const_val() -> Option<ConstValue> {
     None
@@ -4169,7 +7637,6 @@ impl Code {
 pub enum CompoundField {
     Field(String),
     Method(String),
-    OverloadedOperator(Operators),
     TraitMethod(String, String),
 }
 
@@ -4210,7 +7677,7 @@ impl Position {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InnerPath {
     pub file: String,
     pub block: Option<String>,
@@ -4239,9 +7706,7 @@ impl InnerPath {
                 for structt in file.structs.iter_mut() {
                     if &structt.identifier == b {
                         for fun in structt.functions.iter_mut() {
-                            if fun.identifier.as_ref().unwrap() == &self.ident
-                                || fun.identifier.as_ref().unwrap() == b
-                            {
+                            if fun.identifier.as_ref().unwrap() == &self.ident {
                                 return Ok(fun);
                             }
                         }
@@ -4277,6 +7742,16 @@ impl InnerPath {
                         }
                     }
                 }
+                // signatures of trait methods
+                for traitt in file.traits.iter() {
+                    if &traitt.identifier == b {
+                        for fun in traitt.methods.iter() {
+                            if fun.identifier.as_ref() == Some(&self.ident) {
+                                return Ok(fun);
+                            }
+                        }
+                    }
+                }
                 Err(CodegenError::FunctionNotFound(self.clone()))
             }
             None => {
@@ -4843,6 +8318,19 @@ fn native_unary_operand(
     if op.is_none() {
         return Ok(kind.clone());
     }
+    if let Some(overload) = find_overload(objects, kind, &op.unwrap().0, None, line)? {
+        return call_overload(
+            objects, &overload, None, code, context, fun, line, register, generics,
+        );
+    }
+    if kind.check_type(&dictionary::KindType::Struct) {
+        Err(CodegenError::OverloadNotFound(
+            kind.clone(),
+            op.unwrap().0,
+            None,
+            op.unwrap().1,
+        ))?;
+    }
     match op.unwrap().0 {
         Operators::Not => {
             if kind.is_primitive_simple() {
@@ -4917,6 +8405,266 @@ fn native_unary_operand(
     }
 }
 
+/// Operator overload picked for the operands of an operator
+pub(crate) struct OverloadCall {
+    /// method the overload was turned into
+    pub path: InnerPath,
+    /// kind the right operand is cast to
+    pub arg: Option<Kind>,
+    pub return_kind: Kind,
+    /// generics of the struct and of the overload
+    pub bindings: HashMap<String, Kind>,
+}
+
+/// Finds the overload of `op` on the struct `left` by the kind of the right operand
+///
+/// Overloads that take exactly `right` win over overloads `right` can be cast to,
+/// unary operators are looked up with `right` set to None
+pub(crate) fn find_overload(
+    objects: &Context,
+    left: &Kind,
+    op: &Operators,
+    right: Option<&Kind>,
+    line: &Line,
+) -> Result<Option<OverloadCall>, CodegenError> {
+    let (ident, owner) = match &left.body {
+        TypeBody::Type {
+            main,
+            generics,
+            kind: dictionary::KindType::Struct,
+            ..
+        } => (main.last().unwrap(), generics),
+        _ => return Ok(None),
+    };
+    let file = left.file.as_deref().unwrap_or_default();
+    let structt = match find_struct(objects, ident, file) {
+        Some((_, structt)) => structt,
+        None => return Ok(None),
+    };
+    let mut exact = Vec::new();
+    let mut castable = Vec::new();
+    for (idx, overload) in structt.overloads.iter().enumerate() {
+        if overload.operator != tokenizer::Tokens::Operator(*op) || overload.arg.is_some() != right.is_some() {
+            continue;
+        }
+        let path = InnerPath {
+            file: file.to_string(),
+            block: Some(structt.identifier.clone()),
+            ident: dictionary::Overload::method_ident(idx),
+            kind: ImportKinds::Rd,
+        };
+        let mut bindings = HashMap::new();
+        for (decl, kind) in structt.generics.iter().zip(owner.iter()) {
+            bindings.insert(decl.identifier.clone(), kind.clone());
+        }
+        let arg = match (&overload.arg, right) {
+            (Some(arg), Some(right)) => {
+                let names = overload
+                    .generics
+                    .iter()
+                    .map(|decl| decl.identifier.clone())
+                    .collect::<Vec<_>>();
+                infer_generics(&arg.kind, right, &names, &mut bindings);
+                if has_unbound_generics(&arg.kind, &names, &bindings) {
+                    continue;
+                }
+                Some(correct_kind(objects, &arg.kind, &path, line, &bindings)?)
+            }
+            _ => None,
+        };
+        let return_kind = match &overload.return_type {
+            Some(kind) => correct_kind(objects, kind, &path, line, &bindings)?,
+            None => Kind {
+                body: TypeBody::Type {
+                    refs: 0,
+                    main: vec!["null".to_string()],
+                    generics: vec![],
+                    nullable: false,
+                    kind: dictionary::KindType::Primitive,
+                },
+                line: line.clone(),
+                file: Some(file.to_string()),
+            },
+        };
+        let found = OverloadCall {
+            path,
+            arg: arg.clone(),
+            return_kind,
+            bindings,
+        };
+        match (arg, right) {
+            (Some(arg), Some(right)) if arg.cmp(right, &found.bindings).is_equal() => {
+                exact.push(found)
+            }
+            (Some(arg), Some(right)) if castable_to(objects, right, &arg) => castable.push(found),
+            (None, None) => exact.push(found),
+            _ => (),
+        }
+    }
+    let candidates = match exact.is_empty() {
+        true => castable,
+        false => exact,
+    };
+    if candidates.len() > 1 {
+        Err(CodegenError::AmbiguousOverload(
+            left.clone(),
+            *op,
+            right.cloned(),
+            line.clone(),
+        ))?;
+    }
+    Ok(candidates.into_iter().next())
+}
+
+/// Whether `cast` can turn a value of kind `from` into `to`
+fn castable_to(objects: &Context, from: &Kind, to: &Kind) -> bool {
+    if from.is_null() {
+        return to.get_nullable();
+    }
+    if from.is_array() && to.is_array() {
+        return true;
+    }
+    if implements_trait(objects, from, to) {
+        return true;
+    }
+    let is_enum = |kind: &Kind| is_number_enum(objects, kind);
+    if (is_enum(from) && to.is_number()) || (from.is_number() && is_enum(to)) {
+        return true;
+    }
+    let is_value = |kind: &Kind| kind.is_number() || kind.is_bool();
+    from.is_primitive_simple()
+        && to.is_primitive_simple()
+        && (to.is_string() || (is_value(from) && is_value(to)))
+}
+
+/// Calls an operator overload with self in `register` and the right operand in reg2
+///
+/// The result is put in `register`
+fn call_overload(
+    objects: &mut Context,
+    overload: &OverloadCall,
+    right: Option<&Kind>,
+    code: &mut Code,
+    context: &mut runtime_types::Context,
+    fun: &InnerPath,
+    line: &Line,
+    register: usize,
+    generics: &HashMap<String, Kind>,
+) -> Result<Kind, CodegenError> {
+    use Instructions::*;
+    if let (Some(arg), Some(right)) = (&overload.arg, right) {
+        if cast(
+            objects,
+            right,
+            arg,
+            code,
+            context,
+            fun,
+            line,
+            GENERAL_REG2,
+            generics,
+        )
+        .is_none()
+        {
+            Err(CodegenError::CouldNotCastTo(
+                arg.clone(),
+                right.clone(),
+                line.clone(),
+            ))?;
+        }
+    }
+    // overloads of generic structs are called through a copy made for the concrete kinds
+    let path = match generic_decls(objects, &overload.path)?.is_empty() {
+        true => overload.path.clone(),
+        false => specialize(objects, &overload.path, context, &overload.bindings, line)?,
+    };
+    let id = path.get(objects)?.id;
+    code.extend(&[OpenArgs, Freeze, WriteArg(0, register)]);
+    if overload.arg.is_some() {
+        code.push(WriteArg(1, GENERAL_REG2));
+    }
+    code.extend(&[Jump(id), Unfreeze, Move(RETURN_REG, register)]);
+    Ok(overload.return_kind.clone())
+}
+
+/// Applies a binary operator to reg1 and reg2 and puts the result in reg1
+///
+/// Operators that are not native to the kinds are looked up in the overloads of the struct on the left,
+/// compound assignments fall back to the plain operator and `!=` to the negated `==`
+fn operand(
+    objects: &mut Context,
+    op: &Operators,
+    left: &Kind,
+    right: &Kind,
+    code: &mut Code,
+    context: &mut runtime_types::Context,
+    fun: &InnerPath,
+    line: &Line,
+    generics: &HashMap<String, Kind>,
+) -> Result<Kind, CodegenError> {
+    use Instructions::*;
+    if let Some(kind) = native_operand(objects, op, left, right, code, context, fun, line, generics) {
+        return Ok(kind);
+    }
+    let plain = match op {
+        Operators::AddEq => Some(Operators::Plus),
+        Operators::SubEq => Some(Operators::Minus),
+        Operators::MulEq => Some(Operators::Star),
+        Operators::DivEq => Some(Operators::Slash),
+        _ => None,
+    };
+    if let Some(overload) = find_overload(objects, left, op, Some(right), line)? {
+        return call_overload(
+            objects,
+            &overload,
+            Some(right),
+            code,
+            context,
+            fun,
+            line,
+            GENERAL_REG1,
+            generics,
+        );
+    }
+    if let Some(plain) = plain {
+        return operand(objects, &plain, left, right, code, context, fun, line, generics);
+    }
+    if let Operators::NotEqual = op {
+        if let Some(overload) = find_overload(objects, left, &Operators::DoubleEq, Some(right), line)? {
+            if overload.return_kind.is_bool() {
+                let kind = call_overload(
+                    objects,
+                    &overload,
+                    Some(right),
+                    code,
+                    context,
+                    fun,
+                    line,
+                    GENERAL_REG1,
+                    generics,
+                )?;
+                code.push(Not(GENERAL_REG1, GENERAL_REG1));
+                return Ok(kind);
+            }
+        }
+    }
+    if left.check_type(&dictionary::KindType::Struct) {
+        Err(CodegenError::OverloadNotFound(
+            left.clone(),
+            *op,
+            Some(right.clone()),
+            line.clone(),
+        ))?;
+    }
+    // reachable in generic code that was specialized for a kind without the operator
+    Err(CodegenError::InvalidOperator(
+        left.clone(),
+        right.clone(),
+        *op,
+        line.clone(),
+    ))
+}
+
 fn cast(
     _objects: &mut Context,
     from: &Kind,
@@ -4931,7 +8679,7 @@ fn cast(
     use Instructions::*;
     let from = &correct_kind(_objects, from, _fun, _line, generics).ok()?;
     let to = &correct_kind(_objects, to, _fun, _line, generics).ok()?;
-    if from.check_type(&dictionary::KindType::Enum) && to.is_number() {
+    if is_number_enum(_objects, from) && to.is_number() {
         return cast(
             _objects,
             &Kind {
@@ -4954,7 +8702,7 @@ fn cast(
             generics,
         );
     }
-    if from.is_number() && to.check_type(&dictionary::KindType::Enum) {
+    if from.is_number() && is_number_enum(_objects, to) {
         return cast(
             _objects,
             from,
@@ -4989,6 +8737,10 @@ fn cast(
     if from.cmp(to, generics).is_equal() {
         return Some(());
     }
+    // trait objects are the same pointer as the struct
+    if implements_trait(_objects, from, to) {
+        return Some(());
+    }
     if from.is_primitive_simple() && to.is_primitive_simple() {
         if to.is_string() {
             if register != GENERAL_REG2 {
@@ -5021,7 +8773,7 @@ enum ScopeTerminator {
     None,
 }
 
-fn correct_kind(
+pub(crate) fn correct_kind(
     objects: &Context,
     _kind: &Kind,
     fun: &InnerPath,
@@ -5055,9 +8807,15 @@ fn correct_kind(
                             line: line.clone(),
                             file: Some(fun.file.clone()),
                         });
-                    } else {
-                        unreachable!("this is a bug in the compiler, please report it");
                     }
+                    // generic bound to a concrete kind in a specialized function
+                    let mut real = _kind.clone();
+                    *real.refs_mut() += *refs;
+                    if *nullable {
+                        *real.nullable_mut() = true;
+                    }
+                    real.line = line.clone();
+                    return Ok(real);
                 }
             }
         }
@@ -5091,13 +8849,224 @@ fn correct_kind(
                 };
             }
             file.ident = main.last().unwrap().clone();
-            let mut real = get_kind(objects, &file, line)?;
+            if main.len() > 1 && !is_public(objects, &file.ident, &file.file) {
+                Err(CodegenError::NotPublic(
+                    file.ident.clone(),
+                    file.file.clone(),
+                    line.clone(),
+                ))?;
+            }
+            let mut real = match get_kind(objects, &file, line) {
+                Ok(real) => real,
+                Err(err) => match find_use(objects, &file.ident, &file.file)
+                    .map(|(module, item)| (module.to_string(), item.to_string()))
+                {
+                    Some((module, item)) if main.len() == 1 => {
+                        if !is_public(objects, &item, &module) {
+                            return Err(CodegenError::NotPublic(item, module, line.clone()));
+                        }
+                        file.file = module;
+                        file.ident = item;
+                        get_kind(objects, &file, line)?
+                    }
+                    _ => Err(err)?,
+                },
+            };
             *real.refs_mut() = *refs;
             *real.nullable_mut() = *nullable;
             real.line = line.clone();
+            if let (
+                TypeBody::Type {
+                    generics: written, ..
+                },
+                TypeBody::Type { generics: real, .. },
+            ) = (&_kind.body, &mut real.body)
+            {
+                for generic in written {
+                    real.push(correct_kind(objects, generic, fun, line, generics)?);
+                }
+            }
             Ok(real)
         }
-        _ => Ok(_kind.clone()),
+        TypeBody::Tuple {
+            types,
+            refs,
+            nullable,
+        } => {
+            let mut corrected = Vec::new();
+            for kind in types {
+                corrected.push(correct_kind(objects, kind, fun, line, generics)?);
+            }
+            Ok(Kind {
+                body: TypeBody::Tuple {
+                    types: corrected,
+                    refs: *refs,
+                    nullable: *nullable,
+                },
+                line: _kind.line,
+                file: _kind.file.clone(),
+            })
+        }
+        _ => Ok(substitute_generics(_kind, generics)),
+    }
+}
+
+/// Replaces generics bound to concrete kinds anywhere inside of the kind
+pub(crate) fn substitute_generics(kind: &Kind, generics: &HashMap<String, Kind>) -> Kind {
+    let mut result = kind.clone();
+    match &mut result.body {
+        TypeBody::Type {
+            refs,
+            main,
+            generics: args,
+            nullable,
+            ..
+        } => {
+            if main.len() == 1 {
+                if let Some(bound) = generics.get(&main[0]) {
+                    if !matches!(bound.body, TypeBody::Generic { .. }) {
+                        let mut bound = bound.clone();
+                        *bound.refs_mut() += *refs;
+                        if *nullable {
+                            *bound.nullable_mut() = true;
+                        }
+                        bound.line = kind.line;
+                        return bound;
+                    }
+                }
+            }
+            for arg in args.iter_mut() {
+                *arg = substitute_generics(arg, generics);
+            }
+        }
+        TypeBody::Array { type_, .. } => {
+            **type_ = substitute_generics(type_, generics);
+        }
+        TypeBody::Tuple { types, .. } => {
+            for kind in types.iter_mut() {
+                *kind = substitute_generics(kind, generics);
+            }
+        }
+        TypeBody::Function {
+            args, return_type, ..
+        } => {
+            for arg in args.iter_mut() {
+                arg.kind = substitute_generics(&arg.kind, generics);
+            }
+            if let Some(kind) = return_type.as_mut() {
+                *kind = substitute_generics(kind, generics);
+            }
+        }
+        TypeBody::Generic {
+            identifier,
+            refs,
+            nullable,
+            ..
+        } => {
+            if let Some(bound) = generics.get(identifier) {
+                if !matches!(bound.body, TypeBody::Generic { .. }) {
+                    let mut bound = bound.clone();
+                    *bound.refs_mut() += *refs;
+                    if *nullable {
+                        *bound.nullable_mut() = true;
+                    }
+                    bound.line = kind.line;
+                    return bound;
+                }
+            }
+        }
+        TypeBody::Void => (),
+    }
+    result
+}
+
+/// Binds generics used in the declared kind to the parts of the kind that was passed in
+fn infer_generics(
+    declared: &Kind,
+    got: &Kind,
+    names: &[String],
+    bindings: &mut HashMap<String, Kind>,
+) {
+    let bind = |ident: &String, refs: usize, bindings: &mut HashMap<String, Kind>| {
+        if !names.contains(ident) || bindings.contains_key(ident) || got.is_null() {
+            return;
+        }
+        let mut kind = got.clone();
+        *kind.refs_mut() = kind.get_refs().saturating_sub(refs);
+        if kind.get_nullable() {
+            *kind.nullable_mut() = false;
+        }
+        bindings.insert(ident.clone(), kind);
+    };
+    match (&declared.body, &got.body) {
+        (
+            TypeBody::Generic {
+                identifier, refs, ..
+            },
+            _,
+        ) => bind(identifier, *refs, bindings),
+        (
+            TypeBody::Type {
+                main,
+                refs,
+                generics,
+                ..
+            },
+            _,
+        ) => {
+            if main.len() == 1 && names.contains(&main[0]) {
+                bind(&main[0], *refs, bindings);
+                return;
+            }
+            if let TypeBody::Type {
+                generics: generics2,
+                ..
+            } = &got.body
+            {
+                for (declared, got) in generics.iter().zip(generics2.iter()) {
+                    infer_generics(declared, got, names, bindings);
+                }
+            }
+        }
+        (TypeBody::Array { type_, .. }, TypeBody::Array { type_: type_2, .. }) => {
+            infer_generics(type_, type_2, names, bindings);
+        }
+        (TypeBody::Tuple { types, .. }, TypeBody::Tuple { types: types_2, .. }) => {
+            for (declared, got) in types.iter().zip(types_2.iter()) {
+                infer_generics(declared, got, names, bindings);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Whether a declared kind mentions a generic that is not bound yet
+fn has_unbound_generics(kind: &Kind, names: &[String], bindings: &HashMap<String, Kind>) -> bool {
+    match &kind.body {
+        TypeBody::Type { main, generics, .. } => {
+            (main.len() == 1 && names.contains(&main[0]) && !bindings.contains_key(&main[0]))
+                || generics
+                    .iter()
+                    .any(|generic| has_unbound_generics(generic, names, bindings))
+        }
+        TypeBody::Generic { identifier, .. } => {
+            names.contains(identifier) && !bindings.contains_key(identifier)
+        }
+        TypeBody::Array { type_, .. } => has_unbound_generics(type_, names, bindings),
+        TypeBody::Tuple { types, .. } => types
+            .iter()
+            .any(|kind| has_unbound_generics(kind, names, bindings)),
+        TypeBody::Function {
+            args, return_type, ..
+        } => {
+            args.iter()
+                .any(|arg| has_unbound_generics(&arg.kind, names, bindings))
+                || return_type
+                    .as_ref()
+                    .as_ref()
+                    .is_some_and(|kind| has_unbound_generics(kind, names, bindings))
+        }
+        _ => false,
     }
 }
 
@@ -0,0 +1,729 @@
+//! Errors of every compiler stage turned into one shape,
+//! printed as annotated source excerpts or as JSON lines for tools
+
+use crate::codegen::CodegenError;
+use crate::expression_parser::{ArgError, TreeTransformError};
+use crate::grammar_check::GrammarError;
+use crate::intermediate::AnalyzationError::ErrType;
+use crate::intermediate::dictionary::TypeComparison;
+use crate::lexer::tokenizer::Tokens;
+use crate::lexing_preprocessor::parse_err::Errors;
+use crate::lint::Warning;
+use crate::tree_walker::tree_walker::{Err, Line};
+use crate::typecheck::TypeError;
+use crate::{ErrorOrigin, LinkingError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// how diagnostics are written out, `--message-format`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageFormat {
+    /// source excerpts with carets
+    Human,
+    /// one JSON object per line
+    Json,
+}
+
+impl std::str::FromStr for MessageFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(MessageFormat::Human),
+            "json" => Ok(MessageFormat::Json),
+            _ => Err(format!("unknown message format '{s}', expected human or json")),
+        }
+    }
+}
+
+/// place in the source with a short explanation
+#[derive(Debug, Clone)]
+pub struct Label {
+    pub line: Line,
+    pub message: String,
+}
+
+impl Label {
+    /// byte range of `source` the label points at
+    pub fn span(&self, source: &str) -> Option<(usize, usize)> {
+        span(source, &self.line)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// stable identifier of the error, like `E0610`
+    pub code: &'static str,
+    pub message: String,
+    /// file the labels point into
+    pub file: String,
+    pub primary: Option<Label>,
+    pub secondary: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            file: String::new(),
+            primary: None,
+            secondary: Vec::new(),
+            notes: Vec::new(),
+            help: None,
+        }
+    }
+    pub fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(code, message)
+        }
+    }
+    pub fn in_file(mut self, file: &str) -> Self {
+        self.file = file.to_string();
+        self
+    }
+    /// points the diagnostic at `line` and drops the location the message ends with
+    pub fn at(mut self, line: Option<Line>) -> Self {
+        if let Some(line) = line {
+            for suffix in [format!(" at {line}"), format!(" at line {line}")] {
+                if let Some(message) = self.message.strip_suffix(&suffix) {
+                    self.message = message.to_string();
+                }
+            }
+            self.primary = Some(Label {
+                line,
+                message: String::new(),
+            });
+        }
+        self
+    }
+    pub fn with_label(mut self, message: impl Into<String>) -> Self {
+        if let Some(primary) = &mut self.primary {
+            primary.message = message.into();
+        }
+        self
+    }
+    pub fn with_secondary(mut self, line: Line, message: impl Into<String>) -> Self {
+        self.secondary.push(Label {
+            line,
+            message: message.into(),
+        });
+        self
+    }
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// annotated excerpt of `source`, without it only the locations are printed
+    pub fn render(&self, source: Option<&str>) -> String {
+        let mut labels = Vec::new();
+        if let Some(primary) = &self.primary {
+            labels.push((primary, '^'));
+        }
+        for label in &self.secondary {
+            labels.push((label, '-'));
+        }
+        let width = labels
+            .iter()
+            .map(|(label, _)| label.line.line.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let pad = " ".repeat(width);
+
+        let mut out = format!("{}[{}]: {}\n", self.severity, self.code, self.message);
+        match &self.primary {
+            Some(primary) if primary.line.line > 0 => out.push_str(&format!(
+                "{pad}--> {}:{}:{}\n",
+                self.file, primary.line.line, primary.line.column
+            )),
+            _ if !self.file.is_empty() => out.push_str(&format!("{pad}--> {}\n", self.file)),
+            _ => {}
+        }
+        if let Some(source) = source {
+            for (label, mark) in &labels {
+                let Some((text, column, len)) = excerpt(source, &label.line) else {
+                    continue;
+                };
+                out.push_str(&format!("{pad} |\n"));
+                out.push_str(&format!("{:>width$} | {text}\n", label.line.line));
+                out.push_str(&format!(
+                    "{pad} | {}{}",
+                    " ".repeat(column),
+                    mark.to_string().repeat(len)
+                ));
+                if !label.message.is_empty() {
+                    out.push_str(&format!(" {}", label.message));
+                }
+                out.push('\n');
+            }
+        } else {
+            for label in &self.secondary {
+                out.push_str(&format!("{pad} = {} at {}\n", label.message, label.line));
+            }
+        }
+        for note in &self.notes {
+            out.push_str(&format!("{pad} = note: {note}\n"));
+        }
+        if let Some(help) = &self.help {
+            out.push_str(&format!("{pad} = help: {help}\n"));
+        }
+        out
+    }
+
+    /// single line JSON object, spans are byte offsets into the file
+    pub fn to_json(&self, source: Option<&str>) -> String {
+        let mut labels = Vec::new();
+        if let Some(primary) = &self.primary {
+            labels.push(json_label(primary, true, source));
+        }
+        for label in &self.secondary {
+            labels.push(json_label(label, false, source));
+        }
+        let notes: Vec<String> = self.notes.iter().map(|note| json_string(note)).collect();
+        format!(
+            "{{\"severity\":\"{}\",\"code\":\"{}\",\"message\":{},\"file\":{},\"labels\":[{}],\"notes\":[{}],\"help\":{}}}",
+            self.severity,
+            self.code,
+            json_string(&self.message),
+            json_string(&self.file),
+            labels.join(","),
+            notes.join(","),
+            match &self.help {
+                Some(help) => json_string(help),
+                None => String::from("null"),
+            }
+        )
+    }
+}
+
+/// prints diagnostics to stdout, sources are read from the files they point into
+///
+/// files of modules are relative to `root`, the directory of the main file
+pub fn emit(diagnostics: &[Diagnostic], format: MessageFormat, root: &std::path::Path) {
+    for diagnostic in diagnostics {
+        let mut diagnostic = diagnostic.clone();
+        if !diagnostic.file.is_empty() && !diagnostic.file.starts_with('#') {
+            diagnostic.file = root.join(&diagnostic.file).to_string_lossy().to_string();
+        }
+        let source = std::fs::read_to_string(&diagnostic.file).ok();
+        match format {
+            MessageFormat::Human => println!("{}", diagnostic.render(source.as_deref())),
+            MessageFormat::Json => println!("{}", diagnostic.to_json(source.as_deref())),
+        }
+    }
+}
+
+/// prints text meant for people, like the progress of a build
+///
+/// with JSON diagnostics it goes to stderr, so stdout holds nothing but them
+pub fn status(format: MessageFormat, message: impl std::fmt::Display) {
+    match format {
+        MessageFormat::Human => println!("{message}"),
+        MessageFormat::Json => eprintln!("{message}"),
+    }
+}
+
+/// byte range `line` covers in `source`, only a line and column are known
+/// for some of them so the word starting there is used
+fn span(source: &str, line: &Line) -> Option<(usize, usize)> {
+    if let Some(span) = line.span() {
+        if span.end <= source.len() {
+            return Some((span.start, span.end));
+        }
+    }
+    if line.line == 0 {
+        return None;
+    }
+    let line_start = line_start(source, line.line)?;
+    let start = line_start + line.column.saturating_sub(1);
+    if start > source.len() {
+        return None;
+    }
+    let word = source[start..]
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(source.len() - start);
+    Some((start, start + word.max(1)))
+}
+
+fn line_start(source: &str, line: usize) -> Option<usize> {
+    if line == 1 {
+        return Some(0);
+    }
+    source
+        .match_indices('\n')
+        .nth(line - 2)
+        .map(|(idx, _)| idx + 1)
+}
+
+/// text of the line, column of the span in characters and how many characters to underline
+fn excerpt<'a>(source: &'a str, line: &Line) -> Option<(&'a str, usize, usize)> {
+    let (start, end) = span(source, line)?;
+    let line_start = source[..start].rfind('\n').map_or(0, |idx| idx + 1);
+    let line_end = source[start..]
+        .find('\n')
+        .map_or(source.len(), |idx| start + idx);
+    let text = source.get(line_start..line_end)?.trim_end_matches('\r');
+    let column = source.get(line_start..start)?.chars().count();
+    // spans over more lines are underlined to the end of the first one
+    let len = source.get(start..end.min(line_end))?.chars().count();
+    Some((text, column, len.max(1)))
+}
+
+fn json_label(label: &Label, primary: bool, source: Option<&str>) -> String {
+    let (start, end) = match source.and_then(|source| span(source, &label.line)) {
+        Some(span) => span,
+        None => (label.line.start, label.line.end),
+    };
+    format!(
+        "{{\"primary\":{primary},\"line\":{},\"column\":{},\"start\":{start},\"end\":{end},\"message\":{}}}",
+        label.line.line,
+        label.line.column,
+        json_string(&label.message)
+    )
+}
+
+pub(crate) fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl ErrorOrigin {
+    /// every error of the stage, `file` is where they occured
+    pub fn diagnostics(&self, file: &str) -> Vec<Diagnostic> {
+        let diagnostics = match self {
+            ErrorOrigin::LexingError(errs)
+            | ErrorOrigin::CodeBlockParserError(errs)
+            | ErrorOrigin::IntermediateError(errs)
+            | ErrorOrigin::LibLoadError(errs) => errs.iter().map(Diagnostic::from).collect(),
+            ErrorOrigin::ParsingError(errs) => errs
+                .iter()
+                .map(|(err, line)| Diagnostic::from((err, *line)))
+                .collect(),
+            ErrorOrigin::AnalyzationError(errs) => errs.iter().map(Diagnostic::from).collect(),
+            ErrorOrigin::LinkingError(err) => vec![Diagnostic::from(err)],
+        };
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| {
+                if diagnostic.file.is_empty() {
+                    diagnostic.in_file(file)
+                } else {
+                    diagnostic
+                }
+            })
+            .collect()
+    }
+}
+
+impl From<&Errors> for Diagnostic {
+    fn from(err: &Errors) -> Self {
+        let (code, line) = match err {
+            Errors::InvalidNumber(line, _) => ("E0101", line),
+            Errors::InvalidChar(line, _) => ("E0102", line),
+            Errors::CharacterTooLong(line, _) => ("E0103", line),
+            Errors::InvalidInterpolation(line, _) => ("E0104", line),
+            Errors::Unclosed(line, _) => ("E0105", line),
+        };
+        let diagnostic = Diagnostic::error(code, err.to_string()).at(Some(*line));
+        match err {
+            Errors::CharacterTooLong(..) => {
+                diagnostic.with_help("use double quotes for a string")
+            }
+            // pointed at the opening quotes like an unclosed bracket
+            Errors::Unclosed(_, open) => {
+                let literal = match open {
+                    Tokens::Quotes => "char",
+                    _ => "string",
+                };
+                Diagnostic::error(code, format!("{literal} literal is not closed"))
+                    .at(Some(*line))
+                    .with_help(format!("close it with `{open}` before the end of the file"))
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<(&Err, Line)> for Diagnostic {
+    fn from((err, line): (&Err, Line)) -> Self {
+        let (err, unclosed) = match err {
+            Err::Unclosed(err, open, open_line) => (err.as_ref(), Some((open, *open_line))),
+            _ => (err, None),
+        };
+        let code = match err {
+            Err::Expected(..) => "E0201",
+            Err::ExpectedOneOf(..) => "E0202",
+            Err::Msg(..) => "E0203",
+            Err::FileEnd => "E0204",
+            Err::FileEndPeaceful => "E0205",
+            Err::Pass(..) => "E0206",
+            Err::WrongEndingToken(..) => "E0207",
+            Err::EmptyNodeParameter(..) => "E0208",
+            Err::Unclosed(..) => "E0202",
+        };
+        let diagnostic = Diagnostic::error(code, err.to_string()).at(Some(line));
+        match unclosed {
+            Some((open, open_line)) => {
+                diagnostic.with_secondary(open_line, format!("`{open}` is not closed"))
+            }
+            None => diagnostic,
+        }
+    }
+}
+
+impl From<&ErrType> for Diagnostic {
+    fn from(err: &ErrType) -> Self {
+        if let ErrType::TreeTransformError(inner, line) = err {
+            // the expression error knows the operator or value that is wrong
+            let line = match inner {
+                TreeTransformError::NoValue(line)
+                | TreeTransformError::ExcessOperator(line)
+                | TreeTransformError::ExcessValue(line) => *line,
+                TreeTransformError::NotImplementedCuzLazy => *line,
+            };
+            return Diagnostic::error("E0308", inner.to_string()).at(Some(line));
+        }
+        let (code, line) = match err {
+            ErrType::EnumVariantAssignedNumber(.., line) => ("E0301", Some(*line)),
+            ErrType::EnumVariantAssignedIdent(.., line) => ("E0302", Some(*line)),
+            ErrType::ConflictingNames(.., line) => ("E0303", Some(*line)),
+            ErrType::ConflictingArgsName(.., line) => ("E0304", Some(*line)),
+            ErrType::BadImpl(.., line) => ("E0305", Some(*line)),
+            ErrType::NonExistentIdentifier(.., line) => ("E0306", Some(*line)),
+            ErrType::StructVariantAssignedIdent(.., line) => ("E0307", Some(*line)),
+            ErrType::TreeTransformError(.., line) => ("E0308", Some(*line)),
+            ErrType::InvalidRegister(.., line) => ("E0309", Some(*line)),
+            ErrType::InvalidConstant(.., line) => ("E0310", Some(*line)),
+            ErrType::ImportPathDoesNotExist(.., line) => ("E0311", Some(*line)),
+            ErrType::NotCodeBlock(line) => ("E0312", Some(*line)),
+            ErrType::NotOperator(line) => ("E0313", Some(*line)),
+            ErrType::CannotInitializeConstant(..) => ("E0314", None),
+            ErrType::MissingOperator(line) => ("E0315", Some(*line)),
+            ErrType::EnumPayloadAssignedNumber(.., line) => ("E0316", Some(*line)),
+            ErrType::InvalidFormatSpec(.., line) => ("E0317", Some(*line)),
+            ErrType::InvalidPattern(.., line) => ("E0318", Some(*line)),
+            ErrType::InvalidDefault(.., line) => ("E0319", Some(*line)),
+            ErrType::MissingDefault(.., line) => ("E0320", Some(*line)),
+            ErrType::PositionalAfterNamed(line) => ("E0321", Some(*line)),
+        };
+        let diagnostic = Diagnostic::error(code, err.to_string()).at(line);
+        match err {
+            ErrType::InvalidDefault(..) => {
+                diagnostic.with_note("default values must be numbers, chars, strings, bools or null")
+            }
+            ErrType::MissingDefault(ident, _) => {
+                diagnostic.with_help(format!("give {ident} a default value or move it before the arguments with one"))
+            }
+            ErrType::PositionalAfterNamed(..) => {
+                diagnostic.with_help("write positional arguments before the named ones")
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&LinkingError> for Diagnostic {
+    fn from(err: &LinkingError) -> Self {
+        match err {
+            LinkingError::FileNotFound(..) => Diagnostic::error("E0401", err.to_string()),
+            LinkingError::CouldNotOpen(..) => Diagnostic::error("E0402", err.to_string()),
+            LinkingError::Imported(inner, chain) => {
+                let mut diagnostic = Diagnostic::from(inner.as_ref());
+                let mut chain = chain.iter();
+                if let Some(edge) = chain.next() {
+                    diagnostic = diagnostic
+                        .in_file(&edge.from)
+                        .at(Some(edge.line))
+                        .with_label("imported here");
+                }
+                for edge in chain {
+                    diagnostic =
+                        diagnostic.with_note(format!("imported from '{}' at {}", edge.from, edge.line));
+                }
+                diagnostic
+            }
+            LinkingError::CircularImport(cycle) => {
+                // the imports are listed as notes instead of under the message
+                let message = err.to_string().lines().next().unwrap_or_default().to_string();
+                let mut diagnostic = Diagnostic::error("E0403", message)
+                    .in_file(&cycle[0].from)
+                    .at(Some(cycle[0].line))
+                    .with_label(format!("imports '{}'", cycle[0].to));
+                for edge in cycle.iter().skip(1) {
+                    diagnostic = diagnostic.with_note(format!(
+                        "'{}' imports '{}' at {}",
+                        edge.from, edge.to, edge.line
+                    ));
+                }
+                diagnostic.with_help("move what the modules share into a module neither of them imports")
+            }
+            LinkingError::LibraryNotFound(lib, chain) => {
+                let mut diagnostic = Diagnostic::error("E0404", err.to_string());
+                if let Some(edge) = chain.first() {
+                    diagnostic = diagnostic
+                        .in_file(&edge.from)
+                        .at(Some(edge.line))
+                        .with_label("imported here");
+                }
+                diagnostic.with_help(format!("add {lib} to the dependencies in Ruda.toml"))
+            }
+        }
+    }
+}
+
+impl From<&TypeError> for Diagnostic {
+    fn from(err: &TypeError) -> Self {
+        let (code, line) = match err {
+            TypeError::Mismatch(.., line) => ("E0501", Some(*line)),
+            TypeError::NullableAssignment(.., line) => ("E0502", Some(*line)),
+            TypeError::ExpectedBool(.., line) => ("E0503", Some(*line)),
+            TypeError::InvalidOperator(.., line) => ("E0504", Some(*line)),
+            TypeError::UnaryNotApplicable(.., line) => ("E0505", Some(*line)),
+            TypeError::IncorrectNumberOfArgs(.., line) => ("E0506", Some(*line)),
+            TypeError::IncorrectNumberOfGenerics(.., line) => ("E0507", Some(*line)),
+            TypeError::ArgMismatch(.., line) => ("E0508", Some(*line)),
+            TypeError::GenericMismatch(.., line) => ("E0509", Some(*line)),
+            TypeError::FieldNotFound(.., line) => ("E0510", Some(*line)),
+            TypeError::CannotIndex(.., line) => ("E0511", Some(*line)),
+            TypeError::IndexNotNumber(.., line) => ("E0512", Some(*line)),
+            TypeError::ArrayElementMismatch(.., line) => ("E0513", Some(*line)),
+            TypeError::NotNullable(.., line) => ("E0514", Some(*line)),
+            TypeError::MissingReturnValue(.., line) => ("E0515", Some(*line)),
+            TypeError::UnexpectedReturnValue(.., line) => ("E0516", Some(*line)),
+            TypeError::NotInitializedNoType(line) => ("E0517", Some(*line)),
+            TypeError::NotInitializedNotNullable(.., line) => ("E0518", Some(*line)),
+            TypeError::NotIterable(.., line) => ("E0519", Some(*line)),
+            TypeError::VariableNotFound(.., line) => ("E0520", Some(*line)),
+            TypeError::TraitNotFound(.., line) => ("E0521", Some(*line)),
+            TypeError::MissingTraitMethod(.., line) => ("E0522", Some(*line)),
+            TypeError::TraitMethodMismatch(.., line) => ("E0523", Some(*line)),
+            TypeError::NotInTrait(.., line) => ("E0524", Some(*line)),
+            TypeError::BoundNotSatisfied(.., line) => ("E0525", Some(*line)),
+            TypeError::OverloadNotFound(.., line) => ("E0526", Some(*line)),
+            TypeError::AmbiguousOverload(.., line) => ("E0527", Some(*line)),
+            TypeError::CannotMatch(.., line) => ("E0528", Some(*line)),
+            TypeError::IncorrectNumberOfBindings(.., line) => ("E0529", Some(*line)),
+            TypeError::NotPublic(.., line) => ("E0530", Some(*line)),
+            TypeError::UseNotFound(.., line) => ("E0531", Some(*line)),
+            TypeError::CannotInterpolate(.., line) => ("E0532", Some(*line)),
+            TypeError::FormatNotApplicable(.., line) => ("E0533", Some(*line)),
+            TypeError::NotATuple(.., line) => ("E0534", Some(*line)),
+            TypeError::TupleIndexOutOfBounds(.., line) => ("E0535", Some(*line)),
+            TypeError::PatternLength(.., line) => ("E0536", Some(*line)),
+            TypeError::InvalidArgs(err) => return Diagnostic::from(err),
+        };
+        let diagnostic = Diagnostic::error(code, err.to_string()).at(line);
+        match err {
+            TypeError::Mismatch(_, _, TypeComparison::ArrayDiff(expected, got), _) => {
+                diagnostic.with_note(format!("the arrays have {expected} and {got} elements"))
+            }
+            TypeError::Mismatch(_, _, TypeComparison::ReferenceDiff(_), _) => {
+                diagnostic.with_note("the types differ only in references")
+            }
+            TypeError::NullableAssignment(..) => {
+                diagnostic.with_help("test the value with `?` before assigning it")
+            }
+            TypeError::NotPublic(item, module, _) => {
+                diagnostic.with_help(format!("mark {item} as `pub` in {module}"))
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&Warning> for Diagnostic {
+    fn from(warning: &Warning) -> Self {
+        let diagnostic = Diagnostic::warning(warning.lint().code(), warning.to_string())
+            .at(Some(warning.line()));
+        match warning {
+            Warning::UnusedVariable(name, _) => {
+                diagnostic.with_help(format!("prefix it with an underscore if it is intentional: _{name}"))
+            }
+            Warning::ShadowedVariable(_, previous, _) => {
+                diagnostic.with_secondary(*previous, "first declared here")
+            }
+            Warning::WhileTrue(_) => diagnostic.with_help("use `loop` instead"),
+            Warning::NullableComparison(..) => {
+                diagnostic.with_help("test the value with `?` before comparing it")
+            }
+            Warning::IgnoredResult(..) => {
+                diagnostic.with_help("use the value or handle the error with `try`")
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&CodegenError> for Diagnostic {
+    fn from(err: &CodegenError) -> Self {
+        let (code, line) = match err {
+            CodegenError::CannotInitializeConstant => ("E0601", None),
+            CodegenError::FunctionNotFound(..) => ("E0602", None),
+            CodegenError::ExpectedBool(line) => ("E0603", Some(*line)),
+            CodegenError::DerefereString(.., line) => ("E0604", Some(*line)),
+            CodegenError::ReferenceString(.., line) => ("E0605", Some(*line)),
+            CodegenError::VariableTypeMismatch(.., line) => ("E0606", Some(*line)),
+            CodegenError::NotInitializedNoType(line) => ("E0607", Some(*line)),
+            CodegenError::TypeNotNullable(line) => ("E0608", Some(*line)),
+            CodegenError::VariableAlreadyDeclared(_, line, _) => ("E0609", Some(*line)),
+            CodegenError::VariableNotFound(.., line) => ("E0610", Some(*line)),
+            CodegenError::CannotIndexFile(line) => ("E0611", Some(*line)),
+            CodegenError::CannotIndexFunction(line) => ("E0612", Some(*line)),
+            CodegenError::CanCallOnlyFunctions(line) => ("E0613", Some(*line)),
+            CodegenError::ArgTypeMismatch(.., line) => ("E0614", Some(*line)),
+            CodegenError::CannotAttachMethodsToFunctions(line) => ("E0615", Some(*line)),
+            CodegenError::ImportIsNotAValidValue(line) => ("E0616", Some(*line)),
+            CodegenError::IncorrectArgs(line) => ("E0617", Some(*line)),
+            CodegenError::ExressionNotHandledProperly(line) => ("E0618", Some(*line)),
+            CodegenError::InvalidOperator(.., line) => ("E0619", Some(*line)),
+            CodegenError::CouldNotCastTo(.., line) => ("E0620", Some(*line)),
+            CodegenError::ExpectedNumber(line) => ("E0621", Some(*line)),
+            CodegenError::UnaryNotApplicable(.., line) => ("E0622", Some(*line)),
+            CodegenError::CoudNotCastAnArrayToANonArray(.., line) => ("E0623", Some(*line)),
+            CodegenError::FunctionDoesNotReturn(line) => ("E0624", Some(*line)),
+            CodegenError::IncorrectNumberOfArgs(.., line) => ("E0625", Some(*line)),
+            CodegenError::ImportNotFound(.., line) => ("E0626", Some(*line)),
+            CodegenError::KindNotFound(.., line) => ("E0627", Some(*line)),
+            CodegenError::CannotRefDerefNumLiteral(line) => ("E0628", Some(*line)),
+            CodegenError::CannotIndexNonArray(.., line) => ("E0629", Some(*line)),
+            CodegenError::FieldNotInStruct(.., line) => ("E0630", Some(*line)),
+            CodegenError::FieldNotInEnum(.., line) => ("E0631", Some(*line)),
+            CodegenError::CannotDereference(.., line) => ("E0632", Some(*line)),
+            CodegenError::CannotReference(.., line) => ("E0633", Some(*line)),
+            CodegenError::CannotGetKind(..) => ("E0634", None),
+            CodegenError::CannotCallType(.., line) => ("E0635", Some(*line)),
+            CodegenError::CannotCastNull(.., line) => ("E0636", Some(*line)),
+            CodegenError::SwitchWithoutCases(line) => ("E0637", Some(*line)),
+            CodegenError::IncorrectNumberOfGenerics(.., line) => ("E0638", Some(*line)),
+            CodegenError::UnresolvedInstructionStops(..) => ("E0639", None),
+            CodegenError::CannotCast(.., line) => ("E0640", Some(*line)),
+            CodegenError::CannotTestNullable(line) => ("E0641", Some(*line)),
+            CodegenError::CannotTestNonNullable(.., line) => ("E0642", Some(*line)),
+            CodegenError::CannotIterate(.., line) => ("E0643", Some(*line)),
+            CodegenError::ErrorNotFound(.., line) => ("E0644", Some(*line)),
+            CodegenError::FieldNotInError(.., line) => ("E0645", Some(*line)),
+            CodegenError::CannotSwitch(.., line) => ("E0646", Some(*line)),
+            CodegenError::SwitchNotExhaustive(.., line) => ("E0647", Some(*line)),
+            CodegenError::DuplicateCase(line) => ("E0648", Some(*line)),
+            CodegenError::MethodNotInTrait(.., line) => ("E0649", Some(*line)),
+            CodegenError::CannotInferGeneric(.., line) => ("E0650", Some(*line)),
+            CodegenError::BoundNotSatisfied(.., line) => ("E0651", Some(*line)),
+            CodegenError::OverloadNotFound(.., line) => ("E0652", Some(*line)),
+            CodegenError::AmbiguousOverload(.., line) => ("E0653", Some(*line)),
+            CodegenError::CannotMatch(.., line) => ("E0654", Some(*line)),
+            CodegenError::MatchNotExhaustive(.., line) => ("E0655", Some(*line)),
+            CodegenError::PatternNotOfKind(.., line) => ("E0656", Some(*line)),
+            CodegenError::IncorrectNumberOfBindings(.., line) => ("E0657", Some(*line)),
+            CodegenError::UnreachableArm(line) => ("E0658", Some(*line)),
+            CodegenError::ArmWithoutValue(line) => ("E0659", Some(*line)),
+            CodegenError::NotPublic(.., line) => ("E0660", Some(*line)),
+            CodegenError::CannotInterpolate(.., line) => ("E0661", Some(*line)),
+            CodegenError::FormatNotApplicable(.., line) => ("E0662", Some(*line)),
+            CodegenError::NotATuple(.., line) => ("E0663", Some(*line)),
+            CodegenError::TupleIndexOutOfBounds(.., line) => ("E0664", Some(*line)),
+            CodegenError::PatternLength(.., line) => ("E0665", Some(*line)),
+            CodegenError::InvalidArgs(err) => return Diagnostic::from(err),
+            CodegenError::InFile(file, err) => return Diagnostic::from(err.as_ref()).in_file(file),
+        };
+        let diagnostic = Diagnostic::error(code, err.to_string()).at(line);
+        match err {
+            CodegenError::VariableAlreadyDeclared(_, _, other) => {
+                diagnostic.with_secondary(*other, "first declared here")
+            }
+            CodegenError::SwitchNotExhaustive(missing, _)
+            | CodegenError::MatchNotExhaustive(missing, _) => diagnostic
+                .with_label(format!("{} not covered", missing.join(", ")))
+                .with_help("add the missing cases or a default one"),
+            CodegenError::UnreachableArm(..) => {
+                diagnostic.with_note("the arms before it already cover every value")
+            }
+            CodegenError::NotPublic(item, module, _) => {
+                diagnostic.with_help(format!("mark {item} as `pub` in {module}"))
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&ArgError> for Diagnostic {
+    fn from(err: &ArgError) -> Self {
+        let (code, line) = match err {
+            ArgError::TooMany(.., line) => ("E0701", *line),
+            ArgError::UnknownName(.., line) => ("E0702", *line),
+            ArgError::DuplicateName(.., line) => ("E0703", *line),
+            ArgError::Missing(.., line) => ("E0704", *line),
+        };
+        let diagnostic = Diagnostic::error(code, err.to_string()).at(Some(line));
+        match err {
+            ArgError::Missing(ident, _) => {
+                diagnostic.with_help(format!("pass a value for {ident}, for example `{ident}: ...`"))
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&GrammarError> for Diagnostic {
+    fn from(err: &GrammarError) -> Self {
+        let code = match err {
+            GrammarError::Syntax(..) => "E0801",
+            GrammarError::UnknownStructure(..) => "E0802",
+            GrammarError::UnknownTarget(..) => "E0803",
+            GrammarError::UnknownType(..) => "E0804",
+            GrammarError::UnknownCommand(..) => "E0805",
+            GrammarError::InvalidBack(..) => "E0806",
+            GrammarError::BackOutOfBounds(..) => "E0807",
+            GrammarError::LeftRecursion(..) => "E0808",
+            GrammarError::Unreachable(..) => "W0801",
+        };
+        let diagnostic = match err.is_warning() {
+            true => Diagnostic::warning(code, err.to_string()),
+            false => Diagnostic::error(code, err.to_string()),
+        }
+        .at(Some(err.line()));
+        match err {
+            GrammarError::UnknownTarget(..) => {
+                diagnostic.with_help("add it to the parameters of the structure or to `globals`")
+            }
+            GrammarError::LeftRecursion(..) => {
+                diagnostic.with_note("the parser opens the structures again and again without reading a token")
+            }
+            _ => diagnostic,
+        }
+    }
+}
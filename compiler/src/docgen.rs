@@ -0,0 +1,600 @@
+//! Documentation of packages as static HTML or Markdown
+//!
+//! Every module and library gets a page with the signatures of its items, their docs,
+//! fields, variants, methods and implemented traits. Kinds in signatures link to the
+//! page of the item they name, items of modules link to a copy of their source.
+
+use std::collections::HashMap;
+
+use crate::diagnostic::json_string;
+use crate::intermediate::dictionary::Dictionary;
+use crate::libloader;
+use crate::symbols::{self, Symbol, SymbolKind};
+
+/// project, dependency or the standard library
+pub struct Package {
+    pub name: String,
+    pub modules: Vec<Module>,
+}
+
+/// module of a package or a binary library
+pub struct Module {
+    /// `main.rd`, `geometry/mod.rd` or `#io`
+    pub name: String,
+    /// None for binary libraries
+    pub source: Option<String>,
+    pub symbols: Vec<Symbol>,
+}
+
+impl Module {
+    /// items of a module, private items are left out unless `private` is set
+    pub fn from_dictionary(name: &str, dictionary: &Dictionary, source: Option<String>, private: bool) -> Self {
+        let symbols = symbols::module_symbols(name, dictionary)
+            .into_iter()
+            .filter(|symbol| symbol.kind != SymbolKind::Module && (symbol.public || private))
+            .collect();
+        Self {
+            name: name.to_string(),
+            source,
+            symbols,
+        }
+    }
+
+    /// items of a binary library, `name` is the name it is imported by (`#io`)
+    pub fn from_library(name: &str, dictionary: &libloader::Dictionary) -> Self {
+        Self {
+            name: name.to_string(),
+            source: None,
+            symbols: symbols::library_symbols(name, dictionary),
+        }
+    }
+}
+
+/// kinds of items in the order they are listed on a page
+const SECTIONS: [(SymbolKind, &str); 8] = [
+    (SymbolKind::Struct, "Structs"),
+    (SymbolKind::Enum, "Enums"),
+    (SymbolKind::Trait, "Traits"),
+    (SymbolKind::Type, "Types"),
+    (SymbolKind::UserData, "Userdata"),
+    (SymbolKind::Function, "Functions"),
+    (SymbolKind::Constant, "Constants"),
+    (SymbolKind::Error, "Errors"),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Html,
+    Markdown,
+}
+
+impl Format {
+    fn extension(&self) -> &'static str {
+        match self {
+            Format::Html => "html",
+            Format::Markdown => "md",
+        }
+    }
+}
+
+/// where a kind can be linked to
+struct Target {
+    package: String,
+    page: String,
+    anchor: String,
+}
+
+/// items of all packages by their names
+struct Index {
+    targets: HashMap<String, Vec<Target>>,
+    /// trait name with the items that implement it
+    implementors: HashMap<String, Vec<(String, Target)>>,
+}
+
+impl Index {
+    fn new(packages: &[Package], format: Format) -> Self {
+        let mut index = Self {
+            targets: HashMap::new(),
+            implementors: HashMap::new(),
+        };
+        for package in packages {
+            for module in &package.modules {
+                let page = page(&package.name, &module.name, format);
+                for symbol in &module.symbols {
+                    let target = || Target {
+                        package: package.name.clone(),
+                        page: page.clone(),
+                        anchor: anchor(symbol, None),
+                    };
+                    if is_kind(symbol.kind) {
+                        index.targets.entry(symbol.name.clone()).or_default().push(target());
+                    }
+                    for traitt in &symbol.implements {
+                        let name = traitt.rsplit('.').next().unwrap_or(traitt).to_string();
+                        index
+                            .implementors
+                            .entry(name)
+                            .or_default()
+                            .push((symbol.name.clone(), target()));
+                    }
+                }
+            }
+        }
+        index
+    }
+
+    /// item named `name`, items of the same page and package are preferred
+    fn resolve(&self, name: &str, package: &str, page: &str) -> Option<&Target> {
+        let targets = self.targets.get(name)?;
+        targets
+            .iter()
+            .find(|target| target.page == page)
+            .or_else(|| targets.iter().find(|target| target.package == package))
+            .or_else(|| targets.first())
+    }
+
+    /// link to the target from `page`
+    fn href(&self, target: &Target, page: &str) -> String {
+        match target.page == page {
+            true => format!("#{}", target.anchor),
+            false => format!("{}{}#{}", root(page), target.page, target.anchor),
+        }
+    }
+}
+
+/// items that are kinds of values and can be linked from signatures
+fn is_kind(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::Struct | SymbolKind::Enum | SymbolKind::Trait | SymbolKind::Type | SymbolKind::UserData | SymbolKind::Error
+    )
+}
+
+/// path of the page of a module relative to the output directory
+fn page(package: &str, module: &str, format: Format) -> String {
+    format!("{package}/{}.{}", module.trim_start_matches('#'), format.extension())
+}
+
+/// path of the copy of a module's source
+fn source_page(package: &str, module: &str, format: Format) -> String {
+    match format {
+        Format::Html => format!("src/{package}/{module}.html"),
+        Format::Markdown => format!("src/{package}/{module}"),
+    }
+}
+
+/// relative path from the page to the output directory
+fn root(page: &str) -> String {
+    "../".repeat(page.matches('/').count())
+}
+
+/// `struct.Circle` for items, `method.Circle.diameter` for their children
+fn anchor(symbol: &Symbol, parent: Option<&Symbol>) -> String {
+    let kind = match symbol.kind {
+        SymbolKind::Function => "fun".to_string(),
+        kind => kind.to_string(),
+    };
+    match parent {
+        Some(parent) => format!("{kind}.{}.{}", parent.name, symbol.name),
+        None => format!("{kind}.{}", symbol.name),
+    }
+}
+
+/// splits a signature into identifiers and the text between them
+fn words(text: &str) -> Vec<(bool, &str)> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut ident = false;
+    for (i, c) in text.char_indices() {
+        let is_ident = c.is_alphanumeric() || c == '_';
+        if is_ident != ident && i > start {
+            words.push((ident, &text[start..i]));
+            start = i;
+        }
+        ident = is_ident;
+    }
+    if start < text.len() {
+        words.push((ident, &text[start..]));
+    }
+    words
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// first paragraph of the docs, used in lists and search results
+fn summary(docs: &Option<String>) -> String {
+    docs.as_deref()
+        .unwrap_or("")
+        .split("\n\n")
+        .next()
+        .unwrap_or("")
+        .lines()
+        .map(|line| line.trim())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// documentation as HTML files, returns the path of every file with its contents
+pub fn html(packages: &[Package]) -> Vec<(String, String)> {
+    let index = Index::new(packages, Format::Html);
+    let mut files = vec![
+        ("index.html".to_string(), html_index(packages)),
+        ("style.css".to_string(), STYLE.to_string()),
+        ("search.js".to_string(), search(packages)),
+    ];
+    for package in packages {
+        for module in &package.modules {
+            let page = page(&package.name, &module.name, Format::Html);
+            files.push((page.clone(), html_module(&index, package, module, &page)));
+            if let Some(source) = &module.source {
+                let path = source_page(&package.name, &module.name, Format::Html);
+                files.push((path.clone(), html_source(&module.name, source, &path)));
+            }
+        }
+    }
+    files
+}
+
+fn html_page(title: &str, page: &str, body: &str) -> String {
+    let root = root(page);
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <link rel=\"stylesheet\" href=\"{root}style.css\">\n<script src=\"{root}search.js\" defer></script>\n\
+         </head>\n<body data-root=\"{root}\">\n<nav><a href=\"{root}index.html\">Index</a>\
+         <input id=\"search\" type=\"search\" placeholder=\"Search\" autocomplete=\"off\"></nav>\n\
+         <div id=\"results\"></div>\n<main>\n{body}</main>\n</body>\n</html>\n",
+        escape(title)
+    )
+}
+
+fn html_index(packages: &[Package]) -> String {
+    let mut body = String::from("<h1>Documentation</h1>\n");
+    for package in packages {
+        body.push_str(&format!("<h2>{}</h2>\n<ul>\n", escape(&package.name)));
+        for module in &package.modules {
+            body.push_str(&format!(
+                "<li><a href=\"{}\">{}</a> <span class=\"count\">{} items</span></li>\n",
+                page(&package.name, &module.name, Format::Html),
+                escape(&module.name),
+                module.symbols.len()
+            ));
+        }
+        body.push_str("</ul>\n");
+    }
+    html_page("Documentation", "index.html", &body)
+}
+
+/// signature with the kinds it mentions linked
+fn html_signature(index: &Index, package: &str, page: &str, detail: &str) -> String {
+    let mut out = String::new();
+    for (ident, word) in words(detail) {
+        match ident.then(|| index.resolve(word, package, page)).flatten() {
+            Some(target) => out.push_str(&format!("<a href=\"{}\">{}</a>", index.href(target, page), escape(word))),
+            None => out.push_str(&escape(word)),
+        }
+    }
+    out
+}
+
+/// docs written in Markdown, only paragraphs, code blocks and inline code are rendered
+fn html_docs(docs: &str) -> String {
+    fn inline(text: &str) -> String {
+        let mut out = String::new();
+        for (i, part) in text.split('`').enumerate() {
+            match i % 2 {
+                0 => out.push_str(&escape(part)),
+                _ => out.push_str(&format!("<code>{}</code>", escape(part))),
+            }
+        }
+        out
+    }
+    let mut out = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<Vec<&str>> = None;
+    let flush = |out: &mut String, paragraph: &mut Vec<&str>| {
+        if !paragraph.is_empty() {
+            out.push_str(&format!("<p>{}</p>\n", inline(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+    };
+    for line in docs.lines() {
+        if line.trim_start().starts_with("```") {
+            match code.take() {
+                Some(lines) => out.push_str(&format!("<pre><code>{}</code></pre>\n", escape(&lines.join("\n")))),
+                None => {
+                    flush(&mut out, &mut paragraph);
+                    code = Some(Vec::new());
+                }
+            }
+            continue;
+        }
+        match code.as_mut() {
+            Some(lines) => lines.push(line),
+            None if line.trim().is_empty() => flush(&mut out, &mut paragraph),
+            None => paragraph.push(line.trim()),
+        }
+    }
+    if let Some(lines) = code {
+        out.push_str(&format!("<pre><code>{}</code></pre>\n", escape(&lines.join("\n"))));
+    }
+    flush(&mut out, &mut paragraph);
+    out
+}
+
+fn html_module(index: &Index, package: &Package, module: &Module, page: &str) -> String {
+    let mut body = format!(
+        "<h1><span class=\"kind\">{}</span> <code>{}</code></h1>\n",
+        match module.source {
+            Some(_) => "Module",
+            None => "Library",
+        },
+        escape(&module.name)
+    );
+    let source = source_page(&package.name, &module.name, Format::Html);
+    for (kind, title) in SECTIONS {
+        let items = module.symbols.iter().filter(|symbol| symbol.kind == kind).collect::<Vec<_>>();
+        if items.is_empty() {
+            continue;
+        }
+        body.push_str(&format!("<h2>{title}</h2>\n"));
+        for symbol in items {
+            body.push_str(&format!("<section class=\"item\" id=\"{}\">\n", escape(&anchor(symbol, None))));
+            body.push_str(&format!(
+                "<pre class=\"signature\"><code>{}</code></pre>\n",
+                html_signature(index, &package.name, page, &symbol.detail)
+            ));
+            if let (Some(line), Some(_)) = (symbol.line, &module.source) {
+                body.push_str(&format!(
+                    "<a class=\"source\" href=\"{}{source}#L{}\">source</a>\n",
+                    root(page),
+                    line.line
+                ));
+            }
+            if let Some(docs) = &symbol.docs {
+                body.push_str(&format!("<div class=\"docs\">{}</div>\n", html_docs(docs)));
+            }
+            if !symbol.implements.is_empty() {
+                let traits = symbol
+                    .implements
+                    .iter()
+                    .map(|traitt| html_signature(index, &package.name, page, traitt))
+                    .collect::<Vec<_>>();
+                body.push_str(&format!("<p class=\"impls\">Implements {}</p>\n", traits.join(", ")));
+            }
+            if symbol.kind == SymbolKind::Trait {
+                if let Some(implementors) = index.implementors.get(&symbol.name) {
+                    let links = implementors
+                        .iter()
+                        .map(|(name, target)| format!("<a href=\"{}\">{}</a>", index.href(target, page), escape(name)))
+                        .collect::<Vec<_>>();
+                    body.push_str(&format!("<p class=\"impls\">Implemented by {}</p>\n", links.join(", ")));
+                }
+            }
+            if !symbol.children.is_empty() {
+                body.push_str("<ul class=\"children\">\n");
+                for child in &symbol.children {
+                    body.push_str(&format!(
+                        "<li id=\"{}\"><code>{}</code>",
+                        escape(&anchor(child, Some(symbol))),
+                        html_signature(index, &package.name, page, &child.detail)
+                    ));
+                    if let Some(docs) = &child.docs {
+                        body.push_str(&html_docs(docs));
+                    }
+                    body.push_str("</li>\n");
+                }
+                body.push_str("</ul>\n");
+            }
+            body.push_str("</section>\n");
+        }
+    }
+    html_page(&format!("{} - {}", module.name, package.name), page, &body)
+}
+
+fn html_source(name: &str, source: &str, page: &str) -> String {
+    let mut body = format!("<h1>Source of <code>{}</code></h1>\n<pre class=\"source\">", escape(name));
+    for (i, line) in source.lines().enumerate() {
+        body.push_str(&format!(
+            "<span id=\"L{n}\"><a class=\"line\" href=\"#L{n}\">{n:>4}</a> {}</span>\n",
+            escape(line),
+            n = i + 1
+        ));
+    }
+    body.push_str("</pre>\n");
+    html_page(name, page, &body)
+}
+
+/// every item with the page it is on, loaded by all of the HTML pages
+fn search(packages: &[Package]) -> String {
+    let mut items = Vec::new();
+    for package in packages {
+        for module in &package.modules {
+            let page = page(&package.name, &module.name, Format::Html);
+            for symbol in &module.symbols {
+                items.push(format!(
+                    "{{\"name\":{},\"kind\":\"{}\",\"path\":{},\"module\":{},\"summary\":{}}}",
+                    json_string(&symbol.name),
+                    symbol.kind,
+                    json_string(&format!("{page}#{}", anchor(symbol, None))),
+                    json_string(&format!("{}::{}", package.name, module.name)),
+                    json_string(&summary(&symbol.docs))
+                ));
+            }
+        }
+    }
+    format!("const SEARCH = [\n{}\n];\n{SEARCH_SCRIPT}", items.join(",\n"))
+}
+
+/// documentation as Markdown files, returns the path of every file with its contents
+pub fn markdown(packages: &[Package]) -> Vec<(String, String)> {
+    let index = Index::new(packages, Format::Markdown);
+    let mut out = String::from("# Documentation\n");
+    for package in packages {
+        out.push_str(&format!("\n## {}\n\n", package.name));
+        for module in &package.modules {
+            out.push_str(&format!(
+                "- [`{}`]({})\n",
+                module.name,
+                page(&package.name, &module.name, Format::Markdown)
+            ));
+        }
+    }
+    let mut files = vec![("index.md".to_string(), out)];
+    for package in packages {
+        for module in &package.modules {
+            let page = page(&package.name, &module.name, Format::Markdown);
+            files.push((page.clone(), markdown_module(&index, package, module, &page)));
+            if let Some(source) = &module.source {
+                files.push((source_page(&package.name, &module.name, Format::Markdown), source.clone()));
+            }
+        }
+    }
+    files
+}
+
+/// links to the kinds a signature mentions, code blocks can not contain links
+fn markdown_links(index: &Index, package: &str, page: &str, symbol: &Symbol) -> String {
+    let mut links = Vec::new();
+    for (ident, word) in words(&symbol.detail) {
+        if !ident || word == symbol.name {
+            continue;
+        }
+        if let Some(target) = index.resolve(word, package, page) {
+            let link = format!("[{word}]({})", index.href(target, page));
+            if !links.contains(&link) {
+                links.push(link);
+            }
+        }
+    }
+    links.join(", ")
+}
+
+fn markdown_module(index: &Index, package: &Package, module: &Module, page: &str) -> String {
+    let mut out = format!(
+        "# {} `{}`\n",
+        match module.source {
+            Some(_) => "Module",
+            None => "Library",
+        },
+        module.name
+    );
+    let source = source_page(&package.name, &module.name, Format::Markdown);
+    for (kind, title) in SECTIONS {
+        let items = module.symbols.iter().filter(|symbol| symbol.kind == kind).collect::<Vec<_>>();
+        if items.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n## {title}\n"));
+        for symbol in items {
+            out.push_str(&format!(
+                "\n<a id=\"{}\"></a>\n### {}\n\n```ruda\n{}\n```\n",
+                anchor(symbol, None),
+                symbol.name,
+                symbol.detail
+            ));
+            let links = markdown_links(index, &package.name, page, symbol);
+            if !links.is_empty() {
+                out.push_str(&format!("\nSee {links}\n"));
+            }
+            if let Some(docs) = &symbol.docs {
+                out.push_str(&format!("\n{docs}\n"));
+            }
+            if !symbol.implements.is_empty() {
+                let traits = symbol
+                    .implements
+                    .iter()
+                    .map(|traitt| {
+                        let name = traitt.rsplit('.').next().unwrap_or(traitt);
+                        match index.resolve(name, &package.name, page) {
+                            Some(target) => format!("[{traitt}]({})", index.href(target, page)),
+                            None => format!("`{traitt}`"),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                out.push_str(&format!("\nImplements {}\n", traits.join(", ")));
+            }
+            if symbol.kind == SymbolKind::Trait {
+                if let Some(implementors) = index.implementors.get(&symbol.name) {
+                    let links = implementors
+                        .iter()
+                        .map(|(name, target)| format!("[{name}]({})", index.href(target, page)))
+                        .collect::<Vec<_>>();
+                    out.push_str(&format!("\nImplemented by {}\n", links.join(", ")));
+                }
+            }
+            if let (Some(line), Some(_)) = (symbol.line, &module.source) {
+                out.push_str(&format!("\n[source]({}{source}#L{})\n", root(page), line.line));
+            }
+            if !symbol.children.is_empty() {
+                out.push('\n');
+                for child in &symbol.children {
+                    out.push_str(&format!(
+                        "- <a id=\"{}\"></a>`{}`",
+                        anchor(child, Some(symbol)),
+                        child.detail
+                    ));
+                    let links = markdown_links(index, &package.name, page, child);
+                    if !links.is_empty() {
+                        out.push_str(&format!(" ({links})"));
+                    }
+                    let summary = summary(&child.docs);
+                    if !summary.is_empty() {
+                        out.push_str(&format!(" - {summary}"));
+                    }
+                    out.push('\n');
+                }
+            }
+        }
+    }
+    out
+}
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 0; color: #222; }
+nav { display: flex; gap: 1em; align-items: center; padding: 0.5em 1em; background: #333; }
+nav a { color: #fff; }
+#search { flex: 1; max-width: 30em; padding: 0.3em; }
+#results { margin: 0 1em; }
+#results a { display: block; padding: 0.2em 0; }
+main { max-width: 60em; margin: 0 auto; padding: 1em; }
+.item { border-top: 1px solid #ddd; padding: 0.5em 0; }
+.signature { background: #f4f4f4; padding: 0.5em; overflow-x: auto; }
+.source { float: right; font-size: 0.9em; }
+.kind, .count, .summary { color: #777; }
+.children code { background: #f4f4f4; }
+pre.source span { display: block; }
+pre.source span:target { background: #ffa; }
+.line { color: #999; text-decoration: none; }
+";
+
+const SEARCH_SCRIPT: &str = "document.addEventListener(\"DOMContentLoaded\", () => {
+    const input = document.getElementById(\"search\");
+    const results = document.getElementById(\"results\");
+    const root = document.body.dataset.root;
+    input.addEventListener(\"input\", () => {
+        const query = input.value.trim().toLowerCase();
+        results.innerHTML = \"\";
+        if (query === \"\") {
+            return;
+        }
+        const found = SEARCH.filter((item) => item.name.toLowerCase().includes(query));
+        found.sort((a, b) => (b.name.toLowerCase() === query) - (a.name.toLowerCase() === query));
+        for (const item of found.slice(0, 50)) {
+            const link = document.createElement(\"a\");
+            link.href = root + item.path;
+            link.textContent = item.kind + \" \" + item.module + \"::\" + item.name;
+            if (item.summary) {
+                const summary = document.createElement(\"span\");
+                summary.className = \"summary\";
+                summary.textContent = \" - \" + item.summary;
+                link.appendChild(summary);
+            }
+            results.appendChild(link);
+        }
+    });
+});
+";
@@ -6,6 +6,8 @@ use crate::intermediate::dictionary::*;
 use crate::intermediate::AnalyzationError::ErrType;
 use crate::lexer::tokenizer::{Operators, Tokens};
 use crate::tree_walker::tree_walker::{Line, Node};
+use runtime::format_spec::{FormatSpec, Radix};
+use serde::{Deserialize, Serialize};
 use crate::{intermediate, lexer};
 use intermediate::dictionary::*;
 use intermediate::*;
@@ -53,18 +55,20 @@ pub fn expr_into_tree(node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -
         }
     }
     let mut transform = transform_expr(&nodes, errors, file_name);
-    let res = list_into_tree(&mut transform);
-    if let Ok(mut val) = res {
-        match &mut val {
-            ValueType::Expression(val) => {
-                val.is_root = true;
+    match list_into_tree(&mut transform) {
+        Ok(mut val) => {
+            match &mut val {
+                ValueType::Expression(val) => {
+                    val.is_root = true;
+                }
+                _ => {}
             }
-            _ => {}
+            val
+        }
+        Err(err) => {
+            errors.push(ErrType::TreeTransformError(err, node.line));
+            ValueType::Blank
         }
-        return val;
-    } else {
-        println!("error occured while parsing expression: {:?}", res);
-        unreachable!("Blank expression parse error")
     }
 }
 
@@ -218,6 +222,19 @@ fn get_root(_node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -> Root {
     let node = step_inside_val(&_node, "value");
     match &node.name {
         Tokens::Text(txt) => {
+            if txt == "match_expr" {
+                return Root::Match(Box::new(get_match(&node, errors, file_name)));
+            }
+            if txt == "interpolation" {
+                return Root::Interpolation(get_interpolation(&node, errors, file_name));
+            }
+            if txt == "tuple_expr" {
+                let values = step_inside_arr(&node, "values")
+                    .iter()
+                    .map(|value| expr_into_tree(value, errors, file_name))
+                    .collect();
+                return Root::Literal(Literals::Tuple(values));
+            }
             if txt == "free_parenthesis" {
                 let expr = try_get_parenthesis(&node, errors, file_name);
                 if let Some(expr) = expr {
@@ -275,6 +292,95 @@ fn get_root(_node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -> Root {
     unreachable!("get_root has to be called on root node, please report this bug")
 }
 
+fn get_interpolation(
+    node: &Node,
+    errors: &mut Vec<ErrType>,
+    file_name: &str,
+) -> Vec<InterpolationPart> {
+    let mut parts = Vec::new();
+    for part in step_inside_arr(&node, "parts") {
+        if let Tokens::String(text) = &step_inside_val(&part, "text").name {
+            parts.push(InterpolationPart::Text(text.clone()));
+            continue;
+        }
+        let value = expr_into_tree(step_inside_val(&part, "value"), errors, file_name);
+        let format = match &step_inside_val(&part, "format").name {
+            Tokens::FormatSpec(spec) => match FormatSpec::parse(spec) {
+                Ok(parsed) => Some((spec.clone(), parsed)),
+                Err(reason) => {
+                    errors.push(ErrType::InvalidFormatSpec(reason, part.line));
+                    None
+                }
+            },
+            _ => None,
+        };
+        parts.push(InterpolationPart::Value(EmbeddedValue {
+            value,
+            format,
+            line: part.line,
+        }));
+    }
+    parts
+}
+
+fn get_match(node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -> Match {
+    let expr = expr_into_tree(step_inside_val(&node, "expression"), errors, file_name);
+    let mut arms = Vec::new();
+    for arm in step_inside_arr(&node, "arms") {
+        let pattern = step_inside_val(&arm, "pattern");
+        let (path, bindings) = match &step_inside_val(&pattern, "ignore").name {
+            Tokens::Text(txt) if txt == "_" => (None, Vec::new()),
+            _ => {
+                let path = step_inside_arr(&pattern, "path")
+                    .iter()
+                    .filter_map(|ident| match &step_inside_val(&ident, "identifier").name {
+                        Tokens::Text(txt) => Some(txt.to_string()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                let bindings = step_inside_arr(&pattern, "bindings")
+                    .iter()
+                    .map(|binding| match &step_inside_val(&binding, "ignore").name {
+                        Tokens::Text(txt) if txt == "_" => None,
+                        _ => Some(get_ident(&binding)),
+                    })
+                    .collect::<Vec<_>>();
+                (Some(path), bindings)
+            }
+        };
+        let guard = step_inside_val(&arm, "guard");
+        let guard = match &guard.name {
+            Tokens::Text(txt) if txt == "expression" => {
+                Some(expr_into_tree(&guard, errors, file_name))
+            }
+            _ => None,
+        };
+        let code = step_inside_val(&arm, "code");
+        let body = match &code.name {
+            Tokens::Text(txt) if txt == "code_block" => {
+                ArmBody::Block(crate::codeblock_parser::generate_tree(&code, errors, file_name))
+            }
+            _ => ArmBody::Value(expr_into_tree(
+                step_inside_val(&arm, "value"),
+                errors,
+                file_name,
+            )),
+        };
+        arms.push(MatchArm {
+            path,
+            bindings,
+            guard,
+            body,
+            line: arm.line,
+        });
+    }
+    Match {
+        expr,
+        arms,
+        line: node.line,
+    }
+}
+
 pub fn try_get_literal(
     node: &Node,
     errors: &mut Vec<ErrType>,
@@ -373,18 +479,42 @@ pub fn try_get_variable(
     Some(((ident, node.line), tail))
 }
 
-pub fn get_args(node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -> Vec<ValueType> {
+/// Returns positional arguments followed by named ones
+pub fn get_args(
+    node: &Node,
+    errors: &mut Vec<ErrType>,
+    file_name: &str,
+) -> (Vec<ValueType>, Vec<(String, ValueType, Line)>) {
     let mut result = vec![];
+    let mut named = vec![];
+    let mut after_named = false;
     for child in step_inside_arr(&node, "expressions") {
+        if let Tokens::Text(txt) = &child.name {
+            if txt == "named_arg" {
+                let expr = expr_into_tree(step_inside_val(&child, "expression"), errors, file_name);
+                named.push((get_ident(&child), expr, child.line));
+                after_named = true;
+                continue;
+            }
+        }
         let expr = expr_into_tree(&child, errors, file_name);
         if let ValueType::Expression(exrp) = &expr {
             if exrp.left.is_none() && exrp.right.is_none() {
                 continue;
             }
         }
+        // the grammar leaves an empty expression after each named argument
+        if after_named && matches!(expr, ValueType::Blank) {
+            after_named = false;
+            continue;
+        }
+        after_named = false;
+        if let Some((_, _, line)) = named.last() {
+            errors.push(ErrType::PositionalAfterNamed(*line));
+        }
         result.push(expr);
     }
-    result
+    (result, named)
 }
 
 pub fn try_get_parenthesis(
@@ -411,13 +541,17 @@ pub fn get_tail(node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -> Vec<
                 continue;
             }
             else if txt == "nested" {
+                if let Tokens::Number(idx, _) = step_inside_val(&child, "index").name {
+                    tail.push((TailNodes::TupleIndex(idx as usize), child.line));
+                    continue;
+                }
                 tail.push((TailNodes::Nested(get_ident(&child)), child.line));
                 continue;
             }
             else if txt == "function_call" {
                 let generic = get_generics_expr(&child, errors, file_name);
-                let args = get_args(step_inside_val(&child, "parenthesis"), errors, file_name);
-                tail.push((TailNodes::Call(FunctionCall { generic, args }), child.line));
+                let (args, named) = get_args(step_inside_val(&child, "parenthesis"), errors, file_name);
+                tail.push((TailNodes::Call(FunctionCall { generic, args, named }), child.line));
                 continue;
             }
             else if txt == "cast" {
@@ -428,6 +562,9 @@ pub fn get_tail(node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -> Vec<
         }else if let Tokens::Optional = &child.name {
             tail.push((TailNodes::Nullable, child.line));
             continue;
+        }else if let Tokens::Operator(Operators::Not) = &child.name {
+            tail.push((TailNodes::Propagate, child.line));
+            continue;
         }
     }
     tail
@@ -488,7 +625,7 @@ pub fn get_ref_type(node: &Node) -> Ref {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Ref {
     Dereferencing(usize),
     Reference(usize),
@@ -507,7 +644,7 @@ pub fn try_get_op(node: &Node, _errors: &mut Vec<ErrType>) -> Option<Operators>
     None
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExprNode {
     pub left: Option<ValueType>,
     pub right: Option<ValueType>,
@@ -541,7 +678,7 @@ impl ExprNode {
         }
     }
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ValueType {
     AnonymousFunction(Function),
     // parenthesis moved as a root of value
@@ -572,7 +709,7 @@ impl ValueType {
         }
     }
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Literal {
     pub unary: Vec<(Operators, Line)>,
     pub refs: Ref,
@@ -586,14 +723,16 @@ impl Literal {
         self.refs == Ref::None && self.modificatior.is_none()
     }
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Literals {
     Number(Tokens),
     Array(ArrayRule),
     String(String),
     Char(char),
+    /// (1, "one")
+    Tuple(Vec<ValueType>),
 }
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum ArrayRule {
     Fill {
         value: Box<ValueType>,
@@ -612,7 +751,7 @@ impl fmt::Debug for ArrayRule {
         }
     }
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Variable {
     pub unary: Vec<(Operators, Line)>,
     pub refs: Ref,
@@ -629,11 +768,99 @@ pub struct Variable {
     pub line: Line,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Root {
     Literal(Literals),
     Parenthesis(Box<ValueType>),
     Identifier(String),
+    Match(Box<Match>),
+    /// "score: {score}, best: {best:.2}"
+    Interpolation(Vec<InterpolationPart>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum InterpolationPart {
+    Text(String),
+    Value(EmbeddedValue),
+}
+
+/// value embedded in a string, `{best:.2}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddedValue {
+    pub value: ValueType,
+    /// format specifier as written and parsed
+    #[serde(with = "format_spec")]
+    pub format: Option<(String, FormatSpec)>,
+    pub line: Line,
+}
+
+/// format specifiers are cached as written and parsed again when they are loaded
+mod format_spec {
+    use runtime::format_spec::FormatSpec;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(format: &Option<(String, FormatSpec)>, serializer: S) -> Result<S::Ok, S::Error> {
+        format.as_ref().map(|(spec, _)| spec).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<(String, FormatSpec)>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(spec) => match FormatSpec::parse(&spec) {
+                Ok(parsed) => Ok(Some((spec, parsed))),
+                Err(reason) => Err(D::Error::custom(reason)),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+impl EmbeddedValue {
+    /// whether the format specifier can be used on a value of the kind
+    pub fn format_fits(&self, kind: &Kind) -> bool {
+        let spec = match &self.format {
+            Some((_, spec)) => spec,
+            None => return true,
+        };
+        let name = match &kind.body {
+            TypeBody::Type { main, .. } if kind.is_primitive_simple() => main[0].as_str(),
+            _ => return false,
+        };
+        if spec.radix != Radix::Decimal && name != "int" && name != "uint" {
+            return false;
+        }
+        if spec.precision.is_some() && name != "float" && name != "string" {
+            return false;
+        }
+        if spec.zero && !kind.is_number() {
+            return false;
+        }
+        true
+    }
+}
+
+/// match shape { Shape.Circle(r) => r * r, _ => 0 }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Match {
+    pub expr: ValueType,
+    pub arms: Vec<MatchArm>,
+    pub line: Line,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchArm {
+    /// path to the variant, None for `_`
+    pub path: Option<Vec<String>>,
+    /// names given to the fields of the variant, None for fields ignored with `_`
+    pub bindings: Vec<Option<String>>,
+    pub guard: Option<ValueType>,
+    pub body: ArmBody,
+    pub line: Line,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ArmBody {
+    Value(ValueType),
+    Block(Vec<crate::codeblock_parser::Nodes>),
 }
 
 impl Variable {
@@ -654,16 +881,93 @@ impl Variable {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FunctionCall {
     pub generic: Vec<Kind>,
     pub args: Vec<ValueType>,
+    /// `f(x: 3, scale: 2.0)`
+    pub named: Vec<(String, ValueType, Line)>,
 }
+impl FunctionCall {
+    /// Orders arguments of the call the same way as parameters of the called function
+    ///
+    /// Named arguments are moved to the position of their parameter
+    /// and parameters left out of the call get their default value
+    pub fn resolve(&self, params: &[Arg], line: &Line) -> Result<FunctionCall, ArgError> {
+        // plain calls keep the errors about the number of arguments
+        if self.named.is_empty()
+            && (self.args.len() == params.len() || params.iter().all(|param| param.default.is_none()))
+        {
+            return Ok(self.clone());
+        }
+        if self.args.len() > params.len() {
+            return Err(ArgError::TooMany(params.len(), self.args.len(), *line));
+        }
+        let mut slots = self.args.iter().cloned().map(Some).collect::<Vec<_>>();
+        slots.resize(params.len(), None);
+        for (name, value, line) in self.named.iter() {
+            let idx = match params.iter().position(|param| &param.identifier == name) {
+                Some(idx) => idx,
+                None => return Err(ArgError::UnknownName(name.clone(), *line)),
+            };
+            if slots[idx].is_some() {
+                return Err(ArgError::DuplicateName(name.clone(), *line));
+            }
+            slots[idx] = Some(value.clone());
+        }
+        let mut args = Vec::new();
+        for (slot, param) in slots.into_iter().zip(params.iter()) {
+            let value = match slot {
+                Some(value) => value,
+                None => match param.default.as_ref().and_then(|default| default.to_value(*line)) {
+                    Some(value) => value,
+                    None => return Err(ArgError::Missing(param.identifier.clone(), *line)),
+                },
+            };
+            args.push(value);
+        }
+        Ok(FunctionCall {
+            generic: self.generic.clone(),
+            args,
+            named: vec![],
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
+pub enum ArgError {
+    /// (expected, got, line)
+    TooMany(usize, usize, Line),
+    UnknownName(String, Line),
+    DuplicateName(String, Line),
+    Missing(String, Line),
+}
+
+impl std::fmt::Display for ArgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgError::TooMany(expected, got, line) => {
+                write!(f, "expected at most {expected} arguments, got {got} at {line}")
+            }
+            ArgError::UnknownName(name, line) => {
+                write!(f, "no argument named {name} at {line}")
+            }
+            ArgError::DuplicateName(name, line) => {
+                write!(f, "argument {name} is given more than once at {line}")
+            }
+            ArgError::Missing(name, line) => write!(f, "missing argument {name} at {line}"),
+        }
+    }
+}
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TailNodes {
     Nested(String),
     Index(ValueType),
+    /// position in a tuple, `pair.0`
+    TupleIndex(usize),
     Call(FunctionCall),
     Nullable,
+    /// bang operator `divide(a, b)!`, forwards the error to the caller
+    Propagate,
     Cast(Kind),
 }
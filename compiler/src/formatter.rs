@@ -0,0 +1,759 @@
+//! Canonical layout of Ruda sources (`ruda fmt`)
+//!
+//! The formatter works on the tokens of a file, not on its syntax tree, so it
+//! keeps every token and comment in place and only decides where the spaces,
+//! line breaks and indentation go. Line breaks written by the author are kept,
+//! the output is checked to tokenize into the same program as the input.
+
+use crate::lexer::tokenizer::{deparse_token, Operators, Tokens};
+use crate::lexing_preprocessor::lexing_preprocessor::refactor;
+use crate::lexing_preprocessor::parse_err::Errors;
+use crate::tree_walker::tree_walker::Line;
+
+/// lines longer than this are wrapped at their argument list
+pub const MAX_WIDTH: usize = 100;
+pub const INDENT: &str = "    ";
+
+/// words in front of a value, a minus after them is a sign and a parenthesis is not a call
+const KEYWORDS: [&str; 22] = [
+    "if", "else", "while", "for", "in", "return", "yeet", "switch", "match", "step", "loop",
+    "break", "continue", "let", "const", "import", "as", "pub", "impl", "trait", "try", "catch",
+];
+
+#[derive(Debug)]
+pub enum FormatError {
+    /// the file can not be tokenized
+    Lexing(Vec<Errors>),
+    /// something between two tokens is neither whitespace nor a comment
+    Unexpected(Line),
+    /// a bracket is not closed or closes a different one, the file has to be fixed first
+    Unbalanced(Line),
+    /// the formatted file would be a different program, this is a bug of the formatter
+    Changed(Line),
+}
+
+impl std::fmt::Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::Lexing(errs) => {
+                write!(f, "File could not be tokenized:")?;
+                for err in errs {
+                    write!(f, "\n{}", err)?;
+                }
+                Ok(())
+            }
+            FormatError::Unexpected(line) => {
+                write!(f, "Unexpected text between tokens at {line}")
+            }
+            FormatError::Unbalanced(line) => {
+                write!(f, "Unbalanced bracket at {line}, fix the syntax errors first")
+            }
+            FormatError::Changed(line) => write!(
+                f,
+                "Formatting would change the meaning of the file at {line}, the file was left as it is"
+            ),
+        }
+    }
+}
+
+/// formats the whole source, the result always ends with a new line
+pub fn format(source: &str) -> Result<String, FormatError> {
+    let tokens = lex(source)?;
+    let items = items(source, &tokens)?;
+    let mut lines = layout(source, &items)?;
+    let mut wrapped = Vec::with_capacity(lines.len());
+    for line in lines.drain(..) {
+        wrap(line, &mut wrapped);
+    }
+    // imports are reordered after the check, moving whole lines can not merge tokens
+    let output = render(&wrapped);
+    verify(&tokens, &output)?;
+    sort_imports(&mut wrapped);
+    Ok(render(&wrapped))
+}
+
+/// true if the source is already formatted
+pub fn check(source: &str) -> Result<bool, FormatError> {
+    Ok(format(source)? == source)
+}
+
+fn lex(source: &str) -> Result<Vec<(Tokens, Line)>, FormatError> {
+    let (tokens, lines, mut errors) = crate::tokenize(source, false);
+    if !errors.is_empty() {
+        return Err(FormatError::Lexing(errors));
+    }
+    let (tokens, lines) = match refactor(tokens, lines, &mut errors) {
+        Ok(res) => res,
+        Err(_) => return Err(FormatError::Lexing(errors)),
+    };
+    if !errors.is_empty() {
+        return Err(FormatError::Lexing(errors));
+    }
+    Ok(tokens
+        .into_iter()
+        .zip(lines)
+        .filter(|(token, _)| *token != Tokens::EndOfFile)
+        .collect())
+}
+
+/// token of the output, embedded strings are a single item
+struct Item {
+    token: Tokens,
+    text: String,
+    line: Line,
+}
+
+fn items(source: &str, tokens: &[(Tokens, Line)]) -> Result<Vec<Item>, FormatError> {
+    let mut items = Vec::with_capacity(tokens.len());
+    let mut i = 0;
+    while i < tokens.len() {
+        let (token, line) = &tokens[i];
+        let start = match line.span() {
+            Some(span) => span.start,
+            None => return Err(FormatError::Unexpected(*line)),
+        };
+        let mut last = i;
+        if *token == Tokens::InterpolationStart {
+            let mut depth = 0;
+            for (j, (inner, _)) in tokens.iter().enumerate().skip(i) {
+                match inner {
+                    Tokens::InterpolationStart => depth += 1,
+                    Tokens::InterpolationEnd => depth -= 1,
+                    _ => {}
+                }
+                if depth == 0 {
+                    last = j;
+                    break;
+                }
+            }
+        }
+        let end = match tokens[last].1.span() {
+            Some(span) => span.end,
+            None => return Err(FormatError::Unexpected(tokens[last].1)),
+        };
+        let mut line = *line;
+        line.end = end;
+        items.push(Item {
+            token: token.clone(),
+            text: source[start..end].trim_end().to_string(),
+            line,
+        });
+        i = last + 1;
+    }
+    Ok(items)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Token(usize),
+    Comment(String),
+}
+
+#[derive(Clone)]
+struct OutLine {
+    indent: usize,
+    /// text of the parts and if there is a space in front of it
+    parts: Vec<(Part, String, bool)>,
+}
+
+impl OutLine {
+    fn new(indent: usize) -> Self {
+        Self {
+            indent,
+            parts: Vec::new(),
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.indent * INDENT.len()
+            + self
+                .parts
+                .iter()
+                .map(|(_, text, space)| text.len() + *space as usize)
+                .sum::<usize>()
+    }
+}
+
+/// comment between two tokens and the number of line breaks in front of it
+struct Gap {
+    comments: Vec<(usize, String)>,
+    /// line breaks after the last comment
+    breaks: usize,
+    space: bool,
+}
+
+fn gap(source: &str, from: usize, to: usize, line: Line) -> Result<Gap, FormatError> {
+    let text = &source[from..to];
+    let mut gap = Gap {
+        comments: Vec::new(),
+        breaks: 0,
+        space: !text.is_empty(),
+    };
+    let mut rest = text;
+    loop {
+        let trimmed = rest.trim_start();
+        gap.breaks += rest[..rest.len() - trimmed.len()].matches('\n').count();
+        rest = trimmed;
+        if rest.is_empty() {
+            break;
+        }
+        let len = if rest.starts_with("//") {
+            rest.find('\n').unwrap_or(rest.len())
+        } else if rest.starts_with("/*") {
+            match rest.find("*/") {
+                Some(idx) => idx + 2,
+                None => return Err(FormatError::Unexpected(line)),
+            }
+        } else {
+            return Err(FormatError::Unexpected(line));
+        };
+        gap.comments.push((gap.breaks, rest[..len].trim_end().to_string()));
+        gap.breaks = 0;
+        rest = &rest[len..];
+    }
+    Ok(gap)
+}
+
+fn is_open(token: &Tokens) -> bool {
+    matches!(
+        token,
+        Tokens::Parenteses(false) | Tokens::CurlyBracket(false) | Tokens::SquareBracket(false)
+    )
+}
+
+fn is_close(token: &Tokens) -> bool {
+    matches!(
+        token,
+        Tokens::Parenteses(true) | Tokens::CurlyBracket(true) | Tokens::SquareBracket(true)
+    )
+}
+
+/// opening bracket of a closing one
+fn closing(token: &Tokens) -> &'static str {
+    match token {
+        Tokens::Parenteses(true) => "(",
+        Tokens::CurlyBracket(true) => "{",
+        _ => "[",
+    }
+}
+
+fn is_keyword(item: &Item) -> bool {
+    matches!(&item.token, Tokens::Text(text) if KEYWORDS.contains(&text.as_str()))
+}
+
+/// the item can be the left side of a binary operator
+fn ends_value(item: &Item) -> bool {
+    match &item.token {
+        Tokens::Text(_) => !is_keyword(item),
+        Tokens::Number(..)
+        | Tokens::String(_)
+        | Tokens::Char(_)
+        | Tokens::InterpolationStart
+        | Tokens::Parenteses(true)
+        | Tokens::SquareBracket(true)
+        | Tokens::Optional => true,
+        _ => false,
+    }
+}
+
+/// operators that always have a space on both sides
+fn is_spaced(token: &Tokens) -> bool {
+    use Operators::*;
+    matches!(
+        token,
+        Tokens::Operator(
+            Equal | DoubleEq | NotEqual | LessEq | MoreEq | AddEq | SubEq | MulEq | DivEq | And
+                | Or
+        )
+    )
+}
+
+/// operators that are binary after a value and unary anywhere else
+fn is_arithmetic(token: &Tokens) -> bool {
+    use Operators::*;
+    matches!(
+        token,
+        Tokens::Operator(Plus | Minus | Star | Slash | Mod | Ampersant | Pipe | Not)
+    )
+}
+
+/// binary use of an operator
+fn is_binary(items: &[Item], idx: usize) -> bool {
+    if items[idx].token == Tokens::Operator(Operators::Not) {
+        return false;
+    }
+    idx > 0 && (ends_value(&items[idx - 1]) || is_bang(items, idx - 1))
+}
+
+/// postfix `!` of `divide(a, b)!` and `fun divide()!`
+fn is_bang(items: &[Item], idx: usize) -> bool {
+    items[idx].token == Tokens::Operator(Operators::Not) && idx > 0 && ends_value(&items[idx - 1])
+}
+
+fn space_between(items: &[Item], idx: usize, original: bool) -> bool {
+    let prev = &items[idx - 1];
+    let cur = &items[idx];
+    let (p, c) = (&prev.token, &cur.token);
+    let word = |item: &Item| {
+        item.text
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+    };
+    if matches!(c, Tokens::Comma | Tokens::Semicolon)
+        || matches!(c, Tokens::Parenteses(true) | Tokens::SquareBracket(true))
+        || matches!(p, Tokens::Parenteses(false) | Tokens::SquareBracket(false))
+    {
+        return false;
+    }
+    if matches!(c, Tokens::Dot | Tokens::DoubleDot | Tokens::DoubleColon)
+        || matches!(p, Tokens::Dot | Tokens::DoubleDot | Tokens::DoubleColon)
+    {
+        return false;
+    }
+    if *c == Tokens::Colon {
+        return false;
+    }
+    // `0..=5`
+    if *p == Tokens::Operator(Operators::Equal)
+        && idx > 1
+        && items[idx - 2].token == Tokens::DoubleDot
+    {
+        return false;
+    }
+    // `overload + (other: T)`
+    let overload = |item: &Item| item.token == Tokens::Text("overload".to_string());
+    if overload(prev) || idx > 1 && overload(&items[idx - 2]) {
+        return true;
+    }
+    if matches!(p, Tokens::Colon | Tokens::Comma | Tokens::Semicolon) {
+        return true;
+    }
+    if *p == Tokens::CurlyBracket(false) {
+        return *c != Tokens::CurlyBracket(true);
+    }
+    if matches!(c, Tokens::CurlyBracket(_)) {
+        return true;
+    }
+    // `} else {`
+    if *p == Tokens::CurlyBracket(true)
+        && matches!(c, Tokens::Text(text) if ["else", "catch", "finally"].contains(&text.as_str()))
+    {
+        return true;
+    }
+    // `=>` of match arms
+    if *p == Tokens::Operator(Operators::Equal)
+        && *c == Tokens::Operator(Operators::AngleBracket(true))
+        && !original
+    {
+        return false;
+    }
+    if is_spaced(p) || is_spaced(c) {
+        return true;
+    }
+    if is_arithmetic(c) {
+        // `Vec<&int>`
+        if *p == Tokens::Operator(Operators::AngleBracket(false)) && is_generic(items, idx - 1) {
+            return false;
+        }
+        if is_binary(items, idx) {
+            return true;
+        }
+        if *c == Tokens::Operator(Operators::Not) && ends_value(prev) {
+            return false;
+        }
+        return is_keyword(prev) || is_arithmetic(p) || original;
+    }
+    if is_arithmetic(p) {
+        if is_bang(items, idx - 1) {
+            return word(cur);
+        }
+        return is_binary(items, idx - 1);
+    }
+    // `x?` and `int?`
+    if *c == Tokens::Optional {
+        return false;
+    }
+    let angle = |token: &Tokens| matches!(token, Tokens::Operator(Operators::AngleBracket(_)));
+    if angle(c) && is_generic(items, idx) {
+        return false;
+    }
+    if angle(p) && is_generic(items, idx - 1) {
+        // nothing follows the opening bracket, `Vec<T> name` is the only space after the closing one
+        return *p == Tokens::Operator(Operators::AngleBracket(true)) && word(cur);
+    }
+    // comparison
+    if angle(p) || angle(c) {
+        return true;
+    }
+    if matches!(c, Tokens::Parenteses(false) | Tokens::SquareBracket(false)) {
+        if is_keyword(prev) {
+            return true;
+        }
+        if ends_value(prev) || *p == Tokens::CurlyBracket(true) {
+            return false;
+        }
+        return original;
+    }
+    if word(prev) && word(cur) {
+        return true;
+    }
+    if ends_value(prev) && ends_value(cur) {
+        return true;
+    }
+    original
+}
+
+/// whether the angle bracket at `idx` encloses generic arguments, like those of `Vec<T>`
+fn is_generic(items: &[Item], idx: usize) -> bool {
+    match items[idx].token {
+        Tokens::Operator(Operators::AngleBracket(false)) => generic_end(items, idx).is_some(),
+        Tokens::Operator(Operators::AngleBracket(true)) => (0..idx).rev().any(|open| {
+            items[open].token == Tokens::Operator(Operators::AngleBracket(false))
+                && generic_end(items, open) == Some(idx)
+        }),
+        _ => false,
+    }
+}
+
+/// closing bracket of the generic arguments opened at `open`
+///
+/// the arguments have to be on one line, consist only of what a type can and be followed
+/// by something a type can, otherwise the brackets are comparisons like `a < b, c > d`
+fn generic_end(items: &[Item], open: usize) -> Option<usize> {
+    if open == 0 || !matches!(items[open - 1].token, Tokens::Text(_)) || is_keyword(&items[open - 1]) {
+        return None;
+    }
+    let line = items[open].line.line;
+    let mut depth = 0;
+    let mut brackets = 0;
+    for idx in open..items.len() {
+        if items[idx].line.line != line {
+            return None;
+        }
+        match &items[idx].token {
+            Tokens::Operator(Operators::AngleBracket(false)) => depth += 1,
+            Tokens::Operator(Operators::AngleBracket(true)) => {
+                depth -= 1;
+                if depth == 0 {
+                    return match items.get(idx + 1).map(|item| &item.token) {
+                        None
+                        | Some(
+                            Tokens::Parenteses(_)
+                            | Tokens::SquareBracket(_)
+                            | Tokens::CurlyBracket(_)
+                            | Tokens::Comma
+                            | Tokens::Semicolon
+                            | Tokens::Dot
+                            | Tokens::DoubleColon
+                            | Tokens::Optional
+                            | Tokens::Operator(Operators::Equal | Operators::AngleBracket(true)),
+                        ) => Some(idx),
+                        Some(_) if items[idx + 1].line.line != line => Some(idx),
+                        Some(_) => None,
+                    };
+                }
+            }
+            Tokens::Parenteses(false) | Tokens::SquareBracket(false) => brackets += 1,
+            Tokens::Parenteses(true) | Tokens::SquareBracket(true) => {
+                if brackets == 0 {
+                    return None;
+                }
+                brackets -= 1;
+            }
+            Tokens::Text(_)
+            | Tokens::Number(..)
+            | Tokens::Comma
+            | Tokens::Colon
+            | Tokens::Semicolon
+            | Tokens::Dot
+            | Tokens::DoubleColon
+            | Tokens::Optional
+            | Tokens::Operator(Operators::Ampersant) => (),
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// splits the items into lines and indents them
+fn layout(source: &str, items: &[Item]) -> Result<Vec<OutLine>, FormatError> {
+    let mut lines: Vec<OutLine> = Vec::new();
+    let mut current = OutLine::new(0);
+    // open brackets and the indentation of the lines they are on
+    let mut open: Vec<(usize, &Item)> = Vec::new();
+    let mut prev_end = 0;
+    for (idx, item) in items.iter().enumerate() {
+        let gap = gap(source, prev_end, item.line.start, item.line)?;
+        prev_end = item.line.end;
+        let block = open.last().map_or(0, |(indent, _)| indent + 1);
+
+        for (before, comment) in &gap.comments {
+            comment_line(&mut lines, &mut current, block, *before, comment);
+        }
+        let after_line_comment = gap
+            .comments
+            .last()
+            .is_some_and(|(_, comment)| comment.starts_with("//"));
+        let breaks = gap.breaks;
+
+        let joins_brace = item.token == Tokens::CurlyBracket(false)
+            && gap.comments.is_empty()
+            && idx > 0
+            && matches!(
+                items[idx - 1].token,
+                Tokens::Parenteses(true)
+                    | Tokens::SquareBracket(true)
+                    | Tokens::Text(_)
+                    | Tokens::Optional
+                    | Tokens::Operator(Operators::AngleBracket(true))
+                    | Tokens::Operator(Operators::Not)
+            );
+        let starts_line =
+            current.parts.is_empty() || after_line_comment || (breaks > 0 && !joins_brace);
+        if starts_line {
+            let indent = match is_close(&item.token) {
+                true => open.last().map_or(0, |(indent, _)| *indent),
+                false if continues(items, idx, &current) => block + 1,
+                false => block,
+            };
+            let blank = breaks.max(after_line_comment as usize);
+            new_line(&mut lines, &mut current, indent, blank);
+            if is_close(&item.token) {
+                // no empty line in front of a closing bracket
+                while lines.last().is_some_and(|line| line.parts.is_empty()) {
+                    lines.pop();
+                }
+            }
+            current.parts.push((Part::Token(idx), item.text.clone(), false));
+        } else {
+            let space = match current.parts.last() {
+                Some((Part::Comment(_), _, _)) => true,
+                _ => space_between(items, idx, gap.space),
+            };
+            current.parts.push((Part::Token(idx), item.text.clone(), space));
+        }
+
+        if is_open(&item.token) {
+            open.push((current.indent, item));
+        } else if is_close(&item.token) {
+            let matching = open.pop().is_some_and(|(_, opener)| {
+                deparse_token(&opener.token) == closing(&item.token)
+            });
+            if !matching {
+                return Err(FormatError::Unbalanced(item.line));
+            }
+        }
+    }
+    if let Some((_, opener)) = open.last() {
+        return Err(FormatError::Unbalanced(opener.line));
+    }
+    let end = items.last().map_or(Line::from((1, 1)), |item| item.line);
+    let gap = gap(source, prev_end, source.len(), end)?;
+    let block = open.last().map_or(0, |(indent, _)| indent + 1);
+    for (before, comment) in &gap.comments {
+        comment_line(&mut lines, &mut current, block, *before, comment);
+    }
+    if !current.parts.is_empty() {
+        lines.push(current);
+    }
+    Ok(lines)
+}
+
+/// comments right after a token stay on its line, the others get their own line
+fn comment_line(
+    lines: &mut Vec<OutLine>,
+    current: &mut OutLine,
+    indent: usize,
+    breaks: usize,
+    comment: &str,
+) {
+    let part = (Part::Comment(comment.to_string()), comment.to_string());
+    if breaks == 0 && !current.parts.is_empty() {
+        current.parts.push((part.0, part.1, true));
+    } else {
+        new_line(lines, current, indent, breaks);
+        current.parts.push((part.0, part.1, false));
+    }
+}
+
+/// finishes the current line and starts a new one, `breaks` over one leave an empty line
+fn new_line(lines: &mut Vec<OutLine>, current: &mut OutLine, indent: usize, breaks: usize) {
+    let previous = std::mem::replace(current, OutLine::new(indent));
+    if previous.parts.is_empty() {
+        return;
+    }
+    let opens_block = previous
+        .parts
+        .last()
+        .is_some_and(|(_, text, _)| text == "{");
+    lines.push(previous);
+    if breaks > 1 && !opens_block {
+        lines.push(OutLine::new(0));
+    }
+}
+
+/// the line continues an expression of the line before it
+fn continues(items: &[Item], idx: usize, current: &OutLine) -> bool {
+    if items[idx].token == Tokens::Dot {
+        return true;
+    }
+    let last = match current.parts.last() {
+        Some((Part::Token(last), _, _)) => *last,
+        _ => return false,
+    };
+    let token = &items[last].token;
+    is_spaced(token) || is_arithmetic(token) && is_binary(items, last)
+}
+
+/// breaks a long line after the opening parenthesis of its first argument list,
+/// every argument goes on its own line
+fn wrap(line: OutLine, out: &mut Vec<OutLine>) {
+    if line.width() <= MAX_WIDTH {
+        out.push(line);
+        return;
+    }
+    let mut found = None;
+    for (start, (_, text, _)) in line.parts.iter().enumerate() {
+        if text != "(" || !matches!(line.parts[start].0, Part::Token(_)) {
+            continue;
+        }
+        let mut depth = 0;
+        let mut commas = Vec::new();
+        for (idx, (part, text, _)) in line.parts.iter().enumerate().skip(start) {
+            if !matches!(part, Part::Token(_)) {
+                continue;
+            }
+            match text.as_str() {
+                "(" | "[" | "{" => depth += 1,
+                ")" | "]" | "}" => depth -= 1,
+                "," if depth == 1 => commas.push(idx),
+                _ => {}
+            }
+            if depth == 0 {
+                if !commas.is_empty() {
+                    found = Some((start, commas, idx));
+                }
+                break;
+            }
+        }
+        if found.is_some() {
+            break;
+        }
+    }
+    let (start, commas, end) = match found {
+        Some(found) => found,
+        None => {
+            out.push(line);
+            return;
+        }
+    };
+    let mut parts = line.parts.into_iter();
+    let mut head = OutLine::new(line.indent);
+    head.parts.extend(parts.by_ref().take(start + 1));
+    out.push(head);
+    let mut from = start + 1;
+    for to in commas.into_iter().chain(std::iter::once(end - 1)) {
+        let mut arg = OutLine::new(line.indent + 1);
+        arg.parts.extend(parts.by_ref().take(to + 1 - from));
+        if let Some(first) = arg.parts.first_mut() {
+            first.2 = false;
+        }
+        wrap(arg, out);
+        from = to + 1;
+    }
+    let mut tail = OutLine::new(line.indent);
+    tail.parts.extend(parts);
+    if let Some(first) = tail.parts.first_mut() {
+        first.2 = false;
+    }
+    wrap(tail, out);
+}
+
+fn import_path(line: &OutLine) -> Option<&str> {
+    match line.parts.as_slice() {
+        [(_, import, _), (_, path, _), ..] if line.indent == 0 && import == "import" => {
+            Some(path.as_str())
+        }
+        _ => None,
+    }
+}
+
+/// `#[allow(...)]` in front of an item
+fn is_attribute(line: &OutLine) -> bool {
+    match line.parts.as_slice() {
+        [(_, hash, _), (_, bracket, _), ..] => line.indent == 0 && hash == "#" && bracket == "[",
+        _ => false,
+    }
+}
+
+/// sorts every group of imports that follow each other, libraries go first
+///
+/// attributes move together with the import below them
+fn sort_imports(lines: &mut [OutLine]) {
+    let mut start = 0;
+    while start < lines.len() {
+        let mut imports = Vec::new();
+        let mut end = start;
+        loop {
+            let mut next = end;
+            while next < lines.len() && is_attribute(&lines[next]) {
+                next += 1;
+            }
+            match lines.get(next).and_then(import_path) {
+                Some(path) => {
+                    imports.push((path.to_string(), end..next + 1));
+                    end = next + 1;
+                }
+                None => break,
+            }
+        }
+        if imports.is_empty() {
+            start += 1;
+            continue;
+        }
+        imports.sort_by_key(|(path, _)| (!path.starts_with("\"#"), path.clone()));
+        let sorted = imports
+            .iter()
+            .flat_map(|(_, range)| lines[range.clone()].to_vec())
+            .collect::<Vec<_>>();
+        lines[start..end].clone_from_slice(&sorted);
+        start = end;
+    }
+}
+
+fn render(lines: &[OutLine]) -> String {
+    let mut output = String::new();
+    for line in lines {
+        if !line.parts.is_empty() {
+            output.push_str(&INDENT.repeat(line.indent));
+        }
+        for (_, text, space) in &line.parts {
+            if *space {
+                output.push(' ');
+            }
+            output.push_str(text);
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// the output has to consist of the same tokens as the input
+fn verify(tokens: &[(Tokens, Line)], output: &str) -> Result<(), FormatError> {
+    let formatted = match lex(output) {
+        Ok(formatted) => formatted,
+        Err(_) => return Err(FormatError::Changed(Line::from((1, 1)))),
+    };
+    for (idx, (token, line)) in tokens.iter().enumerate() {
+        match formatted.get(idx) {
+            Some((other, _)) if other == token => {}
+            _ => return Err(FormatError::Changed(*line)),
+        }
+    }
+    if formatted.len() != tokens.len() {
+        let line = formatted[tokens.len().min(formatted.len() - 1)].1;
+        return Err(FormatError::Changed(line));
+    }
+    Ok(())
+}
@@ -0,0 +1,387 @@
+//! Checks of the grammar files, `ruda.ast` and `registry.ast`
+//!
+//! A mistake in a grammar used to show up only when some source was parsed with it,
+//! as a panic of the parser or a tree missing a part. The checks find them in the grammar itself.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::ast_parser::ast_parser::{parse_grammar, Head, HeadParam, Node, NodeType, Tree};
+use crate::lexer::tokenizer::{deparse_token, Tokens};
+use crate::tree_walker::tree_walker::Line;
+
+/// token types the parser understands, like `"'string"`
+const TYPES: [&str; 12] = [
+    "'string",
+    "'number",
+    "'float",
+    "'int",
+    "'text",
+    "'char",
+    "'eof",
+    "'interpolation_start",
+    "'interpolation_end",
+    "'format",
+    "'any",
+    "'doc",
+];
+/// commands the parser understands, like `end !`
+const COMMANDS: [&str; 2] = ["end", "notempty"];
+
+#[derive(Debug, Clone)]
+pub enum GrammarError {
+    /// (reason, line) | the file is not in the format of a grammar
+    Syntax(String, Line),
+    /// (structure, line)
+    UnknownStructure(String, Line),
+    /// (parameter, structure, line) | target of `set` or `notempty` that does not exist
+    UnknownTarget(String, String, Line),
+    /// (type, line)
+    UnknownType(String, Line),
+    /// (command, line)
+    UnknownCommand(String, Line),
+    /// (value, line)
+    InvalidBack(String, Line),
+    /// (back, furthest, line) | `back` leaves the structure
+    BackOutOfBounds(usize, usize, Line),
+    /// (reason, line) | element the parser never gets to
+    Unreachable(String, Line),
+    /// (structures, line) | structures that start with each other without reading a token
+    LeftRecursion(Vec<String>, Line),
+}
+
+impl GrammarError {
+    pub fn line(&self) -> Line {
+        match self {
+            GrammarError::Syntax(_, line)
+            | GrammarError::UnknownStructure(_, line)
+            | GrammarError::UnknownTarget(_, _, line)
+            | GrammarError::UnknownType(_, line)
+            | GrammarError::UnknownCommand(_, line)
+            | GrammarError::InvalidBack(_, line)
+            | GrammarError::BackOutOfBounds(_, _, line)
+            | GrammarError::Unreachable(_, line)
+            | GrammarError::LeftRecursion(_, line) => *line,
+        }
+    }
+    /// the grammar still works, only some of it is useless
+    pub fn is_warning(&self) -> bool {
+        matches!(self, GrammarError::Unreachable(..))
+    }
+}
+
+impl std::fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrammarError::Syntax(reason, line) => write!(f, "{reason} at {line}"),
+            GrammarError::UnknownStructure(name, line) => {
+                write!(f, "structure {name} does not exist at {line}")
+            }
+            GrammarError::UnknownTarget(param, structure, line) => write!(
+                f,
+                "{param} is not a parameter of structure {structure} or a global at {line}"
+            ),
+            GrammarError::UnknownType(kind, line) => write!(f, "unknown token type {kind} at {line}"),
+            GrammarError::UnknownCommand(command, line) => write!(f, "unknown command {command} at {line}"),
+            GrammarError::InvalidBack(value, line) => {
+                write!(f, "back must be a number, found \"{value}\" at {line}")
+            }
+            GrammarError::BackOutOfBounds(back, furthest, line) => write!(
+                f,
+                "back=\"{back}\" goes past the start of the structure, at most {furthest} elements are before it at {line}"
+            ),
+            GrammarError::Unreachable(reason, line) => write!(f, "unreachable element, {reason} at {line}"),
+            GrammarError::LeftRecursion(structures, line) => write!(
+                f,
+                "left recursion, {} starts with itself at {line}",
+                structures.join(" -> ")
+            ),
+        }
+    }
+}
+
+/// checks the grammar, errors and warnings are ordered by line
+pub fn check(source: &str) -> Vec<GrammarError> {
+    match parse_grammar(source) {
+        Ok((tree, globals)) => check_tree(&tree, &globals),
+        Err(errors) => errors,
+    }
+}
+
+/// checks an already parsed grammar
+pub fn check_tree(tree: &Tree, globals: &[HeadParam]) -> Vec<GrammarError> {
+    let mut errors = Vec::new();
+    for head in tree.values() {
+        let checker = Checker { tree, globals, head };
+        checker.scope(&head.nodes, 0, &mut errors);
+    }
+    left_recursion(tree, &mut errors);
+    errors.sort_by_key(|err| (err.line().line, err.line().column));
+    errors
+}
+
+fn param_name(param: &HeadParam) -> &str {
+    match param {
+        HeadParam::Array(name) | HeadParam::Value(name) => name,
+    }
+}
+
+fn node_line(node: &NodeType) -> Line {
+    match node {
+        NodeType::Maybe(node) | NodeType::Expect(node) | NodeType::Command(node) => node.line,
+        NodeType::ArgsCondition(con) => con.line,
+    }
+}
+
+/// elements that leave the scope or the run of alternatives when they match
+fn redirects(node: &Node) -> bool {
+    ["back", "end", "err"].iter().any(|arg| node.arguments.contains_key(*arg))
+}
+
+struct Checker<'a> {
+    tree: &'a Tree,
+    globals: &'a [HeadParam],
+    head: &'a Head,
+}
+
+impl<'a> Checker<'a> {
+    fn has_param(&self, name: &str) -> bool {
+        self.head.parameters.iter().any(|param| param_name(param) == name)
+            || self.globals.iter().any(|param| param_name(param) == name)
+    }
+
+    /// `reach` is how many elements `back` can skip in the scopes around this one
+    fn scope(&self, nodes: &[NodeType], reach: usize, errors: &mut Vec<GrammarError>) {
+        // element that always leaves the scope
+        let mut ended: Option<Line> = None;
+        // optional elements right before this one that leave when they match
+        let mut alternatives: Vec<&Node> = Vec::new();
+        for (i, node) in nodes.iter().enumerate() {
+            if let Some(end) = ended {
+                errors.push(GrammarError::Unreachable(
+                    format!("the element at line {} always leaves the scope", end.line),
+                    node_line(node),
+                ));
+                return;
+            }
+            match node {
+                NodeType::Expect(node) => {
+                    alternatives.clear();
+                    self.element(node, i, reach, errors);
+                    if let Tokens::Text(_) = node.kind {
+                        self.scope(&node.nodes, reach + i + 1, errors);
+                    } else if let Some(nested) = node.nodes.first() {
+                        errors.push(GrammarError::Unreachable(
+                            String::from("elements nested in a token that is not optional are never parsed"),
+                            node_line(nested),
+                        ));
+                    }
+                    if redirects(node) {
+                        ended = Some(node.line);
+                    }
+                }
+                NodeType::Maybe(node) => {
+                    let shadowing = alternatives.iter().find(|alternative| {
+                        alternative.kind == Tokens::String(String::from("'any"))
+                            || (alternative.kind == node.kind && !matches!(node.kind, Tokens::Text(_)))
+                    });
+                    if let Some(alternative) = shadowing {
+                        errors.push(GrammarError::Unreachable(
+                            format!(
+                                "{} is matched by the alternative at line {} first",
+                                deparse_token(&node.kind),
+                                alternative.line.line
+                            ),
+                            node.line,
+                        ));
+                    }
+                    self.element(node, i, reach, errors);
+                    match node.kind {
+                        Tokens::Text(_) => self.scope(&node.nodes, reach + i + 1, errors),
+                        _ => self.scope(&node.nodes, reach + i + 2, errors),
+                    }
+                    if redirects(node) {
+                        alternatives.push(node);
+                    }
+                }
+                NodeType::Command(node) => {
+                    alternatives.clear();
+                    self.command(node, errors);
+                    if node.kind == Tokens::Text(String::from("end")) {
+                        ended = Some(node.line);
+                    }
+                }
+                NodeType::ArgsCondition(con) => {
+                    alternatives.clear();
+                    self.scope(&con.nodes, reach + i + 2, errors);
+                }
+            }
+        }
+    }
+
+    /// kind and arguments of a token or a structure at `idx` of its scope
+    fn element(&self, node: &Node, idx: usize, reach: usize, errors: &mut Vec<GrammarError>) {
+        let structure = match &node.kind {
+            Tokens::Text(name) => match self.tree.get(name) {
+                Some(structure) => Some(structure),
+                None => {
+                    errors.push(GrammarError::UnknownStructure(name.to_string(), node.line));
+                    None
+                }
+            },
+            Tokens::String(kind) if kind.starts_with('\'') && !TYPES.contains(&kind.as_str()) => {
+                errors.push(GrammarError::UnknownType(kind.to_string(), node.line));
+                None
+            }
+            _ => None,
+        };
+        if let Some(targets) = node.arguments.get("set") {
+            for target in targets.split_whitespace() {
+                if !self.has_param(target) {
+                    errors.push(GrammarError::UnknownTarget(
+                        target.to_string(),
+                        self.head.name.to_string(),
+                        node.line,
+                    ));
+                }
+            }
+        }
+        if let (Some(target), Some(structure)) = (node.arguments.get("notempty"), structure) {
+            if !structure.parameters.iter().any(|param| param_name(param) == target) {
+                errors.push(GrammarError::UnknownTarget(
+                    target.to_string(),
+                    structure.name.to_string(),
+                    node.line,
+                ));
+            }
+        }
+        if let Some(back) = node.arguments.get("back") {
+            match back.parse::<usize>() {
+                Ok(back) if back > idx + reach => {
+                    errors.push(GrammarError::BackOutOfBounds(back, idx + reach, node.line));
+                }
+                Ok(_) => (),
+                Err(_) => errors.push(GrammarError::InvalidBack(back.to_string(), node.line)),
+            }
+        }
+    }
+
+    fn command(&self, node: &Node, errors: &mut Vec<GrammarError>) {
+        let name = match &node.kind {
+            Tokens::Text(name) if COMMANDS.contains(&name.as_str()) => name,
+            kind => {
+                errors.push(GrammarError::UnknownCommand(deparse_token(kind), node.line));
+                return;
+            }
+        };
+        if name == "notempty" {
+            match node.arguments.get("nodes") {
+                Some(targets) => {
+                    for target in targets.split_whitespace() {
+                        if !self.head.parameters.iter().any(|param| param_name(param) == target) {
+                            errors.push(GrammarError::UnknownTarget(
+                                target.to_string(),
+                                self.head.name.to_string(),
+                                node.line,
+                            ));
+                        }
+                    }
+                }
+                None => errors.push(GrammarError::Syntax(
+                    String::from("command notempty needs the argument nodes"),
+                    node.line,
+                )),
+            }
+        }
+    }
+}
+
+/// structures the scope can start with before reading a token,
+/// returns whether the scope can match without reading one
+fn leading<'a>(nodes: &'a [NodeType], empty: &HashSet<&str>, out: &mut Vec<(&'a str, Line)>) -> bool {
+    for node in nodes {
+        match node {
+            NodeType::Expect(node) => match &node.kind {
+                Tokens::Text(name) => {
+                    out.push((name, node.line));
+                    if !empty.contains(name.as_str()) {
+                        return false;
+                    }
+                }
+                _ => {
+                    if !node.arguments.contains_key("peek") {
+                        return false;
+                    }
+                }
+            },
+            NodeType::Maybe(node) => {
+                if let Tokens::Text(name) = &node.kind {
+                    out.push((name, node.line));
+                }
+            }
+            NodeType::Command(_) => (),
+            NodeType::ArgsCondition(con) => {
+                leading(&con.nodes, empty, out);
+            }
+        }
+    }
+    true
+}
+
+/// structures that can open themselves again before reading a token never finish
+fn left_recursion(tree: &Tree, errors: &mut Vec<GrammarError>) {
+    let mut empty = HashSet::new();
+    loop {
+        let before = empty.len();
+        for (name, head) in tree.iter() {
+            if !empty.contains(name.as_str()) && leading(&head.nodes, &empty, &mut Vec::new()) {
+                empty.insert(name.as_str());
+            }
+        }
+        if empty.len() == before {
+            break;
+        }
+    }
+    let edges = tree
+        .iter()
+        .map(|(name, head)| {
+            let mut out = Vec::new();
+            leading(&head.nodes, &empty, &mut out);
+            (name.as_str(), out)
+        })
+        .collect::<HashMap<_, _>>();
+    let mut names = edges.keys().copied().collect::<Vec<_>>();
+    names.sort();
+    for start in names {
+        // shortest way back to the start, every cycle is reported once from its first structure
+        let mut previous: HashMap<&str, (&str, Line)> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        let mut closing = None;
+        while let Some(name) = queue.pop_front() {
+            for (next, line) in edges.get(name).into_iter().flatten() {
+                if *next == start {
+                    closing = Some((name, *line));
+                    break;
+                }
+                if *next > start && edges.contains_key(next) && !previous.contains_key(next) {
+                    previous.insert(next, (name, *line));
+                    queue.push_back(next);
+                }
+            }
+            if closing.is_some() {
+                break;
+            }
+        }
+        let (mut name, mut line) = match closing {
+            Some(closing) => closing,
+            None => continue,
+        };
+        let mut cycle = vec![start.to_string()];
+        while name != start {
+            cycle.push(name.to_string());
+            (name, line) = previous[name];
+        }
+        cycle.push(start.to_string());
+        cycle.reverse();
+        errors.push(GrammarError::LeftRecursion(cycle, line));
+    }
+}
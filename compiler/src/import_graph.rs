@@ -0,0 +1,145 @@
+//! Modules of a program and the imports between them
+//!
+//! The graph is built while the modules are loaded, it is used to
+//! detect circular imports and to explain where a missing module
+//! or library was imported from.
+
+use std::collections::HashSet;
+
+use crate::tree_walker::tree_walker::Line;
+use crate::LinkingError;
+
+/// import written in a module
+#[derive(Debug, Clone)]
+pub struct ImportEdge {
+    /// importing module
+    pub from: String,
+    /// imported module, binary libraries start with `#`
+    pub to: String,
+    pub line: Line,
+}
+
+#[derive(Debug)]
+pub struct ImportGraph {
+    /// modules in the order they were loaded, the main module is first
+    pub modules: Vec<String>,
+    /// imports in the order they were found
+    pub edges: Vec<ImportEdge>,
+}
+
+impl ImportGraph {
+    pub fn new(main: &str) -> Self {
+        Self {
+            modules: vec![main.to_string()],
+            edges: Vec::new(),
+        }
+    }
+
+    /// imports leading from the main module to the module, the closest import first
+    ///
+    /// every module is reached through the import it was first found by
+    pub fn chain(&self, module: &str) -> Vec<ImportEdge> {
+        let mut chain: Vec<ImportEdge> = Vec::new();
+        let mut current = module;
+        while current != self.modules[0] {
+            let edge = match self.edges.iter().find(|edge| edge.to == current) {
+                Some(edge) => edge,
+                None => break,
+            };
+            if chain.iter().any(|found| found.from == edge.from) {
+                break;
+            }
+            chain.push(edge.clone());
+            current = &edge.from;
+        }
+        chain
+    }
+
+    /// finds modules importing each other in a circle,
+    /// returns the imports of the circle starting and ending in the same module
+    pub fn cycle(&self) -> Option<Vec<ImportEdge>> {
+        let mut done = HashSet::new();
+        let mut path = vec![self.modules[0].as_str()];
+        let mut edges = Vec::new();
+        self.visit(&mut path, &mut edges, &mut done)
+    }
+
+    fn visit<'a>(
+        &'a self,
+        path: &mut Vec<&'a str>,
+        edges: &mut Vec<&'a ImportEdge>,
+        done: &mut HashSet<&'a str>,
+    ) -> Option<Vec<ImportEdge>> {
+        let module = *path.last().unwrap();
+        for edge in self.edges.iter().filter(|edge| edge.from == module) {
+            if edge.to.starts_with("#") || done.contains(edge.to.as_str()) {
+                continue;
+            }
+            if let Some(start) = path.iter().position(|module| *module == edge.to) {
+                let mut cycle = edges[start..]
+                    .iter()
+                    .map(|edge| (*edge).clone())
+                    .collect::<Vec<_>>();
+                cycle.push(edge.clone());
+                return Some(cycle);
+            }
+            path.push(&edge.to);
+            edges.push(edge);
+            if let Some(cycle) = self.visit(path, edges, done) {
+                return Some(cycle);
+            }
+            path.pop();
+            edges.pop();
+        }
+        done.insert(module);
+        None
+    }
+
+    /// modules the module imports directly or through other modules, sorted
+    pub fn dependencies(&self, module: &str) -> Vec<String> {
+        let mut found = HashSet::new();
+        let mut next = vec![module];
+        while let Some(current) = next.pop() {
+            for edge in self.edges.iter().filter(|edge| edge.from == current) {
+                if !edge.to.starts_with("#") && edge.to != module && found.insert(edge.to.as_str()) {
+                    next.push(&edge.to);
+                }
+            }
+        }
+        let mut dependencies = found.into_iter().map(String::from).collect::<Vec<_>>();
+        dependencies.sort();
+        dependencies
+    }
+
+    /// checks that every imported binary library is loaded
+    pub fn check_libraries(&self, is_loaded: impl Fn(&str) -> bool) -> Result<(), LinkingError> {
+        for edge in self.edges.iter() {
+            if edge.to.starts_with("#") && !is_loaded(&edge.to) {
+                let mut chain = vec![edge.clone()];
+                chain.extend(self.chain(&edge.from));
+                return Err(LinkingError::LibraryNotFound(edge.to.clone(), chain));
+            }
+        }
+        Ok(())
+    }
+
+    /// the graph in the DOT format of Graphviz
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph imports {\n");
+        for module in self.modules.iter() {
+            dot.push_str(&format!("    \"{module}\";\n"));
+        }
+        for edge in self.edges.iter() {
+            let style = match edge.to.starts_with("#") {
+                true => " style=dashed",
+                false => "",
+            };
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"{style}];\n",
+                edge.from, edge.to, edge.line.line
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
@@ -1,5 +1,6 @@
 pub mod dictionary {
     use runtime::runtime_types;
+    use serde::{Deserialize, Serialize};
 
     use super::{
         AnalyzationError::{self, ErrType},
@@ -92,6 +93,7 @@ pub mod dictionary {
                     Root::Identifier(val) => match val.as_str() {
                         "true" => Some(ConstValue::Bool(true)),
                         "false" => Some(ConstValue::Bool(false)),
+                        "null" => Some(ConstValue::Null),
                         _ => {
                             let const_val = dictionary.find_const(&val);
                             if let Some(const_val) = const_val {
@@ -107,6 +109,8 @@ pub mod dictionary {
                     },
                     Root::Literal(lit) => ConstValue::from_literal(lit, dictionary, errors),
                     Root::Parenthesis(val) => analyze_const(val, dictionary, errors),
+                    Root::Match(_) => None,
+                    Root::Interpolation(_) => None,
                 };
                 match val {
                     Some(mut val) => {
@@ -198,8 +202,10 @@ pub mod dictionary {
                 let mut result = Enum {
                     identifier: get_ident(&node),
                     keys: vec![],
+                    fields: vec![],
                     methods: vec![],
                     overloads: vec![],
+                    public: public(&node),
                     line: node.line,
                     docs: get_docs(&node),
                 };
@@ -224,7 +230,28 @@ pub mod dictionary {
                             ))
                         }
                     }
+                    let fields = step_inside_arr(&enum_value, "fields")
+                        .iter()
+                        .map(|arg| Arg {
+                            identifier: get_ident(&arg),
+                            kind: get_type(step_inside_val(&arg, "type"), errors, file_name),
+                            line: arg.line,
+                            default: None,
+                        })
+                        .collect::<Vec<_>>();
                     result.keys.push((ident, n, enum_value.line));
+                    result.fields.push(fields);
+                }
+                // variants with data are told apart by their type, not by a number
+                if result.has_payload() {
+                    for enum_value in step_inside_arr(&node, "values") {
+                        if let Tokens::Number(..) = get_token(&enum_value, "default") {
+                            errors.push(ErrType::EnumPayloadAssignedNumber(
+                                result.identifier.to_string(),
+                                enum_value.line,
+                            ))
+                        }
+                    }
                 }
                 if dictionary.register_id(result.identifier.to_string(), IdentifierKinds::Enum) {
                     dictionary.enums.push(result);
@@ -306,8 +333,10 @@ pub mod dictionary {
                                         identifier: ident,
                                         kind,
                                         line: arg.line,
+                                        default: get_default(&arg, errors, file_name),
                                     })
                                 }
+                                check_defaults(&args, errors);
                                 let code = if constructor.nodes.contains_key("code") {
                                     codeblock_parser::generate_tree(
                                         step_inside_val(&constructor, "code"),
@@ -344,6 +373,9 @@ pub mod dictionary {
                                     id: 0,
                                     takes_self: false,
                                     docs: get_docs(&node),
+                                    inline: InlineHint::Never,
+                                    inlining: false,
+                                    allow: Vec::new(),
                                 });
                                 Some(functions.len() - 1)
                             } else {
@@ -369,6 +401,7 @@ pub mod dictionary {
                     impls,
                     constructor,
                     docs: get_docs(&node),
+                    allow: get_allow(&node),
                 };
                 for key in step_inside_arr(node, "keys") {
                     let ident = get_ident(&key);
@@ -427,7 +460,7 @@ pub mod dictionary {
                 let alias = match try_get_ident(&node) {
                     Some(alias) => alias,
                     None => {
-                        let alias = path.split("/").last().unwrap();
+                        let alias = path.trim_end_matches("/").split("/").last().unwrap();
                         if alias.starts_with("#") {
                             kind = ImportKinds::Dll;
                             alias[1..].to_string()
@@ -441,6 +474,7 @@ pub mod dictionary {
                     alias: alias.trim_end_matches(".rd").to_string(),
                     line: node.line,
                     kind,
+                    allow: get_allow(&node),
                 });
             }
             "KWFun" => {
@@ -456,7 +490,7 @@ pub mod dictionary {
                 }
             }
             "KWLet" => {
-                let identifier = get_ident(&node);
+                let identifier = get_ident(step_inside_val(&node, "identifier"));
                 let kind = if let Tokens::Text(txt) = &step_inside_val(node, "type").name {
                     if txt == "type_specifier" {
                         Some(get_type(
@@ -496,6 +530,7 @@ pub mod dictionary {
                         real_value: None,
                         line: node.line,
                         docs: get_docs(&node),
+                        allow: get_allow(&node),
                     })
                 } else {
                     errors.push(ErrType::ConflictingNames(identifier.to_string(), node.line))
@@ -533,6 +568,7 @@ pub mod dictionary {
                 let identifier = get_ident(&node);
                 let mut functions = Vec::new();
                 let mut overloads = Vec::new();
+                let mut defaults = Vec::new();
                 let traits = get_traits(&node, errors);
                 for method in step_inside_arr(&node, "methods") {
                     if let Tokens::Text(txt) = &method.name {
@@ -541,7 +577,12 @@ pub mod dictionary {
                                 overloads.push(get_overload_siginifier(&method, errors, file_name))
                             }
                             "KWFun" => {
-                                functions.push(get_fun_siginifier(&method, errors, file_name));
+                                let fun = get_fun_siginifier(&method, errors, file_name);
+                                let body = step_inside_val(&method, "code");
+                                if body.name == Tokens::Text("code_block".to_string()) {
+                                    defaults.push(fun.identifier.clone().unwrap());
+                                }
+                                functions.push(fun);
                             }
                             _ => {}
                         }
@@ -551,6 +592,7 @@ pub mod dictionary {
                     dictionary.traits.push(Trait {
                         identifier,
                         methods: functions,
+                        defaults,
                         overloads,
                         traits,
                         public: is_pub,
@@ -572,6 +614,7 @@ pub mod dictionary {
                         identifier: ident,
                         kind,
                         line: arg.line,
+                        default: None,
                     })
                 }
                 let mut fields = Vec::new();
@@ -611,8 +654,10 @@ pub mod dictionary {
                 let mut path = Vec::new();
                 for part in step_inside_arr(&node, "path") {
                     match &part.name {
-                        Tokens::Text(txt) => {
-                            path.push(PathPart::Identifier(txt.to_string()));
+                        Tokens::Text(txt) if txt == "ident" => {
+                            if let Tokens::Text(txt) = &step_inside_val(&part, "identifier").name {
+                                path.push(PathPart::Identifier(txt.to_string()));
+                            }
                         }
                         Tokens::Operator(Operators::Star) => {
                             path.push(PathPart::Asterisk);
@@ -660,7 +705,14 @@ pub mod dictionary {
         } else {
             None
         };
-        let arg = step_inside_val(&node, "arg");
+        let arg = try_step_inside_val(&node, "arg")
+            .filter(|arg| arg.name == Tokens::Text("arg".to_string()))
+            .map(|arg| Arg {
+                identifier: get_ident(&arg),
+                kind: get_type(step_inside_val(&arg, "type"), errors, file_name),
+                line: arg.line,
+                default: None,
+            });
 
         // fujj
         let code = if node.nodes.contains_key("code") {
@@ -671,11 +723,7 @@ pub mod dictionary {
 
         Overload {
             operator,
-            arg: Arg {
-                identifier: get_ident(&arg),
-                kind: get_type(step_inside_val(&arg, "type"), errors, file_name),
-                line: arg.line,
-            },
+            arg,
             stack_size: None,
             location: None,
             return_type: kind,
@@ -757,6 +805,7 @@ pub mod dictionary {
                             identifier: ident,
                             kind,
                             line: arg.line,
+                            default: get_default(arg, errors, file_name),
                         });
                     }
                     _ => {
@@ -765,6 +814,7 @@ pub mod dictionary {
                 }
             }
         }
+        check_defaults(&args, errors);
         let can_yeet = step_inside_val(&node, "errorable").name
             == Tokens::Operator(crate::lexer::tokenizer::Operators::Not);
         let public = if node.nodes.contains_key("public") {
@@ -794,13 +844,45 @@ pub mod dictionary {
             instrs_end: 0,
             return_type: kind,
             generics,
-            public: false,
+            public,
             code,
             line: node.line,
             pointers: None,
             id: 0,
             takes_self,
             docs: get_docs(&node),
+            inline: get_inline(&node),
+            inlining: false,
+            allow: get_allow(&node),
+        }
+    }
+    /// Default value of an argument, it has to be known at compile time
+    pub fn get_default(arg: &Node, errors: &mut Vec<ErrType>, file_name: &str) -> Option<ConstValue> {
+        let node = try_step_inside_val(arg, "default")?;
+        match &node.name {
+            Tokens::Text(txt) if txt == "expression" => (),
+            _ => return None,
+        }
+        let value = expression_parser::expr_into_tree(node, errors, file_name);
+        match analyze_const(&value, &Dictionary::new(), errors) {
+            Some(ConstValue::Function(_) | ConstValue::Array(_) | ConstValue::Undefined) | None => {
+                errors.push(ErrType::InvalidDefault(get_ident(arg), node.line));
+                None
+            }
+            default => default,
+        }
+    }
+    /// Only trailing arguments can be left out, so every argument after a default needs one too
+    pub fn check_defaults(args: &[Arg], errors: &mut Vec<ErrType>) {
+        let mut defaults = false;
+        for arg in args {
+            match arg.default {
+                Some(_) => defaults = true,
+                None if defaults => {
+                    errors.push(ErrType::MissingDefault(arg.identifier.clone(), arg.line))
+                }
+                None => (),
+            }
         }
     }
     pub fn public(node: &Node) -> bool {
@@ -809,6 +891,18 @@ pub mod dictionary {
         }
         false
     }
+    pub fn get_inline(node: &Node) -> InlineHint {
+        if let Some(inline) = try_step_inside_val(node, "inline") {
+            if let Tokens::Text(txt) = &inline.name {
+                match txt.as_str() {
+                    "inline" => return InlineHint::Always,
+                    "noinline" => return InlineHint::Never,
+                    _ => (),
+                }
+            }
+        }
+        InlineHint::Auto
+    }
     pub fn get_operator(node: &Node) -> Tokens {
         step_inside_val(node, "op").name.clone()
     }
@@ -864,6 +958,22 @@ pub mod dictionary {
             };
         }
         let refs = count_refs(node);
+        // types of binary libraries are parsed with a grammar without tuples
+        let tuple = try_step_inside_val(&node, "tuple");
+        if let Some(tuple) = tuple.filter(|tuple| tuple.name == Tokens::Text(String::from("tuple_decl"))) {
+            return Kind {
+                body: TypeBody::Tuple {
+                    types: step_inside_arr(&tuple, "types")
+                        .iter()
+                        .map(|kind| get_type(kind, errors, file_name))
+                        .collect(),
+                    refs,
+                    nullable,
+                },
+                line: node.line,
+                file: Some(file_name.to_string()),
+            };
+        }
         let main = if let Some(type_ident) =
             try_step_inside_arr(step_inside_val(&node, "main"), "nodes")
         {
@@ -958,6 +1068,18 @@ pub mod dictionary {
         }
         None
     }
+    /// lints silenced on the item with `#[allow(...)]`
+    pub fn get_allow(node: &Node) -> Vec<String> {
+        let mut result = Vec::new();
+        for attribute in try_step_inside_arr(node, "allow").into_iter().flatten() {
+            for lint in step_inside_arr(attribute, "lints") {
+                if let Tokens::Text(txt) = &step_inside_val(lint, "identifier").name {
+                    result.push(txt.to_string());
+                }
+            }
+        }
+        result
+    }
     pub fn get_token<'a>(node: &'a Node, ident: &'a str) -> &'a Tokens {
         return &step_inside_val(&node, ident).name;
     }
@@ -1004,7 +1126,7 @@ pub mod dictionary {
         RSO(String, Option<Dictionary>),
     }
     /// all of the defined types/variables (enum, struct, function) in the current scope will be registered here
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Dictionary {
         pub functions: Vec<Function>,
         pub types: Vec<TypeDef>,
@@ -1028,22 +1150,26 @@ pub mod dictionary {
             None
         }
     }
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Import {
         pub path: String,
         pub alias: String,
         pub line: Line,
         pub kind: ImportKinds,
+        /// lints silenced with `#[allow(...)]`
+        pub allow: Vec<String>,
     }
-    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
     pub enum ImportKinds {
         Dll,
         Rd,
     }
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Trait {
         pub identifier: String,
         pub methods: Vec<Function>,
+        /// methods that have a default body
+        pub defaults: Vec<String>,
         pub overloads: Vec<Overload>,
         // dependences
         pub traits: Vec<NestedIdent>,
@@ -1051,7 +1177,7 @@ pub mod dictionary {
         pub line: Line,
         pub docs: Option<String>,
     }
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub enum IdentifierKinds {
         Function,
         Type,
@@ -1076,7 +1202,7 @@ pub mod dictionary {
             }
         }
     }
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct TypeDef {
         pub kind: Kind,
         pub identifier: String,
@@ -1087,13 +1213,13 @@ pub mod dictionary {
         pub line: Line,
         pub docs: Option<String>,
     }
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct GenericDecl {
         pub identifier: String,
         pub traits: Vec<NestedIdent>,
         pub line: Line,
     }
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Error {
         pub identifier: String,
         pub src_loc: usize,
@@ -1102,38 +1228,50 @@ pub mod dictionary {
         pub line: Line,
         pub docs: Option<String>,
     }
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub enum ErrorField {
         Expression(expression_parser::ValueType),
         CodeBlock(Vec<codeblock_parser::Nodes>),
     }
-    #[derive(Debug, Clone, PartialEq)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Arg {
         pub identifier: String,
         pub kind: Kind,
         pub line: Line,
+        /// value used when the argument is left out of a call
+        pub default: Option<ConstValue>,
     }
     impl Arg {
-        pub fn from_barg(barg: &(String, Kind, libloader::MemoryTypes, Line)) -> Arg {
+        pub fn from_barg(
+            barg: &(String, Kind, libloader::MemoryTypes, Line, Option<ConstValue>),
+        ) -> Arg {
             Arg {
                 identifier: barg.0.clone(),
                 kind: barg.1.clone(),
                 line: barg.3,
+                default: barg.4.clone(),
             }
         }
     }
-    #[derive(Debug, Clone)]
+    impl PartialEq for Arg {
+        /// default values do not change the kind of a function
+        fn eq(&self, other: &Self) -> bool {
+            self.identifier == other.identifier && self.kind == other.kind && self.line == other.line
+        }
+    }
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Use {
-        path: Vec<PathPart>,
-        alias: Option<String>,
-        line: Line,
+        /// modules leading to the item, the last part is the item or `*`
+        pub path: Vec<PathPart>,
+        pub alias: Option<String>,
+        pub line: Line,
     }
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub enum PathPart {
         Identifier(String),
         Asterisk,
     }
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Function {
         /// function identifiers will be changed to allow for function overload
         /// name mangler rules: "{identifier}:{args.foreach("{typeof}:")}"
@@ -1161,6 +1299,23 @@ pub mod dictionary {
         pub id: usize,
         pub takes_self: bool,
         pub docs: Option<String>,
+        pub inline: InlineHint,
+        /// set by codegen while the body is being inlined into a caller
+        /// so that recursive functions are never expanded into themselves
+        pub inlining: bool,
+        /// lints silenced with `#[allow(...)]`
+        pub allow: Vec<String>,
+    }
+    /// inline attribute of a function
+    ///
+    /// pub inline fun danda() {}
+    /// noinline fun danda() {}
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+    pub enum InlineHint {
+        /// left to the size heuristic in codegen
+        Auto,
+        Always,
+        Never,
     }
     /// used to correct function calls
     #[derive(Debug, Clone)]
@@ -1168,11 +1323,11 @@ pub mod dictionary {
         pub location: usize,
         pub function: InnerPath,
     }
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Overload {
         pub operator: Tokens,
-        /// type of args in order
-        pub arg: Arg,
+        /// right hand side of the operator, None for unary operators
+        pub arg: Option<Arg>,
         /// size needed to allocate on stack while function call (args.len() included)
         pub stack_size: Option<usize>,
         /// location in bytecode, so runtime knows where to jump
@@ -1184,19 +1339,35 @@ pub mod dictionary {
         pub line: Line,
         pub docs: Option<String>,
     }
-    #[derive(Debug)]
+    impl Overload {
+        /// identifier of the method the overload at `idx` of a struct is turned into
+        pub fn method_ident(idx: usize) -> String {
+            format!("overload {idx}")
+        }
+    }
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Enum {
         pub identifier: String,
         /// enum values and their offset
         /// enum ErrCode { Continue = 100, SwitchingProtocols, ..., Ok = 200, ... }
         pub keys: Vec<(String, usize, Line)>,
+        /// fields of every variant in the order of `keys`, empty for variants without data
+        /// enum Shape { Circle(r: float), Rect(w: float, h: float), Empty }
+        pub fields: Vec<Vec<Arg>>,
         pub methods: Vec<Function>,
         pub overloads: Vec<Overload>,
+        pub public: bool,
         pub line: Line,
         pub docs: Option<String>,
     }
+    impl Enum {
+        /// enums with data in any variant are stored as objects instead of numbers
+        pub fn has_payload(&self) -> bool {
+            self.fields.iter().any(|fields| !fields.is_empty())
+        }
+    }
     pub type NestedIdent = Vec<String>;
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Struct {
         pub generics: Vec<GenericDecl>,
         pub identifier: String,
@@ -1213,6 +1384,8 @@ pub mod dictionary {
         /// index of function that is a constructor
         pub constructor: Option<usize>,
         pub docs: Option<String>,
+        /// lints silenced with `#[allow(...)]` for the struct and all of its methods
+        pub allow: Vec<String>,
     }
     impl Struct {
         pub fn get_field(&self, name: &str) -> Option<(crate::codegen::CompoundField, usize)> {
@@ -1236,26 +1409,17 @@ pub mod dictionary {
                     }
                 }
             }
-            for overload in &self.overloads {
-                if &overload.arg.identifier == name {
-                    return Some((
-                        crate::codegen::CompoundField::OverloadedOperator(todo!()),
-                        0,
-                    ));
-                }
-            }
-
             None
         }
     }
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Implementation {
         pub target: NestedIdent,
         pub functions: Vec<Function>,
         pub overloads: Vec<Overload>,
         pub line: Line,
     }
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Variable {
         pub kind: Option<Kind>,
         pub identifier: String,
@@ -1263,7 +1427,7 @@ pub mod dictionary {
         pub location: usize,
         pub line: Line,
     }
-    #[derive(Debug)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct Constant {
         pub identifier: String,
         /// location on stack
@@ -1273,8 +1437,10 @@ pub mod dictionary {
         pub real_value: Option<ConstValue>,
         pub line: Line,
         pub docs: Option<String>,
+        /// lints silenced with `#[allow(...)]`
+        pub allow: Vec<String>,
     }
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Serialize, Deserialize)]
     pub enum ConstValue {
         Number(f64),
         Int(i64),
@@ -1338,6 +1504,7 @@ pub mod dictionary {
                     }
                 },
                 expression_parser::Literals::String(str) => Some(ConstValue::String(str.clone())),
+                expression_parser::Literals::Tuple(_) => None,
             }
         }
         pub fn into_number(&self) -> Option<(f64, char)> {
@@ -1350,6 +1517,37 @@ pub mod dictionary {
                 _ => None,
             }
         }
+        /// Literal that evaluates to the constant, used to fill in default values of arguments
+        pub fn to_value(&self, line: Line) -> Option<ValueType> {
+            let (number, unary) = match self {
+                ConstValue::Number(n) => (Some(Tokens::Number(n.abs(), 'n')), *n < 0.0),
+                ConstValue::Int(n) => (Some(Tokens::Number(n.abs() as f64, 'i')), *n < 0),
+                ConstValue::Float(n) => (Some(Tokens::Number(n.abs(), 'f')), *n < 0.0),
+                ConstValue::Uint(n) => (Some(Tokens::Number(*n as f64, 'u')), false),
+                _ => (None, false),
+            };
+            let root = match (self, number) {
+                (_, Some(number)) => Root::Literal(expression_parser::Literals::Number(number)),
+                (ConstValue::Char(c), _) => Root::Literal(expression_parser::Literals::Char(*c)),
+                (ConstValue::String(text), _) => {
+                    Root::Literal(expression_parser::Literals::String(text.clone()))
+                }
+                (ConstValue::Bool(b), _) => Root::Identifier(b.to_string()),
+                (ConstValue::Null, _) => Root::Identifier("null".to_string()),
+                _ => return None,
+            };
+            Some(ValueType::Value(expression_parser::Variable {
+                unary: match unary {
+                    true => vec![(Operators::Minus, line)],
+                    false => vec![],
+                },
+                refs: expression_parser::Ref::None,
+                modificatior: None,
+                root: (root, line),
+                tail: vec![],
+                line,
+            }))
+        }
         pub fn is_array(&self) -> bool {
             match self {
                 ConstValue::Array(_) => true,
@@ -1600,7 +1798,7 @@ pub mod dictionary {
             Ok(())
         }
     }
-    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
     pub enum KindType {
         Struct,
         Enum,
@@ -1648,7 +1846,7 @@ pub mod dictionary {
                 refs: 0,
                 main: vec![],
                 generics: vec![],
-                line: Line { line: 0, column: 0 },
+                line: Line { line: 0, column: 0, start: 0, end: 0 },
                 nullable: false,
                 file: None,
                 kind: KindType::None,
@@ -1661,7 +1859,7 @@ pub mod dictionary {
                 refs: 0,
                 main: vec!["null".to_string()],
                 generics: vec![],
-                line: Line { line: 0, column: 0 },
+                line: Line { line: 0, column: 0, start: 0, end: 0 },
                 nullable: false,
                 file: None,
                 kind: KindType::Primitive,
@@ -1864,7 +2062,7 @@ pub mod dictionary {
                 refs: 0,
                 main,
                 generics: Vec::new(),
-                line: Line { line: 0, column: 0 },
+                line: Line { line: 0, column: 0, start: 0, end: 0 },
                 nullable: false,
                 file: Some(file),
                 kind: KindType::BinFun,
@@ -1893,7 +2091,7 @@ pub mod dictionary {
                 refs: 0,
                 path: vec![],
                 generics: vec![],
-                line: Line { line: 0, column: 0 },
+                line: Line { line: 0, column: 0, start: 0, end: 0 },
                 name: String::new(),
                 nullable: false,
                 file: None,
@@ -2028,7 +2226,7 @@ pub mod dictionary {
             false
         }
     }
-    #[derive(Debug)]
+    #[derive(Debug, Clone)]
     pub enum TypeComparison {
         /// types are equal
         Equal,
@@ -2118,6 +2316,18 @@ pub mod AnalyzationError {
         CannotInitializeConstant(String),
         /// missong_operator | occurs when expression expects operator but there is none
         MissingOperator(Line),
+        /// enum line col | occurs when you try to assign a number to a variant of an enum that carries data
+        EnumPayloadAssignedNumber(String, Line),
+        /// reason line col | occurs when the format specifier of a value embedded in a string is not valid
+        InvalidFormatSpec(String, Line),
+        /// reason line col | occurs when a variable is declared with a tuple pattern where it can not be
+        InvalidPattern(String, Line),
+        /// argument line col | occurs when the default value of an argument is not a constant
+        InvalidDefault(String, Line),
+        /// argument line col | occurs when an argument without a default value follows one with it
+        MissingDefault(String, Line),
+        /// line col | occurs when a positional argument is written after a named one
+        PositionalAfterNamed(Line),
     }
 
     impl std::fmt::Display for ErrType {
@@ -2168,6 +2378,24 @@ pub mod AnalyzationError {
                 ErrType::MissingOperator(line) => {
                     write!(f, "missing operator at {line}")
                 }
+                ErrType::EnumPayloadAssignedNumber(ident, line) => {
+                    write!(f, "variants of enum {ident} carry data and can not be assigned numbers at {line}")
+                }
+                ErrType::InvalidFormatSpec(reason, line) => {
+                    write!(f, "invalid format specifier: {reason} at {line}")
+                }
+                ErrType::InvalidPattern(reason, line) => {
+                    write!(f, "invalid pattern: {reason} at {line}")
+                }
+                ErrType::InvalidDefault(ident, line) => {
+                    write!(f, "default value of argument {ident} is not a constant at {line}")
+                }
+                ErrType::MissingDefault(ident, line) => {
+                    write!(f, "argument {ident} follows an argument with a default value and needs one too at {line}")
+                }
+                ErrType::PositionalAfterNamed(line) => {
+                    write!(f, "positional argument after a named one at {line}")
+                }
             }
         }
     }
@@ -2177,13 +2405,14 @@ use std::{borrow::BorrowMut, collections::HashMap};
 
 use super::dictionary::*;
 use crate::{codegen::InnerPath, libloader, tree_walker::tree_walker::Line};
-#[derive(Clone, PartialEq)]
+use serde::{Deserialize, Serialize};
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Kind {
     pub body: TypeBody,
     pub line: Line,
     pub file: Option<String>,
 }
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TypeBody {
     Function {
         args: Vec<Arg>,
@@ -2209,6 +2438,12 @@ pub enum TypeBody {
         refs: usize,
         nullable: bool,
     },
+    /// (int, string)
+    Tuple {
+        types: Vec<Kind>,
+        refs: usize,
+        nullable: bool,
+    },
     Void,
 }
 
@@ -2303,6 +2538,26 @@ impl std::fmt::Debug for Kind {
                     write!(f, "?")?;
                 }
             }
+            TypeBody::Tuple {
+                types,
+                refs,
+                nullable,
+            } => {
+                for _ in 0..*refs {
+                    write!(f, "&")?;
+                }
+                write!(f, "(")?;
+                for (i, kind) in types.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}", kind)?;
+                }
+                write!(f, ")")?;
+                if *nullable {
+                    write!(f, "?")?;
+                }
+            }
             TypeBody::Void => {
                 write!(f, "*void*")?;
             }
@@ -2386,6 +2641,20 @@ impl Kind {
         }
     }
 
+    pub fn from_error(ident: String, file: String, line: Line) -> Self {
+        Kind {
+            body: TypeBody::Type {
+                refs: 0,
+                main: vec![ident],
+                generics: Vec::new(),
+                nullable: false,
+                kind: KindType::Error,
+            },
+            line,
+            file: Some(file),
+        }
+    }
+
     pub fn from_fun(fun: &Function, file: String) -> Self {
         let main = vec![fun.identifier.as_ref().unwrap().clone()];
         Kind {
@@ -2467,7 +2736,7 @@ impl Kind {
     pub fn void() -> Self {
         Kind {
             body: TypeBody::Void,
-            line: Line { line: 0, column: 0 },
+            line: Line { line: 0, column: 0, start: 0, end: 0 },
             file: None,
         }
     }
@@ -2500,6 +2769,13 @@ impl Kind {
         }
     }
 
+    pub fn is_tuple(&self) -> bool {
+        match &self.body {
+            TypeBody::Tuple { .. } => true,
+            _ => false,
+        }
+    }
+
     pub fn is_void(&self) -> bool {
         match &self.body {
             TypeBody::Void => true,
@@ -2528,7 +2804,33 @@ impl Kind {
                 return_type,
                 refs,
             } => {
-                todo!()
+                if let TypeBody::Function {
+                    args: args2,
+                    return_type: return_type2,
+                    refs: refs2,
+                } = &other.body
+                {
+                    if *refs != *refs2 {
+                        return TypeComparison::ReferenceDiff(*refs as i32 - *refs2 as i32);
+                    }
+                    if args.len() != args2.len() {
+                        return TypeComparison::NotEqual;
+                    }
+                    for (arg, arg2) in args.iter().zip(args2.iter()) {
+                        if arg.kind.cmp(&arg2.kind, generics).is_not_equal() {
+                            return TypeComparison::NotEqual;
+                        }
+                    }
+                    match (return_type.as_ref(), return_type2.as_ref()) {
+                        (Some(kind), Some(kind2)) if kind.cmp(kind2, generics).is_not_equal() => {
+                            TypeComparison::NotEqual
+                        }
+                        (Some(_), Some(_)) | (None, None) => TypeComparison::Equal,
+                        _ => TypeComparison::NotEqual,
+                    }
+                } else {
+                    TypeComparison::NotEqual
+                }
             }
             TypeBody::Type {
                 refs,
@@ -2605,6 +2907,39 @@ impl Kind {
                     TypeComparison::NotEqual
                 }
             }
+            TypeBody::Tuple {
+                types,
+                refs,
+                nullable,
+            } => {
+                if *nullable && other.is_null() {
+                    return TypeComparison::Equal;
+                }
+                if let TypeBody::Tuple {
+                    types: types_2,
+                    refs: refs_2,
+                    nullable: nullable_2,
+                } = &other.body
+                {
+                    if !nullable && *nullable_2 {
+                        return TypeComparison::NotNullable;
+                    }
+                    if *refs != *refs_2 {
+                        return TypeComparison::ReferenceDiff(*refs as i32 - *refs_2 as i32);
+                    }
+                    if types.len() != types_2.len() {
+                        return TypeComparison::NotEqual;
+                    }
+                    for (kind, kind_2) in types.iter().zip(types_2.iter()) {
+                        if !kind.cmp(kind_2, generics).is_equal() {
+                            return TypeComparison::NotEqual;
+                        }
+                    }
+                    TypeComparison::Equal
+                } else {
+                    TypeComparison::NotEqual
+                }
+            }
             TypeBody::Void => match &other.body {
                 TypeBody::Void => TypeComparison::Equal,
                 _ => TypeComparison::NotEqual,
@@ -2647,6 +2982,7 @@ impl Kind {
             TypeBody::Function { refs, .. } => *refs,
             TypeBody::Type { refs, .. } => *refs,
             TypeBody::Array { refs, .. } => *refs,
+            TypeBody::Tuple { refs, .. } => *refs,
             TypeBody::Generic { refs, .. } => *refs,
             _ => 0,
         }
@@ -2657,6 +2993,7 @@ impl Kind {
             TypeBody::Function { refs, .. } => refs,
             TypeBody::Type { refs, .. } => refs,
             TypeBody::Array { refs, .. } => refs,
+            TypeBody::Tuple { refs, .. } => refs,
             TypeBody::Generic { refs, .. } => refs,
             _ => unreachable!("cannot get refs of non type"),
         }
@@ -2667,6 +3004,7 @@ impl Kind {
             TypeBody::Function { .. } => false,
             TypeBody::Type { nullable, .. } => *nullable,
             TypeBody::Array { nullable, .. } => *nullable,
+            TypeBody::Tuple { nullable, .. } => *nullable,
             TypeBody::Generic { nullable, .. } => *nullable,
             _ => false,
         }
@@ -2676,6 +3014,7 @@ impl Kind {
         match &mut self.body {
             TypeBody::Type { nullable, .. } => nullable,
             TypeBody::Array { nullable, .. } => nullable,
+            TypeBody::Tuple { nullable, .. } => nullable,
             TypeBody::Generic { nullable, .. } => nullable,
             _ => unreachable!("cannot get nullable of non type"),
         }
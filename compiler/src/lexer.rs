@@ -3,34 +3,39 @@ pub mod tokenizer {
         lexing_preprocessor::refactor,
         parse_err::{self},
     }, intermediate::{dictionary::{ConstValue, ShallowType}, Kind}, tree_walker::tree_walker::Line};
+    use serde::{Deserialize, Serialize};
     const RESERVED_CHARS: &str = " +-*/=%;:,.({<[]>})&|!?\"'\\";
     pub fn tokenize(
         file: &[u8],
         format: bool,
-    ) -> (Vec<Tokens>, Vec<(usize, usize)>, Vec<parse_err::Errors>) {
+    ) -> (Vec<Tokens>, Vec<Line>, Vec<parse_err::Errors>) {
         let allocation_size = (file.len() as f64 * 0.7) as usize;
         let mut tokens: Vec<Tokens> = Vec::with_capacity(allocation_size);
-        let mut text_pos: Vec<(usize, usize)> = Vec::with_capacity(allocation_size);
-        text_pos.push((0, 0));
+        let mut text_pos: Vec<Line> = Vec::with_capacity(allocation_size);
         let mut errors: Vec<parse_err::Errors> = vec![];
 
+        let (mut column, mut line) = (0, 0);
         let mut i = 0;
         while i < file.len() {
             let res = get_token(&file[i..]);
-            text_pos.push((
-                text_pos[text_pos.len() - 1].0 + res.1,
-                text_pos[text_pos.len() - 1].1,
-            ));
+            let mut pos = Line::from((column, line));
+            pos.start = i;
+            pos.end = i + res.1;
+            text_pos.push(pos);
+            column += res.1;
             if let Tokens::Whitespace(txt) = &res.0 {
                 if txt == "\n" {
-                    let len = text_pos.len() - 1;
-                    text_pos[len].1 += 1;
-                    text_pos[len].0 = 0;
+                    line += 1;
+                    column = 0;
                 }
             }
             tokens.push(res.0);
             i += res.1;
         }
+        let mut end = Line::from((column, line));
+        end.start = file.len();
+        end.end = file.len();
+        text_pos.push(end);
         if !format {
             return (tokens, text_pos, errors);
         }
@@ -133,6 +138,7 @@ pub mod tokenizer {
             Tokens::Space => " ".to_string(),
             Tokens::Text(string) => string.to_string(),
             Tokens::DoubleColon => "::".to_string(),
+            Tokens::DoubleDot => "..".to_string(),
             Tokens::Number(num, t) => {
                 let mut res = num.to_string();
                 res.push(*t);
@@ -159,7 +165,14 @@ pub mod tokenizer {
                 res   
             }
             Tokens::EndOfFile => unreachable!("end of file token"),
-            
+            Tokens::InterpolationStart => "\"".to_string(),
+            Tokens::InterpolationEnd => "\"".to_string(),
+            Tokens::FormatSpec(spec) => {
+                let mut res = ":".to_string();
+                res.push_str(spec);
+                res
+            }
+
         }
     }
     pub fn find_ws_str(expression: &[u8], tokens_str: &str) -> usize {
@@ -174,7 +187,7 @@ pub mod tokenizer {
         idx
     }
     /// "+-*/=%;:,.({<[]>})&|!?\"'\\"
-    #[derive(Debug, PartialEq, Clone)]
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
     pub enum Tokens {
         /// opening 0, closing 1
         Parenteses(bool),
@@ -198,18 +211,25 @@ pub mod tokenizer {
         /// in case we can not identify token at the moment
         Text(String),
         DoubleColon,
+        DoubleDot,
         Number(f64, char),
         Tab,
         Deleted,
         DocComment(String),
         EndOfFile,
+        /// opening quotes of a string with embedded values
+        InterpolationStart,
+        /// closing quotes of a string with embedded values
+        InterpolationEnd,
+        /// format specifier of an embedded value, "{value:>8}"
+        FormatSpec(String),
     }
     impl std::fmt::Display for Tokens {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             write!(f, "{}", deparse_token(self))
         }
     }
-    #[derive(Debug, PartialEq, Clone, Copy, Eq)]
+    #[derive(Debug, PartialEq, Clone, Copy, Eq, Serialize, Deserialize)]
     pub enum Operators {
         Plus,
         Minus,
@@ -233,6 +253,11 @@ pub mod tokenizer {
         /// opening 0, closing 1
         AngleBracket(bool),
     }
+    impl std::fmt::Display for Operators {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", deparse_token(&Tokens::Operator(*self)))
+        }
+    }
     impl Tokens {
         pub fn into_const_number(&self, line: Line) -> Option<(ConstValue, Kind)> {
             match self {
@@ -7,20 +7,28 @@ pub mod lexing_preprocessor {
     use super::parse_err::Errors;
     pub fn refactor(
         mut tokens: Vec<Tokens>,
-        lines: Vec<(usize, usize)>,
+        mut lines: Vec<Line>,
         errors: &mut Vec<Errors>,
-    ) -> Result<(Vec<Tokens>, Vec<(usize, usize)>), LexingErr> {
+    ) -> Result<(Vec<Tokens>, Vec<Line>), LexingErr> {
         let mut i = 0;
         tokens.push(Tokens::EndOfFile);
         while i < tokens.len() {
-            i += process_token(&mut tokens, i, &lines, errors);
+            let found = errors.len();
+            let step = process_token(&mut tokens, i, &lines, errors);
+            merge_span(&tokens, i, &mut lines);
+            for err in errors[found..].iter_mut() {
+                if *err.line_mut() == lines[i] {
+                    *err.line_mut() = lines[i];
+                }
+            }
+            i += step;
         }
         Ok(clear(&tokens, &lines))
     }
     fn process_token(
         tokens: &mut Vec<Tokens>,
         idx: usize,
-        lines: &Vec<(usize, usize)>,
+        lines: &Vec<Line>,
         errors: &mut Vec<Errors>,
     ) -> usize {
         match &tokens[idx] {
@@ -34,19 +42,22 @@ pub mod lexing_preprocessor {
                 };
                 if let Some(first) = first {
                     if first.is_ascii_digit() {
-                        // float
-                        if let Tokens::Dot = &tokens[idx + 1] {
+                        // float (two dots are a range, a dot before the number is a tuple index)
+                        if tokens.get(idx + 1) == Some(&Tokens::Dot)
+                            && tokens.get(idx + 2) != Some(&Tokens::Dot)
+                            && !is_tuple_index(tokens, idx)
+                        {
                             let first_num = if let Ok(num) = txt.parse::<usize>() {
                                 num
                             } else {
                                 // syntax err: incorrect number
                                 errors.push(Errors::InvalidNumber(
-                                    Line::from(lines[idx]),
+                                    lines[idx],
                                     txt.to_string(),
                                 ));
                                 return 1;
                             };
-                            if let Tokens::Text(txt2) = &tokens[idx + 2] {
+                            if let Some(Tokens::Text(txt2)) = tokens.get(idx + 2) {
                                 let mut float = String::from("0.");
                                 float.push_str(txt2);
                                 if let Ok(num2) = float.parse::<f64>() {
@@ -59,7 +70,7 @@ pub mod lexing_preprocessor {
                                     let mut res = txt.to_string();
                                     res.push('.');
                                     res.push_str(txt2);
-                                    errors.push(Errors::InvalidNumber(Line::from(lines[idx]), res));
+                                    errors.push(Errors::InvalidNumber(lines[idx], res));
                                     return 1;
                                 };
                             } else {
@@ -72,7 +83,7 @@ pub mod lexing_preprocessor {
                                     tokens[idx] = Tokens::Number(num as f64, 'i')
                                 } else {
                                     errors.push(Errors::InvalidNumber(
-                                        Line::from(lines[idx]),
+                                        lines[idx],
                                         txt.to_string(),
                                     ));
                                     // syntax err: incorrect number
@@ -82,7 +93,7 @@ pub mod lexing_preprocessor {
                                     tokens[idx] = Tokens::Number(num as f64, last)
                                 } else {
                                     errors.push(Errors::InvalidNumber(
-                                        Line::from(lines[idx]),
+                                        lines[idx],
                                         txt.to_string(),
                                     ));
                                     // syntax err: incorrect number
@@ -109,14 +120,20 @@ pub mod lexing_preprocessor {
                 let mut i = idx + 1;
                 let mut res = String::new();
                 while tokens[i] != Tokens::DoubleQuotes {
-                    res.push_str(&deparse_token(&tokens[i]));
-                    i += 1;
-                    if i == tokens.len() {
-                        // syntax err: end of string never found
-                        remove_range(tokens, idx + 1, tokens.len());
+                    if tokens[i] == Tokens::EndOfFile {
+                        errors.push(Errors::Unclosed(lines[idx], Tokens::DoubleQuotes));
+                        remove_range(tokens, idx + 1, i);
                         tokens[idx] = Tokens::String(res);
                         return 1;
                     }
+                    res.push_str(&deparse_token(&tokens[i]));
+                    i += 1;
+                }
+                if tokens[idx + 1..i]
+                    .iter()
+                    .any(|tok| matches!(tok, Tokens::CurlyBracket(_)))
+                {
+                    return interpolate(tokens, idx, i, lines, errors);
                 }
                 remove_range(tokens, idx + 1, i + 1);
                 tokens[idx] = Tokens::String(res);
@@ -125,14 +142,14 @@ pub mod lexing_preprocessor {
                 let mut i = idx + 1;
                 let mut res = String::from("\"");
                 while tokens[i] != Tokens::Quotes {
-                    res.push_str(&deparse_token(&tokens[i]));
-                    i += 1;
-                    if i == tokens.len() {
-                        // syntax err: end of string never found
-                        remove_range(tokens, idx + 1, tokens.len());
+                    if tokens[i] == Tokens::EndOfFile {
+                        errors.push(Errors::Unclosed(lines[idx], Tokens::Quotes));
+                        remove_range(tokens, idx + 1, i);
                         tokens[idx] = Tokens::String(res);
                         return 1;
                     }
+                    res.push_str(&deparse_token(&tokens[i]));
+                    i += 1;
                 }
                 remove_range(tokens, idx + 1, i + 1);
                 res.push('"');
@@ -142,12 +159,12 @@ pub mod lexing_preprocessor {
                         if temp.len() == 1 {
                             tokens[idx] = Tokens::Char(temp.chars().next().unwrap());
                         } else {
-                            errors.push(Errors::CharacterTooLong(Line::from(lines[idx]), temp));
+                            errors.push(Errors::CharacterTooLong(lines[idx], temp));
                         }
                     }
                     Err(err) => {
                         // syntax err: invalid string
-                        errors.push(Errors::InvalidChar(Line::from(lines[idx]), err.to_string()));
+                        errors.push(Errors::InvalidChar(lines[idx], err.to_string()));
                         tokens[idx] = Tokens::String(res);
                     }
                 }
@@ -165,6 +182,13 @@ pub mod lexing_preprocessor {
                 }
                 return 1;
             }
+            Tokens::Dot => {
+                if let Tokens::Dot = tokens[idx + 1] {
+                    tokens[idx] = Tokens::DoubleDot;
+                    remove(tokens, idx + 1);
+                }
+                return 1;
+            }
             Tokens::Semicolon => {
                 if tokens.len() != idx + 1 {
                     while let Tokens::Semicolon = tokens[idx + 1] {
@@ -288,10 +312,108 @@ pub mod lexing_preprocessor {
         }
         1
     }
+    /// rewrites a string with embedded values "a {b:spec} c" into
+    /// InterpolationStart String({a }) { b FormatSpec(spec) } String( c) InterpolationEnd
+    ///
+    /// tokens of the embedded values stay in place so they are processed as usual,
+    /// `{{` and `}}` are the braces themselves
+    fn interpolate(
+        tokens: &mut Vec<Tokens>,
+        start: usize,
+        end: usize,
+        lines: &Vec<Line>,
+        errors: &mut Vec<Errors>,
+    ) -> usize {
+        tokens[start] = Tokens::InterpolationStart;
+        tokens[end] = Tokens::InterpolationEnd;
+        // text before the next value and the index of its first token
+        let mut text = String::new();
+        let mut text_start = None;
+        let mut i = start + 1;
+        while i < end {
+            match &tokens[i] {
+                Tokens::CurlyBracket(closing)
+                    if i + 1 < end && tokens[i + 1] == Tokens::CurlyBracket(*closing) =>
+                {
+                    text.push_str(&deparse_token(&tokens[i]));
+                    text_start.get_or_insert(i);
+                    i += 2;
+                }
+                Tokens::CurlyBracket(true) => {
+                    errors.push(Errors::InvalidInterpolation(
+                        lines[i],
+                        "unmatched '}', use '}}' to write it".to_string(),
+                    ));
+                    i += 1;
+                }
+                Tokens::CurlyBracket(false) => {
+                    if let Some(text_start) = text_start.take() {
+                        tokens[text_start] = Tokens::String(std::mem::take(&mut text));
+                        remove_range(tokens, text_start + 1, i);
+                    }
+                    // end of the value and the colon before its format
+                    let mut depth = 0;
+                    let mut colon = None;
+                    let mut j = i + 1;
+                    while j < end {
+                        match &tokens[j] {
+                            Tokens::CurlyBracket(false)
+                            | Tokens::Parenteses(false)
+                            | Tokens::SquareBracket(false) => depth += 1,
+                            Tokens::CurlyBracket(true) if depth == 0 => break,
+                            Tokens::CurlyBracket(true)
+                            | Tokens::Parenteses(true)
+                            | Tokens::SquareBracket(true) => depth -= 1,
+                            Tokens::Colon if depth == 0 && colon.is_none() => colon = Some(j),
+                            _ => {}
+                        }
+                        j += 1;
+                    }
+                    if j == end {
+                        errors.push(Errors::InvalidInterpolation(
+                            lines[i],
+                            "unclosed '{', use '{{' to write it".to_string(),
+                        ));
+                        remove_range(tokens, i, end);
+                        break;
+                    }
+                    let value_end = colon.unwrap_or(j);
+                    if tokens[i + 1..value_end]
+                        .iter()
+                        .all(|tok| matches!(tok, Tokens::Space | Tokens::Whitespace(_)))
+                    {
+                        errors.push(Errors::InvalidInterpolation(
+                            lines[i],
+                            "missing value in '{}'".to_string(),
+                        ));
+                    }
+                    if let Some(colon) = colon {
+                        let mut spec = String::new();
+                        for tok in tokens[colon + 1..j].iter() {
+                            spec.push_str(&deparse_token(tok));
+                        }
+                        tokens[colon] = Tokens::FormatSpec(spec);
+                        remove_range(tokens, colon + 1, j);
+                    }
+                    i = j + 1;
+                }
+                tok => {
+                    text.push_str(&deparse_token(tok));
+                    text_start.get_or_insert(i);
+                    i += 1;
+                }
+            }
+        }
+        if let Some(text_start) = text_start {
+            tokens[text_start] = Tokens::String(text);
+            remove_range(tokens, text_start + 1, end);
+        }
+        1
+    }
     fn clear(
         tokens: &Vec<Tokens>,
-        lines: &Vec<(usize, usize)>,
-    ) -> (Vec<Tokens>, Vec<(usize, usize)>) {
+        lines: &Vec<Line>,
+    ) -> (Vec<Tokens>, Vec<Line>) {
         let mut result = (Vec::new(), Vec::new());
         for (i, tok) in tokens.iter().enumerate() {
             if *tok != Tokens::Deleted {
@@ -301,10 +423,32 @@ pub mod lexing_preprocessor {
         }
         result
     }
+    /// tokens deleted right after a processed token were merged into it,
+    /// so its span covers them too
+    fn merge_span(tokens: &Vec<Tokens>, idx: usize, lines: &mut Vec<Line>) {
+        if tokens[idx] == Tokens::Deleted {
+            return;
+        }
+        let mut last = idx;
+        while last + 1 < tokens.len() && tokens[last + 1] == Tokens::Deleted {
+            last += 1;
+        }
+        if last > idx {
+            lines[idx] = lines[idx].to(&lines[last]);
+        }
+    }
     fn not_end(idx: usize, tokens: &Vec<Tokens>) -> bool {
         idx < tokens.len()
     }
-    fn remove(tokens: &mut Vec<Tokens>, idx: usize) -> usize {
+    /// whether the token follows a single dot, like `0` in `pair.0.1`
+    fn is_tuple_index(tokens: &Vec<Tokens>, idx: usize) -> bool {
+        let mut before = tokens[..idx]
+            .iter()
+            .rev()
+            .filter(|tok| **tok != Tokens::Deleted);
+        before.next() == Some(&Tokens::Dot) && before.next() != Some(&Tokens::Dot)
+    }
+        fn remove(tokens: &mut Vec<Tokens>, idx: usize) -> usize {
         tokens[idx] = Tokens::Deleted;
         0
     }
@@ -318,7 +462,7 @@ pub mod lexing_preprocessor {
 }
 
 pub mod parse_err {
-    use crate::tree_walker::tree_walker::Line;
+    use crate::{lexer::tokenizer::Tokens, tree_walker::tree_walker::Line};
 
     #[derive(Debug)]
     pub enum Errors {
@@ -328,6 +472,21 @@ pub mod parse_err {
         InvalidChar(Line, String),
         // character
         CharacterTooLong(Line, String),
+        // reason
+        InvalidInterpolation(Line, String),
+        // opening quotes of a literal that reaches the end of the file
+        Unclosed(Line, Tokens),
+    }
+    impl Errors {
+        pub fn line_mut(&mut self) -> &mut Line {
+            match self {
+                Errors::InvalidNumber(line, _)
+                | Errors::InvalidChar(line, _)
+                | Errors::CharacterTooLong(line, _)
+                | Errors::InvalidInterpolation(line, _)
+                | Errors::Unclosed(line, _) => line,
+            }
+        }
     }
     impl std::fmt::Display for Errors {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -341,6 +500,12 @@ pub mod parse_err {
                 Errors::CharacterTooLong(line, chr) => {
                     write!(f, "Character too long: {} at line {}", chr, line)
                 }
+                Errors::InvalidInterpolation(line, reason) => {
+                    write!(f, "Invalid interpolation: {} at line {}", reason, line)
+                }
+                Errors::Unclosed(line, open) => {
+                    write!(f, "`{}` at {} is not closed", open, line)
+                }
             }
         }
     }
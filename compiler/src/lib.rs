@@ -1,6 +1,6 @@
 use intermediate::dictionary;
 
-use crate::ast_parser::ast_parser::{generate_ast as gen_ast, Head, HeadParam};
+use crate::ast_parser::ast_parser::{Head, HeadParam};
 use crate::intermediate::AnalyzationError::ErrType;
 use crate::lexer::tokenizer::Tokens;
 use crate::lexing_preprocessor::parse_err::Errors;
@@ -9,6 +9,9 @@ use std::path::Path;
 use std::{collections::HashMap, env, fs::File, hint::black_box, io::Read, time::SystemTime};
 
 use crate::{intermediate::AnalyzationError, tree_walker::tree_walker::ArgNodeType};
+use crate::import_graph::{ImportEdge, ImportGraph};
+pub use crate::session::{DiskFiles, FileProvider, MemoryFiles, Session};
+use crate::cache::Cache;
 
 mod ast_parser;
 mod lexer;
@@ -22,10 +25,21 @@ mod expression_parser;
 mod intermediate;
 mod libloader;
 pub mod prep_objects;
+pub mod import_graph;
 pub mod codegen;
+pub mod typecheck;
+pub mod diagnostic;
+pub mod symbols;
+pub mod formatter;
+pub mod lint;
+pub mod repl;
+pub mod session;
+pub mod cache;
+pub mod docgen;
+pub mod grammar_check;
 mod rdasm_opt;
 
-pub fn tokenize(content: &str, formating: bool) -> (Vec<Tokens>, Vec<(usize, usize)>, Vec<Errors>) {
+pub fn tokenize(content: &str, formating: bool) -> (Vec<Tokens>, Vec<tree_walker::tree_walker::Line>, Vec<Errors>) {
     use lexer::tokenizer::*;
     let mut tokens = tokenize(&content.as_bytes(), formating);
     tokens
@@ -35,10 +49,10 @@ pub fn tokenize(content: &str, formating: bool) -> (Vec<Tokens>, Vec<(usize, usi
 pub enum ErrorOrigin {
     LexingError(Vec<lexing_preprocessor::parse_err::Errors>),
     ParsingError(
-        (
+        Vec<(
             tree_walker::tree_walker::Err,
             tree_walker::tree_walker::Line,
-        ),
+        )>,
     ),
     CodeBlockParserError(Vec<lexing_preprocessor::parse_err::Errors>),
     IntermediateError(Vec<lexing_preprocessor::parse_err::Errors>),
@@ -57,10 +71,11 @@ impl std::fmt::Display for ErrorOrigin {
                 }
                 Ok(())
             }
-            ErrorOrigin::ParsingError((err, line)) => {
+            ErrorOrigin::ParsingError(errs) => {
                 write!(f, "Parsing error:\n")?;
-                write!(f, "{}\n", err)?;
-                write!(f, "Line: {}", line)?;
+                for (err, line) in errs {
+                    write!(f, "{} at {}\n", err, line)?;
+                }
                 Ok(())
             }
             ErrorOrigin::CodeBlockParserError(errs) => {
@@ -93,7 +108,7 @@ impl std::fmt::Display for ErrorOrigin {
             }
             ErrorOrigin::LinkingError(err) => {
                 write!(f, "Linking error:\n")?;
-                write!(f, "{:?}", err)?;
+                write!(f, "{}", err)?;
                 Ok(())
             }
         }
@@ -106,36 +121,70 @@ pub enum LinkingError {
     FileNotFound(String, String),
     /// file, reason
     CouldNotOpen(String, String),
+    /// error, imports leading to the module it occured in
+    Imported(Box<LinkingError>, Vec<ImportEdge>),
+    /// imports of the circle, the last one leads back to the first module
+    CircularImport(Vec<ImportEdge>),
+    /// library, imports leading to it
+    LibraryNotFound(String, Vec<ImportEdge>),
+}
+
+impl std::fmt::Display for LinkingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkingError::FileNotFound(file, reason) => {
+                write!(f, "could not find module '{file}' ({reason})")
+            }
+            LinkingError::CouldNotOpen(file, reason) => {
+                write!(f, "could not open module '{file}' ({reason})")
+            }
+            LinkingError::Imported(err, chain) => {
+                write!(f, "{err}")?;
+                for edge in chain {
+                    write!(f, "\n    imported from '{}' at {}", edge.from, edge.line)?;
+                }
+                Ok(())
+            }
+            LinkingError::CircularImport(cycle) => {
+                write!(f, "circular import: '{}'", cycle[0].from)?;
+                for edge in cycle {
+                    write!(f, " -> '{}'", edge.to)?;
+                }
+                for edge in cycle {
+                    write!(f, "\n    '{}' imports '{}' at {}", edge.from, edge.to, edge.line)?;
+                }
+                Ok(())
+            }
+            LinkingError::LibraryNotFound(library, chain) => {
+                write!(f, "library '{library}' is not installed")?;
+                for edge in chain {
+                    write!(f, "\n    imported from '{}' at {}", edge.from, edge.line)?;
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
 pub type Dictionaries = HashMap<String, intermediate::dictionary::Dictionary>;
 
 pub fn read_source(root: &str, main: &str) -> Result<Option<String>, ErrorOrigin> {
+    read_source_from(&DiskFiles, root, main)
+}
+
+/// reads the module at `main` relative to `root` from `files`
+fn read_source_from(files: &dyn FileProvider, root: &str, main: &str) -> Result<Option<String>, ErrorOrigin> {
     if main.starts_with("#") {
         return Ok(None);
     }
-    let root = std::path::PathBuf::from(root);
-    let mut string = String::new();
-    let path = root.join(main);
-    let mut file = match File::open(&path) {
-        Ok(file) => file,
-        Err(err) => {
-            return Err(ErrorOrigin::LinkingError(LinkingError::FileNotFound(
-                path.to_str().unwrap().to_string(),
-                err.to_string(),
-            )));
-        }
-    };
-    match file.read_to_string(&mut string) {
-        Ok(_) => {}
-        Err(err) => {
-            return Err(ErrorOrigin::LinkingError(LinkingError::CouldNotOpen(
-                path.to_str().unwrap().to_string(),
-                err.to_string(),
-            )));
-        }
-    };
-    Ok(Some(string))
+    let path = session::join(root, main);
+    match files.read(&path) {
+        Ok(source) => Ok(Some(source)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(ErrorOrigin::LinkingError(
+            LinkingError::FileNotFound(path, err.to_string()),
+        )),
+        Err(err) => Err(ErrorOrigin::LinkingError(LinkingError::CouldNotOpen(path, err.to_string()))),
+    }
 }
 
 pub fn new_imports(imports: &mut Vec<String>, new: Vec<String>) {
@@ -144,10 +193,134 @@ pub fn new_imports(imports: &mut Vec<String>, new: Vec<String>) {
     imports.dedup();
 }
 
+/// file loaded when a directory is imported, `import "shapes"` loads `shapes/mod.rd`
+pub const MODULE_INDEX: &str = "mod.rd";
+
+/// returns the path of an imported module relative to the root
+///
+/// imports are relative to the directory of the importing file,
+/// the same file imported in different ways always gets the same path
+/// so it is loaded only once
+pub fn module_path(root: &str, from: &str, import: &str) -> String {
+    module_path_in(&DiskFiles, root, from, import)
+}
+
+fn module_path_in(files: &dyn FileProvider, root: &str, from: &str, import: &str) -> String {
+    let mut parts = from.split("/").collect::<Vec<_>>();
+    // file name of the importing module
+    parts.pop();
+    for part in import.split("/") {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    let mut path = parts.join("/");
+    if files.is_dir(&session::join(root, &path)) {
+        path.push('/');
+        path.push_str(MODULE_INDEX);
+    }
+    path
+}
+
+/// replaces paths of the imported modules with their canonical paths, returns the imports
+fn canonical_imports(
+    files: &dyn FileProvider,
+    root: &str,
+    file: &str,
+    dictionary: &mut intermediate::dictionary::Dictionary,
+) -> Vec<ImportEdge> {
+    let mut imports = Vec::new();
+    for import in dictionary.imports.iter_mut() {
+        if !import.path.starts_with("#") {
+            import.path = module_path_in(files, root, file, &import.path);
+        }
+        imports.push(ImportEdge {
+            from: file.to_string(),
+            to: import.path.clone(),
+            line: import.line,
+        });
+    }
+    imports
+}
+
 pub fn build_dictionaries(
     main: &str,
     ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
 ) -> Result<Dictionaries, (ErrorOrigin, String)> {
+    build_import_graph(main, ast).map(|(dictionaries, _)| dictionaries)
+}
+
+/// loads every module of the program, returns them with the imports between them
+///
+/// circular imports are an error
+pub fn build_import_graph(
+    main: &str,
+    ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
+) -> Result<(Dictionaries, ImportGraph), (ErrorOrigin, String)> {
+    build_import_graph_with(main, ast, &HashMap::new())
+}
+
+/// same as `build_import_graph`, modules found in `sources` are not read from the disk
+///
+/// sources are keyed by the module path relative to the root (`main.rd`, `shapes/mod.rd`),
+/// editors use it to check files that were not saved yet
+pub fn build_import_graph_with(
+    main: &str,
+    ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
+    sources: &HashMap<String, String>,
+) -> Result<(Dictionaries, ImportGraph), (ErrorOrigin, String)> {
+    let root = Path::new(main).parent().and_then(|root| root.to_str()).unwrap_or("");
+    build_import_graph_from(main, ast, &session::Overlay { root, sources })
+}
+
+/// same as `build_import_graph`, every module is read from `files`
+pub fn build_import_graph_from(
+    main: &str,
+    ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
+    files: &dyn FileProvider,
+) -> Result<(Dictionaries, ImportGraph), (ErrorOrigin, String)> {
+    load_modules(main, ast, files, None)
+}
+
+/// same as `build_import_graph`, modules that did not change are taken from the cache
+pub fn build_import_graph_cached(
+    main: &str,
+    ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
+    cache: &mut Cache,
+) -> Result<(Dictionaries, ImportGraph), (ErrorOrigin, String)> {
+    load_modules(main, ast, &DiskFiles, Some(cache))
+}
+
+/// parses the module or takes its dictionary from the cache
+fn cached_dictionary(
+    source: &str,
+    ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
+    file_name: &str,
+    mut cache: Option<&mut Cache>,
+) -> Result<intermediate::dictionary::Dictionary, ErrorOrigin> {
+    if let Some(dictionary) = cache.as_deref_mut().and_then(|cache| cache.dictionary(file_name, source)) {
+        return Ok(dictionary);
+    }
+    let res = build_dictionary(source, ast, file_name)?;
+    if res.1.len() > 0 {
+        return Err(ErrorOrigin::AnalyzationError(res.1));
+    }
+    if let Some(cache) = cache {
+        cache.store_dictionary(file_name, source, &res.0);
+    }
+    Ok(res.0)
+}
+
+fn load_modules(
+    main: &str,
+    ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
+    files: &dyn FileProvider,
+    mut cache: Option<&mut Cache>,
+) -> Result<(Dictionaries, ImportGraph), (ErrorOrigin, String)> {
     // root is the directory of the main file
     let main_path = std::path::Path::new(main);
     let main_ = main_path
@@ -160,91 +333,57 @@ pub fn build_dictionaries(
         .expect("internal error 0. please contact the developer.")
         .to_str()
         .expect("internal error 0. please contact the developer.");
-    let main = match read_source(root, main_) {
+    let main = match read_source_from(files, root, main_) {
         Ok(main) => main.unwrap(),
         Err(err) => {
             return Err((err, main_.to_string()));
         }
     };
-    let mut imports = Vec::new();
     let mut dictionaries = Dictionaries::new();
-    match build_dictionary(&main, ast, main_) {
-        Ok(res) => {
-            if res.1.len() > 0 {
-                return Err((ErrorOrigin::AnalyzationError(res.1), main_.to_string()));
-            }
-            new_imports(&mut imports, res.2);
-            dictionaries.insert(main_.to_string(), res.0);
+    let mut graph = ImportGraph::new(main_);
+    match cached_dictionary(&main, ast, main_, cache.as_deref_mut()) {
+        Ok(mut dictionary) => {
+            graph.edges.extend(canonical_imports(files, root, main_, &mut dictionary));
+            dictionaries.insert(main_.to_string(), dictionary);
         }
         Err(err) => {
-            return Err((err, root.to_string()));
+            return Err((err, main_.to_string()));
         }
     };
-    let mut i = 0;
-    while i < imports.len() {
-        if imports[i].starts_with("#") {
-            imports.remove(i);
-        } else {
-            i += 1;
+    // imports are loaded in the order they were found
+    let mut next = 0;
+    while next < graph.edges.len() {
+        let import = graph.edges[next].to.clone();
+        next += 1;
+        if import.starts_with("#") || dictionaries.contains_key(&import) {
+            continue;
         }
-    }
-    loop {
-        let mut found_imports = Vec::new();
-        for import in &imports {
-            if !dictionaries.contains_key(import) {
-                match read_source(root, import) {
-                    Ok(main) => {
-                        if main.is_none() {
-                            found_imports.push(import.clone());
-                            continue;
-                        }
-                        match build_dictionary(&main.unwrap(), ast, import) {
-                            Ok(res) => {
-                                if res.1.len() > 0 {
-                                    return Err((
-                                        ErrorOrigin::AnalyzationError(res.1),
-                                        import.clone(),
-                                    ));
-                                }
-                                found_imports.extend(res.2);
-                                dictionaries.insert(import.clone(), res.0);
-                            }
-                            Err(err) => {
-                                return Err((err, import.to_string()));
-                            }
-                        };
-                    }
-                    Err(err) => {
-                        return Err((err, import.to_string()));
-                    }
-                };
+        let source = match read_source_from(files, root, &import) {
+            Ok(source) => source.unwrap(),
+            Err(ErrorOrigin::LinkingError(err)) => {
+                let err = LinkingError::Imported(Box::new(err), graph.chain(&import));
+                return Err((ErrorOrigin::LinkingError(err), import));
             }
-        }
-        let mut i = 0;
-        while i < found_imports.len() {
-            if found_imports[i].starts_with("#") {
-                found_imports.remove(i);
-            } else {
-                i += 1;
+            Err(err) => {
+                return Err((err, import));
             }
-        }
-        new_imports(&mut imports, found_imports);
-        // check if all imports are in the dictionary
-        let mut all = true;
-        for import in &imports {
-            if !dictionaries.contains_key(import) {
-                all = false;
-                break;
+        };
+        match cached_dictionary(&source, ast, &import, cache.as_deref_mut()) {
+            Ok(mut dictionary) => {
+                graph.edges.extend(canonical_imports(files, root, &import, &mut dictionary));
+                graph.modules.push(import.clone());
+                dictionaries.insert(import, dictionary);
             }
-        }
-        if all {
-            // for dict in &dictionaries {
-            //     println!("Dictionary: {}", dict.0);
-            //     println!("{:?}", dict.1);
-            // }
-            return Ok(dictionaries);
-        }
+            Err(err) => {
+                return Err((err, import));
+            }
+        };
     }
+    if let Some(cycle) = graph.cycle() {
+        let file = cycle[0].from.clone();
+        return Err((ErrorOrigin::LinkingError(LinkingError::CircularImport(cycle)), file));
+    }
+    Ok((dictionaries, graph))
 }
 
 /// returns all of the binaries as dictionaries in the order of the paths
@@ -252,6 +391,25 @@ pub fn build_binaries(
     paths: &Vec<String>,
     ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
     binaries: &mut Vec<libloader::Dictionary>,
+) -> Result<(), String> {
+    load_binaries(paths, ast, binaries, None)
+}
+
+/// same as `build_binaries`, binaries that did not change are not loaded again
+pub fn build_binaries_cached(
+    paths: &Vec<String>,
+    ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
+    binaries: &mut Vec<libloader::Dictionary>,
+    cache: &mut Cache,
+) -> Result<(), String> {
+    load_binaries(paths, ast, binaries, Some(cache))
+}
+
+fn load_binaries(
+    paths: &Vec<String>,
+    ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
+    binaries: &mut Vec<libloader::Dictionary>,
+    mut cache: Option<&mut Cache>,
 ) -> Result<(), String> {
     for path in paths {
         const SUFFIXES: [&str; 2] = [".dll", ".so"];
@@ -262,12 +420,27 @@ pub fn build_binaries(
                 break;
             }
         }
-        binaries.push(libload(&path, ast, &name)?);
+        binaries.push(libload_cached(&path, ast, &name, cache.as_deref_mut())?);
     }
     Ok(())
 }
 
 pub fn build_std_lib(ast: &mut (HashMap<String, Head>, Vec<HeadParam>)) -> Result<Vec<(libloader::Dictionary, String)>, String> {
+    load_std_lib(ast, None)
+}
+
+/// same as `build_std_lib`, libraries that did not change are not loaded again
+pub fn build_std_lib_cached(
+    ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
+    cache: &mut Cache,
+) -> Result<Vec<(libloader::Dictionary, String)>, String> {
+    load_std_lib(ast, Some(cache))
+}
+
+fn load_std_lib(
+    ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
+    mut cache: Option<&mut Cache>,
+) -> Result<Vec<(libloader::Dictionary, String)>, String> {
     let mut binaries = Vec::new();
     let mut path = match env::var("RUDA_PATH") {
         Ok(path) => path,
@@ -300,16 +473,9 @@ pub fn build_std_lib(ast: &mut (HashMap<String, Head>, Vec<HeadParam>)) -> Resul
                 return Err(format!("Could not read stdlib directory."));
             }
         };
-        let filename = match file.file_name().to_str() {
-            Some(name) => {
-                let mut tmp = String::from("#");
-                tmp.push_str(name.strip_suffix(".dll").unwrap());
-                tmp
-            }
-            None => {
-                return Err(format!("Could not read stdlib directory."));
-            }
-        };
+        if file.file_name().to_str().is_none() {
+            return Err(format!("Could not read stdlib directory."));
+        }
         const SUFFIXES: [&str; 2] = [".dll", ".so"];
         let mut name = Path::new(path).file_name().unwrap().to_str().unwrap().to_string();
         for suffix in &SUFFIXES {
@@ -318,7 +484,8 @@ pub fn build_std_lib(ast: &mut (HashMap<String, Head>, Vec<HeadParam>)) -> Resul
                 break;
             }
         }
-        let lib = libload(path, ast, &name)?;
+        let filename = format!("#{name}");
+        let lib = libload_cached(path, ast, &name, cache.as_deref_mut())?;
         binaries.push((lib, filename));
     }
 
@@ -384,6 +551,34 @@ pub fn libload(
     ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
     file_identifier: &str,
 ) -> Result<libloader::Dictionary, String> {
+    let register = register(file)?;
+    libloader::load(&register.as_bytes(), ast, file_identifier)
+}
+
+/// same as `libload`, the declarations of a binary that did not change are taken from the cache
+fn libload_cached(
+    file: &str,
+    ast: &mut (HashMap<String, Head>, Vec<HeadParam>),
+    file_identifier: &str,
+    cache: Option<&mut Cache>,
+) -> Result<libloader::Dictionary, String> {
+    let cache = match cache {
+        Some(cache) => cache,
+        None => return libload(file, ast, file_identifier),
+    };
+    let register = match cache.register(Path::new(file)) {
+        Some(register) => register,
+        None => {
+            let register = register(file)?;
+            cache.store_register(Path::new(file), &register);
+            register
+        }
+    };
+    libloader::load(&register.as_bytes(), ast, file_identifier)
+}
+
+/// declarations the binary registers
+fn register(file: &str) -> Result<String, String> {
     let lib = unsafe {
         match libloading::Library::new(file) {
             Ok(lib) => lib,
@@ -400,33 +595,28 @@ pub fn libload(
             }
         }
     }();
-    let lib = libloader::load(&register.as_bytes(), ast, file_identifier);
-    lib
+    Ok(register)
 }
 
 pub fn generate_ast(ruda_path: &str) -> Result<Asts, AstGenError> {
-    use lexer::tokenizer::*;
-    // ruda_path + "/ruda.ast"
     let ast_path = std::path::PathBuf::from(ruda_path).join("ruda.ast");
-    let ast_path = match ast_path.to_str() {
-        Some(path) => path,
-        None => return Err(AstGenError::NotFound(AstType::Ast)),
-    };
-    let (ast, globals) = if let Some(ast) = gen_ast(&ast_path) {
-        ast
-    } else {
-        return Err(AstGenError::CouldNotOpen(AstType::Ast));
+    let source = match std::fs::read_to_string(&ast_path) {
+        Ok(source) => source,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Err(AstGenError::NotFound(AstType::Ast))
+        }
+        Err(_) => return Err(AstGenError::CouldNotOpen(AstType::Ast)),
     };
+    let (ast, globals) = load_grammar(&source, AstType::Ast)?;
     let registry_path = std::path::PathBuf::from(ruda_path).join("registry.ast");
-    let registry_path = match registry_path.to_str() {
-        Some(path) => path,
-        None => return Err(AstGenError::NotFound(AstType::Registry)),
-    };
-    let (registry, _) = if let Some(ast) = gen_ast(&registry_path) {
-        ast
-    } else {
-        return Err(AstGenError::CouldNotOpen(AstType::Registry));
+    let source = match std::fs::read_to_string(&registry_path) {
+        Ok(source) => source,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Err(AstGenError::NotFound(AstType::Registry))
+        }
+        Err(_) => return Err(AstGenError::CouldNotOpen(AstType::Registry)),
     };
+    let (registry, _) = load_grammar(&source, AstType::Registry)?;
     return Ok(Asts {
         ast,
         params: globals,
@@ -434,11 +624,41 @@ pub fn generate_ast(ruda_path: &str) -> Result<Asts, AstGenError> {
     });
 }
 
+/// parses the grammar and checks it for mistakes, only warnings of the check are let through
+fn load_grammar(source: &str, kind: AstType) -> Result<(HashMap<String, Head>, Vec<HeadParam>), AstGenError> {
+    let (tree, params) = match ast_parser::ast_parser::parse_grammar(source) {
+        Ok(grammar) => grammar,
+        Err(errors) => return Err(AstGenError::Invalid(kind, errors)),
+    };
+    let errors = grammar_check::check_tree(&tree, &params)
+        .into_iter()
+        .filter(|err| !err.is_warning())
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        return Err(AstGenError::Invalid(kind, errors));
+    }
+    Ok((tree, params))
+}
+
+/// grammar of the language, the same as `ruda.ast` in RUDA_PATH
+pub const RUDA_AST: &str = include_str!("../ast/ruda.ast");
+/// grammar of the library declarations, the same as `registry.ast` in RUDA_PATH
+pub const REGISTRY_AST: &str = include_str!("../ast/registry.ast");
+
+/// same as `generate_ast`, the grammars are the ones the compiler was built with
+pub fn embedded_ast() -> Result<Asts, AstGenError> {
+    let (ast, params) = load_grammar(RUDA_AST, AstType::Ast)?;
+    let (registry, _) = load_grammar(REGISTRY_AST, AstType::Registry)?;
+    Ok(Asts { ast, params, registry })
+}
+
 #[derive(Debug)]
 pub enum AstGenError {
     NotFound(AstType),
     ParseError(AstType),
     CouldNotOpen(AstType),
+    /// mistakes found in the grammar
+    Invalid(AstType, Vec<grammar_check::GrammarError>),
 }
 
 impl std::fmt::Display for AstGenError {
@@ -447,6 +667,13 @@ impl std::fmt::Display for AstGenError {
             AstGenError::NotFound(ast) => write!(f, "Could not find {} file.", ast),
             AstGenError::ParseError(ast) => write!(f, "Could not parse {} file.", ast),
             AstGenError::CouldNotOpen(ast) => write!(f, "Could not open {} file.", ast),
+            AstGenError::Invalid(ast, errors) => {
+                write!(f, "The {} file is not a valid grammar.", ast)?;
+                for err in errors {
+                    write!(f, "\n    {}", err)?;
+                }
+                Ok(())
+            }
         }
     }
 }
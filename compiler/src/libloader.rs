@@ -331,13 +331,13 @@ pub fn load(
     Ok(dictionary)
 }
 
-fn get_constructor(node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -> Option<(Vec<(String, Kind, MemoryTypes, Line)>, bool, Vec<GenericDecl>, usize)> {
+fn get_constructor(node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -> Option<(Vec<(String, Kind, MemoryTypes, Line, Option<intermediate::dictionary::ConstValue>)>, bool, Vec<GenericDecl>, usize)> {
     if let Tokens::Text(txt) = &node.name {
         if txt != "KWConstructor" {
             return None;
         }
     }
-    let mut args: Vec<(String, Kind, MemoryTypes, Line)> = Vec::new();
+    let mut args: Vec<(String, Kind, MemoryTypes, Line, Option<intermediate::dictionary::ConstValue>)> = Vec::new();
     let generics = get_generics_decl(&node, errors);
     let assign = get_assign(&node);
     for arg in step_inside_arr(node, "args") {
@@ -345,7 +345,8 @@ fn get_constructor(node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -> O
         let mem_loc = get_mem_loc(&arg);
         let arg_type = get_type(step_inside_val(&arg, "type"), errors, file_name);
         let line = arg.line;
-        args.push((ident, arg_type, mem_loc, line));
+        let default = get_default(&arg, errors);
+        args.push((ident, arg_type, mem_loc, line, default));
     }
     let errorable =
         if let Tokens::Operator(Operators::Not) = step_inside_val(node, "errorable").name {
@@ -367,7 +368,7 @@ fn get_assign(node: &Node) -> usize {
     panic!("hruzostrasna pohroma");
 }
 fn get_fun_siginifier(node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -> Function {
-    let mut args: Vec<(String, Kind, MemoryTypes, Line)> = Vec::new();
+    let mut args: Vec<(String, Kind, MemoryTypes, Line, Option<intermediate::dictionary::ConstValue>)> = Vec::new();
     let mut takes_self = false;
     let generics = get_generics_decl(&node, errors);
     for arg in step_inside_arr(node, "arguments") {
@@ -397,7 +398,8 @@ fn get_fun_siginifier(node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -
             _ => ()
         }
         let line = arg.line;
-        args.push((ident, kind, mem_loc, line));
+        let default = get_default(&arg, errors);
+        args.push((ident, kind, mem_loc, line, default));
     }
     let return_type = if let Tokens::Text(txt) = &step_inside_val(node, "type").name {
         if txt == "type_specifier" {
@@ -439,6 +441,33 @@ fn get_fun_siginifier(node: &Node, errors: &mut Vec<ErrType>, file_name: &str) -
     }
 }
 
+/// Default value of an argument, libraries can only use literals
+fn get_default(arg: &Node, errors: &mut Vec<ErrType>) -> Option<intermediate::dictionary::ConstValue> {
+    use intermediate::dictionary::ConstValue;
+    let val = try_step_inside_val(arg, "default")?;
+    let value = match &try_step_inside_val(val, "value")?.name {
+        Tokens::Number(n, c) => match c {
+            'i' => ConstValue::Int(*n as i64),
+            'u' => ConstValue::Uint(*n as usize),
+            'f' => ConstValue::Float(*n),
+            'c' => ConstValue::Char(*n as u8 as char),
+            _ => ConstValue::Number(*n),
+        },
+        Tokens::String(text) => ConstValue::String(text.to_string()),
+        Tokens::Text(text) => match text.as_str() {
+            "true" => ConstValue::Bool(true),
+            "false" => ConstValue::Bool(false),
+            "null" => ConstValue::Null,
+            _ => {
+                errors.push(ErrType::InvalidConstant(Tokens::Text(text.to_string()), val.line));
+                return None;
+            }
+        },
+        _ => return None,
+    };
+    Some(value)
+}
+
 fn get_mem_loc(node: &Node) -> MemoryTypes {
     let node = step_inside_val(&node, "mem");
     let mem =
@@ -526,7 +555,7 @@ impl Registers {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Dictionary {
     pub functions: Vec<Function>,
     pub structs: Vec<Struct>,
@@ -553,7 +582,7 @@ impl Dictionary {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct UserData {
     pub name: String,
     pub assign: usize,
@@ -576,7 +605,7 @@ impl UserData {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Implementation {
     pub target: Vec<String>,
     pub functions: Vec<Function>,
@@ -584,10 +613,10 @@ pub struct Implementation {
     pub line: Line,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Function {
     pub name: String,
-    pub args: Vec<(String, Kind, MemoryTypes, Line)>,
+    pub args: Vec<(String, Kind, MemoryTypes, Line, Option<intermediate::dictionary::ConstValue>)>,
     pub return_type: Kind,
     pub errorable: bool,
     pub assign: usize,
@@ -596,7 +625,7 @@ pub struct Function {
     pub docs: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Struct {
     pub name: String,
     pub fields: Vec<(String, Kind)>,
@@ -608,7 +637,7 @@ pub struct Struct {
     pub docs: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Enum {
     pub name: String,
     pub variants: Vec<(String, usize)>,
@@ -617,7 +646,7 @@ pub struct Enum {
     pub docs: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Type {
     pub name: String,
     pub kind: Kind,
@@ -625,21 +654,21 @@ pub struct Type {
     pub docs: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Const {
     pub name: String,
     pub value: ConstValue,
     pub docs: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ConstValue {
     Number(f64, char),
     Text(String),
     Bool(bool),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Trait {
     pub name: String,
     pub functions: Vec<Function>,
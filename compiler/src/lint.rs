@@ -0,0 +1,806 @@
+//! Warnings about code that compiles but is most likely a mistake
+//!
+//! Runs after the type checker succeeded. Every warning belongs to a lint
+//! that can be allowed, warned about or denied for the whole project
+//! and silenced for a single item with `#[allow(lint)]`.
+
+use crate::codeblock_parser::{Nodes, TuplePattern};
+use crate::codegen::resolve_trait;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::expression_parser::{ArmBody, ArrayRule, InterpolationPart, Literals, Root, TailNodes, ValueType};
+use crate::intermediate::dictionary::{self, Arg, ErrorField, Function, PathPart};
+use crate::intermediate::{Kind, TypeBody};
+use crate::lexer::tokenizer::{deparse_token, Operators, Tokens};
+use crate::prep_objects::Context;
+use crate::tree_walker::tree_walker::Line;
+
+/// name that stands for every lint in attributes and configuration
+pub const ALL: &str = "warnings";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lint {
+    UnusedVariables,
+    UnusedImports,
+    UnusedFunctions,
+    UnusedConstants,
+    UnreachableCode,
+    ShadowedVariables,
+    WhileTrue,
+    NullableComparisons,
+    IgnoredResults,
+    UnknownLints,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 10] = [
+        Lint::UnusedVariables,
+        Lint::UnusedImports,
+        Lint::UnusedFunctions,
+        Lint::UnusedConstants,
+        Lint::UnreachableCode,
+        Lint::ShadowedVariables,
+        Lint::WhileTrue,
+        Lint::NullableComparisons,
+        Lint::IgnoredResults,
+        Lint::UnknownLints,
+    ];
+
+    /// name used in `#[allow(...)]`, `Ruda.toml` and `-D`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedVariables => "unused_variables",
+            Lint::UnusedImports => "unused_imports",
+            Lint::UnusedFunctions => "unused_functions",
+            Lint::UnusedConstants => "unused_constants",
+            Lint::UnreachableCode => "unreachable_code",
+            Lint::ShadowedVariables => "shadowed_variables",
+            Lint::WhileTrue => "while_true",
+            Lint::NullableComparisons => "nullable_comparisons",
+            Lint::IgnoredResults => "ignored_results",
+            Lint::UnknownLints => "unknown_lints",
+        }
+    }
+
+    /// stable identifier of the warning, like `W0001`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lint::UnusedVariables => "W0001",
+            Lint::UnusedImports => "W0002",
+            Lint::UnusedFunctions => "W0003",
+            Lint::UnusedConstants => "W0004",
+            Lint::UnreachableCode => "W0005",
+            Lint::ShadowedVariables => "W0006",
+            Lint::WhileTrue => "W0007",
+            Lint::NullableComparisons => "W0008",
+            Lint::IgnoredResults => "W0009",
+            Lint::UnknownLints => "W0010",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+/// returns true if `lint` is silenced by one of the names in `allow`
+pub fn allowed(allow: &[String], lint: Lint) -> bool {
+    allow.iter().any(|name| name == lint.name() || name == ALL)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl std::str::FromStr for Level {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Level::Allow),
+            "warn" => Ok(Level::Warn),
+            "deny" => Ok(Level::Deny),
+            _ => Err(format!("unknown lint level '{s}', expected allow, warn or deny")),
+        }
+    }
+}
+
+/// levels of the lints for the whole project, every lint warns by default
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// later entries win, `warnings` sets every lint
+    levels: Vec<(String, Level)>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// sets the level of a lint or of every lint with `warnings`
+    pub fn set(&mut self, name: &str, level: Level) -> Result<(), String> {
+        if name != ALL && Lint::from_name(name).is_none() {
+            return Err(format!("unknown lint '{name}'"));
+        }
+        self.levels.push((name.to_string(), level));
+        Ok(())
+    }
+    pub fn level(&self, lint: Lint) -> Level {
+        self.levels
+            .iter()
+            .rev()
+            .find(|(name, _)| name == lint.name() || name == ALL)
+            .map(|(_, level)| *level)
+            .unwrap_or(Level::Warn)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Warning {
+    UnusedVariable(String, Line),
+    UnusedImport(String, Line),
+    UnusedFunction(String, Line),
+    UnusedConstant(String, Line),
+    /// statement that ends the block, first unreachable statement
+    UnreachableCode(&'static str, Line),
+    /// variable, previous declaration, line
+    ShadowedVariable(String, Line, Line),
+    WhileTrue(Line),
+    NullableComparison(Operators, Line),
+    /// called function
+    IgnoredResult(String, Line),
+    UnknownLint(String, Line),
+}
+
+impl Warning {
+    pub fn lint(&self) -> Lint {
+        match self {
+            Warning::UnusedVariable(..) => Lint::UnusedVariables,
+            Warning::UnusedImport(..) => Lint::UnusedImports,
+            Warning::UnusedFunction(..) => Lint::UnusedFunctions,
+            Warning::UnusedConstant(..) => Lint::UnusedConstants,
+            Warning::UnreachableCode(..) => Lint::UnreachableCode,
+            Warning::ShadowedVariable(..) => Lint::ShadowedVariables,
+            Warning::WhileTrue(..) => Lint::WhileTrue,
+            Warning::NullableComparison(..) => Lint::NullableComparisons,
+            Warning::IgnoredResult(..) => Lint::IgnoredResults,
+            Warning::UnknownLint(..) => Lint::UnknownLints,
+        }
+    }
+    pub fn line(&self) -> Line {
+        match self {
+            Warning::UnusedVariable(_, line)
+            | Warning::UnusedImport(_, line)
+            | Warning::UnusedFunction(_, line)
+            | Warning::UnusedConstant(_, line)
+            | Warning::UnreachableCode(_, line)
+            | Warning::ShadowedVariable(_, _, line)
+            | Warning::WhileTrue(line)
+            | Warning::NullableComparison(_, line)
+            | Warning::IgnoredResult(_, line)
+            | Warning::UnknownLint(_, line) => *line,
+        }
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::UnusedVariable(name, line) => write!(f, "unused variable {name} at {line}"),
+            Warning::UnusedImport(path, line) => write!(f, "unused import {path} at {line}"),
+            Warning::UnusedFunction(name, line) => {
+                write!(f, "function {name} is never used at {line}")
+            }
+            Warning::UnusedConstant(name, line) => {
+                write!(f, "constant {name} is never used at {line}")
+            }
+            Warning::UnreachableCode(after, line) => {
+                write!(f, "unreachable code after {after} at {line}")
+            }
+            Warning::ShadowedVariable(name, _, line) => {
+                write!(f, "variable {name} shadows an earlier declaration at {line}")
+            }
+            Warning::WhileTrue(line) => write!(f, "infinite loop written as while true at {line}"),
+            Warning::NullableComparison(op, line) => {
+                write!(f, "nullable value compared with `{}` at {line}", deparse_token(&Tokens::Operator(*op)))
+            }
+            Warning::IgnoredResult(name, line) => {
+                write!(f, "result of {name} is ignored at {line}")
+            }
+            Warning::UnknownLint(name, line) => write!(f, "unknown lint {name} at {line}"),
+        }
+    }
+}
+
+/// all warnings of the program with the file they occured in
+///
+/// items silence warnings with `#[allow(...)]`, project levels are applied by `diagnostics`
+pub fn lint(objects: &Context) -> Vec<(Warning, String)> {
+    let mut warnings = Vec::new();
+    let mut files = objects.0.keys().cloned().collect::<Vec<_>>();
+    files.sort();
+    for file in files {
+        let mut linter = Linter::new(objects, &file);
+        linter.module(&objects.0[&file]);
+        warnings.extend(linter.warnings.into_iter().map(|warning| (warning, file.clone())));
+    }
+    warnings.extend(crate::typecheck::lints(objects));
+    warnings.sort_by_key(|(warning, file)| (file.clone(), warning.line().line, warning.line().column));
+    warnings
+}
+
+/// turns warnings into diagnostics at the level the project sets for them
+///
+/// returns true if any of them is denied
+pub fn diagnostics(warnings: &[(Warning, String)], config: &Config) -> (Vec<Diagnostic>, bool) {
+    let mut diagnostics = Vec::new();
+    let mut denied = false;
+    let mut seen = Vec::new();
+    for (warning, file) in warnings {
+        let mut diagnostic = match config.level(warning.lint()) {
+            Level::Allow => continue,
+            Level::Warn => Diagnostic::from(warning),
+            Level::Deny => {
+                denied = true;
+                let mut diagnostic = Diagnostic::from(warning);
+                diagnostic.severity = Severity::Error;
+                diagnostic
+            }
+        };
+        diagnostic.file = file.clone();
+        // how to silence a lint is explained once
+        let lint = warning.lint();
+        if !seen.contains(&lint) {
+            seen.push(lint);
+            diagnostic = diagnostic.with_note(format!("`#[allow({})]` silences it for an item", lint.name()));
+        }
+        diagnostics.push(diagnostic);
+    }
+    (diagnostics, denied)
+}
+
+struct Local {
+    name: String,
+    line: Line,
+    used: bool,
+    /// parameters are not reported when unused
+    param: bool,
+}
+
+/// walks the code of one module looking for unused and unreachable code
+struct Linter<'a> {
+    objects: &'a Context,
+    file: String,
+    warnings: Vec<Warning>,
+    scopes: Vec<Vec<Local>>,
+    /// lints silenced for the current item
+    allow: Vec<String>,
+    /// function of the module whose body is walked, references from it to itself are not uses
+    current: Option<String>,
+    /// names of the module referenced by the code, with the function they are referenced from
+    refs: Vec<(String, Option<String>)>,
+}
+
+impl<'a> Linter<'a> {
+    fn new(objects: &'a Context, file: &str) -> Self {
+        Self {
+            objects,
+            file: file.to_string(),
+            warnings: Vec::new(),
+            scopes: Vec::new(),
+            allow: Vec::new(),
+            current: None,
+            refs: Vec::new(),
+        }
+    }
+
+    fn warn(&mut self, warning: Warning) {
+        if !allowed(&self.allow, warning.lint()) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// reports names in `#[allow(...)]` that are not lints
+    fn check_allow(&mut self, allow: &[String], line: Line) {
+        for name in allow {
+            if name != ALL && Lint::from_name(name).is_none() && !allowed(allow, Lint::UnknownLints) {
+                self.warnings.push(Warning::UnknownLint(name.clone(), line));
+            }
+        }
+    }
+
+    fn module(&mut self, dict: &dictionary::Dictionary) {
+        for import in dict.imports.iter() {
+            self.check_allow(&import.allow, import.line);
+        }
+        for using in dict.uses.iter() {
+            if let Some(PathPart::Identifier(first)) = using.path.first() {
+                self.refs.push((first.clone(), None));
+            }
+        }
+        for constant in dict.constants.iter() {
+            self.check_allow(&constant.allow, constant.line);
+            self.allow = constant.allow.clone();
+            self.current = None;
+            self.scopes = vec![Vec::new()];
+            self.expr(&constant.value);
+        }
+        for fun in dict.functions.iter() {
+            self.check_allow(&fun.allow, fun.line);
+            self.function(fun, &[], true);
+        }
+        for structt in dict.structs.iter() {
+            self.check_allow(&structt.allow, structt.line);
+            for (_, kind) in structt.fields.iter() {
+                self.kind(kind);
+            }
+            for target in structt.traits.iter() {
+                self.path(target);
+            }
+            for implementation in structt.impls.iter() {
+                self.path(&implementation.target);
+            }
+            for fun in structt.functions.iter() {
+                if self.copied(structt, fun) {
+                    continue;
+                }
+                self.check_allow(&fun.allow, fun.line);
+                self.function(fun, &structt.allow, false);
+            }
+        }
+        for enumm in dict.enums.iter() {
+            for arg in enumm.fields.iter().flatten() {
+                self.kind(&arg.kind);
+            }
+            for fun in enumm.methods.iter() {
+                self.function(fun, &[], false);
+            }
+        }
+        for typedef in dict.types.iter() {
+            self.kind(&typedef.kind);
+            for fun in typedef.methods.iter() {
+                self.function(fun, &[], false);
+            }
+        }
+        for traitt in dict.traits.iter() {
+            for target in traitt.traits.iter() {
+                self.path(target);
+            }
+            for fun in traitt.methods.iter() {
+                self.function(fun, &[], false);
+            }
+        }
+        for error in dict.errors.iter() {
+            self.allow.clear();
+            self.current = None;
+            self.scopes = vec![Vec::new()];
+            self.args(&error.args);
+            for (_, field) in error.fields.iter() {
+                match field {
+                    ErrorField::Expression(value) => self.expr(value),
+                    ErrorField::CodeBlock(code) => self.block(code),
+                }
+            }
+        }
+        self.unused_items(dict);
+    }
+
+    /// returns true for methods the struct got from an `impl` block or a default of a trait,
+    /// those are walked where they were written
+    fn copied(&self, structt: &dictionary::Struct, fun: &Function) -> bool {
+        let same = |other: &Function| other.identifier == fun.identifier && other.line == fun.line;
+        structt.impls.iter().any(|implementation| {
+            resolve_trait(self.objects, &implementation.target, &self.file)
+                .is_some_and(|(_, traitt)| traitt.methods.iter().any(same))
+        })
+    }
+
+    fn unused_items(&mut self, dict: &dictionary::Dictionary) {
+        let used = |refs: &[(String, Option<String>)], name: &str| {
+            refs.iter()
+                .any(|(reference, from)| reference == name && from.as_deref() != Some(name))
+        };
+        for import in dict.imports.iter() {
+            if !used(&self.refs, &import.alias) && !allowed(&import.allow, Lint::UnusedImports) {
+                self.warnings.push(Warning::UnusedImport(import.path.clone(), import.line));
+            }
+        }
+        for fun in dict.functions.iter() {
+            let name = match &fun.identifier {
+                Some(name) => name,
+                None => continue,
+            };
+            if fun.public || name == "main" || used(&self.refs, name) {
+                continue;
+            }
+            if !allowed(&fun.allow, Lint::UnusedFunctions) {
+                self.warnings.push(Warning::UnusedFunction(name.clone(), fun.line));
+            }
+        }
+        for constant in dict.constants.iter() {
+            if constant.public || used(&self.refs, &constant.identifier) {
+                continue;
+            }
+            if !allowed(&constant.allow, Lint::UnusedConstants) {
+                self.warnings.push(Warning::UnusedConstant(constant.identifier.clone(), constant.line));
+            }
+        }
+    }
+
+    fn function(&mut self, fun: &Function, allow: &[String], top: bool) {
+        self.allow = allow.iter().chain(fun.allow.iter()).cloned().collect();
+        self.current = match top {
+            true => fun.identifier.clone(),
+            false => None,
+        };
+        self.scopes = vec![Vec::new()];
+        self.args(&fun.args);
+        if let Some(kind) = &fun.return_type {
+            self.kind(kind);
+        }
+        self.block(&fun.code);
+        self.scopes.clear();
+    }
+
+    /// declares the parameters in the current scope
+    fn args(&mut self, args: &[Arg]) {
+        for arg in args {
+            self.kind(&arg.kind);
+            self.declare(&arg.identifier, arg.line, true);
+        }
+    }
+
+    fn declare(&mut self, name: &str, line: Line, param: bool) {
+        if name.starts_with('_') || name.starts_with('#') {
+            return;
+        }
+        let previous = self.scopes.iter().flatten().rev().find(|local| local.name == name).map(|local| local.line);
+        if let Some(previous) = previous {
+            self.warn(Warning::ShadowedVariable(name.to_string(), previous, line));
+        }
+        self.scopes.last_mut().unwrap().push(Local {
+            name: name.to_string(),
+            line,
+            used: false,
+            param,
+        });
+    }
+
+    fn destructure(&mut self, pattern: &TuplePattern, line: Line) {
+        for name in pattern.iter().flatten() {
+            self.declare(name, line, false);
+        }
+    }
+
+    /// marks a local as used or records a reference to an item of the module
+    fn read(&mut self, name: &str) {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(local) = scope.iter_mut().rev().find(|local| local.name == name) {
+                local.used = true;
+                return;
+            }
+        }
+        self.refs.push((name.to_string(), self.current.clone()));
+    }
+
+    fn push(&mut self) {
+        self.scopes.push(Vec::new());
+    }
+
+    fn pop(&mut self) {
+        let scope = self.scopes.pop().unwrap_or_default();
+        for local in scope {
+            if !local.used && !local.param {
+                self.warn(Warning::UnusedVariable(local.name, local.line));
+            }
+        }
+    }
+
+    fn block(&mut self, block: &[Nodes]) {
+        self.push();
+        // only the first unreachable statement is reported
+        let mut ended = None;
+        for node in block {
+            if let Some(after) = ended.take() {
+                self.warn(Warning::UnreachableCode(after, line(node)));
+            }
+            self.node(node);
+            ended = match node {
+                Nodes::Return { .. } => Some("return"),
+                Nodes::Break { .. } => Some("break"),
+                Nodes::Continue { .. } => Some("continue"),
+                Nodes::Yeet { .. } => Some("yeet"),
+                _ => None,
+            };
+        }
+        self.pop();
+    }
+
+    fn node(&mut self, node: &Nodes) {
+        match node {
+            Nodes::Let {
+                ident,
+                expr,
+                kind,
+                line,
+                pattern,
+            } => {
+                if let Some(kind) = kind {
+                    self.kind(kind);
+                }
+                if let Some(expr) = expr {
+                    self.expr(expr);
+                }
+                match pattern {
+                    Some(pattern) => self.destructure(pattern, *line),
+                    None => self.declare(ident, *line, false),
+                }
+            }
+            Nodes::If {
+                cond,
+                body,
+                elif,
+                els,
+                ..
+            } => {
+                self.expr(cond);
+                self.block(body);
+                for (cond, body, _) in elif {
+                    self.expr(cond);
+                    self.block(body);
+                }
+                if let Some((body, _)) = els {
+                    self.block(body);
+                }
+            }
+            Nodes::While { cond, body, line, .. } => {
+                if let ValueType::Value(value) = cond {
+                    if value.is_true_simple()
+                        && matches!(&value.root.0, Root::Identifier(ident) if ident == "true")
+                    {
+                        self.warn(Warning::WhileTrue(*line));
+                    }
+                }
+                self.expr(cond);
+                self.block(body);
+            }
+            Nodes::For {
+                ident,
+                expr,
+                range,
+                body,
+                line,
+                ident2,
+                pattern,
+                ..
+            } => {
+                self.expr(expr);
+                if let Some(range) = range {
+                    self.expr(&range.end);
+                    if let Some(step) = &range.step {
+                        self.expr(step);
+                    }
+                }
+                self.push();
+                if let Some(ident2) = ident2 {
+                    self.declare(ident2, *line, false);
+                }
+                match pattern {
+                    Some(pattern) => self.destructure(pattern, *line),
+                    None => self.declare(ident, *line, false),
+                }
+                self.block(body);
+                self.pop();
+            }
+            Nodes::Return { expr, .. } => {
+                if let Some(expr) = expr {
+                    self.expr(expr);
+                }
+            }
+            Nodes::Expr { expr, .. } => self.expr(expr),
+            Nodes::Block { body, .. } | Nodes::Loop { body, .. } => self.block(body),
+            Nodes::Break { .. } | Nodes::Continue { .. } => (),
+            Nodes::Yeet { expr, .. } => {
+                let ((name, _), tail) = expr;
+                if let Some(first) = name.split('.').next() {
+                    self.read(first);
+                }
+                self.tail(tail);
+            }
+            Nodes::Try {
+                body,
+                catch,
+                finally,
+                ..
+            } => {
+                self.block(body);
+                for catch in catch {
+                    for path in catch.kinds.iter() {
+                        self.path(path);
+                    }
+                    self.push();
+                    if let Some(ident) = &catch.ident {
+                        self.declare(ident, catch.line, false);
+                    }
+                    self.block(&catch.body);
+                    self.pop();
+                }
+                if let Some(finally) = finally {
+                    self.block(finally);
+                }
+            }
+            Nodes::Switch {
+                expr, body, default, ..
+            } => {
+                self.expr(expr);
+                for (values, body) in body {
+                    for value in values {
+                        self.expr(value);
+                    }
+                    self.block(body);
+                }
+                if let Some(default) = default {
+                    self.block(default);
+                }
+            }
+            Nodes::Set { target, expr, op, .. } => {
+                self.expr(expr);
+                // plain assignment to a variable does not read it
+                if let (Operators::Equal, ValueType::Value(value)) = (op, target) {
+                    if value.is_true_simple() && matches!(value.root.0, Root::Identifier(_)) {
+                        return;
+                    }
+                }
+                self.expr(target);
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &ValueType) {
+        match expr {
+            ValueType::Expression(node) => {
+                if let Some(left) = &node.left {
+                    self.expr(left);
+                }
+                if let Some(right) = &node.right {
+                    self.expr(right);
+                }
+            }
+            ValueType::Value(value) => {
+                match &value.root.0 {
+                    Root::Literal(literal) => self.literal(literal),
+                    Root::Parenthesis(expr) => self.expr(expr),
+                    Root::Identifier(ident) => self.read(ident),
+                    Root::Match(matchh) => {
+                        self.expr(&matchh.expr);
+                        for arm in matchh.arms.iter() {
+                            if let Some(path) = &arm.path {
+                                self.path(path);
+                            }
+                            self.push();
+                            for binding in arm.bindings.iter().flatten() {
+                                self.declare(binding, arm.line, false);
+                            }
+                            if let Some(guard) = &arm.guard {
+                                self.expr(guard);
+                            }
+                            match &arm.body {
+                                ArmBody::Value(value) => self.expr(value),
+                                ArmBody::Block(block) => self.block(block),
+                            }
+                            self.pop();
+                        }
+                    }
+                    Root::Interpolation(parts) => {
+                        for part in parts {
+                            if let InterpolationPart::Value(embedded) = part {
+                                self.expr(&embedded.value);
+                            }
+                        }
+                    }
+                }
+                self.tail(&value.tail);
+            }
+            ValueType::AnonymousFunction(lambda) => {
+                self.push();
+                self.args(&lambda.args);
+                if let Some(kind) = &lambda.return_type {
+                    self.kind(kind);
+                }
+                self.block(&lambda.code);
+                self.pop();
+            }
+            ValueType::Operator(..) | ValueType::Blank => (),
+        }
+    }
+
+    fn literal(&mut self, literal: &Literals) {
+        match literal {
+            Literals::Array(ArrayRule::Fill { value, size }) => {
+                self.expr(value);
+                self.expr(size);
+            }
+            Literals::Array(ArrayRule::Explicit(values)) | Literals::Tuple(values) => {
+                for value in values {
+                    self.expr(value);
+                }
+            }
+            Literals::Number(_) | Literals::String(_) | Literals::Char(_) => (),
+        }
+    }
+
+    fn tail(&mut self, tail: &[(TailNodes, Line)]) {
+        for (node, _) in tail {
+            match node {
+                TailNodes::Index(index) => self.expr(index),
+                TailNodes::Call(call) => {
+                    for kind in call.generic.iter() {
+                        self.kind(kind);
+                    }
+                    for arg in call.args.iter().chain(call.named.iter().map(|named| &named.1)) {
+                        self.expr(arg);
+                    }
+                }
+                TailNodes::Cast(kind) => self.kind(kind),
+                TailNodes::Nested(_)
+                | TailNodes::TupleIndex(_)
+                | TailNodes::Nullable
+                | TailNodes::Propagate => (),
+            }
+        }
+    }
+
+    /// records the module or item a path starts with
+    fn path(&mut self, path: &[String]) {
+        if let Some(first) = path.first() {
+            self.refs.push((first.clone(), self.current.clone()));
+        }
+    }
+
+    fn kind(&mut self, kind: &Kind) {
+        match &kind.body {
+            TypeBody::Type { main, generics, .. } => {
+                self.path(main);
+                for generic in generics {
+                    self.kind(generic);
+                }
+            }
+            TypeBody::Function {
+                args, return_type, ..
+            } => {
+                for arg in args {
+                    self.kind(&arg.kind);
+                }
+                if let Some(kind) = return_type.as_ref() {
+                    self.kind(kind);
+                }
+            }
+            TypeBody::Array { type_, .. } => self.kind(type_),
+            TypeBody::Tuple { types, .. } => {
+                for kind in types {
+                    self.kind(kind);
+                }
+            }
+            TypeBody::Generic { .. } | TypeBody::Void => (),
+        }
+    }
+}
+
+fn line(node: &Nodes) -> Line {
+    match node {
+        Nodes::Let { line, .. }
+        | Nodes::If { line, .. }
+        | Nodes::While { line, .. }
+        | Nodes::For { line, .. }
+        | Nodes::Return { line, .. }
+        | Nodes::Expr { line, .. }
+        | Nodes::Block { line, .. }
+        | Nodes::Break { line, .. }
+        | Nodes::Continue { line, .. }
+        | Nodes::Loop { line, .. }
+        | Nodes::Yeet { line, .. }
+        | Nodes::Try { line, .. }
+        | Nodes::Switch { line, .. }
+        | Nodes::Set { line, .. } => *line,
+    }
+}
@@ -7,6 +7,7 @@ use self::dict::prep_dict;
 pub type Dictionaries = HashMap<String, dictionary::Dictionary>;
 pub type Binaries = HashMap<String, libloader::Dictionary>;
 
+#[derive(Clone)]
 pub struct Context(pub Dictionaries, pub Binaries);
 
 impl Context {
@@ -20,6 +21,10 @@ impl Context {
         let fns = &self.0.get("main.rd").unwrap().functions;
         fns.iter().find(|f| f.identifier.as_ref().unwrap() == "main").unwrap()
     }
+    pub fn get_main_mut(&mut self) -> &mut dictionary::Function {
+        let fns = &mut self.0.get_mut("main.rd").unwrap().functions;
+        fns.iter_mut().find(|f| f.identifier.as_ref().unwrap() == "main").unwrap()
+    }
 }
 
 pub fn prep(context: &mut Context) -> Result<(), PrepError> {
@@ -36,7 +41,7 @@ pub enum PrepError {
 
 
 mod dict {
-    use crate::intermediate;
+    use crate::{codegen, intermediate};
 
     use super::*;
 
@@ -45,9 +50,82 @@ mod dict {
             Ok(()) => (),
             Err(err) => return Err(err)
         }
+        prep_impls(name, context);
+        prep_overloads(name, context);
         Ok(())
     }
 
+    /// Turns `overload` blocks of structs into methods of the struct
+    ///
+    /// Overloads from `impl` blocks are moved to the struct first,
+    /// the method of an overload is named after its position in `overloads`
+    fn prep_overloads(name: &str, context: &mut Context) {
+        for structt in context.0.get_mut(name).unwrap().structs.iter_mut() {
+            for implementation in structt.impls.iter_mut() {
+                structt.overloads.append(&mut implementation.overloads);
+            }
+            for (idx, overload) in structt.overloads.iter().enumerate() {
+                structt.functions.push(dictionary::Function {
+                    identifier: Some(dictionary::Overload::method_ident(idx)),
+                    args: overload.arg.iter().cloned().collect(),
+                    stack_size: None,
+                    location: 0,
+                    instrs_end: 0,
+                    return_type: overload.return_type.clone(),
+                    can_yeet: false,
+                    generics: overload.generics.clone(),
+                    public: overload.public,
+                    code: overload.code.clone(),
+                    line: overload.line,
+                    pointers: None,
+                    id: 0,
+                    takes_self: true,
+                    docs: overload.docs.clone(),
+                    inline: dictionary::InlineHint::Auto,
+                    inlining: false,
+                    allow: Vec::new(),
+                });
+            }
+        }
+    }
+
+    /// Turns `impl Trait {}` blocks of structs into methods of the struct
+    ///
+    /// Trait methods with a default body that the impl does not provide are copied in,
+    /// whether the impl matches the trait is checked later by the type checker
+    fn prep_impls(name: &str, context: &mut Context) {
+        let structs_len = context.0.get(name).unwrap().structs.len();
+        for idx in 0..structs_len {
+            let mut functions = Vec::new();
+            let mut traits = Vec::new();
+            let structt = &context.0.get(name).unwrap().structs[idx];
+            for implementation in structt.impls.iter() {
+                functions.extend(implementation.functions.iter().cloned());
+                let traitt = match codegen::resolve_trait(context, &implementation.target, name) {
+                    Some((_, traitt)) => traitt,
+                    None => continue,
+                };
+                traits.push(implementation.target.clone());
+                for method in traitt.methods.iter() {
+                    if !traitt.defaults.contains(method.identifier.as_ref().unwrap())
+                        || implementation.functions.iter().any(|fun| fun.identifier == method.identifier)
+                    {
+                        continue;
+                    }
+                    functions.push(method.clone());
+                }
+            }
+            let structt = &mut context.0.get_mut(name).unwrap().structs[idx];
+            for fun in functions {
+                if structt.functions.iter().any(|other| other.identifier == fun.identifier) {
+                    continue;
+                }
+                structt.functions.push(fun);
+            }
+            structt.traits.extend(traits);
+        }
+    }
+
     fn prep_consts(name: &str, context: &mut Context) -> Result<(), super::PrepError> {
         let mut const_values = HashMap::new();
         let mut errs = Vec::with_capacity(context.0.get_mut(name).unwrap().constants.len());
@@ -0,0 +1,400 @@
+//! Compiles and runs the inputs of the REPL
+//!
+//! The REPL keeps the type checked program and the VM running it between the inputs.
+//! A statement is parsed on its own, type checked as the end of `main` and only its
+//! instructions are run, the variables of `main` stay on the stack of the VM.
+//! Imports and declarations rebuild the program around the statements entered so far.
+//! A statement that is an expression is returned from `main` so its value can be printed.
+
+use runtime::runtime_types::{self, ExitCodes, Libs, PointerTypes, Types, RETURN_REG};
+
+use crate::codeblock_parser::Nodes;
+use crate::codegen::{self, InnerPath};
+use crate::diagnostic::Diagnostic;
+use crate::intermediate::Kind;
+use crate::prep_objects::Context;
+use crate::session::{DiskFiles, FileProvider, Session};
+use crate::typecheck;
+
+/// name of the module the REPL program is compiled as
+pub const MAIN: &str = "main.rd";
+
+/// first line of the history, the statements are parsed as the body of the `main` it starts
+const HEADER: &str = "fun main() {\n";
+
+/// instructions `main` starts with: `ReserveStack` and `CloseArgs`
+const PROLOGUE: usize = 2;
+
+/// instructions `main` without a value ends with: `ReadConst`, `Return` and the closing `Return`
+const EPILOGUE: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Input {
+    Import(String),
+    /// name of the declared item and its source
+    Item(String, String),
+    Statement(String),
+}
+
+impl Input {
+    /// sorts the input by the keyword it starts with
+    pub fn new(input: &str) -> Input {
+        let input = input.trim().to_string();
+        let mut words = input
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.starts_with("///") && !line.starts_with("#["))
+            .flat_map(|line| line.split_whitespace());
+        let mut keyword = words.next().unwrap_or("");
+        if keyword == "pub" {
+            keyword = words.next().unwrap_or("");
+        }
+        match keyword {
+            "import" => Input::Import(input),
+            "fun" | "struct" | "enum" | "trait" | "error" | "type" | "const" | "use" => {
+                let name = words
+                    .next()
+                    .unwrap_or("")
+                    .split(|c: char| !c.is_alphanumeric() && c != '_')
+                    .next()
+                    .unwrap_or("")
+                    .to_string();
+                Input::Item(format!("{keyword} {name}"), input)
+            }
+            _ => Input::Statement(input),
+        }
+    }
+
+    /// an input declaring the same thing replaces this one
+    fn replaced_by(&self, other: &Input) -> bool {
+        match (self, other) {
+            (Input::Import(import), Input::Import(other)) => import == other,
+            (Input::Item(name, _), Input::Item(other, _)) => name == other,
+            _ => false,
+        }
+    }
+
+    fn source(&self) -> &str {
+        match self {
+            Input::Import(source) | Input::Item(_, source) | Input::Statement(source) => source,
+        }
+    }
+}
+
+/// the input could not be compiled or crashed while running
+///
+/// the VM reports runtime errors by itself, they come without diagnostics
+pub struct Failed {
+    /// everything entered so far with the failed input
+    pub source: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Failed {
+    fn new(source: &str, diagnostics: Vec<Diagnostic>) -> Self {
+        Self {
+            source: source.to_string(),
+            diagnostics,
+        }
+    }
+}
+
+/// `main.rd` is kept in memory, the modules it imports are read from the working directory
+#[derive(Default)]
+struct Files {
+    main: String,
+}
+
+impl FileProvider for Files {
+    fn read(&self, path: &str) -> std::io::Result<String> {
+        match path == MAIN {
+            true => Ok(self.main.clone()),
+            false => DiskFiles.read(path),
+        }
+    }
+    fn is_dir(&self, path: &str) -> bool {
+        DiskFiles.is_dir(path)
+    }
+}
+
+/// program generated with a statement at the end of `main`
+struct Generated {
+    vm: runtime_types::Context,
+    nodes: Vec<Nodes>,
+    kind: Option<Kind>,
+    /// where the instructions of the statement start and end
+    start: usize,
+    end: usize,
+    /// stack size of `main`
+    frame: usize,
+}
+
+pub struct Repl {
+    session: Session<Files>,
+    libs: Libs,
+    /// type checked program, `main` holds the statements entered so far
+    context: Context,
+    /// imports and declarations with the position they start at in the history
+    declarations: Vec<(usize, Input)>,
+    /// `HEADER` and everything entered after it, the compiler reports positions in it
+    history: String,
+    vm: runtime_types::Context,
+    /// where the stack frame of `main` starts in the VM and its size
+    frame: (usize, usize),
+}
+
+impl Repl {
+    /// `libraries` are the names of the libraries with their declarations,
+    /// `libs` are the functions the VM calls them with
+    pub fn new(libraries: &[(String, String)], libs: Libs) -> Result<Self, Failed> {
+        let mut session = Session::new(Files::default());
+        for (name, declarations) in libraries {
+            session.library(name, declarations);
+        }
+        let mut repl = Self {
+            session,
+            vm: runtime_types::Context::new(libs.clone()),
+            libs,
+            context: Context::new(Default::default(), Default::default()),
+            declarations: Vec::new(),
+            history: HEADER.to_string(),
+            frame: (0, 0),
+        };
+        repl.reset()?;
+        Ok(repl)
+    }
+
+    /// forgets everything entered so far
+    pub fn reset(&mut self) -> Result<(), Failed> {
+        self.declarations.clear();
+        self.history = HEADER.to_string();
+        self.context = self.declare(&[], Vec::new(), HEADER)?;
+        self.vm = runtime_types::Context::new(self.libs.clone());
+        self.frame = (0, 0);
+        Ok(())
+    }
+
+    /// compiles and runs the input, returns the value of an expression
+    pub fn eval(&mut self, input: &str) -> Result<Option<String>, Failed> {
+        let input = input.trim();
+        let (pos, source) = self.place(input);
+        let input = Input::new(input);
+        let statement = match input {
+            Input::Statement(statement) => statement,
+            input => {
+                let mut declarations = self.declarations.clone();
+                declarations.retain(|(_, declared)| !declared.replaced_by(&input));
+                declarations.push((pos, input));
+                let statements = self.context.get_main().code.clone();
+                self.context = self.declare(&declarations, statements, &source)?;
+                self.declarations = declarations;
+                self.history = source;
+                return Ok(None);
+            }
+        };
+        let generated = self.generate(&statement, pos, &source)?;
+        self.load(generated.vm, generated.frame);
+        self.vm.code.ptr = generated.start;
+        self.vm.exit_code = ExitCodes::End;
+        self.vm.break_code = None;
+        // the VM panics on some of the runtime errors, the REPL goes on after them
+        let run = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| self.vm.run()));
+        if run.is_err() || !matches!(self.vm.exit_code, ExitCodes::End) {
+            return Err(Failed::new(&source, Vec::new()));
+        }
+        self.context.get_main_mut().code.extend(generated.nodes);
+        self.history = source;
+        let value = match self.vm.memory.registers[RETURN_REG] {
+            Types::Pointer(loc, PointerTypes::String) => format!("{:?}", self.vm.memory.strings.to_str(loc)),
+            value => value.to_str(&self.vm.memory),
+        };
+        Ok(generated.kind.map(|_| value))
+    }
+
+    /// kind of the value of an expression
+    pub fn type_of(&mut self, expr: &str) -> Result<Option<String>, Failed> {
+        let expr = expr.trim();
+        let (pos, source) = self.place(expr);
+        let nodes = self.parse(expr, pos, &source)?;
+        let count = self.context.get_main().code.len();
+        self.context.get_main_mut().code.extend(nodes);
+        let checked = check(&self.context, &source);
+        let kind = last_value(&self.context);
+        self.context.get_main_mut().code.truncate(count);
+        checked?;
+        Ok(kind.map(|kind| format!("{kind:?}")))
+    }
+
+    /// instructions generated for a statement, it is not run
+    pub fn disassemble(&mut self, statement: &str) -> Result<Vec<String>, Failed> {
+        let statement = statement.trim();
+        let (pos, source) = self.place(statement);
+        let generated = self.generate(statement, pos, &source)?;
+        Ok((generated.start..generated.end)
+            .filter_map(|pos| generated.vm.code.data.get(pos).map(|instr| format!("{pos}. {instr:?}")))
+            .collect())
+    }
+
+    /// position the input starts at and the history with the input
+    fn place(&self, input: &str) -> (usize, String) {
+        (self.history.len(), format!("{}{}\n", self.history, input))
+    }
+
+    /// type checks the declarations with `statements` as the body of `main`
+    fn declare(
+        &mut self,
+        declarations: &[(usize, Input)],
+        statements: Vec<Nodes>,
+        source: &str,
+    ) -> Result<Context, Failed> {
+        let mut text = blank(source);
+        for (pos, declaration) in declarations {
+            let declaration = declaration.source();
+            text.replace_range(*pos..*pos + declaration.len(), declaration);
+        }
+        text.push_str("fun main() {}\n");
+        self.session.files_mut().main = text;
+        let mut context = self
+            .session
+            .check(MAIN)
+            .map_err(|diagnostics| Failed::new(source, diagnostics))?;
+        context.get_main_mut().code = statements;
+        check(&context, source)?;
+        Ok(context)
+    }
+
+    /// statements of the input starting at `pos`
+    fn parse(&mut self, input: &str, pos: usize, source: &str) -> Result<Vec<Nodes>, Failed> {
+        let mut snippet = blank(&source[..pos]);
+        snippet.replace_range(..HEADER.len(), HEADER);
+        snippet.push_str(input);
+        snippet.push_str("\n}\n");
+        let declarations = std::mem::replace(&mut self.session.files_mut().main, snippet);
+        let parsed = self.session.parse(MAIN);
+        self.session.files_mut().main = declarations;
+        let mut dictionaries = parsed.map_err(|diagnostics| Failed::new(source, diagnostics))?;
+        let functions = match dictionaries.get_mut(MAIN) {
+            Some(dictionary) => std::mem::take(&mut dictionary.functions),
+            None => Vec::new(),
+        };
+        Ok(functions
+            .into_iter()
+            .find(|fun| fun.identifier.as_deref() == Some("main"))
+            .map(|fun| fun.code)
+            .unwrap_or_default())
+    }
+
+    /// generates the program with the statement at the end of `main`, the statement is not kept
+    fn generate(&mut self, statement: &str, pos: usize, source: &str) -> Result<Generated, Failed> {
+        let nodes = self.parse(statement, pos, source)?;
+        // the statements entered before generate the same instructions with the new one after them
+        let mut context = self.context.clone();
+        codegen::gen(&mut context, MAIN, &self.session.options)
+            .map_err(|err| Failed::new(source, vec![Diagnostic::from(&err)]))?;
+        let entered = context.get_main();
+        let len = entered.instrs_end - entered.location - PROLOGUE - EPILOGUE;
+        let count = self.context.get_main().code.len();
+        self.context.get_main_mut().code.extend(nodes);
+        let generated = self.generate_main(len, source);
+        let nodes = self.context.get_main_mut().code.split_off(count);
+        let mut generated = generated?;
+        generated.nodes = nodes;
+        Ok(generated)
+    }
+
+    /// generates the program, the instructions of `main` after the first `len` are the new statement
+    fn generate_main(&self, len: usize, source: &str) -> Result<Generated, Failed> {
+        check(&self.context, source)?;
+        let kind = last_value(&self.context);
+        let mut context = self.context.clone();
+        if let Some(kind) = &kind {
+            returns(&mut context, kind);
+        }
+        let vm = codegen::gen(&mut context, MAIN, &self.session.options)
+            .map_err(|err| Failed::new(source, vec![Diagnostic::from(&err)]))?;
+        let fun = context.get_main();
+        Ok(Generated {
+            vm,
+            nodes: Vec::new(),
+            kind,
+            start: fun.location + PROLOGUE + len,
+            end: fun.instrs_end,
+            frame: fun.stack_size.unwrap_or(0),
+        })
+    }
+
+    /// replaces the program of the VM, the heap, the strings and the variables of `main` are kept
+    fn load(&mut self, vm: runtime_types::Context, frame: usize) {
+        let strings = self.vm.memory.strings.pool.len();
+        self.vm.memory.strings.pool.extend(vm.memory.strings.pool);
+        let mut stack = vm.memory.stack.data;
+        for value in stack.iter_mut() {
+            if let Types::Pointer(loc, PointerTypes::String) = value {
+                *loc += strings;
+            }
+        }
+        // the frame of `main` moves right after the constants of the new program
+        let (old, len) = self.frame;
+        let consts = stack.len();
+        stack.resize(consts + frame, Types::Null);
+        for idx in 0..len.min(frame) {
+            stack[consts + idx] = match self.vm.memory.stack.data[old + idx] {
+                Types::Pointer(loc, PointerTypes::Stack) if (old..old + len).contains(&loc) => {
+                    Types::Pointer(loc - old + consts, PointerTypes::Stack)
+                }
+                value => value,
+            };
+        }
+        self.frame = (consts, frame);
+        let memory = &mut self.vm.memory;
+        memory.stack.data = stack;
+        memory.stack.ptr = 2;
+        memory.stack.call_stack[1].end = consts;
+        memory.stack.call_stack[2].end = consts + frame;
+        memory.stack.call_stack[2].pointers_len = 0;
+        memory.args.ptr = 0;
+        memory.non_primitives = vm.memory.non_primitives;
+        memory.fun_table = vm.memory.fun_table;
+        self.vm.catches.catches_ptr = 0;
+        self.vm.code.data = vm.code.data;
+        self.vm.code.entry_point = vm.code.entry_point;
+        self.vm.debug = vm.debug;
+    }
+}
+
+/// `source` with spaces instead of everything but the line breaks, the positions stay the same
+fn blank(source: &str) -> String {
+    source.bytes().map(|byte| if byte == b'\n' { '\n' } else { ' ' }).collect()
+}
+
+fn check(context: &Context, source: &str) -> Result<(), Failed> {
+    let errors = typecheck::check(context);
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(Failed::new(
+            source,
+            errors.iter().map(|(err, file)| Diagnostic::from(err).in_file(file)).collect(),
+        )),
+    }
+}
+
+/// kind of the value of the last statement of `main`
+fn last_value(context: &Context) -> Option<Kind> {
+    typecheck::last_value(context, MAIN, context.get_main())
+}
+
+/// makes `main` return the value of its last statement
+fn returns(context: &mut Context, kind: &Kind) {
+    let path = InnerPath::main();
+    let main = match path.get_mut(context) {
+        Ok(main) => main,
+        Err(_) => return,
+    };
+    if let Some(Nodes::Expr { expr, line }) = main.code.pop() {
+        main.code.push(Nodes::Return {
+            expr: Some(expr),
+            line,
+        });
+        main.return_type = Some(kind.clone());
+    }
+}
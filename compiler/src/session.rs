@@ -0,0 +1,250 @@
+//! Compiles programs without touching the filesystem or RUDA_PATH
+//!
+//! Modules are read through a `FileProvider`, the grammar is the one embedded in the compiler
+//! and the libraries are declared by their sources instead of being loaded from binaries.
+
+use std::collections::HashMap;
+
+use crate::ast_parser::ast_parser::{Head, HeadParam};
+use crate::codegen;
+use crate::diagnostic::Diagnostic;
+use crate::prep_objects::{self, Context, Dictionaries};
+use crate::{build_import_graph_from, embedded_ast, libloader, typecheck, Asts};
+
+/// standard libraries in the order the VM loads them, other libraries get ids after them
+pub const STD_LIBS: [&str; 9] = ["#io", "#string", "#fs", "#algo", "#core", "#time", "#window", "#memory", "#math"];
+
+/// source of the modules of a program
+///
+/// paths use `/` as the separator and are relative to where the provider is rooted
+pub trait FileProvider {
+    /// contents of the file, a missing file is `ErrorKind::NotFound`
+    fn read(&self, path: &str) -> std::io::Result<String>;
+    /// directories are imported through their `mod.rd`
+    fn is_dir(&self, path: &str) -> bool;
+}
+
+/// reads the files from the disk, relative paths are relative to the working directory
+pub struct DiskFiles;
+
+impl FileProvider for DiskFiles {
+    fn read(&self, path: &str) -> std::io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+    fn is_dir(&self, path: &str) -> bool {
+        std::path::Path::new(path).is_dir()
+    }
+}
+
+/// files kept in memory
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFiles {
+    files: HashMap<String, String>,
+}
+
+impl MemoryFiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn insert(&mut self, path: &str, source: &str) {
+        self.files.insert(normalize(path), source.to_string());
+    }
+    pub fn remove(&mut self, path: &str) -> Option<String> {
+        self.files.remove(&normalize(path))
+    }
+}
+
+impl From<HashMap<String, String>> for MemoryFiles {
+    fn from(files: HashMap<String, String>) -> Self {
+        let mut memory = Self::new();
+        for (path, source) in files {
+            memory.insert(&path, &source);
+        }
+        memory
+    }
+}
+
+impl FileProvider for MemoryFiles {
+    fn read(&self, path: &str) -> std::io::Result<String> {
+        match self.files.get(&normalize(path)) {
+            Some(source) => Ok(source.clone()),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no such file in memory",
+            )),
+        }
+    }
+    fn is_dir(&self, path: &str) -> bool {
+        let dir = normalize(path) + "/";
+        self.files.keys().any(|file| file.starts_with(&dir))
+    }
+}
+
+/// modules found in `sources` are read from memory, the rest from the disk
+///
+/// sources are keyed by the module path relative to `root`
+pub(crate) struct Overlay<'a> {
+    pub root: &'a str,
+    pub sources: &'a HashMap<String, String>,
+}
+
+impl FileProvider for Overlay<'_> {
+    fn read(&self, path: &str) -> std::io::Result<String> {
+        match self.relative(path).and_then(|module| self.sources.get(module)) {
+            Some(source) => Ok(source.clone()),
+            None => DiskFiles.read(path),
+        }
+    }
+    fn is_dir(&self, path: &str) -> bool {
+        DiskFiles.is_dir(path)
+    }
+}
+
+impl Overlay<'_> {
+    fn relative<'p>(&self, path: &'p str) -> Option<&'p str> {
+        match self.root.is_empty() {
+            true => Some(path),
+            false => path.strip_prefix(self.root)?.strip_prefix(['/', '\\']),
+        }
+    }
+}
+
+/// path of `module` in the directory `root`
+pub(crate) fn join(root: &str, module: &str) -> String {
+    match root.is_empty() {
+        true => module.to_string(),
+        false => format!("{root}/{module}"),
+    }
+}
+
+fn normalize(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut parts = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// compiles programs read from a `FileProvider`
+pub struct Session<F: FileProvider> {
+    files: F,
+    ast: (HashMap<String, Head>, Vec<HeadParam>),
+    registry: (HashMap<String, Head>, Vec<HeadParam>),
+    /// name of the library with its declarations
+    libraries: Vec<(String, String)>,
+    pub options: codegen::Options,
+}
+
+impl<F: FileProvider> Session<F> {
+    /// session with the grammar embedded in the compiler
+    pub fn new(files: F) -> Self {
+        let asts = embedded_ast().expect("embedded grammar is broken, please contact the developer.");
+        Self::with_asts(files, asts)
+    }
+
+    pub fn with_asts(files: F, asts: Asts) -> Self {
+        Self {
+            files,
+            ast: (asts.ast, asts.params),
+            registry: (asts.registry, Vec::new()),
+            libraries: Vec::new(),
+            options: codegen::Options::default(),
+        }
+    }
+
+    pub fn files(&self) -> &F {
+        &self.files
+    }
+
+    pub fn files_mut(&mut self) -> &mut F {
+        &mut self.files
+    }
+
+    /// declares a library like `#io` by the source its binary registers
+    ///
+    /// a library declared again replaces the previous declarations
+    pub fn library(&mut self, name: &str, declarations: &str) {
+        let name = match name.starts_with('#') {
+            true => name.to_string(),
+            false => format!("#{name}"),
+        };
+        match self.libraries.iter_mut().find(|lib| lib.0 == name) {
+            Some(lib) => lib.1 = declarations.to_string(),
+            None => self.libraries.push((name, declarations.to_string())),
+        }
+    }
+
+    /// parses the program starting at `main` without type checking it
+    pub fn parse(&mut self, main: &str) -> Result<Dictionaries, Vec<Diagnostic>> {
+        match build_import_graph_from(main, &mut self.ast, &self.files) {
+            Ok((dictionaries, _)) => Ok(dictionaries),
+            Err((err, file)) => Err(err.diagnostics(&file)),
+        }
+    }
+
+    /// loads and type checks the program starting at `main`
+    pub fn check(&mut self, main: &str) -> Result<Context, Vec<Diagnostic>> {
+        let (dictionaries, graph) = match build_import_graph_from(main, &mut self.ast, &self.files) {
+            Ok(loaded) => loaded,
+            Err((err, file)) => return Err(err.diagnostics(&file)),
+        };
+        let mut binaries = HashMap::new();
+        let mut count = STD_LIBS.len();
+        for (name, declarations) in &self.libraries {
+            let mut lib = match libloader::load(declarations.as_bytes(), &mut self.registry, &name[1..]) {
+                Ok(lib) => lib,
+                Err(err) => return Err(vec![Diagnostic::error("E0402", err).in_file(name)]),
+            };
+            lib.id = match STD_LIBS.iter().position(|std| std == name) {
+                Some(id) => id,
+                None => {
+                    count += 1;
+                    count - 1
+                }
+            };
+            binaries.insert(name.clone(), lib);
+        }
+        if let Err(err) = graph.check_libraries(|lib| binaries.contains_key(lib)) {
+            return Err(vec![Diagnostic::from(&err)]);
+        }
+        let mut context = Context::new(dictionaries, binaries);
+        if prep_objects::prep(&mut context).is_err() {
+            return Err(vec![Diagnostic::error("E0601", "could not initialize the constants")]);
+        }
+        let errors = typecheck::check(&context);
+        if !errors.is_empty() {
+            return Err(errors
+                .iter()
+                .map(|(err, file)| Diagnostic::from(err).in_file(file))
+                .collect());
+        }
+        Ok(context)
+    }
+
+    /// compiles the program starting at `main` into an executable the VM can run
+    pub fn compile(&mut self, main: &str) -> Result<stringify::Data, Vec<Diagnostic>> {
+        let mut context = self.check(main)?;
+        let vm = match codegen::gen(&mut context, main, &self.options) {
+            Ok(vm) => vm,
+            Err(err) => return Err(vec![Diagnostic::from(&err)]),
+        };
+        Ok(stringify::Data {
+            instructions: vm.code.data,
+            values: vm.memory.stack.data,
+            strings: vm.memory.strings.pool,
+            non_primitives: vm.memory.non_primitives,
+            fun_table: vm.memory.fun_table,
+            shared_libs: Vec::new(),
+            heap: vm.memory.heap.data,
+            entry_point: vm.code.entry_point,
+            debug: vm.debug,
+        })
+    }
+}
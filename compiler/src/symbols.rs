@@ -0,0 +1,425 @@
+//! Named items of modules and binary libraries in a shape editor tooling can use
+//!
+//! Every item is turned into a `Symbol` with a readable signature and its docs,
+//! identifiers of a source are listed with their positions so that names can be
+//! found without running the whole compiler.
+
+use crate::intermediate::dictionary::{Arg, ConstValue, Dictionary, Function, GenericDecl};
+use crate::lexer::tokenizer::Tokens;
+use crate::lexing_preprocessor::lexing_preprocessor::refactor;
+use crate::libloader;
+use crate::tree_walker::tree_walker::Line;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Struct,
+    Field,
+    Enum,
+    Variant,
+    Trait,
+    Type,
+    Constant,
+    Error,
+    UserData,
+    /// imported module or library
+    Module,
+}
+
+impl std::fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SymbolKind::Function => write!(f, "function"),
+            SymbolKind::Method => write!(f, "method"),
+            SymbolKind::Struct => write!(f, "struct"),
+            SymbolKind::Field => write!(f, "field"),
+            SymbolKind::Enum => write!(f, "enum"),
+            SymbolKind::Variant => write!(f, "variant"),
+            SymbolKind::Trait => write!(f, "trait"),
+            SymbolKind::Type => write!(f, "type"),
+            SymbolKind::Constant => write!(f, "constant"),
+            SymbolKind::Error => write!(f, "error"),
+            SymbolKind::UserData => write!(f, "userdata"),
+            SymbolKind::Module => write!(f, "module"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// signature of the item, `fun add(a: int, b: int): int`
+    pub detail: String,
+    pub docs: Option<String>,
+    /// module or library the item is defined in
+    pub file: String,
+    /// None for items of binary libraries
+    pub line: Option<Line>,
+    pub public: bool,
+    /// fields, variants and methods
+    pub children: Vec<Symbol>,
+    /// traits the item implements
+    pub implements: Vec<String>,
+}
+
+impl Symbol {
+    fn new(name: &str, kind: SymbolKind, detail: String, file: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            detail,
+            docs: None,
+            file: file.to_string(),
+            line: None,
+            public: true,
+            children: Vec::new(),
+            implements: Vec::new(),
+        }
+    }
+
+    fn at(mut self, line: Line) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    fn with_docs(mut self, docs: &Option<String>) -> Self {
+        self.docs = docs.as_deref().map(clean_docs);
+        self
+    }
+
+    fn with_public(mut self, public: bool) -> Self {
+        self.public = public;
+        self
+    }
+
+    fn with_children(mut self, children: Vec<Symbol>) -> Self {
+        self.children = children;
+        self
+    }
+
+    fn with_implements<'a>(mut self, traits: impl Iterator<Item = &'a Vec<String>>) -> Self {
+        for traitt in traits {
+            let name = traitt.join(".");
+            if !self.implements.contains(&name) {
+                self.implements.push(name);
+            }
+        }
+        self
+    }
+}
+
+/// items of a module in the order they are stored in the dictionary
+pub fn module_symbols(file: &str, dictionary: &Dictionary) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    for import in &dictionary.imports {
+        let detail = format!("import \"{}\"", import.path);
+        symbols.push(Symbol::new(&import.alias, SymbolKind::Module, detail, file).at(import.line));
+    }
+    for fun in &dictionary.functions {
+        symbols.push(function(fun, SymbolKind::Function, file));
+    }
+    for structt in &dictionary.structs {
+        let mut children = Vec::new();
+        for (name, kind) in &structt.fields {
+            let detail = format!("{name}: {kind:?}");
+            children.push(Symbol::new(name, SymbolKind::Field, detail, file).at(kind.line));
+        }
+        for (i, fun) in structt.functions.iter().enumerate() {
+            let mut method = function(fun, SymbolKind::Method, file);
+            // constructors are named after the struct but declared with `new`
+            if structt.constructor == Some(i) {
+                method.name = String::from("new");
+                method.detail = format!("new({})", args(&fun.args));
+            }
+            children.push(method);
+        }
+        for implementation in &structt.impls {
+            for fun in &implementation.functions {
+                children.push(function(fun, SymbolKind::Method, file));
+            }
+        }
+        let detail = format!("struct {}{}", structt.identifier, generics(&structt.generics));
+        symbols.push(
+            Symbol::new(&structt.identifier, SymbolKind::Struct, detail, file)
+                .at(structt.line)
+                .with_docs(&structt.docs)
+                .with_public(structt.public)
+                .with_children(children)
+                .with_implements(
+                    structt
+                        .traits
+                        .iter()
+                        .chain(structt.impls.iter().map(|implementation| &implementation.target)),
+                ),
+        );
+    }
+    for enumm in &dictionary.enums {
+        let mut children = Vec::new();
+        for (i, (name, value, line)) in enumm.keys.iter().enumerate() {
+            let detail = match enumm.fields.get(i) {
+                Some(fields) if !fields.is_empty() => format!("{name}({})", args(fields)),
+                _ => format!("{name} = {value}"),
+            };
+            children.push(Symbol::new(name, SymbolKind::Variant, detail, file).at(*line));
+        }
+        for fun in &enumm.methods {
+            children.push(function(fun, SymbolKind::Method, file));
+        }
+        let detail = format!("enum {}", enumm.identifier);
+        symbols.push(
+            Symbol::new(&enumm.identifier, SymbolKind::Enum, detail, file)
+                .at(enumm.line)
+                .with_docs(&enumm.docs)
+                .with_public(enumm.public)
+                .with_children(children),
+        );
+    }
+    for traitt in &dictionary.traits {
+        let children = traitt
+            .methods
+            .iter()
+            .map(|fun| function(fun, SymbolKind::Method, file))
+            .collect();
+        let detail = format!("trait {}", traitt.identifier);
+        symbols.push(
+            Symbol::new(&traitt.identifier, SymbolKind::Trait, detail, file)
+                .at(traitt.line)
+                .with_docs(&traitt.docs)
+                .with_public(traitt.public)
+                .with_children(children),
+        );
+    }
+    for typee in &dictionary.types {
+        let detail = format!(
+            "type {}{} = {:?}",
+            typee.identifier,
+            generics(&typee.generics),
+            typee.kind
+        );
+        symbols.push(
+            Symbol::new(&typee.identifier, SymbolKind::Type, detail, file)
+                .at(typee.line)
+                .with_docs(&typee.docs)
+                .with_public(typee.public),
+        );
+    }
+    for constant in &dictionary.constants {
+        let detail = match constant.real_value.as_ref().and_then(const_value) {
+            Some(value) => format!("const {} = {value}", constant.identifier),
+            None => format!("const {}", constant.identifier),
+        };
+        symbols.push(
+            Symbol::new(&constant.identifier, SymbolKind::Constant, detail, file)
+                .at(constant.line)
+                .with_docs(&constant.docs)
+                .with_public(constant.public),
+        );
+    }
+    for error in &dictionary.errors {
+        let detail = format!("error {}({})", error.identifier, args(&error.args));
+        symbols.push(
+            Symbol::new(&error.identifier, SymbolKind::Error, detail, file)
+                .at(error.line)
+                .with_docs(&error.docs),
+        );
+    }
+    symbols
+}
+
+/// items of a binary library, `library` is the name it is imported by (`#io`)
+pub fn library_symbols(library: &str, dictionary: &libloader::Dictionary) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    for fun in &dictionary.functions {
+        symbols.push(bin_function(fun, SymbolKind::Function, library));
+    }
+    for structt in &dictionary.structs {
+        let mut children = Vec::new();
+        for (name, kind) in &structt.fields {
+            let detail = format!("{name}: {kind:?}");
+            children.push(Symbol::new(name, SymbolKind::Field, detail, library));
+        }
+        for fun in &structt.methods {
+            children.push(bin_function(fun, SymbolKind::Method, library));
+        }
+        let detail = format!("struct {}{}", structt.name, generics(&structt.generics));
+        symbols.push(
+            Symbol::new(&structt.name, SymbolKind::Struct, detail, library)
+                .with_docs(&structt.docs)
+                .with_children(children)
+                .with_implements(structt.traits.iter().map(|implementation| &implementation.target)),
+        );
+    }
+    for userdata in &dictionary.user_data {
+        let children = userdata
+            .methods
+            .iter()
+            .map(|fun| bin_function(fun, SymbolKind::Method, library))
+            .collect();
+        let detail = format!("userdata {}{}", userdata.name, generics(&userdata.generics));
+        symbols.push(
+            Symbol::new(&userdata.name, SymbolKind::UserData, detail, library)
+                .with_docs(&userdata.docs)
+                .with_children(children)
+                .with_implements(userdata.impls.iter().map(|implementation| &implementation.target)),
+        );
+    }
+    for enumm in &dictionary.enums {
+        let children = enumm
+            .variants
+            .iter()
+            .map(|(name, value)| {
+                Symbol::new(name, SymbolKind::Variant, format!("{name} = {value}"), library)
+            })
+            .collect();
+        let detail = format!("enum {}", enumm.name);
+        symbols.push(
+            Symbol::new(&enumm.name, SymbolKind::Enum, detail, library)
+                .with_docs(&enumm.docs)
+                .with_children(children),
+        );
+    }
+    for traitt in &dictionary.traits {
+        let children = traitt
+            .functions
+            .iter()
+            .map(|fun| bin_function(fun, SymbolKind::Method, library))
+            .collect();
+        let detail = format!("trait {}", traitt.name);
+        symbols.push(
+            Symbol::new(&traitt.name, SymbolKind::Trait, detail, library)
+                .with_docs(&traitt.docs)
+                .with_children(children),
+        );
+    }
+    for typee in &dictionary.types {
+        let detail = format!("type {} = {:?}", typee.name, typee.kind);
+        symbols.push(
+            Symbol::new(&typee.name, SymbolKind::Type, detail, library).with_docs(&typee.docs),
+        );
+    }
+    for constant in &dictionary.consts {
+        let value = match &constant.value {
+            libloader::ConstValue::Number(n, _) => n.to_string(),
+            libloader::ConstValue::Text(text) => format!("{text:?}"),
+            libloader::ConstValue::Bool(b) => b.to_string(),
+        };
+        let detail = format!("const {} = {value}", constant.name);
+        symbols.push(
+            Symbol::new(&constant.name, SymbolKind::Constant, detail, library)
+                .with_docs(&constant.docs),
+        );
+    }
+    symbols
+}
+
+/// identifiers of the source with their positions, keywords included
+///
+/// returns None if the source can not be tokenized
+pub fn identifiers(source: &str) -> Option<Vec<(String, Line)>> {
+    let (tokens, lines, mut errors) = crate::tokenize(source, false);
+    if !errors.is_empty() {
+        return None;
+    }
+    // strings and comments are merged into single tokens
+    let (tokens, lines) = refactor(tokens, lines, &mut errors).ok()?;
+    let mut idents = Vec::new();
+    for (token, line) in tokens.iter().zip(lines.iter()) {
+        if let Tokens::Text(text) = token {
+            let starts_like_ident = text
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphabetic() || c == '_');
+            if starts_like_ident {
+                idents.push((text.clone(), *line));
+            }
+        }
+    }
+    Some(idents)
+}
+
+fn function(fun: &Function, kind: SymbolKind, file: &str) -> Symbol {
+    let name = fun.identifier.clone().unwrap_or_default();
+    let mut params = Vec::new();
+    if fun.takes_self {
+        params.push("self".to_string());
+    }
+    if !fun.args.is_empty() {
+        params.push(args(&fun.args));
+    }
+    let mut detail = format!("fun {name}{}({})", generics(&fun.generics), params.join(", "));
+    if fun.can_yeet {
+        detail.push('!');
+    }
+    if let Some(kind) = &fun.return_type {
+        detail.push_str(&format!(": {kind:?}"));
+    }
+    Symbol::new(&name, kind, detail, file)
+        .at(fun.line)
+        .with_docs(&fun.docs)
+        .with_public(fun.public)
+}
+
+fn bin_function(fun: &libloader::Function, kind: SymbolKind, library: &str) -> Symbol {
+    let mut params = Vec::new();
+    if fun.takes_self {
+        params.push("self".to_string());
+    }
+    for (name, kind, _, _, _) in &fun.args {
+        params.push(format!("{name}: {kind:?}"));
+    }
+    let mut detail = format!(
+        "fun {}{}({})",
+        fun.name,
+        generics(&fun.generics),
+        params.join(", ")
+    );
+    if fun.errorable {
+        detail.push('!');
+    }
+    if !fun.return_type.is_void() {
+        detail.push_str(&format!(": {:?}", fun.return_type));
+    }
+    Symbol::new(&fun.name, kind, detail, library).with_docs(&fun.docs)
+}
+
+fn args(args: &[Arg]) -> String {
+    args.iter()
+        .map(|arg| format!("{}: {:?}", arg.identifier, arg.kind))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn generics(generics: &[GenericDecl]) -> String {
+    if generics.is_empty() {
+        return String::new();
+    }
+    let names = generics
+        .iter()
+        .map(|generic| generic.identifier.clone())
+        .collect::<Vec<_>>();
+    format!("<{}>", names.join(", "))
+}
+
+/// short literal of a constant, None for values that do not read well
+fn const_value(value: &ConstValue) -> Option<String> {
+    match value {
+        ConstValue::Number(n) | ConstValue::Float(n) => Some(n.to_string()),
+        ConstValue::Int(n) => Some(n.to_string()),
+        ConstValue::Uint(n) => Some(n.to_string()),
+        ConstValue::Char(c) => Some(format!("{c:?}")),
+        ConstValue::Bool(b) => Some(b.to_string()),
+        ConstValue::String(s) => Some(format!("{s:?}")),
+        ConstValue::Null => Some("null".to_string()),
+        _ => None,
+    }
+}
+
+/// doc comments keep the space after `///`, it is removed from every line
+fn clean_docs(docs: &str) -> String {
+    docs.lines()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -1,13 +1,69 @@
 pub mod tree_walker {
     use std::collections::HashMap;
 
+    use serde::{Deserialize, Serialize};
+
     use crate::ast_parser::ast_parser::{self, *};
     use crate::lexer::tokenizer::{self, *};
+    /// most syntax errors reported for one file
+    const MAX_ERRORS: usize = 20;
+    /// iterations of the parser allowed for each token,
+    /// backtracking over broken code could otherwise take forever
+    const STEPS_PER_TOKEN: usize = 400;
+
+    /// parses the tokens, on a syntax error the broken statement
+    /// (or the whole declaration if that does not help) is left out
+    /// and the rest is parsed again to find the following errors
     pub fn generate_tree(
         tokens: &Vec<Tokens>,
         syntax: &mut (Tree, Vec<HeadParam>),
-        lines: &Vec<(usize, usize)>,
-    ) -> Result<(Node, HashMap<String, ArgNodeType>), (Err, Line)> {
+        lines: &Vec<Line>,
+    ) -> Result<(Node, HashMap<String, ArgNodeType>), Vec<(Err, Line)>> {
+        let mut tokens = tokens.clone();
+        let mut lines = lines.clone();
+        let mut errors: Vec<(Err, Line)> = Vec::new();
+        loop {
+            let mut state = ParseState::new(tokens.len());
+            let (err, idx) = match parse(&tokens, syntax, &lines, &mut state) {
+                Ok(tree) if errors.is_empty() => return Ok(tree),
+                Ok(_) => return Err(errors),
+                Err(err) => err,
+            };
+            let (err, at) = state.failure(err, idx, &tokens);
+            let err = match unclosed(&err, &tokens, at) {
+                Some(open) => Err::Unclosed(Box::new(err), tokens[open].clone(), lines[open]),
+                None => err,
+            };
+            let line = after_previous(&tokens, &lines, at);
+            // the parser failed before the last error again, leaving out the statement did not help
+            let repeated = match errors.last() {
+                Some((_, last)) => line.start <= last.start,
+                None => false,
+            };
+            if !repeated {
+                errors.push((err, line));
+            }
+            if errors.len() >= MAX_ERRORS {
+                return Err(errors);
+            }
+            let skip = if repeated {
+                declaration(&tokens, &lines, at)
+            } else {
+                statement(&tokens, &lines, at)
+            };
+            if skip.is_empty() {
+                return Err(errors);
+            }
+            tokens.drain(skip.clone());
+            lines.drain(skip);
+        }
+    }
+    fn parse(
+        tokens: &Vec<Tokens>,
+        syntax: &mut (Tree, Vec<HeadParam>),
+        lines: &Vec<Line>,
+        state: &mut ParseState,
+    ) -> Result<(Node, HashMap<String, ArgNodeType>), (Err, usize)> {
         let mut idx = 0;
         let mut globals_data = HashMap::new();
         for global in &syntax.1 {
@@ -36,13 +92,201 @@ pub mod tree_walker {
             &String::from("entry"),
             &mut globals_data,
             lines,
+            state,
         );
         match product {
             Ok(product) => Ok((product, globals_data)),
-            Err(err) => {
-                // println!("{err:?}\nOriginated at line: {}, column: {}", lines[idx].1 + 1, lines[idx].0 + 1);
-                Err((err.0, Line::from(lines[idx])))
+            Err(err) => Err((err.0, idx.min(tokens.len() - 1))),
+        }
+    }
+    /// where the error is reported, failing on the first token of a line inside of a block
+    /// means something is missing at the end of the line before it,
+    /// between declarations the token itself is wrong
+    fn after_previous(tokens: &Vec<Tokens>, lines: &Vec<Line>, at: usize) -> Line {
+        if at == 0 || lines[at - 1].line == lines[at].line {
+            return lines[at];
+        }
+        let depth = tokens[..at].iter().fold(0isize, |depth, tok| match tok {
+            Tokens::CurlyBracket(false) => depth + 1,
+            Tokens::CurlyBracket(true) => depth - 1,
+            _ => depth,
+        });
+        if depth <= 0 {
+            return lines[at];
+        }
+        let prev = lines[at - 1];
+        Line {
+            line: prev.line,
+            column: prev.column + (prev.end - prev.start),
+            start: prev.end,
+            end: prev.end,
+        }
+    }
+    /// bracket that a missing `)` or `]` closes, only if it is not closed later in the block either
+    fn unclosed(err: &Err, tokens: &Vec<Tokens>, at: usize) -> Option<usize> {
+        let expected = match err {
+            Err::Expected(expected, _) => std::slice::from_ref(expected),
+            Err::ExpectedOneOf(expected, _) => expected.as_slice(),
+            _ => return None,
+        };
+        let (open, close) = expected.iter().find_map(|tok| match tok {
+            Tokens::Parenteses(true) => Some((Tokens::Parenteses(false), Tokens::Parenteses(true))),
+            Tokens::SquareBracket(true) => {
+                Some((Tokens::SquareBracket(false), Tokens::SquareBracket(true)))
+            }
+            _ => None,
+        })?;
+        let mut depth = 0;
+        let mut blocks = 0;
+        for tok in &tokens[at..] {
+            match tok {
+                Tokens::CurlyBracket(false) => blocks += 1,
+                Tokens::CurlyBracket(true) if blocks == 0 => break,
+                Tokens::CurlyBracket(true) => blocks -= 1,
+                _ if *tok == close && depth == 0 => return None,
+                _ if *tok == close => depth -= 1,
+                _ if *tok == open => depth += 1,
+                _ => {}
+            }
+        }
+        let mut depth = 0;
+        for idx in (0..at).rev() {
+            if tokens[idx] == close {
+                depth += 1;
+            } else if tokens[idx] == open {
+                if depth == 0 {
+                    return Some(idx);
+                }
+                depth -= 1;
+            }
+        }
+        None
+    }
+    /// keywords starting a declaration, the parser picks up from them after an error
+    const DECLARATIONS: [&str; 11] = [
+        "fun", "const", "struct", "type", "import", "trait", "enum", "error", "use", "pub", "impl",
+    ];
+    /// whether a declaration starts at the token, only those at the start of a line count
+    fn is_declaration(tokens: &Vec<Tokens>, lines: &Vec<Line>, idx: usize) -> bool {
+        if lines[idx].column != 1 {
+            return false;
+        }
+        match &tokens[idx] {
+            Tokens::Text(txt) => DECLARATIONS.contains(&txt.as_str()),
+            Tokens::DocComment(_) => true,
+            _ => false,
+        }
+    }
+    /// tokens of the declaration the parser failed in
+    fn declaration(tokens: &Vec<Tokens>, lines: &Vec<Line>, at: usize) -> std::ops::Range<usize> {
+        let last = tokens.len() - 1;
+        let mut start = at.min(last);
+        while start > 0 && !is_declaration(tokens, lines, start) {
+            start -= 1;
+        }
+        let mut end = start + 1;
+        while end < last && !is_declaration(tokens, lines, end) {
+            end += 1;
+        }
+        start..end.min(last)
+    }
+    /// tokens of the line the parser failed on,
+    /// failing on the first token of a line means the line before it was not finished
+    fn statement(tokens: &Vec<Tokens>, lines: &Vec<Line>, at: usize) -> std::ops::Range<usize> {
+        let last = tokens.len() - 1;
+        if at == 0 || at > last {
+            return declaration(tokens, lines, at);
+        }
+        let line = if at == last || lines[at - 1].line != lines[at].line {
+            lines[at - 1].line
+        } else {
+            lines[at].line
+        };
+        let mut start = at.min(last - 1);
+        while start > 0 && lines[start - 1].line == line {
+            start -= 1;
+        }
+        while start < last && lines[start].line != line {
+            start += 1;
+        }
+        let mut end = start;
+        while end < last && lines[end].line == line {
+            end += 1;
+        }
+        let mut depth = 0isize;
+        for idx in start..end {
+            if is_declaration(tokens, lines, idx) {
+                return declaration(tokens, lines, at);
             }
+            match tokens[idx] {
+                Tokens::CurlyBracket(true) => depth += 1,
+                Tokens::CurlyBracket(false) => depth -= 1,
+                _ => {}
+            }
+        }
+        // leaving out a brace would break the blocks around the statement
+        if depth != 0 {
+            return declaration(tokens, lines, at);
+        }
+        start..end
+    }
+    /// step budget and the furthest place the rules failed at in one run of the parser
+    pub struct ParseState {
+        steps: usize,
+        furthest: usize,
+        /// tokens the rules expected at the furthest place
+        expected: Vec<Tokens>,
+        /// error of a rule at the furthest place that does not name tokens
+        other: Option<Err>,
+    }
+    impl ParseState {
+        fn new(tokens: usize) -> Self {
+            Self {
+                steps: tokens * STEPS_PER_TOKEN,
+                furthest: 0,
+                expected: Vec::new(),
+                other: None,
+            }
+        }
+        fn fail(&mut self, idx: usize, err: &Err) {
+            if idx < self.furthest {
+                return;
+            }
+            if idx > self.furthest {
+                self.furthest = idx;
+                self.expected.clear();
+                self.other = None;
+            }
+            let expected = match err {
+                Err::Expected(expected, _) | Err::WrongEndingToken(expected, _) => {
+                    vec![expected.clone()]
+                }
+                Err::ExpectedOneOf(expected, _) => expected.clone(),
+                Err::Msg(_) | Err::Pass(_) => {
+                    self.other = Some(err.clone());
+                    return;
+                }
+                _ => return,
+            };
+            for tok in expected {
+                if !self.expected.contains(&tok) {
+                    self.expected.push(tok);
+                }
+            }
+        }
+        /// the error to report and the token it points at
+        fn failure(&self, err: Err, idx: usize, tokens: &Vec<Tokens>) -> (Err, usize) {
+            let at = self.furthest.min(tokens.len() - 1);
+            if !self.expected.is_empty() {
+                return (
+                    Err::ExpectedOneOf(self.expected.clone(), tokens[at].clone()),
+                    at,
+                );
+            }
+            if let Some(other) = &self.other {
+                return (other.clone(), at);
+            }
+            (err, idx)
         }
     }
     fn prep_nodes(syntax: &Tree, id: &String) -> Option<HashMap<String, ArgNodeType>> {
@@ -81,13 +325,15 @@ pub mod tree_walker {
         idx: &mut usize,
         id: &String,
         globals: &mut HashMap<String, ArgNodeType>,
-        lines: &Vec<(usize, usize)>,
+        lines: &Vec<Line>,
+        state: &mut ParseState,
     ) -> Result<Node, (Err, bool)> {
+        let start = *idx;
         let mut result = Node {
             name: Tokens::Text(id.into()),
             data: None,
-            nodes: prep_nodes(&syntax, &id).expect(&(id.to_string() + &format!(": {:?}", Line::from(lines[*idx])))),
-            line: Line::from(lines[*idx]),
+            nodes: prep_nodes(&syntax, &id).expect(&(id.to_string() + &format!(": {:?}", lines[*idx]))),
+            line: lines[*idx],
         };
         match parse_scope(
             &tokens,
@@ -99,8 +345,12 @@ pub mod tree_walker {
             &mut false,
             globals,
             lines,
+            state,
         ) {
             Ok(_) => {
+                if *idx > start {
+                    result.line = result.line.to(&lines[*idx - 1]);
+                }
                 return Ok(result);
             }
             Err(err) => {
@@ -118,7 +368,8 @@ pub mod tree_walker {
         data: &mut HashMap<String, super::tree_walker::ArgNodeType>,
         harderr: &mut bool,
         globals: &mut HashMap<String, ArgNodeType>,
-        lines: &Vec<(usize, usize)>,
+        lines: &Vec<Line>,
+        state: &mut ParseState,
     ) -> Result<Option<(usize, ReturnActions)>, (Err, bool)> {
         let mut node_idx = 0;
         let mut advance_tok;
@@ -218,7 +469,7 @@ pub mod tree_walker {
                                 }
                             }
                         };
-                        set($token, $node, &mut place, fix_line(&lines[*idx]));
+                        set($token, $node, &mut place, lines[*idx]);
                     }
                 }
                 if let Some(ident) = $args.get("data") {
@@ -257,6 +508,7 @@ pub mod tree_walker {
                     harderr,
                     globals,
                     lines,
+                    state,
                 ) {
                     Ok(back) => match back {
                         Some(back) => match back.1 {
@@ -290,6 +542,7 @@ pub mod tree_walker {
                     &$ident.into(),
                     globals,
                     lines,
+                    state,
                 ) {
                     Ok(nd) => {
                         ScopeEnter!(&$node, true);
@@ -317,23 +570,16 @@ pub mod tree_walker {
         }
         macro_rules! Error {
             ($error: expr, $reset: expr) => {
-                return Err(($error, *harderr));
+                let error = $error;
+                state.fail(*idx, &error);
+                return Err((error, *harderr));
             };
             ($node: expr) => {
                 if node_idx == 0 {
                     Error!(Err::Expected($node.clone(), tokens[*idx].clone()), true);
                 }
                 Error!(
-                    Err::ExpectedOneOf(
-                        extract_tokens_range(
-                            &nodes,
-                            (
-                                maybes_start(&nodes, node_idx - 1),
-                                maybes_end(&nodes, node_idx)
-                            )
-                        ),
-                        tokens[*idx].clone()
-                    ),
+                    Err::ExpectedOneOf(expected_at(&nodes, node_idx), tokens[*idx].clone()),
                     true
                 );
             };
@@ -350,6 +596,10 @@ pub mod tree_walker {
         };
         advance_tok = false;
         while node_idx < nodes.len() {
+            if state.steps == 0 {
+                return Err((Err::Msg(String::from("the parser gave up")), true));
+            }
+            state.steps -= 1;
             advance_node = true;
             advance_tok = true;
             if *idx >= tokens.len() {
@@ -495,7 +745,7 @@ pub mod tree_walker {
         token_found: TokenOrNode,
         token_expected: &Tokens,
         place: &mut ArgNodeType,
-        line: (usize, usize),
+        line: Line,
     ) {
         match place {
             ArgNodeType::Array(arr) => match token_found {
@@ -517,20 +767,20 @@ pub mod tree_walker {
     fn construct_token(
         token_found: &Tokens,
         token_expected: &Tokens,
-        line: (usize, usize),
+        line: Line,
     ) -> Node {
         match token_expected {
             Tokens::String(_) => Node {
                 name: token_found.clone(),
                 data: Some(token_expected.clone()),
                 nodes: HashMap::new(),
-                line: Line::from(line),
+                line,
             },
             _ => Node {
                 name: token_found.clone(),
                 data: None,
                 nodes: HashMap::new(),
-                line: Line::from(line),
+                line,
             },
         }
     }
@@ -587,6 +837,24 @@ pub mod tree_walker {
                     }
                     return CompareResult::NotEq;
                 }
+                "'interpolation_start" => {
+                    if let Tokens::InterpolationStart = source_token {
+                        return CompareResult::Eq;
+                    }
+                    return CompareResult::NotEq;
+                }
+                "'interpolation_end" => {
+                    if let Tokens::InterpolationEnd = source_token {
+                        return CompareResult::Eq;
+                    }
+                    return CompareResult::NotEq;
+                }
+                "'format" => {
+                    if let Tokens::FormatSpec(_) = source_token {
+                        return CompareResult::Eq;
+                    }
+                    return CompareResult::NotEq;
+                }
                 "'any" => CompareResult::Eq,
                 "'doc" => {
                     if let Tokens::DocComment(_) = source_token {
@@ -638,6 +906,16 @@ pub mod tree_walker {
             _ => idx + 1,
         }
     }
+    /// tokens accepted in place of the node, the node itself and the maybes right in front of it
+    fn expected_at(syntax: &Vec<ast_parser::NodeType>, idx: usize) -> Vec<Tokens> {
+        let mut start = idx;
+        while start > 0 && matches!(syntax[start - 1], NodeType::Maybe(_)) {
+            start -= 1;
+        }
+        let mut result = extract_tokens_range(syntax, (start, idx + 1));
+        result.retain(|tok| !matches!(tok, Tokens::String(txt) if txt == "'any"));
+        result
+    }
     fn extract_tokens_range(
         syntax: &Vec<ast_parser::NodeType>,
         range: (usize, usize),
@@ -680,15 +958,17 @@ pub mod tree_walker {
         /// expected found
         WrongEndingToken(Tokens, Tokens),
         EmptyNodeParameter(String),
+        /// error at a missing closing bracket and the bracket it closes
+        Unclosed(Box<Err>, Tokens, Line),
     }
     impl std::fmt::Display for Err {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             match self {
                 Err::Expected(expected, found) => {
-                    write!(f, "Expected {:?}, found {:?}", expected, found)
+                    write!(f, "expected {}, found {}", describe(expected), describe_found(found))
                 }
                 Err::ExpectedOneOf(expected, found) => {
-                    write!(f, "Expected one of {:?}, found {:?}", expected, found)
+                    write!(f, "expected {}, found {}", describe_all(expected), describe_found(found))
                 }
                 Err::Msg(msg) => {
                     write!(f, "{}", msg)
@@ -703,19 +983,100 @@ pub mod tree_walker {
                     write!(f, "{}", msg)
                 }
                 Err::WrongEndingToken(expected, found) => {
-                    write!(f, "Expected {:?}, found {:?}", expected, found)
+                    write!(f, "expected {}, found {}", describe(expected), describe_found(found))
                 }
                 Err::EmptyNodeParameter(ident) => {
                     write!(f, "Empty node parameter: {}", ident)
                 }
+                Err::Unclosed(err, open, line) => {
+                    write!(f, "{err}, `{open}` at {line} is not closed")
+                }
             }
         }
     }
 
-    #[derive(Clone, Copy, PartialEq, Eq)]
+    /// how a token of the source or of a rule is called in errors
+    fn describe(tok: &Tokens) -> String {
+        match tok {
+            Tokens::String(txt) => match txt.as_str() {
+                "'text" => String::from("identifier"),
+                "'string" | "'interpolation_start" => String::from("string"),
+                "'number" => String::from("number"),
+                "'int" => String::from("integer"),
+                "'float" => String::from("float"),
+                "'char" => String::from("char"),
+                "'eof" => String::from("end of file"),
+                "'doc" => String::from("doc comment"),
+                "'format" => String::from("format specifier"),
+                "'any" => String::from("anything"),
+                _ => format!("`{txt}`"),
+            },
+            // name of a rule
+            Tokens::Text(txt) if txt.starts_with("KW") => {
+                format!("`{}`", txt[2..].to_lowercase())
+            }
+            Tokens::Text(txt) => txt.replace('_', " "),
+            Tokens::EndOfFile => String::from("end of file"),
+            Tokens::Number(..) => String::from("number"),
+            Tokens::Char(_) => String::from("char"),
+            Tokens::DocComment(_) => String::from("doc comment"),
+            _ => format!("`{tok}`"),
+        }
+    }
+    /// how a token of the source is called in errors
+    fn describe_found(tok: &Tokens) -> String {
+        match tok {
+            Tokens::Text(txt) => format!("`{txt}`"),
+            Tokens::String(_) | Tokens::InterpolationStart => String::from("string"),
+            _ => describe(tok),
+        }
+    }
+    /// `a`, `b` or `c`, operators are named together
+    ///
+    /// rules are only named when no token was expected
+    fn describe_all(toks: &Vec<Tokens>) -> String {
+        let rule = |tok: &&Tokens| matches!(tok, Tokens::Text(_));
+        let toks: Vec<&Tokens> = if toks.iter().all(|tok| rule(&tok)) {
+            toks.iter().collect()
+        } else {
+            toks.iter().filter(|tok| !rule(tok)).collect()
+        };
+        let operators = toks
+            .iter()
+            .filter(|tok| matches!(tok, Tokens::Operator(_)))
+            .count();
+        let mut names: Vec<String> = Vec::new();
+        for tok in toks {
+            let name = match tok {
+                Tokens::Operator(_) if operators > 2 => String::from("an operator"),
+                _ => describe(tok),
+            };
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        match names.split_last() {
+            Some((last, [])) => last.clone(),
+            Some((last, rest)) => format!("{} or {last}", rest.join(", ")),
+            None => String::from("nothing"),
+        }
+    }
+
+    #[derive(Clone, Copy, Eq, Serialize, Deserialize)]
     pub struct Line {
         pub line: usize,
         pub column: usize,
+        /// byte offset of the first character
+        pub start: usize,
+        /// byte offset after the last character
+        pub end: usize,
+    }
+
+    /// spans are left out, the same place reached through a node or a token is equal
+    impl PartialEq for Line {
+        fn eq(&self, other: &Self) -> bool {
+            self.line == other.line && self.column == other.column
+        }
     }
 
     impl std::fmt::Debug for Line {
@@ -736,8 +1097,25 @@ pub mod tree_walker {
             Self {
                 line: line.1 + 1,
                 column: line.0 + 1,
+                start: 0,
+                end: 0,
             }
         }
+        /// byte range the line points to, None if it is not known
+        pub fn span(&self) -> Option<std::ops::Range<usize>> {
+            if self.end > self.start {
+                Some(self.start..self.end)
+            } else {
+                None
+            }
+        }
+        /// stretches the span to the end of `other`
+        pub fn to(mut self, other: &Line) -> Self {
+            if other.end > self.end {
+                self.end = other.end;
+            }
+            self
+        }
     }
     /// structures defined by user
     #[derive(Debug, Clone)]
@@ -768,7 +1146,4 @@ pub mod tree_walker {
             }
         }
     }
-    pub fn fix_line(line: &(usize, usize)) -> (usize, usize) {
-        (line.0 + 1, line.1 + 1)
-    }
 }
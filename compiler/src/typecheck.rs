@@ -0,0 +1,1942 @@
+//! Static type checking of the whole program
+//!
+//! Runs after the dictionaries are built and before codegen.
+//! Unlike codegen, which stops at the first error, the checker
+//! walks every function and collects all the errors it finds.
+//! Values it can not infer (for example most of the binary libraries)
+//! are skipped instead of being reported.
+
+use std::collections::HashMap;
+
+use crate::codeblock_parser::{Nodes, TuplePattern};
+use crate::codegen::{
+    correct_kind, find_enum, find_fun, find_import, find_overload, find_struct, find_trait,
+    find_use, implements_trait, is_number_enum, is_public, module_has, resolve_trait, use_module,
+    CodegenError, FunctionKind, InnerPath,
+};
+use crate::expression_parser::{
+    ArgError, ArmBody, ArrayRule, FunctionCall, InterpolationPart, Literals, Match, Ref, Root, TailNodes, ValueType,
+    Variable,
+};
+use crate::intermediate::dictionary::{
+    self, Arg, Function, GenericDecl, ImportKinds, KindType, TypeComparison,
+};
+use crate::intermediate::{Kind, TypeBody};
+use crate::lexer::tokenizer::Operators;
+use crate::lint::{self, Warning};
+use crate::prep_objects::Context;
+use crate::tree_walker::tree_walker::Line;
+
+/// Checks all functions of the program, returns errors with the file they occured in
+pub fn check(objects: &Context) -> Vec<(TypeError, String)> {
+    run(objects, &modules(objects)).errors
+}
+
+/// same as `check`, only the functions of `files` are checked
+///
+/// the rest of the modules are still used to look up the types,
+/// builds skip the modules that did not change since they were checked
+pub fn check_files(objects: &Context, files: &[String]) -> Vec<(TypeError, String)> {
+    run(objects, files).errors
+}
+
+/// warnings that need the types of values, see `lint::lint`
+pub fn lints(objects: &Context) -> Vec<(Warning, String)> {
+    run(objects, &modules(objects)).warnings
+}
+
+/// kind of the value the last statement of a function evaluates to
+///
+/// None if the statement is not an expression or it has no value,
+/// the REPL uses it to print the values of entered expressions
+pub fn last_value(objects: &Context, file: &str, fun: &Function) -> Option<Kind> {
+    let mut checker = Checker::new(objects);
+    checker.file = file.to_string();
+    checker.uses(objects.0.get(file)?);
+    checker.enter(fun, file, None);
+    let (last, rest) = fun.code.split_last()?;
+    checker.scopes.push(HashMap::new());
+    for node in rest {
+        checker.node(node);
+    }
+    let kind = match last {
+        Nodes::Expr { expr, .. } => checker.expr(expr)?,
+        _ => return None,
+    };
+    match kind.is_null() || kind.body == TypeBody::Void {
+        true => None,
+        false => Some(kind),
+    }
+}
+
+/// names of all of the modules in a stable order
+fn modules(objects: &Context) -> Vec<String> {
+    let mut files = objects.0.keys().cloned().collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+fn run<'a>(objects: &'a Context, files: &[String]) -> Checker<'a> {
+    let mut checker = Checker::new(objects);
+    for file in files {
+        let dict = match objects.0.get(file) {
+            Some(dict) => dict,
+            None => continue,
+        };
+        checker.file = file.clone();
+        checker.uses(dict);
+        for fun in dict.functions.iter() {
+            checker.check_fun(fun, file, None);
+        }
+        for structt in dict.structs.iter() {
+            checker.file = file.clone();
+            checker.impls(structt, file);
+            for fun in structt.functions.iter() {
+                checker.check_fun(fun, file, Some(structt));
+            }
+        }
+    }
+    checker
+}
+
+#[derive(Debug, Clone)]
+pub enum TypeError {
+    /// (expected, got, comparison, line)
+    Mismatch(Kind, Kind, TypeComparison, Line),
+    /// (kind, line) | nullable value assigned to a non nullable target
+    NullableAssignment(Kind, Line),
+    /// (got, line)
+    ExpectedBool(Kind, Line),
+    /// (left, right, operator, line)
+    InvalidOperator(Kind, Kind, Operators, Line),
+    /// (kind, unary, line)
+    UnaryNotApplicable(Kind, Operators, Line),
+    /// (expected, got, line)
+    IncorrectNumberOfArgs(usize, usize, Line),
+    /// (expected, got, line)
+    IncorrectNumberOfGenerics(usize, usize, Line),
+    /// (arg, expected, got, line)
+    ArgMismatch(String, Kind, Kind, Line),
+    /// (generic, first, second, line) | generic inferred as two different types
+    GenericMismatch(String, Kind, Kind, Line),
+    /// (field, kind, line)
+    FieldNotFound(String, Kind, Line),
+    /// (kind, line)
+    CannotIndex(Kind, Line),
+    /// (index kind, line)
+    IndexNotNumber(Kind, Line),
+    /// (first, other, line)
+    ArrayElementMismatch(Kind, Kind, Line),
+    /// (kind, line) | `?` used on a value that can not be null
+    NotNullable(Kind, Line),
+    /// (return type, line)
+    MissingReturnValue(Kind, Line),
+    /// (returned, line)
+    UnexpectedReturnValue(Kind, Line),
+    /// (line)
+    NotInitializedNoType(Line),
+    /// (kind, line)
+    NotInitializedNotNullable(Kind, Line),
+    /// (kind, line)
+    NotIterable(Kind, Line),
+    VariableNotFound(String, Line),
+    /// (trait, line)
+    TraitNotFound(String, Line),
+    /// (method, trait, line)
+    MissingTraitMethod(String, String, Line),
+    /// (method, trait, line) | implemented method has a different signature
+    TraitMethodMismatch(String, String, Line),
+    /// (method, trait, line)
+    NotInTrait(String, String, Line),
+    /// (kind, trait, generic, line) | kind does not implement a trait required by a generic
+    BoundNotSatisfied(Kind, String, String, Line),
+    /// (struct, operator, right, line) | right is None for unary operators
+    OverloadNotFound(Kind, Operators, Option<Kind>, Line),
+    /// (struct, operator, right, line)
+    AmbiguousOverload(Kind, Operators, Option<Kind>, Line),
+    /// (kind, line) | only enums can be matched
+    CannotMatch(Kind, Line),
+    /// (variant, expected, got, line)
+    IncorrectNumberOfBindings(String, usize, usize, Line),
+    /// (item, module, line)
+    NotPublic(String, String, Line),
+    /// (path, line) | path of `use` does not lead to anything
+    UseNotFound(String, Line),
+    /// (kind, line) | only primitive values can be embedded in a string
+    CannotInterpolate(Kind, Line),
+    /// (format, kind, line)
+    FormatNotApplicable(String, Kind, Line),
+    /// (kind, line) | position or pattern used on a value that is not a tuple
+    NotATuple(Kind, Line),
+    /// (tuple, index, line)
+    TupleIndexOutOfBounds(Kind, usize, Line),
+    /// (tuple, names, line) | tuple destructured into a different number of names
+    PatternLength(Kind, usize, Line),
+    /// (reason) | named arguments or default values do not fit the called function
+    InvalidArgs(ArgError),
+}
+
+impl std::fmt::Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeError::Mismatch(expected, got, _, line) => {
+                write!(f, "expected {expected:?}, got {got:?} at {line}")
+            }
+            TypeError::NullableAssignment(kind, line) => {
+                write!(
+                    f,
+                    "nullable value of type {kind:?} assigned to non nullable at {line}"
+                )
+            }
+            TypeError::ExpectedBool(got, line) => {
+                write!(f, "expected bool, got {got:?} at {line}")
+            }
+            TypeError::InvalidOperator(left, right, op, line) => {
+                write!(
+                    f,
+                    "operator `{op}` can not be applied to {left:?} and {right:?} at {line}"
+                )
+            }
+            TypeError::UnaryNotApplicable(kind, op, line) => {
+                write!(f, "unary `{op}` can not be applied to {kind:?} at {line}")
+            }
+            TypeError::IncorrectNumberOfArgs(expected, got, line) => {
+                write!(f, "expected {expected} arguments, got {got} at {line}")
+            }
+            TypeError::IncorrectNumberOfGenerics(expected, got, line) => {
+                write!(
+                    f,
+                    "expected {expected} generic arguments, got {got} at {line}"
+                )
+            }
+            TypeError::ArgMismatch(arg, expected, got, line) => {
+                write!(
+                    f,
+                    "argument {arg} expected {expected:?}, got {got:?} at {line}"
+                )
+            }
+            TypeError::GenericMismatch(generic, first, second, line) => {
+                write!(
+                    f,
+                    "generic {generic} is both {first:?} and {second:?} at {line}"
+                )
+            }
+            TypeError::FieldNotFound(field, kind, line) => {
+                write!(f, "field {field} not found in {kind:?} at {line}")
+            }
+            TypeError::CannotIndex(kind, line) => {
+                write!(f, "can not index into {kind:?} at {line}")
+            }
+            TypeError::IndexNotNumber(kind, line) => {
+                write!(f, "index must be a number, got {kind:?} at {line}")
+            }
+            TypeError::ArrayElementMismatch(first, other, line) => {
+                write!(f, "array of {first:?} can not contain {other:?} at {line}")
+            }
+            TypeError::NotNullable(kind, line) => {
+                write!(
+                    f,
+                    "{kind:?} can not be null, there is nothing to test at {line}"
+                )
+            }
+            TypeError::MissingReturnValue(kind, line) => {
+                write!(f, "function must return {kind:?} at {line}")
+            }
+            TypeError::UnexpectedReturnValue(kind, line) => {
+                write!(
+                    f,
+                    "function does not return a value, got {kind:?} at {line}"
+                )
+            }
+            TypeError::NotInitializedNoType(line) => {
+                write!(f, "variable needs a type or a value at {line}")
+            }
+            TypeError::NotInitializedNotNullable(kind, line) => {
+                write!(f, "variable of type {kind:?} must be initialized at {line}")
+            }
+            TypeError::NotIterable(kind, line) => {
+                write!(f, "can not iterate over {kind:?} at {line}")
+            }
+            TypeError::VariableNotFound(ident, line) => {
+                write!(f, "variable {ident} not found at {line}")
+            }
+            TypeError::TraitNotFound(ident, line) => {
+                write!(f, "trait {ident} not found at {line}")
+            }
+            TypeError::MissingTraitMethod(method, traitt, line) => {
+                write!(
+                    f,
+                    "method {method} of trait {traitt} is not implemented at {line}"
+                )
+            }
+            TypeError::TraitMethodMismatch(method, traitt, line) => {
+                write!(
+                    f,
+                    "method {method} does not match its signature in trait {traitt} at {line}"
+                )
+            }
+            TypeError::NotInTrait(method, traitt, line) => {
+                write!(f, "method {method} is not a member of trait {traitt} at {line}")
+            }
+            TypeError::BoundNotSatisfied(kind, traitt, generic, line) => {
+                write!(
+                    f,
+                    "{kind:?} does not implement trait {traitt} required by generic {generic} at {line}"
+                )
+            }
+            TypeError::OverloadNotFound(kind, op, Some(right), line) => {
+                write!(f, "{kind:?} has no overload of {op:?} for {right:?} at {line}")
+            }
+            TypeError::OverloadNotFound(kind, op, None, line) => {
+                write!(f, "{kind:?} has no overload of unary {op:?} at {line}")
+            }
+            TypeError::AmbiguousOverload(kind, op, Some(right), line) => {
+                write!(
+                    f,
+                    "more than one overload of {op:?} on {kind:?} accepts {right:?} at {line}"
+                )
+            }
+            TypeError::AmbiguousOverload(kind, op, None, line) => {
+                write!(f, "{kind:?} has more than one overload of unary {op:?} at {line}")
+            }
+            TypeError::CannotMatch(kind, line) => {
+                write!(f, "can not match on {kind:?}, only enums can be matched at {line}")
+            }
+            TypeError::IncorrectNumberOfBindings(variant, expected, got, line) => {
+                write!(
+                    f,
+                    "variant {variant} has {expected} fields, pattern binds {got} at {line}"
+                )
+            }
+            TypeError::NotPublic(item, module, line) => {
+                write!(f, "{item} is not public in module {module} at {line}")
+            }
+            TypeError::UseNotFound(path, line) => {
+                write!(f, "nothing to use at {path} at {line}")
+            }
+            TypeError::CannotInterpolate(kind, line) => {
+                write!(f, "value of type {kind:?} can not be embedded in a string at {line}")
+            }
+            TypeError::FormatNotApplicable(format, kind, line) => {
+                write!(f, "format {format} can not be used on {kind:?} at {line}")
+            }
+            TypeError::NotATuple(kind, line) => {
+                write!(f, "expected a tuple, got {kind:?} at {line}")
+            }
+            TypeError::TupleIndexOutOfBounds(kind, idx, line) => {
+                write!(f, "tuple {kind:?} has no element {idx} at {line}")
+            }
+            TypeError::PatternLength(kind, names, line) => {
+                write!(f, "tuple {kind:?} can not be destructured into {names} names at {line}")
+            }
+            TypeError::InvalidArgs(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+/// What a part of a value turned out to be while walking its tail
+#[derive(Debug, Clone)]
+enum Resolved {
+    Value(Kind),
+    /// function and the struct it belongs to
+    Fun(InnerPath),
+    BinFun(Kind),
+    Import(String, ImportKinds),
+    Struct(InnerPath),
+    /// kind of the enum whose variant follows
+    Enum(Kind),
+    /// variant of an enum with data, constructed by calling it with its fields
+    Variant(Kind, Vec<Arg>, String),
+    Unknown,
+}
+
+struct Checker<'a> {
+    objects: &'a Context,
+    errors: Vec<(TypeError, String)>,
+    warnings: Vec<(Warning, String)>,
+    /// lints silenced for the checked function
+    allow: Vec<String>,
+    /// function called by the last tail of a value if its result should not be ignored
+    last_call: Option<String>,
+    /// None for variables of unknown type
+    scopes: Vec<HashMap<String, Option<Kind>>>,
+    file: String,
+    generics: HashMap<String, Kind>,
+    return_type: Option<Kind>,
+    is_constructor: bool,
+}
+
+impl<'a> Checker<'a> {
+    fn new(objects: &'a Context) -> Self {
+        Self {
+            objects,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            allow: Vec::new(),
+            last_call: None,
+            scopes: Vec::new(),
+            file: String::new(),
+            generics: HashMap::new(),
+            return_type: None,
+            is_constructor: false,
+        }
+    }
+
+    fn err(&mut self, err: TypeError) {
+        self.errors.push((err, self.file.clone()));
+    }
+
+    fn warn(&mut self, warning: Warning) {
+        if !lint::allowed(&self.allow, warning.lint()) {
+            self.warnings.push((warning, self.file.clone()));
+        }
+    }
+
+    fn check_fun(&mut self, fun: &Function, file: &str, structt: Option<&dictionary::Struct>) {
+        self.enter(fun, file, structt);
+        self.block(&fun.code);
+    }
+
+    /// prepares the scopes and generics for checking the body of `fun`
+    fn enter(&mut self, fun: &Function, file: &str, structt: Option<&dictionary::Struct>) {
+        self.file = file.to_string();
+        self.generics.clear();
+        let mut decls = fun.generics.clone();
+        if let Some(structt) = structt {
+            decls.extend(structt.generics.clone());
+        }
+        for generic in decls {
+            self.generics.insert(
+                generic.identifier.clone(),
+                Kind {
+                    body: TypeBody::Generic {
+                        identifier: generic.identifier.clone(),
+                        constraints: Vec::new(),
+                        nullable: false,
+                        refs: 0,
+                    },
+                    line: generic.line,
+                    file: Some(file.to_string()),
+                },
+            );
+        }
+        self.scopes = vec![HashMap::new()];
+        self.is_constructor = false;
+        self.allow = fun.allow.clone();
+        if let Some(structt) = structt {
+            self.allow.extend(structt.allow.iter().cloned());
+            let kind =
+                Kind::from_struct(structt.identifier.clone(), file.to_string(), structt.line);
+            if fun.takes_self || fun.identifier.as_ref() == Some(&structt.identifier) {
+                self.scopes[0].insert("self".to_string(), Some(kind));
+            }
+            self.is_constructor = fun.identifier.as_ref() == Some(&structt.identifier);
+        }
+        for arg in fun.args.iter() {
+            let kind = self.resolve(&arg.kind, file, &arg.line);
+            self.scopes[0].insert(arg.identifier.clone(), kind);
+        }
+        self.return_type = match &fun.return_type {
+            Some(kind) => self.resolve(kind, file, &fun.line),
+            None => None,
+        };
+    }
+
+    /// checks that every `impl Trait` of the struct provides the methods of the trait
+    fn impls(&mut self, structt: &dictionary::Struct, file: &str) {
+        for implementation in structt.impls.iter() {
+            let (trait_file, traitt) =
+                match resolve_trait(self.objects, &implementation.target, file) {
+                    Some(traitt) => traitt,
+                    None => {
+                        self.err(TypeError::TraitNotFound(
+                            implementation.target.join("."),
+                            implementation.line,
+                        ));
+                        continue;
+                    }
+                };
+            for fun in implementation.functions.iter() {
+                let ident = fun.identifier.clone().unwrap();
+                match traitt
+                    .methods
+                    .iter()
+                    .find(|method| method.identifier == fun.identifier)
+                {
+                    Some(method) => {
+                        if !self.same_signature(method, trait_file, fun, file) {
+                            self.err(TypeError::TraitMethodMismatch(
+                                ident,
+                                traitt.identifier.clone(),
+                                fun.line,
+                            ));
+                        }
+                    }
+                    None => self.err(TypeError::NotInTrait(
+                        ident,
+                        traitt.identifier.clone(),
+                        fun.line,
+                    )),
+                }
+            }
+            for method in traitt.methods.iter() {
+                let ident = method.identifier.clone().unwrap();
+                if !traitt.defaults.contains(&ident)
+                    && !implementation
+                        .functions
+                        .iter()
+                        .any(|fun| fun.identifier == method.identifier)
+                {
+                    self.err(TypeError::MissingTraitMethod(
+                        ident,
+                        traitt.identifier.clone(),
+                        implementation.line,
+                    ));
+                }
+            }
+        }
+    }
+
+    fn same_signature(&self, method: &Function, trait_file: &str, fun: &Function, file: &str) -> bool {
+        let same = |a: &Kind, b: &Kind| match (
+            self.resolve(a, trait_file, &a.line),
+            self.resolve(b, file, &b.line),
+        ) {
+            (Some(a), Some(b)) => a.cmp(&b, &self.generics).is_equal(),
+            _ => a.cmp(b, &self.generics).is_equal(),
+        };
+        method.takes_self == fun.takes_self
+            && method.args.len() == fun.args.len()
+            && method
+                .args
+                .iter()
+                .zip(fun.args.iter())
+                .all(|(a, b)| same(&a.kind, &b.kind))
+            && match (&method.return_type, &fun.return_type) {
+                (Some(a), Some(b)) => same(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+
+    /// whether both values are structs implementing the same trait
+    fn share_trait(&self, first: &Kind, other: &Kind) -> bool {
+        let (name, file) = match &first.body {
+            TypeBody::Type {
+                main,
+                kind: KindType::Struct,
+                ..
+            } => (main.last().unwrap(), first.file.clone().unwrap_or_default()),
+            _ => return false,
+        };
+        let structt = match find_struct(self.objects, name, &file) {
+            Some((_, structt)) => structt,
+            None => return false,
+        };
+        structt
+            .traits
+            .iter()
+            .filter_map(|ident| resolve_trait(self.objects, ident, &file))
+            .any(|(file, traitt)| {
+                implements_trait(self.objects, other, &Kind::from_trait(traitt, file.to_string()))
+            })
+    }
+
+    /// finds the real type behind a written one
+    fn resolve(&self, kind: &Kind, file: &str, line: &Line) -> Option<Kind> {
+        self.resolve_with(kind, file, line, &self.generics)
+    }
+
+    /// finds the real type behind a written one, generics bound in `generics` are replaced
+    fn resolve_with(
+        &self,
+        kind: &Kind,
+        file: &str,
+        line: &Line,
+        generics: &HashMap<String, Kind>,
+    ) -> Option<Kind> {
+        let path = InnerPath {
+            file: file.to_string(),
+            block: None,
+            ident: String::new(),
+            kind: ImportKinds::Rd,
+        };
+        match &kind.body {
+            TypeBody::Array {
+                type_,
+                size,
+                refs,
+                nullable,
+            } => Some(Kind {
+                body: TypeBody::Array {
+                    type_: Box::new(self.resolve_with(type_, file, line, generics)?),
+                    size: *size,
+                    refs: *refs,
+                    nullable: *nullable,
+                },
+                line: *line,
+                file: Some(file.to_string()),
+            }),
+            TypeBody::Function { .. } => None,
+            _ => correct_kind(self.objects, kind, &path, line, generics).ok(),
+        }
+    }
+
+    /// checks the body of an anonymous function,
+    /// variables of the enclosing function stay visible since they can be captured
+    fn lambda(&mut self, lambda: &Function) {
+        let file = self.file.clone();
+        let return_type = self.return_type.take();
+        let is_constructor = std::mem::replace(&mut self.is_constructor, false);
+        let mut scope = HashMap::new();
+        for arg in lambda.args.iter() {
+            scope.insert(arg.identifier.clone(), self.resolve(&arg.kind, &file, &arg.line));
+        }
+        self.scopes.push(scope);
+        self.return_type = match &lambda.return_type {
+            Some(kind) => self.resolve(kind, &file, &lambda.line),
+            None => None,
+        };
+        self.block(&lambda.code);
+        self.scopes.pop();
+        self.return_type = return_type;
+        self.is_constructor = is_constructor;
+    }
+
+    fn block(&mut self, block: &Vec<Nodes>) {
+        self.scopes.push(HashMap::new());
+        for node in block {
+            self.node(node);
+        }
+        self.scopes.pop();
+    }
+
+    /// declares the names of a tuple pattern
+    fn destructure(&mut self, pattern: &TuplePattern, kind: Option<Kind>, line: &Line) {
+        let types = match kind {
+            Some(Kind {
+                body: TypeBody::Tuple { ref types, .. },
+                ..
+            }) => {
+                if types.len() != pattern.len() {
+                    self.err(TypeError::PatternLength(
+                        kind.clone().unwrap(),
+                        pattern.len(),
+                        *line,
+                    ));
+                }
+                types.clone()
+            }
+            Some(kind) => {
+                if known(&kind) {
+                    self.err(TypeError::NotATuple(kind, *line));
+                }
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+        for (idx, name) in pattern.iter().enumerate() {
+            if let Some(name) = name {
+                self.declare(name, types.get(idx).cloned());
+            }
+        }
+    }
+
+    fn declare(&mut self, ident: &str, kind: Option<Kind>) {
+        let len = self.scopes.len();
+        self.scopes[len - 1].insert(ident.to_string(), kind);
+    }
+
+    fn find_var(&self, ident: &str) -> Option<Option<Kind>> {
+        for scope in self.scopes.iter().rev() {
+            if let Some(kind) = scope.get(ident) {
+                return Some(kind.clone());
+            }
+        }
+        None
+    }
+
+    fn condition(&mut self, cond: &ValueType, line: &Line) {
+        if let Some(kind) = self.expr(cond) {
+            if known(&kind) && !kind.is_bool() {
+                self.err(TypeError::ExpectedBool(kind, *line));
+            }
+        }
+    }
+
+    fn node(&mut self, node: &Nodes) {
+        match node {
+            Nodes::Let {
+                ident,
+                expr,
+                kind,
+                line,
+                pattern,
+            } => {
+                let declared = match kind {
+                    Some(kind) => self.resolve(kind, &self.file.clone(), line),
+                    None => None,
+                };
+                let value = match expr {
+                    Some(expr) => self.expr(expr),
+                    None => None,
+                };
+                match (kind, expr) {
+                    (None, None) => self.err(TypeError::NotInitializedNoType(*line)),
+                    (Some(_), None) => {
+                        if let Some(declared) = &declared {
+                            if !declared.get_nullable() {
+                                self.err(TypeError::NotInitializedNotNullable(
+                                    declared.clone(),
+                                    *line,
+                                ));
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+                if let (Some(declared), Some(value)) = (&declared, &value) {
+                    self.assign(value, declared, line);
+                }
+                let kind = match (kind, value) {
+                    (Some(_), _) => declared,
+                    (None, Some(value)) if !value.is_null() => Some(value),
+                    _ => None,
+                };
+                if let Some(pattern) = pattern {
+                    self.destructure(pattern, kind.clone(), line);
+                }
+                self.declare(ident, kind);
+            }
+            Nodes::If {
+                cond,
+                body,
+                elif,
+                els,
+                line,
+            } => {
+                self.condition(cond, line);
+                self.block(body);
+                for (cond, body, line) in elif {
+                    self.condition(cond, line);
+                    self.block(body);
+                }
+                if let Some((body, _)) = els {
+                    self.block(body);
+                }
+            }
+            Nodes::While {
+                cond, body, line, ..
+            } => {
+                self.condition(cond, line);
+                self.block(body);
+            }
+            Nodes::For {
+                ident,
+                expr,
+                range,
+                body,
+                ident2,
+                line,
+                pattern,
+                ..
+            } => {
+                let kind = self.expr(expr);
+                let element = match (kind, range) {
+                    (Some(kind), Some(range)) => {
+                        for value in std::iter::once(&range.end).chain(range.step.iter()) {
+                            if let Some(value) = self.expr(value) {
+                                self.assign(&value, &kind, &range.line);
+                            }
+                        }
+                        if known(&kind) && !kind.is_number() {
+                            self.err(TypeError::NotIterable(kind, *line));
+                            None
+                        } else {
+                            Some(kind)
+                        }
+                    }
+                    (
+                        Some(Kind {
+                            body: TypeBody::Array { type_, .. },
+                            ..
+                        }),
+                        None,
+                    ) => Some(*type_),
+                    (Some(kind), None) if kind.is_string() => Some(primitive("char", kind.line)),
+                    (Some(kind), None) => {
+                        if known(&kind) {
+                            self.err(TypeError::NotIterable(kind, *line));
+                        }
+                        None
+                    }
+                    (None, _) => None,
+                };
+                self.scopes.push(HashMap::new());
+                if let Some(pattern) = pattern {
+                    self.destructure(pattern, element.clone(), line);
+                }
+                self.declare(ident, element);
+                if let Some(ident2) = ident2 {
+                    self.declare(ident2, Some(primitive("uint", *line)));
+                }
+                self.block(body);
+                self.scopes.pop();
+            }
+            Nodes::Return { expr, line } => {
+                if self.is_constructor {
+                    if let Some(expr) = expr {
+                        self.expr(expr);
+                    }
+                    return;
+                }
+                match (expr, self.return_type.clone()) {
+                    (Some(expr), Some(ret)) => {
+                        if let Some(kind) = self.expr(expr) {
+                            self.assign(&kind, &ret, line);
+                        }
+                    }
+                    (Some(expr), None) => {
+                        if let Some(kind) = self.expr(expr) {
+                            if known(&kind) && !kind.is_null() {
+                                self.err(TypeError::UnexpectedReturnValue(kind, *line));
+                            }
+                        }
+                    }
+                    (None, Some(ret)) => {
+                        if !ret.get_nullable() {
+                            self.err(TypeError::MissingReturnValue(ret, *line));
+                        }
+                    }
+                    (None, None) => (),
+                }
+            }
+            Nodes::Expr { expr, .. } => match expr {
+                ValueType::Value(value) if value.is_true_simple() => match &value.root.0 {
+                    Root::Match(matchh) => {
+                        self.matchh(matchh, true);
+                    }
+                    _ => {
+                        self.expr(expr);
+                    }
+                },
+                ValueType::Value(value)
+                    if value.unary.is_empty()
+                        && matches!(
+                            value.tail.iter().rfind(|(node, _)| !matches!(node, TailNodes::Propagate)),
+                            Some((TailNodes::Call(_), _))
+                        ) =>
+                {
+                    self.last_call = None;
+                    self.expr(expr);
+                    if let Some(name) = self.last_call.take() {
+                        self.warn(Warning::IgnoredResult(name, value.line));
+                    }
+                }
+                _ => {
+                    self.expr(expr);
+                }
+            },
+            Nodes::Block { body, .. } | Nodes::Loop { body, .. } => self.block(body),
+            Nodes::Break { .. } | Nodes::Continue { .. } => (),
+            Nodes::Yeet { expr, .. } => {
+                for (node, _) in &expr.1 {
+                    if let TailNodes::Call(call) = node {
+                        self.args(call);
+                    }
+                }
+            }
+            Nodes::Try {
+                body,
+                catch,
+                finally,
+                ..
+            } => {
+                self.block(body);
+                for catch in catch {
+                    self.scopes.push(HashMap::new());
+                    if let Some(ident) = &catch.ident {
+                        let name = match catch.kinds.as_slice() {
+                            [path] => path.last().unwrap().clone(),
+                            _ => "Error".to_string(),
+                        };
+                        let kind = Kind::from_error(name, self.file.clone(), catch.line);
+                        self.declare(ident, Some(kind));
+                    }
+                    self.block(&catch.body);
+                    self.scopes.pop();
+                }
+                if let Some(finally) = finally {
+                    self.block(finally);
+                }
+            }
+            Nodes::Switch {
+                expr,
+                body,
+                default,
+                line,
+            } => {
+                let kind = self.expr(expr);
+                for (values, body) in body {
+                    for case in values {
+                        if let (Some(case), Some(kind)) = (self.expr(case), &kind) {
+                            self.assign(&case, kind, line);
+                        }
+                    }
+                    self.block(body);
+                }
+                if let Some(default) = default {
+                    self.block(default);
+                }
+            }
+            Nodes::Set {
+                target,
+                expr,
+                op,
+                line,
+            } => {
+                let target = self.expr(target);
+                let value = self.expr(expr);
+                let (target, value) = match (target, value) {
+                    (Some(target), Some(value)) => (target, value),
+                    _ => return,
+                };
+                let op = match op {
+                    Operators::Equal => {
+                        self.assign(&value, &target, line);
+                        return;
+                    }
+                    // overloads of compound operators are looked up before the plain ones
+                    op if target.check_type(&KindType::Struct) => *op,
+                    Operators::AddEq => Operators::Plus,
+                    Operators::SubEq => Operators::Minus,
+                    Operators::MulEq => Operators::Star,
+                    Operators::DivEq => Operators::Slash,
+                    op => *op,
+                };
+                if let Some(result) = self.binary(&target, &value, &op, line) {
+                    self.assign(&result, &target, line);
+                }
+            }
+        }
+    }
+
+    /// reports an error if value of type `from` can not be stored as `to`
+    ///
+    /// follows the same rules as implicit casts in codegen
+    fn assign(&mut self, from: &Kind, to: &Kind, line: &Line) {
+        if !known(from) || !known(to) {
+            return;
+        }
+        if from.is_array() && to.is_array() {
+            if let (TypeBody::Array { type_: a, .. }, TypeBody::Array { type_: b, .. }) =
+                (&from.body, &to.body)
+            {
+                self.assign(a, b, line);
+            }
+            return;
+        }
+        if from.is_null() {
+            if !to.get_nullable() && !to.is_null() {
+                self.err(TypeError::Mismatch(
+                    to.clone(),
+                    from.clone(),
+                    TypeComparison::NotNullable,
+                    *line,
+                ));
+            }
+            return;
+        }
+        let cmp = from.cmp(to, &self.generics);
+        if cmp.is_equal() {
+            if from.get_nullable() && !to.get_nullable() {
+                self.err(TypeError::NullableAssignment(from.clone(), *line));
+            }
+            return;
+        }
+        if implements_trait(self.objects, from, to) {
+            return;
+        }
+        if from.is_primitive_simple()
+            && to.is_primitive_simple()
+            && (!from.is_string() || to.is_string())
+        {
+            return;
+        }
+        let is_enum = |kind: &Kind| is_number_enum(self.objects, kind);
+        if (is_enum(from) && to.is_number()) || (from.is_number() && is_enum(to)) {
+            return;
+        }
+        self.err(TypeError::Mismatch(to.clone(), from.clone(), cmp, *line));
+    }
+
+    fn expr(&mut self, expr: &ValueType) -> Option<Kind> {
+        match expr {
+            ValueType::Expression(node) => {
+                let left = node.left.as_ref().and_then(|left| self.expr(left));
+                let right = node.right.as_ref().and_then(|right| self.expr(right));
+                let op = node.operator?;
+                match (left, right) {
+                    (Some(left), Some(right)) => self.binary(&left, &right, &op, &node.line),
+                    _ => match op {
+                        Operators::DoubleEq
+                        | Operators::NotEqual
+                        | Operators::AngleBracket(_)
+                        | Operators::LessEq
+                        | Operators::MoreEq
+                        | Operators::And
+                        | Operators::Or => Some(primitive("bool", node.line)),
+                        _ => None,
+                    },
+                }
+            }
+            ValueType::Value(value) => self.value(value),
+            ValueType::AnonymousFunction(lambda) => {
+                self.lambda(lambda);
+                None
+            }
+            ValueType::Operator(_, _) | ValueType::Blank => None,
+        }
+    }
+
+    fn binary(&mut self, left: &Kind, right: &Kind, op: &Operators, line: &Line) -> Option<Kind> {
+        if !known(left) || !known(right) {
+            return None;
+        }
+        let nullable = |kind: &Kind| kind.get_nullable() && !kind.is_null();
+        if matches!(
+            op,
+            Operators::DoubleEq
+                | Operators::NotEqual
+                | Operators::AngleBracket(_)
+                | Operators::LessEq
+                | Operators::MoreEq
+        ) && (nullable(left) || nullable(right))
+        {
+            self.warn(Warning::NullableComparison(*op, *line));
+        }
+        if left.check_type(&KindType::Struct) && !right.is_null() {
+            return self.overload(left, Some(right), op, line);
+        }
+        let invalid = |this: &mut Self| {
+            this.err(TypeError::InvalidOperator(
+                left.clone(),
+                right.clone(),
+                *op,
+                *line,
+            ));
+        };
+        match op {
+            Operators::And | Operators::Or => {
+                if !left.is_bool() || !right.is_bool() {
+                    invalid(self);
+                }
+                Some(primitive("bool", *line))
+            }
+            Operators::DoubleEq | Operators::NotEqual => Some(primitive("bool", *line)),
+            Operators::AngleBracket(_) | Operators::LessEq | Operators::MoreEq => {
+                if left.is_primitive_simple() && right.is_primitive_simple() {
+                    if !left.is_number() || !right.is_number() {
+                        invalid(self);
+                    }
+                    return Some(primitive("bool", *line));
+                }
+                None
+            }
+            Operators::Plus => {
+                if left.is_number() && right.is_number() {
+                    return Some(right.clone());
+                }
+                if left.is_string() && right.is_primitive_simple() {
+                    return Some(primitive("string", *line));
+                }
+                if left.is_primitive_simple() && right.is_primitive_simple() {
+                    invalid(self);
+                }
+                None
+            }
+            Operators::Minus | Operators::Star | Operators::Slash | Operators::Mod => {
+                if left.is_number() && right.is_number() {
+                    return Some(right.clone());
+                }
+                if left.is_primitive_simple() && right.is_primitive_simple() {
+                    invalid(self);
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// kind returned by the overload of `op` on the struct `kind`
+    ///
+    /// follows the same fallbacks as codegen: compound operators to the plain ones
+    /// and `!=` to `==`
+    fn overload(
+        &mut self,
+        kind: &Kind,
+        right: Option<&Kind>,
+        op: &Operators,
+        line: &Line,
+    ) -> Option<Kind> {
+        let fallback = match op {
+            Operators::AddEq => Some(Operators::Plus),
+            Operators::SubEq => Some(Operators::Minus),
+            Operators::MulEq => Some(Operators::Star),
+            Operators::DivEq => Some(Operators::Slash),
+            Operators::NotEqual => Some(Operators::DoubleEq),
+            _ => None,
+        };
+        for candidate in std::iter::once(*op).chain(fallback) {
+            match find_overload(self.objects, kind, &candidate, right, line) {
+                Ok(Some(overload)) => return Some(overload.return_kind),
+                Ok(None) => (),
+                Err(CodegenError::AmbiguousOverload(..)) => {
+                    self.err(TypeError::AmbiguousOverload(
+                        kind.clone(),
+                        candidate,
+                        right.cloned(),
+                        *line,
+                    ));
+                    return None;
+                }
+                // generics of the struct that are not known here
+                Err(_) => return None,
+            }
+        }
+        self.err(TypeError::OverloadNotFound(
+            kind.clone(),
+            *op,
+            right.cloned(),
+            *line,
+        ));
+        None
+    }
+
+    fn value(&mut self, value: &Variable) -> Option<Kind> {
+        let line = value.root.1;
+        let mut current = match &value.root.0 {
+            Root::Literal(literal) => match self.literal(literal, &line) {
+                Some(kind) => Resolved::Value(kind),
+                None => Resolved::Unknown,
+            },
+            Root::Parenthesis(expr) => match self.expr(expr) {
+                Some(kind) => Resolved::Value(kind),
+                None => Resolved::Unknown,
+            },
+            Root::Identifier(ident) => self.identifier(ident, &line),
+            Root::Match(matchh) => match self.matchh(matchh, false) {
+                Some(kind) => Resolved::Value(kind),
+                None => Resolved::Unknown,
+            },
+            Root::Interpolation(parts) => {
+                self.interpolation(parts);
+                Resolved::Value(primitive("string", line))
+            }
+        };
+        for (tail, line) in value.tail.iter() {
+            let called = match (&current, tail) {
+                (Resolved::Fun(path), TailNodes::Call(_)) => path
+                    .get(self.objects)
+                    .ok()
+                    .filter(|fun| fun.can_yeet && fun.return_type.is_some())
+                    .and_then(|fun| fun.identifier.clone()),
+                _ => None,
+            };
+            current = self.tail(current, tail, line);
+            if !matches!(tail, TailNodes::Propagate) {
+                self.last_call = called;
+            }
+        }
+        let mut kind = match current {
+            Resolved::Value(kind) | Resolved::Variant(kind, ..) => kind,
+            _ => return None,
+        };
+        match value.refs {
+            Ref::Reference(depth) => {
+                if let Some(refs) = refs_of(&mut kind) {
+                    *refs += depth;
+                }
+            }
+            Ref::Dereferencing(depth) => {
+                if let Some(refs) = refs_of(&mut kind) {
+                    *refs = refs.saturating_sub(depth);
+                }
+            }
+            Ref::None => (),
+        }
+        for (un, line) in value.unary.iter() {
+            if kind.check_type(&KindType::Struct) {
+                kind = self.overload(&kind, None, un, line)?;
+                continue;
+            }
+            match un {
+                Operators::Minus if !kind.is_number() && known(&kind) => {
+                    self.err(TypeError::UnaryNotApplicable(kind.clone(), *un, *line));
+                }
+                Operators::Not if !kind.is_bool() && known(&kind) => {
+                    self.err(TypeError::UnaryNotApplicable(kind.clone(), *un, *line));
+                }
+                _ => (),
+            }
+        }
+        Some(kind)
+    }
+
+    fn interpolation(&mut self, parts: &[InterpolationPart]) {
+        for part in parts {
+            let embedded = match part {
+                InterpolationPart::Value(embedded) => embedded,
+                InterpolationPart::Text(_) => continue,
+            };
+            let kind = match self.expr(&embedded.value) {
+                Some(kind) if known(&kind) => kind,
+                _ => continue,
+            };
+            if !kind.is_primitive_simple() {
+                self.err(TypeError::CannotInterpolate(kind, embedded.line));
+            } else if !embedded.format_fits(&kind) {
+                let format = embedded.format.as_ref().unwrap().0.clone();
+                self.err(TypeError::FormatNotApplicable(format, kind, embedded.line));
+            }
+        }
+    }
+
+    fn literal(&mut self, literal: &Literals, line: &Line) -> Option<Kind> {
+        match literal {
+            Literals::Number(num) => num.into_const_number(*line).map(|(_, kind)| kind),
+            Literals::String(_) => Some(primitive("string", *line)),
+            Literals::Char(_) => Some(primitive("char", *line)),
+            Literals::Tuple(values) => {
+                let types = values
+                    .iter()
+                    .map(|value| self.expr(value))
+                    .collect::<Vec<_>>();
+                Some(tuple(types.into_iter().collect::<Option<Vec<_>>>()?, *line))
+            }
+            Literals::Array(ArrayRule::Explicit(values)) => {
+                let mut first: Option<Kind> = None;
+                for value in values {
+                    if let ValueType::Blank = value {
+                        continue;
+                    }
+                    let kind = match self.expr(value) {
+                        Some(kind) => kind,
+                        None => continue,
+                    };
+                    match &first {
+                        Some(first) => {
+                            if known(first)
+                                && known(&kind)
+                                && !first.is_primitive_simple()
+                                && first.cmp(&kind, &self.generics).is_not_equal()
+                                && !self.share_trait(first, &kind)
+                            {
+                                self.err(TypeError::ArrayElementMismatch(
+                                    first.clone(),
+                                    kind,
+                                    *line,
+                                ));
+                            }
+                        }
+                        None => first = Some(kind),
+                    }
+                }
+                Some(array(first?, values.len(), *line))
+            }
+            Literals::Array(ArrayRule::Fill { value, size }) => {
+                if let Some(size) = self.expr(size) {
+                    if known(&size) && !size.is_number() {
+                        self.err(TypeError::IndexNotNumber(size, *line));
+                    }
+                }
+                Some(array(self.expr(value)?, 0, *line))
+            }
+        }
+    }
+
+    fn identifier(&mut self, ident: &str, line: &Line) -> Resolved {
+        match ident {
+            "true" | "false" => return Resolved::Value(primitive("bool", *line)),
+            "null" => return Resolved::Value(primitive("null", *line)),
+            _ => (),
+        }
+        if let Some(kind) = self.find_var(ident) {
+            return match kind {
+                Some(kind) => Resolved::Value(kind),
+                None => Resolved::Unknown,
+            };
+        }
+        let file = self.file.clone();
+        if let Some((path, kind)) = find_import(self.objects, ident, &file) {
+            return Resolved::Import(path.to_string(), kind);
+        }
+        if let Some(resolved) = self.member(&file, ident) {
+            return resolved;
+        }
+        // visibility of used items is checked once at the `use`
+        if let Some((module, item)) = find_use(self.objects, ident, &file) {
+            if let Some((path, kind)) = find_import(self.objects, item, module) {
+                return Resolved::Import(path.to_string(), kind);
+            }
+            return self.member(module, item).unwrap_or(Resolved::Unknown);
+        }
+        let dict = self.objects.0.get(&file).unwrap();
+        if !dict.identifiers.iter().any(|(name, _)| name == ident) {
+            self.err(TypeError::VariableNotFound(ident.to_string(), *line));
+        }
+        Resolved::Unknown
+    }
+
+    /// checks that the paths of every `use` in the file lead to an item
+    fn uses(&mut self, dict: &dictionary::Dictionary) {
+        for usee in dict.uses.iter() {
+            let path = usee
+                .path
+                .iter()
+                .map(|part| match part {
+                    dictionary::PathPart::Identifier(ident) => ident.as_str(),
+                    dictionary::PathPart::Asterisk => "*",
+                })
+                .collect::<Vec<_>>()
+                .join(".");
+            let module = match use_module(self.objects, usee, &self.file) {
+                Some((module, _)) => module,
+                None => {
+                    self.err(TypeError::UseNotFound(path, usee.line));
+                    continue;
+                }
+            };
+            let item = match usee.path.last() {
+                Some(dictionary::PathPart::Identifier(item)) => item,
+                _ => continue,
+            };
+            if find_import(self.objects, item, module).is_none()
+                && !module_has(self.objects, item, module)
+            {
+                self.err(TypeError::UseNotFound(path, usee.line));
+            } else if !is_public(self.objects, item, module) {
+                self.err(TypeError::NotPublic(item.clone(), module.to_string(), usee.line));
+            }
+        }
+    }
+
+    /// item of another module, only public items can be used
+    fn module_member(&mut self, file: &str, ident: &str, line: &Line) -> Resolved {
+        if !is_public(self.objects, ident, file) {
+            self.err(TypeError::NotPublic(ident.to_string(), file.to_string(), *line));
+        }
+        if let Some((path, kind)) = find_import(self.objects, ident, file) {
+            return Resolved::Import(path.to_string(), kind);
+        }
+        self.member(file, ident).unwrap_or(Resolved::Unknown)
+    }
+
+    /// finds a function or a struct in a module
+    fn member(&self, file: &str, ident: &str) -> Option<Resolved> {
+        match find_fun(self.objects, ident, file) {
+            Some(FunctionKind::Fun(path)) => return Some(Resolved::Fun(path)),
+            Some(FunctionKind::Binary(path)) => {
+                let fun = path.get_bin(self.objects).ok()?;
+                let kind = match fun.return_type.is_primitive_simple() {
+                    true => fun.return_type.clone(),
+                    false => Kind::void(),
+                };
+                return Some(Resolved::BinFun(kind));
+            }
+            _ => (),
+        }
+        if let Some((file, structt)) = find_struct(self.objects, ident, file) {
+            return Some(Resolved::Struct(InnerPath {
+                file: file.to_string(),
+                block: Some(structt.identifier.clone()),
+                ident: structt.identifier.clone(),
+                kind: ImportKinds::Rd,
+            }));
+        }
+        if let Some((file, enumm)) = find_enum(self.objects, ident, file) {
+            return Some(Resolved::Enum(Kind::from_enum(enumm, file.to_string())));
+        }
+        None
+    }
+
+    /// variant of an enum used as a value
+    fn variant(&mut self, kind: Kind, ident: &str, line: &Line) -> Resolved {
+        let file = kind.file.clone().unwrap_or_default();
+        let name = match &kind.body {
+            TypeBody::Type { main, .. } => main.last().unwrap().clone(),
+            _ => return Resolved::Unknown,
+        };
+        let enumm = match find_enum(self.objects, &name, &file) {
+            Some((_, enumm)) => enumm,
+            None => return Resolved::Unknown,
+        };
+        match enumm.keys.iter().position(|key| key.0 == ident) {
+            Some(idx) if enumm.has_payload() => {
+                Resolved::Variant(kind, enumm.fields[idx].clone(), file)
+            }
+            Some(_) => Resolved::Value(kind),
+            None => {
+                self.err(TypeError::FieldNotFound(ident.to_string(), kind, *line));
+                Resolved::Unknown
+            }
+        }
+    }
+
+    /// checks the arms of a match, returns the kind of the arms if the match is used as a value
+    fn matchh(&mut self, matchh: &Match, is_statement: bool) -> Option<Kind> {
+        let kind = self.expr(&matchh.expr);
+        // fields of every variant with the file they are declared in
+        let variants = match &kind {
+            Some(Kind {
+                body:
+                    TypeBody::Type {
+                        main,
+                        kind: KindType::Enum,
+                        ..
+                    },
+                file: Some(file),
+                ..
+            }) => find_enum(self.objects, main.last().unwrap(), file).map(|(file, enumm)| {
+                let variants = enumm
+                    .keys
+                    .iter()
+                    .map(|key| key.0.clone())
+                    .zip(enumm.fields.iter().cloned())
+                    .collect::<Vec<_>>();
+                (file.to_string(), variants)
+            }),
+            Some(kind) if known(kind) && !kind.check_type(&KindType::BEnum) => {
+                self.err(TypeError::CannotMatch(kind.clone(), matchh.line));
+                None
+            }
+            _ => None,
+        };
+        let mut result: Option<Kind> = None;
+        for arm in matchh.arms.iter() {
+            self.scopes.push(HashMap::new());
+            let fields = match (&arm.path, &variants, &kind) {
+                (Some(path), Some((file, variants)), Some(kind)) => {
+                    let ident = path.last().unwrap();
+                    match variants.iter().find(|variant| &variant.0 == ident) {
+                        Some((_, fields)) => Some((file.clone(), fields.clone())),
+                        None => {
+                            self.err(TypeError::FieldNotFound(
+                                ident.clone(),
+                                kind.clone(),
+                                arm.line,
+                            ));
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
+            if let (Some((_, fields)), Some(path)) = (&fields, &arm.path) {
+                if !arm.bindings.is_empty() && arm.bindings.len() != fields.len() {
+                    self.err(TypeError::IncorrectNumberOfBindings(
+                        path.last().unwrap().clone(),
+                        fields.len(),
+                        arm.bindings.len(),
+                        arm.line,
+                    ));
+                }
+            }
+            for (idx, binding) in arm.bindings.iter().enumerate() {
+                let binding = match binding {
+                    Some(binding) => binding,
+                    None => continue,
+                };
+                let field = fields.as_ref().and_then(|(file, fields)| {
+                    let field = fields.get(idx)?;
+                    self.resolve(&field.kind, file, &arm.line)
+                });
+                self.declare(binding, field);
+            }
+            if let Some(guard) = &arm.guard {
+                self.condition(guard, &arm.line);
+            }
+            match &arm.body {
+                ArmBody::Value(value) => {
+                    let value = self.expr(value);
+                    match (&result, value) {
+                        _ if is_statement => (),
+                        (Some(first), Some(value)) => self.assign(&value, first, &arm.line),
+                        (None, value) => result = value,
+                        _ => (),
+                    }
+                }
+                ArmBody::Block(body) => self.block(body),
+            }
+            self.scopes.pop();
+        }
+        result
+    }
+
+    fn tail(&mut self, current: Resolved, tail: &TailNodes, line: &Line) -> Resolved {
+        match tail {
+            TailNodes::Nested(ident) => match current {
+                Resolved::Enum(kind) => self.variant(kind, ident, line),
+                Resolved::Variant(kind, _, _) => self.field(&kind, ident, line),
+                Resolved::Import(file, _) => self.module_member(&file, ident, line),
+                Resolved::Value(kind) => self.field(&kind, ident, line),
+                _ => Resolved::Unknown,
+            },
+            TailNodes::Call(call) => match current {
+                Resolved::Fun(path) => match path.get(self.objects) {
+                    Ok(fun) => {
+                        let fun = fun.clone();
+                        let owner = path.block.as_ref().and_then(|block| {
+                            find_struct(self.objects, block, &path.file).map(|(_, owner)| owner)
+                        });
+                        match self.call(&fun, owner, &path.file, call, line) {
+                            Some(kind) => Resolved::Value(kind),
+                            None => Resolved::Unknown,
+                        }
+                    }
+                    Err(_) => Resolved::Unknown,
+                },
+                Resolved::Struct(path) => {
+                    let structt = find_struct(self.objects, &path.ident, &path.file)
+                        .unwrap()
+                        .1;
+                    let kind = Kind::from_struct(
+                        structt.identifier.clone(),
+                        path.file.clone(),
+                        structt.line,
+                    );
+                    match structt.constructor {
+                        Some(idx) => {
+                            let fun = structt.functions[idx].clone();
+                            self.call(&fun, Some(structt), &path.file, call, line);
+                        }
+                        None => self.args(call),
+                    }
+                    Resolved::Value(kind)
+                }
+                Resolved::Variant(kind, fields, file) => {
+                    let call = match call.resolve(&fields, line) {
+                        Ok(call) => call,
+                        Err(err) => {
+                            self.err(TypeError::InvalidArgs(err));
+                            self.args(call);
+                            return Resolved::Value(kind);
+                        }
+                    };
+                    if fields.len() != call.args.len() {
+                        self.err(TypeError::IncorrectNumberOfArgs(
+                            fields.len(),
+                            call.args.len(),
+                            *line,
+                        ));
+                    }
+                    for (arg, field) in call.args.iter().zip(fields.iter()) {
+                        let (value, expected) = match (
+                            self.expr(arg),
+                            self.resolve(&field.kind, &file, line),
+                        ) {
+                            (Some(value), Some(expected)) => (value, expected),
+                            _ => continue,
+                        };
+                        let errors = self.errors.len();
+                        self.assign(&value, &expected, line);
+                        if self.errors.len() > errors {
+                            self.errors.truncate(errors);
+                            self.err(TypeError::ArgMismatch(
+                                field.identifier.clone(),
+                                expected,
+                                value,
+                                *line,
+                            ));
+                        }
+                    }
+                    Resolved::Value(kind)
+                }
+                Resolved::BinFun(kind) => {
+                    self.args(call);
+                    if kind.is_void() {
+                        Resolved::Unknown
+                    } else {
+                        Resolved::Value(kind)
+                    }
+                }
+                _ => {
+                    self.args(call);
+                    Resolved::Unknown
+                }
+            },
+            TailNodes::Index(index) => {
+                if let Some(index) = self.expr(index) {
+                    if known(&index) && !index.is_number() {
+                        self.err(TypeError::IndexNotNumber(index, *line));
+                    }
+                }
+                match current {
+                    Resolved::Value(Kind {
+                        body: TypeBody::Array { type_, .. },
+                        ..
+                    }) => Resolved::Value(*type_),
+                    Resolved::Value(kind) if kind.is_primitive_simple() && !kind.is_string() => {
+                        self.err(TypeError::CannotIndex(kind, *line));
+                        Resolved::Unknown
+                    }
+                    _ => Resolved::Unknown,
+                }
+            }
+            TailNodes::TupleIndex(idx) => match current {
+                Resolved::Value(kind) => match &kind.body {
+                    TypeBody::Tuple { types, .. } => match types.get(*idx) {
+                        Some(element) => Resolved::Value(element.clone()),
+                        None => {
+                            self.err(TypeError::TupleIndexOutOfBounds(kind.clone(), *idx, *line));
+                            Resolved::Unknown
+                        }
+                    },
+                    _ => {
+                        if known(&kind) {
+                            self.err(TypeError::NotATuple(kind, *line));
+                        }
+                        Resolved::Unknown
+                    }
+                },
+                _ => Resolved::Unknown,
+            },
+            TailNodes::Nullable => {
+                if let Resolved::Value(kind) = &current {
+                    if known(kind) && !kind.get_nullable() && !kind.is_null() {
+                        self.err(TypeError::NotNullable(kind.clone(), *line));
+                    }
+                }
+                Resolved::Value(primitive("bool", *line))
+            }
+            TailNodes::Propagate => current,
+            TailNodes::Cast(kind) => match self.resolve(kind, &self.file.clone(), line) {
+                Some(kind) => Resolved::Value(kind),
+                None => Resolved::Unknown,
+            },
+        }
+    }
+
+    fn field(&mut self, kind: &Kind, ident: &str, line: &Line) -> Resolved {
+        let (name, file) = match &kind.body {
+            TypeBody::Type {
+                kind: KindType::Error,
+                ..
+            } => {
+                return match ident {
+                    "message" => Resolved::Value(primitive("string", *line)),
+                    "code" => Resolved::Value(primitive("int", *line)),
+                    _ => {
+                        self.err(TypeError::FieldNotFound(
+                            ident.to_string(),
+                            kind.clone(),
+                            *line,
+                        ));
+                        Resolved::Unknown
+                    }
+                };
+            }
+            TypeBody::Type {
+                main,
+                kind: KindType::Struct,
+                ..
+            } => (
+                main.last().unwrap().clone(),
+                kind.file.clone().unwrap_or_default(),
+            ),
+            TypeBody::Type {
+                main,
+                kind: KindType::Trait,
+                ..
+            } => {
+                let file = kind.file.clone().unwrap_or_default();
+                let traitt = match find_trait(self.objects, main.last().unwrap(), &file) {
+                    Some((_, traitt)) => traitt,
+                    None => return Resolved::Unknown,
+                };
+                if traitt
+                    .methods
+                    .iter()
+                    .any(|method| method.identifier.as_deref() == Some(ident))
+                {
+                    return Resolved::Fun(InnerPath {
+                        file: file.clone(),
+                        block: Some(traitt.identifier.clone()),
+                        ident: ident.to_string(),
+                        kind: ImportKinds::Rd,
+                    });
+                }
+                self.err(TypeError::FieldNotFound(
+                    ident.to_string(),
+                    kind.clone(),
+                    *line,
+                ));
+                return Resolved::Unknown;
+            }
+            _ => return Resolved::Unknown,
+        };
+        let structt = match find_struct(self.objects, &name, &file) {
+            Some((_, structt)) => structt,
+            None => return Resolved::Unknown,
+        };
+        for (field, field_kind) in structt.fields.iter() {
+            if field == ident {
+                return match self.resolve(field_kind, &file, line) {
+                    Some(kind) => Resolved::Value(kind),
+                    None => Resolved::Unknown,
+                };
+            }
+        }
+        for fun in structt.functions.iter() {
+            if fun.identifier.as_deref() == Some(ident) {
+                return Resolved::Fun(InnerPath {
+                    file: file.clone(),
+                    block: Some(name.clone()),
+                    ident: ident.to_string(),
+                    kind: ImportKinds::Rd,
+                });
+            }
+        }
+        if structt.impls.is_empty() && structt.traits.is_empty() {
+            self.err(TypeError::FieldNotFound(
+                ident.to_string(),
+                kind.clone(),
+                *line,
+            ));
+        }
+        Resolved::Unknown
+    }
+
+    /// checks that the kind bound to a generic implements the traits the generic requires
+    fn bounds(&mut self, decl: &GenericDecl, kind: &Kind, file: &str, line: &Line) {
+        if !matches!(
+            kind.body,
+            TypeBody::Type {
+                kind: KindType::Struct | KindType::Primitive | KindType::Enum,
+                ..
+            }
+        ) {
+            return;
+        }
+        for constraint in decl.traits.iter() {
+            let (trait_file, traitt) = match resolve_trait(self.objects, constraint, file) {
+                Some(traitt) => traitt,
+                None => {
+                    self.err(TypeError::TraitNotFound(constraint.join("."), decl.line));
+                    continue;
+                }
+            };
+            let trait_kind = Kind::from_trait(traitt, trait_file.to_string());
+            if !implements_trait(self.objects, kind, &trait_kind) {
+                self.err(TypeError::BoundNotSatisfied(
+                    kind.clone(),
+                    traitt.identifier.clone(),
+                    decl.identifier.clone(),
+                    *line,
+                ));
+            }
+        }
+    }
+
+    /// checks arguments of a call that can not be checked against a signature
+    fn args(&mut self, call: &FunctionCall) {
+        for arg in call.args.iter().chain(call.named.iter().map(|named| &named.1)) {
+            self.expr(arg);
+        }
+    }
+
+    /// checks arguments against the signature and returns the returned type
+    fn call(
+        &mut self,
+        fun: &Function,
+        owner: Option<&dictionary::Struct>,
+        file: &str,
+        call: &FunctionCall,
+        line: &Line,
+    ) -> Option<Kind> {
+        let call = match call.args.first() {
+            Some(ValueType::Blank) => FunctionCall {
+                args: call.args[1..].to_vec(),
+                ..call.clone()
+            },
+            _ => call.clone(),
+        };
+        let call = match call.resolve(&fun.args, line) {
+            Ok(call) => call,
+            Err(err) => {
+                self.err(TypeError::InvalidArgs(err));
+                self.args(&call);
+                return None;
+            }
+        };
+        let args = &call.args[..];
+        // generics of the struct are inferred the same way as generics of the function
+        let is_constructor =
+            owner.is_some_and(|owner| fun.identifier.as_ref() == Some(&owner.identifier));
+        let mut decls = fun.generics.clone();
+        if let Some(owner) = owner {
+            match is_constructor {
+                true => decls = owner.generics.clone(),
+                false => decls.extend(owner.generics.iter().cloned()),
+            }
+        }
+        let explicit = match is_constructor {
+            true => &decls,
+            false => &fun.generics,
+        };
+        if call.generic.len() > explicit.len() {
+            self.err(TypeError::IncorrectNumberOfGenerics(
+                explicit.len(),
+                call.generic.len(),
+                *line,
+            ));
+        }
+        if fun.args.len() != args.len() {
+            self.err(TypeError::IncorrectNumberOfArgs(
+                fun.args.len(),
+                args.len(),
+                *line,
+            ));
+        }
+        let mut bound: HashMap<String, Kind> = HashMap::new();
+        for (decl, kind) in explicit.iter().zip(call.generic.iter()) {
+            if let Some(kind) = self.resolve(kind, &self.file.clone(), line) {
+                bound.insert(decl.identifier.clone(), kind);
+            }
+        }
+        // explicit generic arguments are substituted into the parameters
+        let given = bound.clone();
+        let mut substituted = self.generics.clone();
+        substituted.extend(given.clone());
+        for (idx, value) in args.iter().enumerate() {
+            let got = self.expr(value);
+            let arg = match fun.args.get(idx) {
+                Some(arg) => arg,
+                None => continue,
+            };
+            let got = match got {
+                Some(got) => got,
+                None => continue,
+            };
+            if let Some(generic) = generic_ident(&arg.kind, &decls)
+                .filter(|generic| !given.contains_key(generic))
+            {
+                match bound.get(&generic) {
+                    Some(first) => {
+                        let first = first.clone();
+                        if known(&got)
+                            && known(&first)
+                            && !(got.is_primitive_simple() && first.is_primitive_simple())
+                            && first.cmp(&got, &self.generics).is_not_equal()
+                        {
+                            self.err(TypeError::GenericMismatch(generic, first, got, *line));
+                        }
+                    }
+                    None => {
+                        bound.insert(generic, got);
+                    }
+                }
+                continue;
+            }
+            let expected = match self.resolve_with(&arg.kind, file, &arg.line, &substituted) {
+                Some(kind) => kind,
+                None => continue,
+            };
+            let errors = self.errors.len();
+            self.assign(&got, &expected, line);
+            if self.errors.len() > errors {
+                self.errors.truncate(errors);
+                self.err(TypeError::ArgMismatch(
+                    arg.identifier.clone(),
+                    expected,
+                    got,
+                    *line,
+                ));
+            }
+        }
+        for decl in decls.iter() {
+            if let Some(kind) = bound.get(&decl.identifier) {
+                self.bounds(decl, kind, file, line);
+            }
+        }
+        match &fun.return_type {
+            Some(kind) => match generic_ident(kind, &decls) {
+                Some(generic) => bound.get(&generic).cloned(),
+                None => self.resolve(kind, file, line),
+            },
+            None => Some(primitive("null", *line)),
+        }
+    }
+}
+
+/// types the checker knows enough about to compare
+fn known(kind: &Kind) -> bool {
+    match &kind.body {
+        TypeBody::Type { kind, .. } => *kind != KindType::None,
+        TypeBody::Array { type_, .. } => known(type_),
+        TypeBody::Tuple { types, .. } => types.iter().all(known),
+        TypeBody::Generic { .. } => true,
+        _ => false,
+    }
+}
+
+fn generic_ident(kind: &Kind, decls: &[GenericDecl]) -> Option<String> {
+    let ident = match &kind.body {
+        TypeBody::Generic { identifier, .. } => identifier,
+        TypeBody::Type { main, .. } if main.len() == 1 => &main[0],
+        _ => return None,
+    };
+    decls
+        .iter()
+        .find(|generic| &generic.identifier == ident)
+        .map(|generic| generic.identifier.clone())
+}
+
+fn refs_of(kind: &mut Kind) -> Option<&mut usize> {
+    match &mut kind.body {
+        TypeBody::Type { refs, .. }
+        | TypeBody::Array { refs, .. }
+        | TypeBody::Tuple { refs, .. }
+        | TypeBody::Generic { refs, .. } => Some(refs),
+        _ => None,
+    }
+}
+
+fn primitive(name: &str, line: Line) -> Kind {
+    Kind {
+        body: TypeBody::Type {
+            refs: 0,
+            main: vec![name.to_string()],
+            generics: vec![],
+            nullable: false,
+            kind: KindType::Primitive,
+        },
+        line,
+        file: None,
+    }
+}
+
+fn tuple(types: Vec<Kind>, line: Line) -> Kind {
+    Kind {
+        body: TypeBody::Tuple {
+            types,
+            refs: 0,
+            nullable: false,
+        },
+        line,
+        file: None,
+    }
+}
+
+fn array(kind: Kind, size: usize, line: Line) -> Kind {
+    Kind {
+        body: TypeBody::Array {
+            type_: Box::new(kind),
+            size,
+            refs: 0,
+            nullable: false,
+        },
+        line,
+        file: None,
+    }
+}
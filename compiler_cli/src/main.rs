@@ -1,6 +1,8 @@
 use std::{env, fs::File, io::Read};
 
 use compiler::*;
+use runtime::runtime_error::ErrTypes;
+use runtime::runtime_types::{Context, ExitCodes, Lib, Types};
 
 fn main() {
     let mut args = env::args();
@@ -19,58 +21,49 @@ fn main() {
                 Some(file) => file,
                 None => panic!("File not specified."),
             };
-            println!("Compilation for '{file}' starts.");
+            let mut print_graph = false;
+            let mut format = diagnostic::MessageFormat::Human;
+            for arg in args {
+                if arg == "--print-import-graph" {
+                    print_graph = true;
+                } else if let Some(value) = arg.strip_prefix("--message-format=") {
+                    format = match value.parse() {
+                        Ok(format) => format,
+                        Err(err) => panic!("{err}"),
+                    };
+                }
+            }
+            // with the graph printed, stdout holds nothing but the graph
+            let status = |message: &dyn std::fmt::Display| match print_graph {
+                true => eprintln!("{message}"),
+                false => diagnostic::status(format, message),
+            };
+            status(&format!("Compilation for '{file}' starts."));
             let ruda_path = std::env::var("RUDA_PATH").expect("RUDA_PATH not set.");
             let (ast, params, _registry) = match generate_ast(&ruda_path) {
                 Ok(ast) => (ast.ast, ast.params, ast.registry),
                 Err(err) => {
-                    println!("Failed to load AST.");
-                    println!("{}", err);
+                    status(&"Failed to load AST.");
+                    status(&err);
                     return;
                 }
             };
 
-            println!("AST loaded.");
-            let parsed_tree = build_dictionaries(&file, &mut (ast, params));
+            status(&"AST loaded.");
+            let parsed_tree = build_import_graph(&file, &mut (ast, params));
             match &parsed_tree {
-                Ok(_tree) => {
-                    println!("Dictionary generated.");
+                Ok((_tree, graph)) => {
+                    status(&"Dictionary generated.");
+                    if print_graph {
+                        print!("{}", graph.to_dot());
+                    }
                 }
                 Err(err) => {
-                    println!("Compilation failed.");
-                    println!("Errors:");
-                    let prepend = format!("Err {}: ", err.1);
-                    match &err.0 {
-                        ErrorOrigin::LexingError(err) => {
-                            for err in err {
-                                println!("{prepend}{:?}", err);
-                            }
-                        }
-                        ErrorOrigin::ParsingError(err) => {
-                            println!("{prepend}{:?} at {}", err.0, err.1);
-                        }
-                        ErrorOrigin::CodeBlockParserError(err) => {
-                            for err in err {
-                                println!("{prepend}{:?}", err);
-                            }
-                        }
-                        ErrorOrigin::IntermediateError(err) => {
-                            for err in err {
-                                println!("{prepend}{:?}", err);
-                            }
-                        }
-                        ErrorOrigin::LibLoadError(err) => {
-                            for err in err {
-                                println!("{prepend}{:?}", err);
-                            }
-                        }
-                        ErrorOrigin::LinkingError(err) => {
-                            println!("{prepend}{:?}", err);
-                        }
-                        ErrorOrigin::AnalyzationError(err) => {
-                            println!("{prepend}{:?}", err)
-                        }
-                    }
+                    status(&"Compilation failed.");
+                    let root = std::path::Path::new(&file)
+                        .parent()
+                        .unwrap_or(std::path::Path::new("."));
+                    diagnostic::emit(&err.0.diagnostics(&err.1), format, root);
                 }
             }
         }
@@ -88,6 +81,112 @@ fn main() {
             println!("Tokens generated.");
             println!("{:?}", tokens.0);
         }
+        "fmt" => {
+            let file = match args.nth(0) {
+                Some(file) => file,
+                None => panic!("File not specified."),
+            };
+            let check = args.any(|arg| arg == "--check");
+            let mut source = String::new();
+            File::open(&file)
+                .and_then(|mut opened| opened.read_to_string(&mut source))
+                .expect(&format!("File not found. ({})", file));
+            let formatted = match formatter::format(&source) {
+                Ok(formatted) => formatted,
+                Err(err) => {
+                    println!("Failed to format '{file}'.");
+                    println!("{}", err);
+                    std::process::exit(2);
+                }
+            };
+            if formatted == source {
+                return;
+            }
+            if check {
+                println!("'{file}' is not formatted.");
+                std::process::exit(1);
+            }
+            std::fs::write(&file, formatted).expect(&format!("Could not write '{file}'."));
+        }
+        "session" => {
+            let project = match args.nth(0) {
+                Some(project) => project,
+                None => panic!("Project not specified."),
+            };
+            let stdlib = match args.nth(0) {
+                Some(stdlib) => stdlib,
+                None => panic!("Directory of the standard library not specified."),
+            };
+            let src = std::path::Path::new(&project).join("src");
+            let mut files = MemoryFiles::new();
+            read_sources(&src, "", &mut files);
+            let mut session = Session::new(files);
+            let mut libs: Vec<Lib> = Vec::new();
+            for name in session::STD_LIBS {
+                let path = std::path::Path::new(&stdlib)
+                    .join(&name[1..])
+                    .with_extension(std::env::consts::DLL_EXTENSION);
+                match load_std(&path) {
+                    Some((declarations, call)) => {
+                        session.library(name, &declarations);
+                        libs.push(call);
+                    }
+                    None => libs.push(missing),
+                }
+            }
+            let data = match session.compile("main.rd") {
+                Ok(data) => data,
+                Err(diagnostics) => {
+                    println!("Compilation failed.");
+                    diagnostic::emit(&diagnostics, diagnostic::MessageFormat::Human, &src);
+                    std::process::exit(1);
+                }
+            };
+            let mut ctx = Context::new(libs);
+            ctx.memory.stack.data = data.values;
+            ctx.memory.strings.pool = data.strings;
+            ctx.code.data = data.instructions;
+            ctx.memory.non_primitives = data.non_primitives;
+            ctx.memory.fun_table = data.fun_table;
+            ctx.memory.heap.data = data.heap;
+            ctx.code.ptr = data.entry_point;
+            ctx.code.entry_point = data.entry_point;
+            ctx.debug = data.debug;
+            ctx.run();
+            if !matches!(ctx.exit_code, ExitCodes::End) {
+                std::process::exit(1);
+            }
+        }
+        "grammar-check" => {
+            let mut grammars = Vec::new();
+            for file in args {
+                let source = std::fs::read_to_string(&file).expect(&format!("File not found. ({})", file));
+                grammars.push((file, source));
+            }
+            if grammars.is_empty() {
+                grammars.push((String::from("ruda.ast"), RUDA_AST.to_string()));
+                grammars.push((String::from("registry.ast"), REGISTRY_AST.to_string()));
+            }
+            let mut failed = false;
+            for (file, source) in grammars.iter() {
+                let errors = grammar_check::check(source);
+                for err in errors.iter() {
+                    let diagnostic = diagnostic::Diagnostic::from(err).in_file(file);
+                    println!("{}", diagnostic.render(Some(source)));
+                }
+                let count = errors.iter().filter(|err| !err.is_warning()).count();
+                let warnings = errors.len() - count;
+                if count > 0 {
+                    failed = true;
+                    println!("'{file}' has {count} errors and {warnings} warnings.");
+                } else {
+                    println!("'{file}' is valid, {warnings} warnings.");
+                }
+            }
+            if failed {
+                std::process::exit(1);
+            }
+        }
         "astTest" => {
             let ruda_path = std::env::var("RUDA_PATH").expect("RUDA_PATH not set.");
             if let Ok(ast) = generate_ast(&ruda_path) {
@@ -136,8 +235,16 @@ fn main() {
 
 Usage: {} [command] [args]
 Commands:
-    build [file] - compiles file - not implemented yet
+    build [file] [--print-import-graph] [--message-format=human|json] - compiles file - not implemented yet
+                     --print-import-graph prints imports between the modules in the DOT format
+                     --message-format=json prints every error as a JSON object on its own line
+    session [project] [stdlib] - compiles the project in memory and runs it without RUDA_PATH,
+                     libraries are loaded from the stdlib directory
     tokenize [file] - prints tokens of file
+    fmt [file] [--check] - formats file in place
+                     --check only tells if the file is formatted, exits with 1 if not
+    grammar-check [file...] - checks grammar files for mistakes, the grammars of the compiler without files,
+                     exits with 1 if a grammar has errors
     astTest [file] - tests if AST can be loaded properly, if not, you will get an error hopefully
                      also if you get an infinite loop, it means that one or more of the AST nodes
                      are not terminated properly (missing semicolon)
@@ -153,3 +260,34 @@ Commands:
     }
 }
 
+
+/// loads a library of the standard library, returns its declarations and the function the VM calls
+///
+/// a library that is not installed fails when it is called
+fn load_std(path: &std::path::Path) -> Option<(String, Lib)> {
+    let lib = unsafe { libloading::Library::new(path) }.ok()?;
+    let register = unsafe { lib.get::<fn() -> String>(b"register\0") }.ok()?();
+    let init = unsafe { lib.get::<fn(&(), usize) -> Lib>(b"init\0") }.ok()?;
+    let call = init(&(), 0);
+    std::mem::forget(lib);
+    Some((register, call))
+}
+
+fn missing(_: &mut Context, _: usize) -> Result<Types, ErrTypes> {
+    Err(ErrTypes::Message("library is not installed".to_owned()))
+}
+
+/// puts every `.rd` file of `dir` into `files`, keyed by the path relative to the sources
+fn read_sources(dir: &std::path::Path, prefix: &str, files: &mut MemoryFiles) {
+    let entries = std::fs::read_dir(dir).expect(&format!("Could not read '{}'.", dir.display()));
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let path = format!("{prefix}{name}");
+        if entry.path().is_dir() {
+            read_sources(&entry.path(), &format!("{path}/"), files);
+        } else if name.ends_with(".rd") {
+            let source = std::fs::read_to_string(entry.path()).expect(&format!("Could not read '{path}'."));
+            files.insert(&path, &source);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+import os
+import shutil
+import subprocess
+import tempfile
+
+# Formats every source in examples and tests twice, the second run must not change anything
+# and the formatted sources have to consist of the same tokens (checked by rudac itself)
+
+dirs = ["examples", "tests"]
+# files that are broken on purpose
+skip = [os.path.join("tests", "syntax-errors"), os.path.join("tests", "unclosed-string")]
+
+work_dir = tempfile.mkdtemp()
+failed = []
+count = 0
+
+for folder in dirs:
+    shutil.copytree(os.path.join(os.getcwd(), folder), os.path.join(work_dir, folder))
+
+for folder in dirs:
+    for root, _, files in os.walk(os.path.join(work_dir, folder)):
+        relative = os.path.relpath(root, work_dir)
+        if any(relative.startswith(path) for path in skip):
+            continue
+        for file in files:
+            if not file.endswith(".rd"):
+                continue
+            path = os.path.join(root, file)
+            name = os.path.join(relative, file)
+            count += 1
+
+            # Format the file
+            if subprocess.run(["rudac", "fmt", path]).returncode != 0:
+                print("Error: Failed to format", name)
+                failed.append(name)
+                continue
+
+            # Formatting has to be idempotent
+            if subprocess.run(["rudac", "fmt", path, "--check"]).returncode != 0:
+                print("Error: Formatting is not idempotent for", name)
+                failed.append(name)
+
+shutil.rmtree(work_dir)
+
+if failed:
+    print("Failed", len(failed), "of", count)
+    exit(1)
+
+print("Success,", count, "files formatted")
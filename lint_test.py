@@ -0,0 +1,55 @@
+import json
+import os
+import re
+import subprocess
+
+# Lints the test projects with `ruda lint --message-format json`
+# Every warning has to be announced by a comment with its code at the end of its line, like `// W0001`,
+# and every announced warning has to be reported, so projects without comments must lint clean
+
+projects = ["lint-warnings", "lints"]
+# the fixture has to show that every lint fires
+codes = ["W%04d" % number for number in range(1, 11)]
+expectation = re.compile(r"// *(W\d{4})\s*$")
+
+failed = []
+covered = set()
+
+for name in projects:
+    project = os.path.join("tests", name)
+    expected = set()
+    for root, _, files in os.walk(os.path.join(project, "src")):
+        for file in files:
+            if not file.endswith(".rd"):
+                continue
+            path = os.path.join(root, file)
+            with open(path) as source:
+                for number, line in enumerate(source, 1):
+                    match = expectation.search(line)
+                    if match:
+                        expected.add((os.path.relpath(path, project), number, match.group(1)))
+    result = subprocess.run(["ruda", "lint", "--message-format", "json", project], stdin=subprocess.DEVNULL, capture_output=True, text=True, timeout=60)
+    reported = set()
+    for line in result.stdout.splitlines():
+        diagnostic = json.loads(line)
+        file = os.path.relpath(diagnostic["file"], project)
+        reported.add((file, diagnostic["labels"][0]["line"], diagnostic["code"]))
+    covered.update(code for _, _, code in reported)
+    if reported != expected:
+        print("Error: Unexpected warnings of", name)
+        for warning in sorted(expected - reported):
+            print("    missing", *warning)
+        for warning in sorted(reported - expected):
+            print("    unexpected", *warning)
+        failed.append(name)
+
+missing = [code for code in codes if code not in covered]
+if missing:
+    print("Error: No test project reports", ", ".join(missing))
+    failed.append("coverage")
+
+if failed:
+    print("Failed", len(failed), "of", len(projects))
+    exit(1)
+
+print("Success,", len(projects), "projects linted")
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::io::{stdin, stdout, BufReader};
+
+use compiler::prep_objects::Context;
+use compiler::symbols;
+use compiler::*;
+
+mod project;
+mod rpc;
+mod server;
+mod text;
+
+fn main() {
+    let mut framing = rpc::Framing::Headers;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--stdio" => framing = rpc::Framing::Headers,
+            "--script" => framing = rpc::Framing::Lines,
+            "--help" | "help" => {
+                println!("{HELP}");
+                return;
+            }
+            _ => {
+                eprintln!("Unknown argument: {arg}");
+                eprintln!("Try --help.");
+                std::process::exit(2);
+            }
+        }
+    }
+    let ruda_path = match std::env::var("RUDA_PATH") {
+        Ok(path) => path,
+        Err(err) => {
+            eprintln!("RUDA_PATH not found. {}", err);
+            std::process::exit(1);
+        }
+    };
+    let mut asts = match generate_ast(&ruda_path) {
+        Ok(asts) => asts,
+        Err(err) => {
+            eprintln!("Failed to load AST.");
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    };
+
+    // completion of library members and type checking need the standard library,
+    // everything else works without it
+    let mut registry = (std::mem::take(&mut asts.registry), Vec::new());
+    let mut binaries = HashMap::new();
+    let mut libraries = HashMap::new();
+    match build_std_lib(&mut registry) {
+        Ok(std_lib) => {
+            for (lib, name) in std_lib {
+                libraries.insert(name.clone(), symbols::library_symbols(&name, &lib));
+                binaries.insert(name, lib);
+            }
+        }
+        Err(err) => {
+            eprintln!("Failed to load std lib, libraries will not be checked.");
+            eprintln!("{}", err);
+        }
+    }
+    asts.registry = registry.0;
+
+    let context = Context::new(HashMap::new(), binaries);
+    let mut server = server::Server::new(asts, context, libraries);
+    let stdin = stdin();
+    let mut transport = rpc::Transport::new(BufReader::new(stdin.lock()), stdout(), framing);
+    let code = server.run(&mut transport);
+    std::process::exit(code);
+}
+
+const HELP: &str = r#"Language server for Ruda.
+
+Usage: ruda-lsp [option]
+Options:
+    --stdio  - messages are framed with Content-Length headers (default), used by editors
+    --script - one JSON message per line on stdin and stdout, used by tests
+    --help   - shows this message
+
+RUDA_PATH has to point to the Ruda installation, the grammar and the standard library
+are loaded from there."#;
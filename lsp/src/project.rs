@@ -0,0 +1,315 @@
+//! Programs the open documents belong to
+//!
+//! Every program is compiled as a whole from its main file, the symbols of its
+//! modules are kept from the last run that got past parsing so that a typo
+//! does not take hover or completion away.
+
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use compiler::diagnostic::Diagnostic;
+use compiler::prep_objects::{self, Context};
+use compiler::symbols::{self, Symbol, SymbolKind};
+use compiler::{build_import_graph_with, typecheck, Asts};
+
+use crate::text;
+
+pub struct Module {
+    pub symbols: Vec<Symbol>,
+    /// alias and path of every import
+    pub imports: Vec<(String, String)>,
+}
+
+pub struct Project {
+    pub main: PathBuf,
+    /// directory of the main file, module paths are relative to it
+    pub root: PathBuf,
+    pub modules: HashMap<String, Module>,
+    /// files diagnostics were published for, they are cleared on the next run
+    pub reported: Vec<String>,
+}
+
+/// identifier in a source and the identifier before the dot in front of it
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub name: String,
+    pub start: usize,
+    pub end: usize,
+    /// `io` of `io.println`
+    pub qualifier: Option<String>,
+}
+
+/// main file of the program `file` belongs to
+///
+/// files inside `src` of a project (next to `Ruda.toml`) belong to its main.rd or lib.rd,
+/// any other file is a program on its own
+pub fn find_main(file: &Path) -> PathBuf {
+    for dir in file.ancestors().skip(1) {
+        if !dir.join("Ruda.toml").exists() {
+            continue;
+        }
+        let src = dir.join("src");
+        if !file.starts_with(&src) {
+            break;
+        }
+        for main in ["main.rd", "lib.rd"] {
+            if src.join(main).exists() {
+                return src.join(main);
+            }
+        }
+        break;
+    }
+    file.to_path_buf()
+}
+
+/// every identifier of the source, None if the source can not be tokenized
+pub fn references(text: &str) -> Option<Vec<Reference>> {
+    let idents = symbols::identifiers(text)?;
+    let mut refs: Vec<Reference> = Vec::with_capacity(idents.len());
+    for (name, line) in idents {
+        let (start, end) = (line.start, line.end);
+        let qualifier = match refs.last() {
+            Some(prev) if prev.end <= start && text[prev.end..start].trim() == "." => {
+                Some(prev.name.clone())
+            }
+            _ => None,
+        };
+        refs.push(Reference {
+            name,
+            start,
+            end,
+            qualifier,
+        });
+    }
+    Some(refs)
+}
+
+/// identifier the offset points into or right after
+pub fn reference_at(text: &str, offset: usize) -> Option<Reference> {
+    references(text)?
+        .into_iter()
+        .find(|reference| reference.start <= offset && offset <= reference.end)
+}
+
+/// identifiers are considered to be the same symbol if they were declared at the same place
+pub fn same(a: &Symbol, b: &Symbol) -> bool {
+    a.file == b.file && a.name == b.name && a.kind == b.kind && a.line == b.line
+}
+
+impl Project {
+    pub fn new(main: PathBuf) -> Self {
+        let root = main.parent().map(Path::to_path_buf).unwrap_or_default();
+        Self {
+            main,
+            root,
+            modules: HashMap::new(),
+            reported: Vec::new(),
+        }
+    }
+
+    /// module path of the file, None if it is outside of the root
+    pub fn module_of(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        let parts: Vec<_> = relative
+            .components()
+            .map(|part| part.as_os_str().to_string_lossy().to_string())
+            .collect();
+        Some(parts.join("/"))
+    }
+
+    pub fn path_of(&self, module: &str) -> PathBuf {
+        self.root.join(module)
+    }
+
+    /// compiles the program up to type checking, returns the diagnostics of every file
+    ///
+    /// open documents are used instead of the files on the disk
+    pub fn analyze(
+        &mut self,
+        asts: &mut Asts,
+        context: &mut Context,
+        documents: &HashMap<PathBuf, String>,
+    ) -> HashMap<String, Vec<Diagnostic>> {
+        let mut sources = HashMap::new();
+        for (path, text) in documents {
+            if let Some(module) = self.module_of(path) {
+                sources.insert(module, text.clone());
+            }
+        }
+        let main = self.main.to_string_lossy().to_string();
+        let mut grammar = (std::mem::take(&mut asts.ast), std::mem::take(&mut asts.params));
+        let built = catch_unwind(AssertUnwindSafe(|| {
+            build_import_graph_with(&main, &mut grammar, &sources)
+        }));
+        asts.ast = grammar.0;
+        asts.params = grammar.1;
+
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+        match built {
+            Ok(Ok((dictionaries, _))) => {
+                context.0 = dictionaries;
+                let checked = catch_unwind(AssertUnwindSafe(|| {
+                    // type checking needs every imported library
+                    let missing = context.0.values().any(|dictionary| {
+                        dictionary.imports.iter().any(|import| {
+                            import.path.starts_with('#') && !context.1.contains_key(&import.path)
+                        })
+                    });
+                    if missing {
+                        return Vec::new();
+                    }
+                    let _ = prep_objects::prep(context);
+                    typecheck::check(context)
+                }));
+                match checked {
+                    Ok(errors) => {
+                        for (err, file) in &errors {
+                            diagnostics.push(Diagnostic::from(err).in_file(file));
+                        }
+                    }
+                    Err(_) => eprintln!("ruda-lsp: type checking of '{main}' panicked"),
+                }
+                self.modules.clear();
+                for (file, dictionary) in context.0.iter() {
+                    let imports = dictionary
+                        .imports
+                        .iter()
+                        .map(|import| (import.alias.clone(), import.path.clone()))
+                        .collect();
+                    self.modules.insert(
+                        file.clone(),
+                        Module {
+                            symbols: symbols::module_symbols(file, dictionary),
+                            imports,
+                        },
+                    );
+                }
+            }
+            Ok(Err((err, file))) => {
+                diagnostics.extend(err.diagnostics(&file));
+            }
+            Err(_) => eprintln!("ruda-lsp: compilation of '{main}' panicked"),
+        }
+
+        let main_module = self.module_of(&self.main).unwrap_or_default();
+        let mut by_file: HashMap<String, Vec<Diagnostic>> = HashMap::new();
+        for diagnostic in diagnostics {
+            // binary libraries can not be opened
+            if diagnostic.file.starts_with('#') {
+                continue;
+            }
+            let file = match diagnostic.file.is_empty() {
+                true => main_module.clone(),
+                false => diagnostic.file.clone(),
+            };
+            by_file.entry(file).or_default().push(diagnostic);
+        }
+        by_file
+    }
+
+    /// symbol the identifier refers to
+    ///
+    /// local variables are not symbols, `value.member` is resolved by the member name only
+    /// since the type of the value is not known
+    pub fn resolve(
+        &self,
+        libraries: &HashMap<String, Vec<Symbol>>,
+        module: &str,
+        reference: &Reference,
+    ) -> Option<Symbol> {
+        let name = &reference.name;
+        let qualifier = match &reference.qualifier {
+            Some(qualifier) => qualifier,
+            None => return self.lookup(module, name),
+        };
+        if let Some(members) = self.members_of_import(libraries, module, qualifier) {
+            return members.iter().find(|symbol| &symbol.name == name).cloned();
+        }
+        if let Some(parent) = self.lookup(module, qualifier) {
+            return parent.children.iter().find(|child| &child.name == name).cloned();
+        }
+        let mut files: Vec<_> = self.modules.keys().collect();
+        files.sort_by_key(|file| (file.as_str() != module, file.to_string()));
+        for file in files {
+            for symbol in &self.modules[file].symbols {
+                if let Some(child) = symbol.children.iter().find(|child| &child.name == name) {
+                    return Some(child.clone());
+                }
+            }
+        }
+        None
+    }
+
+    /// item visible in the module under the name
+    pub fn lookup(&self, module: &str, name: &str) -> Option<Symbol> {
+        if let Some(found) = self.modules.get(module).and_then(|current| {
+            current.symbols.iter().find(|symbol| symbol.name == name)
+        }) {
+            return Some(found.clone());
+        }
+        let mut files: Vec<_> = self.modules.keys().collect();
+        files.sort();
+        for file in files {
+            let found = self.modules[file]
+                .symbols
+                .iter()
+                .find(|symbol| symbol.public && symbol.kind != SymbolKind::Module && symbol.name == name);
+            if let Some(found) = found {
+                return Some(found.clone());
+            }
+        }
+        None
+    }
+
+    /// items of the module or library imported as `alias`
+    pub fn members_of_import(
+        &self,
+        libraries: &HashMap<String, Vec<Symbol>>,
+        module: &str,
+        alias: &str,
+    ) -> Option<Vec<Symbol>> {
+        let current = self.modules.get(module)?;
+        let (_, path) = current.imports.iter().find(|(name, _)| name == alias)?;
+        if path.starts_with('#') {
+            return libraries.get(path).cloned();
+        }
+        let imported = self.modules.get(path)?;
+        Some(
+            imported
+                .symbols
+                .iter()
+                .filter(|symbol| symbol.public && symbol.kind != SymbolKind::Module)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// byte range of the name of a symbol declared in this program
+    pub fn name_span(&self, symbol: &Symbol, text: &str) -> Option<(usize, usize)> {
+        let line = symbol.line?;
+        let (anchor, limit) = match line.span() {
+            Some(span) => (span.start, span.end),
+            None => {
+                let anchor = text::line_offset(text, line.line, line.column);
+                (anchor, text.len())
+            }
+        };
+        let candidates: Vec<_> = references(text)?
+            .into_iter()
+            .filter(|reference| reference.name == symbol.name && reference.qualifier.is_none())
+            .collect();
+        // the name follows the keyword of a declaration
+        if let Some(found) = candidates
+            .iter()
+            .find(|reference| reference.start >= anchor && reference.start < limit)
+        {
+            return Some((found.start, found.end));
+        }
+        // fields point at their type, the name is in front of it
+        candidates
+            .iter()
+            .min_by_key(|reference| reference.start.abs_diff(anchor))
+            .map(|reference| (reference.start, reference.end))
+    }
+}
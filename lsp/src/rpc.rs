@@ -0,0 +1,116 @@
+//! JSON-RPC messages over stdio
+//!
+//! Editors frame every message with a `Content-Length` header,
+//! scripts write one message per line (`--script`).
+
+use std::io::{BufRead, Write};
+
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Framing {
+    /// `Content-Length: n\r\n\r\n{...}`
+    Headers,
+    /// one JSON object per line
+    Lines,
+}
+
+pub struct Transport<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    framing: Framing,
+}
+
+impl<R: BufRead, W: Write> Transport<R, W> {
+    pub fn new(input: R, output: W, framing: Framing) -> Self {
+        Self {
+            input,
+            output,
+            framing,
+        }
+    }
+
+    /// next message, None at the end of the input
+    pub fn read(&mut self) -> Option<Result<Value, String>> {
+        let body = match self.framing {
+            Framing::Headers => self.read_framed()?,
+            Framing::Lines => loop {
+                let mut line = String::new();
+                match self.input.read_line(&mut line) {
+                    Ok(0) => return None,
+                    Ok(_) => {}
+                    Err(err) => return Some(Err(err.to_string())),
+                }
+                // empty lines separate the steps of a script
+                if !line.trim().is_empty() {
+                    break Ok(line);
+                }
+            },
+        };
+        Some(body.and_then(|body| {
+            serde_json::from_str(&body).map_err(|err| format!("invalid message: {err}"))
+        }))
+    }
+
+    fn read_framed(&mut self) -> Option<Result<String, String>> {
+        let mut length = None;
+        loop {
+            let mut header = String::new();
+            match self.input.read_line(&mut header) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(err) => return Some(Err(err.to_string())),
+            }
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length:") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+        let length = match length {
+            Some(length) => length,
+            None => return Some(Err("message without Content-Length".to_string())),
+        };
+        let mut body = vec![0; length];
+        if let Err(err) = self.input.read_exact(&mut body) {
+            return Some(Err(err.to_string()));
+        }
+        Some(String::from_utf8(body).map_err(|err| err.to_string()))
+    }
+
+    pub fn write(&mut self, message: &Value) {
+        let body = message.to_string();
+        let res = match self.framing {
+            Framing::Headers => write!(self.output, "Content-Length: {}\r\n\r\n{body}", body.len()),
+            Framing::Lines => writeln!(self.output, "{body}"),
+        };
+        if res.and_then(|_| self.output.flush()).is_err() {
+            eprintln!("ruda-lsp: could not write to the client");
+        }
+    }
+
+    pub fn respond(&mut self, id: Value, result: Value) {
+        self.write(&json!({ "jsonrpc": "2.0", "id": id, "result": result }));
+    }
+
+    pub fn respond_err(&mut self, id: Value, code: i64, message: &str) {
+        self.write(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message },
+        }));
+    }
+
+    pub fn notify(&mut self, method: &str, params: Value) {
+        self.write(&json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+}
+
+/// method of the request is not supported
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// parameters of the request are missing or malformed
+pub const INVALID_PARAMS: i64 = -32602;
+/// request arrived before `initialize`
+pub const SERVER_NOT_INITIALIZED: i64 = -32002;
@@ -0,0 +1,477 @@
+//! Requests and notifications of the protocol
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use compiler::diagnostic::{Diagnostic, Severity};
+use compiler::prep_objects::Context;
+use compiler::symbols::{Symbol, SymbolKind};
+use compiler::Asts;
+use serde_json::{json, Value};
+
+use crate::project::{self, find_main, Project};
+use crate::rpc::{self, Transport};
+use crate::text;
+
+const KEYWORDS: [&str; 31] = [
+    "let", "const", "type", "if", "else", "switch", "match", "while", "for", "step", "loop",
+    "break", "continue", "fun", "return", "overload", "inline", "noinline", "struct", "enum",
+    "impl", "trait", "import", "use", "pub", "new", "as", "try", "catch", "yeet", "error",
+];
+
+pub struct Server {
+    asts: Asts,
+    /// binaries of the standard library, dictionaries are replaced on every run
+    context: Context,
+    /// symbols of the binaries by the name they are imported by (`#io`)
+    libraries: HashMap<String, Vec<Symbol>>,
+    /// text of the open documents
+    documents: HashMap<PathBuf, String>,
+    /// programs by their main file
+    projects: HashMap<PathBuf, Project>,
+    initialized: bool,
+    shutdown: bool,
+}
+
+impl Server {
+    pub fn new(asts: Asts, context: Context, libraries: HashMap<String, Vec<Symbol>>) -> Self {
+        Self {
+            asts,
+            context,
+            libraries,
+            documents: HashMap::new(),
+            projects: HashMap::new(),
+            initialized: false,
+            shutdown: false,
+        }
+    }
+
+    /// handles messages until `exit` or the end of the input, returns the exit code
+    pub fn run<R: BufRead, W: Write>(&mut self, transport: &mut Transport<R, W>) -> i32 {
+        while let Some(message) = transport.read() {
+            let message = match message {
+                Ok(message) => message,
+                Err(err) => {
+                    eprintln!("ruda-lsp: {err}");
+                    continue;
+                }
+            };
+            let method = message["method"].as_str().unwrap_or("").to_string();
+            let params = message.get("params").cloned().unwrap_or(Value::Null);
+            match message.get("id").cloned() {
+                Some(id) => self.request(transport, id, &method, &params),
+                None => {
+                    if method == "exit" {
+                        return if self.shutdown { 0 } else { 1 };
+                    }
+                    self.notification(transport, &method, &params);
+                }
+            }
+        }
+        if self.shutdown {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn request<R: BufRead, W: Write>(
+        &mut self,
+        transport: &mut Transport<R, W>,
+        id: Value,
+        method: &str,
+        params: &Value,
+    ) {
+        if method == "initialize" {
+            self.initialized = true;
+            transport.respond(id, capabilities());
+            return;
+        }
+        if !self.initialized {
+            transport.respond_err(id, rpc::SERVER_NOT_INITIALIZED, "server not initialized");
+            return;
+        }
+        let result = match method {
+            "shutdown" => {
+                self.shutdown = true;
+                Some(Value::Null)
+            }
+            "textDocument/hover" => self.hover(params),
+            "textDocument/definition" => self.definition(params),
+            "textDocument/references" => self.references(params),
+            "textDocument/completion" => self.completion(params),
+            "textDocument/documentSymbol" => self.document_symbols(params),
+            _ => {
+                transport.respond_err(id, rpc::METHOD_NOT_FOUND, &format!("unknown method '{method}'"));
+                return;
+            }
+        };
+        match result {
+            Some(result) => transport.respond(id, result),
+            None => transport.respond_err(id, rpc::INVALID_PARAMS, "invalid params"),
+        }
+    }
+
+    fn notification<R: BufRead, W: Write>(
+        &mut self,
+        transport: &mut Transport<R, W>,
+        method: &str,
+        params: &Value,
+    ) {
+        let path = match document_path(params) {
+            Some(path) => path,
+            None => return,
+        };
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or("");
+                self.documents.insert(path.clone(), text.to_string());
+            }
+            "textDocument/didChange" => {
+                // the whole document is sent on every change
+                let changes = params["contentChanges"].as_array();
+                if let Some(text) = changes.and_then(|changes| changes.last()) {
+                    let text = text["text"].as_str().unwrap_or("");
+                    self.documents.insert(path.clone(), text.to_string());
+                }
+            }
+            "textDocument/didSave" => {}
+            "textDocument/didClose" => {
+                self.documents.remove(&path);
+            }
+            _ => return,
+        }
+        self.analyze(transport, &path);
+    }
+
+    /// compiles the program of the document and publishes its diagnostics
+    fn analyze<R: BufRead, W: Write>(&mut self, transport: &mut Transport<R, W>, path: &Path) {
+        let main = find_main(path);
+        let project = self
+            .projects
+            .entry(main.clone())
+            .or_insert_with(|| Project::new(main));
+        let diagnostics = project.analyze(&mut self.asts, &mut self.context, &self.documents);
+        let mut files: Vec<_> = diagnostics.keys().cloned().collect();
+        // the document gets a fresh list even if nothing was reported before
+        if let Some(module) = project.module_of(path) {
+            files.push(module);
+        }
+        for file in &project.reported {
+            files.push(file.clone());
+        }
+        files.sort();
+        files.dedup();
+        for file in &files {
+            let path = project.path_of(file);
+            let text = source(&self.documents, &path).unwrap_or_default();
+            let uri = text::path_to_uri(&path);
+            let items: Vec<Value> = diagnostics
+                .get(file)
+                .map(|diagnostics| diagnostics.iter().map(|d| diagnostic(d, &text, &uri)).collect())
+                .unwrap_or_default();
+            transport.notify(
+                "textDocument/publishDiagnostics",
+                json!({ "uri": uri, "diagnostics": items }),
+            );
+        }
+        project.reported = diagnostics.into_keys().collect();
+    }
+
+    /// project, module and text of the document and the byte offset of the position
+    fn locate(&self, params: &Value) -> Option<(&Project, String, String, usize)> {
+        let path = document_path(params)?;
+        let project = self.projects.get(&find_main(&path))?;
+        let module = project.module_of(&path)?;
+        let text = source(&self.documents, &path)?;
+        let offset = text::offset(&text, &params["position"])?;
+        Some((project, module, text, offset))
+    }
+
+    fn hover(&self, params: &Value) -> Option<Value> {
+        let (project, module, text, offset) = match self.locate(params) {
+            Some(found) => found,
+            None => return Some(Value::Null),
+        };
+        let reference = match project::reference_at(&text, offset) {
+            Some(reference) => reference,
+            None => return Some(Value::Null),
+        };
+        let symbol = match project.resolve(&self.libraries, &module, &reference) {
+            Some(symbol) => symbol,
+            None => return Some(Value::Null),
+        };
+        Some(json!({
+            "contents": { "kind": "markdown", "value": markdown(&symbol) },
+            "range": text::range(&text, reference.start, reference.end),
+        }))
+    }
+
+    fn definition(&self, params: &Value) -> Option<Value> {
+        let (project, module, text, offset) = match self.locate(params) {
+            Some(found) => found,
+            None => return Some(Value::Null),
+        };
+        let symbol = project::reference_at(&text, offset)
+            .and_then(|reference| project.resolve(&self.libraries, &module, &reference));
+        Some(match symbol.and_then(|symbol| self.location(project, &symbol)) {
+            Some(location) => location,
+            None => Value::Null,
+        })
+    }
+
+    /// place the symbol is declared at, None for items of binary libraries
+    fn location(&self, project: &Project, symbol: &Symbol) -> Option<Value> {
+        if symbol.file.starts_with('#') {
+            return None;
+        }
+        let path = project.path_of(&symbol.file);
+        let text = source(&self.documents, &path)?;
+        let (start, end) = project.name_span(symbol, &text)?;
+        Some(json!({
+            "uri": text::path_to_uri(&path),
+            "range": text::range(&text, start, end),
+        }))
+    }
+
+    fn references(&self, params: &Value) -> Option<Value> {
+        let (project, module, text, offset) = match self.locate(params) {
+            Some(found) => found,
+            None => return Some(Value::Null),
+        };
+        let target = project::reference_at(&text, offset)
+            .and_then(|reference| project.resolve(&self.libraries, &module, &reference));
+        let target = match target {
+            Some(target) => target,
+            None => return Some(Value::Null),
+        };
+        let declaration = match params["context"]["includeDeclaration"].as_bool() {
+            Some(true) => None,
+            _ => self.location(project, &target),
+        };
+        let mut modules: Vec<_> = project.modules.keys().cloned().collect();
+        modules.sort();
+        let mut locations = Vec::new();
+        for module in modules {
+            let path = project.path_of(&module);
+            let text = match source(&self.documents, &path) {
+                Some(text) => text,
+                None => continue,
+            };
+            let references = project::references(&text).unwrap_or_default();
+            for reference in references.iter().filter(|r| r.name == target.name) {
+                let found = project.resolve(&self.libraries, &module, reference);
+                if !found.is_some_and(|found| project::same(&found, &target)) {
+                    continue;
+                }
+                let location = json!({
+                    "uri": text::path_to_uri(&path),
+                    "range": text::range(&text, reference.start, reference.end),
+                });
+                if Some(&location) != declaration.as_ref() {
+                    locations.push(location);
+                }
+            }
+        }
+        Some(Value::Array(locations))
+    }
+
+    fn completion(&self, params: &Value) -> Option<Value> {
+        let (project, module, text, offset) = match self.locate(params) {
+            Some(found) => found,
+            None => return Some(json!([])),
+        };
+        let mut items = Vec::new();
+        match qualifier_before(&text, offset) {
+            Some(qualifier) => {
+                let members = project
+                    .members_of_import(&self.libraries, &module, &qualifier)
+                    .or_else(|| project.lookup(&module, &qualifier).map(|parent| parent.children));
+                for symbol in members.unwrap_or_default() {
+                    items.push(completion_item(&symbol));
+                }
+            }
+            None => {
+                if let Some(current) = project.modules.get(&module) {
+                    for symbol in &current.symbols {
+                        items.push(completion_item(symbol));
+                    }
+                }
+                for keyword in KEYWORDS {
+                    // Keyword
+                    items.push(json!({ "label": keyword, "kind": 14 }));
+                }
+            }
+        }
+        Some(Value::Array(items))
+    }
+
+    fn document_symbols(&self, params: &Value) -> Option<Value> {
+        let path = document_path(params)?;
+        let project = match self.projects.get(&find_main(&path)) {
+            Some(project) => project,
+            None => return Some(json!([])),
+        };
+        let module = project.module_of(&path)?;
+        let text = source(&self.documents, &path)?;
+        let symbols = match project.modules.get(&module) {
+            Some(current) => &current.symbols,
+            None => return Some(json!([])),
+        };
+        let items = symbols
+            .iter()
+            .filter(|symbol| symbol.kind != SymbolKind::Module)
+            .filter_map(|symbol| document_symbol(project, symbol, &text))
+            .collect();
+        Some(Value::Array(items))
+    }
+}
+
+fn capabilities() -> Value {
+    json!({
+        "capabilities": {
+            // the whole document is sent on every change
+            "textDocumentSync": { "openClose": true, "change": 1, "save": true },
+            "hoverProvider": true,
+            "definitionProvider": true,
+            "referencesProvider": true,
+            "documentSymbolProvider": true,
+            "completionProvider": { "triggerCharacters": ["."] },
+        },
+        "serverInfo": { "name": "ruda-lsp", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+fn document_path(params: &Value) -> Option<PathBuf> {
+    text::uri_to_path(params["textDocument"]["uri"].as_str()?)
+}
+
+/// open document or the file on the disk
+fn source(documents: &HashMap<PathBuf, String>, path: &Path) -> Option<String> {
+    match documents.get(path) {
+        Some(text) => Some(text.clone()),
+        None => std::fs::read_to_string(path).ok(),
+    }
+}
+
+/// identifier in front of the dot before the word being typed, `io` of `io.pri`
+fn qualifier_before(text: &str, offset: usize) -> Option<String> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let before = text.get(..offset)?;
+    let word = before.trim_end_matches(is_ident);
+    let before_dot = word.strip_suffix('.')?;
+    let qualifier = &before_dot[before_dot.trim_end_matches(is_ident).len()..];
+    if qualifier.is_empty() {
+        None
+    } else {
+        Some(qualifier.to_string())
+    }
+}
+
+fn diagnostic(diagnostic: &Diagnostic, text: &str, uri: &str) -> Value {
+    let (start, end) = diagnostic
+        .primary
+        .as_ref()
+        .and_then(|label| label.span(text))
+        .unwrap_or((0, 0));
+    let mut message = diagnostic.message.clone();
+    for note in &diagnostic.notes {
+        message.push_str(&format!("\nnote: {note}"));
+    }
+    if let Some(help) = &diagnostic.help {
+        message.push_str(&format!("\nhelp: {help}"));
+    }
+    let severity = match diagnostic.severity {
+        Severity::Error => 1,
+        Severity::Warning => 2,
+    };
+    let mut related = Vec::new();
+    for label in &diagnostic.secondary {
+        if let Some((start, end)) = label.span(text) {
+            related.push(json!({
+                "location": { "uri": uri, "range": text::range(text, start, end) },
+                "message": label.message,
+            }));
+        }
+    }
+    let mut value = json!({
+        "range": text::range(text, start, end),
+        "severity": severity,
+        "code": diagnostic.code,
+        "source": "ruda",
+        "message": message,
+    });
+    if !related.is_empty() {
+        value["relatedInformation"] = Value::Array(related);
+    }
+    value
+}
+
+fn markdown(symbol: &Symbol) -> String {
+    let mut value = format!("```ruda\n{}\n```", symbol.detail);
+    if let Some(docs) = &symbol.docs {
+        value.push_str("\n\n");
+        value.push_str(docs.trim_end());
+    }
+    value
+}
+
+fn completion_item(symbol: &Symbol) -> Value {
+    let kind = match symbol.kind {
+        SymbolKind::Function => 3,
+        SymbolKind::Method => 2,
+        SymbolKind::Struct | SymbolKind::Error => 22,
+        SymbolKind::Field => 5,
+        SymbolKind::Enum => 13,
+        SymbolKind::Variant => 20,
+        SymbolKind::Trait => 8,
+        SymbolKind::Type | SymbolKind::UserData => 7,
+        SymbolKind::Constant => 21,
+        SymbolKind::Module => 9,
+    };
+    let mut item = json!({ "label": symbol.name, "kind": kind, "detail": symbol.detail });
+    if let Some(docs) = &symbol.docs {
+        item["documentation"] = json!({ "kind": "markdown", "value": docs.trim_end() });
+    }
+    item
+}
+
+fn document_symbol(project: &Project, symbol: &Symbol, text: &str) -> Option<Value> {
+    let line = symbol.line?;
+    let (start, end) = match line.span() {
+        Some(span) => (span.start, span.end),
+        None => {
+            let start = text::line_offset(text, line.line, line.column);
+            (start, start)
+        }
+    };
+    let (name_start, name_end) = project.name_span(symbol, text).unwrap_or((start, start));
+    let kind = match symbol.kind {
+        SymbolKind::Function => 12,
+        SymbolKind::Method => 6,
+        SymbolKind::Struct | SymbolKind::Error => 23,
+        SymbolKind::Field => 8,
+        SymbolKind::Enum => 10,
+        SymbolKind::Variant => 22,
+        SymbolKind::Trait => 11,
+        SymbolKind::Type | SymbolKind::UserData => 5,
+        SymbolKind::Constant => 14,
+        SymbolKind::Module => 2,
+    };
+    // the range has to contain the name
+    let (start, end) = (start.min(name_start), end.max(name_end));
+    let children: Vec<Value> = symbol
+        .children
+        .iter()
+        .filter_map(|child| document_symbol(project, child, text))
+        .collect();
+    Some(json!({
+        "name": symbol.name,
+        "detail": symbol.detail,
+        "kind": kind,
+        "range": text::range(text, start, end),
+        "selectionRange": text::range(text, name_start, name_end),
+        "children": children,
+    }))
+}
@@ -0,0 +1,55 @@
+# runs the scripted sessions in tests/ and compares the responses
+#
+# every directory in tests/ is a Ruda project with a session.jsonl (messages sent to the server)
+# and an expected.jsonl (messages the server has to answer with), $ROOT is replaced
+# with the path of the project in both
+#
+# RUDA_PATH has to point to a Ruda installation with the standard library
+import os
+import subprocess
+import sys
+
+lsp_dir = os.path.dirname(os.path.abspath(__file__))
+tests_dir = os.path.join(lsp_dir, "tests")
+
+if "RUDA_PATH" not in os.environ:
+    print("Error: RUDA_PATH not set")
+    exit(1)
+
+if os.system("cd " + lsp_dir + " && cargo build") != 0:
+    print("Error: could not build ruda-lsp")
+    exit(1)
+
+executable = os.path.join(lsp_dir, "target", "debug", "ruda-lsp")
+failed = 0
+
+for test in sorted(os.listdir(tests_dir)):
+    root = os.path.join(tests_dir, test)
+    if not os.path.isdir(root):
+        continue
+    with open(os.path.join(root, "session.jsonl")) as file:
+        session = file.read().replace("$ROOT", root)
+    with open(os.path.join(root, "expected.jsonl")) as file:
+        expected = file.read().replace("$ROOT", root).splitlines()
+
+    result = subprocess.run([executable, "--script"], input=session, capture_output=True, text=True)
+    output = result.stdout.splitlines()
+
+    if output == expected and result.returncode == 0:
+        print(test + ": OK")
+        continue
+    failed += 1
+    print(test + ": FAIL (exit code " + str(result.returncode) + ")")
+    for i in range(max(len(output), len(expected))):
+        got = output[i] if i < len(output) else "<nothing>"
+        want = expected[i] if i < len(expected) else "<nothing>"
+        if got != want:
+            print("  message " + str(i + 1))
+            print("    expected: " + want)
+            print("    got:      " + got)
+    if result.stderr:
+        print(result.stderr)
+
+if failed > 0:
+    print(str(failed) + " test(s) failed")
+    sys.exit(1)
@@ -46,6 +46,18 @@ pub enum Task {
         #[clap(name = "debug", long)]
         debug: bool,
 
+        /// How compiler errors are printed
+        #[clap(name = "message-format", long, default_value = "human")]
+        message_format: MessageFormat,
+
+        /// Lints that fail the build, `warnings` denies all of them
+        #[clap(name = "deny", short = 'D', long)]
+        deny: Vec<String>,
+
+        /// Print how long each phase of the compilation took
+        #[clap(name = "timings", long)]
+        timings: bool,
+
         /// Runtime arguments for the VM
         #[clap(name = "args", last = true)]
         args: Vec<String>,
@@ -59,6 +71,36 @@ pub enum Task {
         /// Path to project
         #[clap(name = "path", default_value = ".")]
         path: String,
+
+        /// How compiler errors are printed
+        #[clap(name = "message-format", long, default_value = "human")]
+        message_format: MessageFormat,
+
+        /// Lints that fail the build, `warnings` denies all of them
+        #[clap(name = "deny", short = 'D', long)]
+        deny: Vec<String>,
+
+        /// Print how long each phase of the compilation took
+        #[clap(name = "timings", long)]
+        timings: bool,
+    },
+    /// Report warnings about the sources of a project
+    Lint {
+        /// Profile to use
+        #[clap(name = "profile", short, long, default_value = "default")]
+        profile: String,
+
+        /// Path to project
+        #[clap(name = "path", default_value = ".")]
+        path: String,
+
+        /// How warnings are printed
+        #[clap(name = "message-format", long, default_value = "human")]
+        message_format: MessageFormat,
+
+        /// Lints that fail the build, `warnings` denies all of them
+        #[clap(name = "deny", short = 'D', long)]
+        deny: Vec<String>,
     },
     /// Install a package
     Install {
@@ -117,6 +159,40 @@ pub enum Task {
         #[clap(name = "args", last = true)]
         args: Vec<String>,
     },
+    /// Format the sources of a project
+    Fmt {
+        /// Path to project or a single file
+        #[clap(name = "path", default_value = ".")]
+        path: String,
+
+        /// only check if the sources are formatted, fails if they are not
+        #[clap(name = "check", long)]
+        check: bool,
+    },
+    /// Generate documentation of a project, its dependencies and the standard library
+    Doc {
+        /// Profile to use
+        #[clap(name = "profile", short, long, default_value = "default")]
+        profile: String,
+
+        /// Path to project
+        #[clap(name = "path", default_value = ".")]
+        path: String,
+
+        /// Write only one format, both are written without it
+        #[clap(name = "format", long)]
+        format: Option<DocFormat>,
+
+        /// Document the private items of the project too
+        #[clap(name = "private", long)]
+        private: bool,
+
+        /// Document only the project, not its dependencies and the standard library
+        #[clap(name = "no-deps", long)]
+        no_deps: bool,
+    },
+    /// Compile and run statements, expressions and declarations as they are entered
+    Repl,
     /// Get an insight of the project with graphical representation
     Lens {
         /// Target to lens
@@ -150,3 +226,28 @@ pub enum LensTarget {
     /// Standard library
     Std,
 }
+
+#[derive(Debug, ValueEnum, Clone, Copy)]
+pub enum DocFormat {
+    /// Static site with search, `target/doc/html`
+    Html,
+    /// Markdown files, `target/doc/markdown`
+    Markdown,
+}
+
+#[derive(Debug, ValueEnum, Clone, Copy)]
+pub enum MessageFormat {
+    /// Source excerpts pointing at the errors
+    Human,
+    /// One JSON object per error
+    Json,
+}
+
+impl From<MessageFormat> for compiler::diagnostic::MessageFormat {
+    fn from(format: MessageFormat) -> Self {
+        match format {
+            MessageFormat::Human => compiler::diagnostic::MessageFormat::Human,
+            MessageFormat::Json => compiler::diagnostic::MessageFormat::Json,
+        }
+    }
+}
@@ -3,45 +3,80 @@ use crate::config;
 use crate::config::Profile;
 use crate::remote;
 use crate::run;
+use compiler::diagnostic::{self, MessageFormat};
+use compiler::lint;
 
-pub fn run(path: &str, profile: &str, _args: Vec<String>, debug: bool) {
+pub fn run(
+    path: &str,
+    profile: &str,
+    _args: Vec<String>,
+    debug: bool,
+    format: MessageFormat,
+    deny: &[String],
+    timings: bool,
+) {
     let config = config::read(path);
     let profile = match config.profile.get(profile) {
         Some(prof) => (profile, prof),
         None => {
-            println!("Profile \"{}\" not found", profile);
+            diagnostic::status(format, format!("Profile \"{}\" not found", profile));
             std::process::exit(1);
         }
     };
     if let config::ProjectKind::Lib = profile.1.kind {
-        println!("Cannot run a library");
+        diagnostic::status(format, "Cannot run a library");
         std::process::exit(1);
     }
     // build dependencies
-    build_deps(&profile.1, profile.1._3rdparty as usize);
+    build_deps(&profile.1, profile.1._3rdparty as usize, format);
     // compile
-    if compile::compile(path, profile) {
+    if compile::compile(path, profile, format, &lints(profile.1, deny), timings) {
         run::run(path, &profile, &_args, debug);
     }
 }
 
-pub fn build(path: &str, profile: &str) -> bool {
+pub fn build(path: &str, profile: &str, format: MessageFormat, deny: &[String], timings: bool) -> bool {
     let config = config::read(path);
     let profile = match config.profile.get(profile) {
         Some(prof) => (profile, prof),
         None => {
-            println!("Profile \"{}\" not found", profile);
+            diagnostic::status(format, format!("Profile \"{}\" not found", profile));
             std::process::exit(1);
         }
     };
     // build dependencies
-    build_deps(&profile.1, config._3rdparty as usize);
+    build_deps(&profile.1, config._3rdparty as usize, format);
     // compile
-    compile::compile(path, profile)
+    compile::compile(path, profile, format, &lints(profile.1, deny), timings)
+}
+
+/// reports warnings of a project without building it
+pub fn lint(path: &str, profile: &str, format: MessageFormat, deny: &[String]) -> bool {
+    let config = config::read(path);
+    let profile = match config.profile.get(profile) {
+        Some(prof) => (profile, prof),
+        None => {
+            diagnostic::status(format, format!("Profile \"{}\" not found", profile));
+            std::process::exit(1);
+        }
+    };
+    compile::lint(path, profile, format, &lints(profile.1, deny))
+}
+
+/// lint levels of the profile with the lints given by `-D` denied
+fn lints(profile: &Profile, deny: &[String]) -> lint::Config {
+    let mut lints = profile.lints.clone();
+    for name in deny {
+        if let Err(err) = lints.set(name, lint::Level::Deny) {
+            println!("{}", err);
+            std::process::exit(1);
+        }
+    }
+    lints
 }
 
 /// Build dependencies for a profile
-pub fn build_deps(profile: &Profile, _3rdparty: usize) {
+pub fn build_deps(profile: &Profile, _3rdparty: usize, format: MessageFormat) {
     for dep in &profile.dependencies {
         let mut path = remote::path(&dep.1.path, &"latest");
         // exists?
@@ -49,7 +84,7 @@ pub fn build_deps(profile: &Profile, _3rdparty: usize) {
             if remote::is_remote(&dep.1.path) {
                 path = remote::install(&dep.1.path, &"latest");
             } else {
-                println!("Dependency {} not found", dep.1.path);
+                diagnostic::status(format, format!("Dependency {} not found", dep.1.path));
                 std::process::exit(1);
             }
         }
@@ -62,12 +97,15 @@ pub fn build_deps(profile: &Profile, _3rdparty: usize) {
             let this_3rdparty = config._3rdparty as usize;
             // only for debug
             if this_3rdparty < _3rdparty {
-                println!("Dependency {} is not allowed", dep.1.path);
-                println!(
-                    "{} is \"{}\" level, but current project allows \"{}\" level",
-                    dep.1.path,
-                    config::_3rdparty::to_str(this_3rdparty),
-                    config::_3rdparty::to_str(_3rdparty)
+                diagnostic::status(format, format!("Dependency {} is not allowed", dep.1.path));
+                diagnostic::status(
+                    format,
+                    format!(
+                        "{} is \"{}\" level, but current project allows \"{}\" level",
+                        dep.1.path,
+                        config::_3rdparty::to_str(this_3rdparty),
+                        config::_3rdparty::to_str(_3rdparty)
+                    ),
                 );
                 std::process::exit(1);
             }
@@ -76,17 +114,17 @@ pub fn build_deps(profile: &Profile, _3rdparty: usize) {
             let profile = match config.profile.get("default") {
                 Some(prof) => (dep.0, prof),
                 None => {
-                    println!("Profile \"{}\" not found in {}", "default", path);
+                    diagnostic::status(format, format!("Profile \"{}\" not found in {}", "default", path));
                     std::process::exit(1);
                 }
             };
             // build dependencies
-            build_deps(&profile.1, this_3rdparty);
+            build_deps(&profile.1, this_3rdparty, format);
             // compile
-            compile::compile(&path, (profile.0, profile.1));
+            compile::compile(&path, (profile.0, profile.1), format, &profile.1.lints, false);
         } else {
             // err
-            println!("Dependency {} is not a package", dep.1.path);
+            diagnostic::status(format, format!("Dependency {} is not a package", dep.1.path));
             std::process::exit(1);
         }
     }
@@ -110,8 +148,8 @@ pub fn restore(path: &str, profile: &str, compile: bool, run: bool, args: Vec<St
     }
     // compile
     if compile || run {
-        build_deps(&profile.1, profile.1._3rdparty as usize);
-        if run && compile::compile(path, profile){
+        build_deps(&profile.1, profile.1._3rdparty as usize, MessageFormat::Human);
+        if run && compile::compile(path, profile, MessageFormat::Human, &profile.1.lints, false) {
             run::run(path, &profile, &args, debug);
         }
     }
@@ -2,9 +2,19 @@ use std::{collections::HashMap, process::id};
 
 use crate::{config::{self, Runtime}, sum};
 
+use compiler::cache::Cache;
+use compiler::diagnostic::{self, Diagnostic, MessageFormat};
+use compiler::lint;
 use compiler::prep_objects::Context;
+use compiler::*;
 
-pub fn compile(path: &str, profile: (&str, &config::Profile)) -> bool{
+pub fn compile(
+    path: &str,
+    profile: (&str, &config::Profile),
+    format: MessageFormat,
+    lints: &lint::Config,
+    timings: bool,
+) -> bool {
     // determine if we have to compile for current profile
     let mut compile = false;
     // check if there is directory for the profile
@@ -22,14 +32,86 @@ pub fn compile(path: &str, profile: (&str, &config::Profile)) -> bool{
         compile = true;
     }
     if !compile {
+        if timings {
+            diagnostic::status(format, format!("Sources did not change, reusing {}", profile_path.join("out.rdbin").display()));
+        }
         return true;
     }
-    // compile
+    diagnostic::status(format, format!("Compiling... {} {}", path, profile.0));
+    let mut timer = Timings::new();
+    let (mut context, src) = match check(path, profile, format, &mut timer) {
+        Some(checked) => checked,
+        None => return false,
+    };
+    if !report_lints(&context, lints, format, &src) {
+        diagnostic::status(format, "Lints failed.");
+        return false;
+    }
+    timer.phase("lints", String::new());
+    let options = codegen::Options {
+        inline: profile.1.inline,
+        ..Default::default()
+    };
+    let executable = match codegen::gen(&mut context, "main.rd", &options) {
+        Ok(ctx) => {
+            // println!("{:?}", ctx.code.data);
+            // println!("{:?}", ctx.code.entry_point);
+            // println!("{:?}", ctx.memory.heap.data);
+            // println!("{:?}", ctx.memory.stack.data);
+            // println!("{:?}", ctx.memory.strings.pool);
+            // println!("{:?}", ctx.memory.non_primitives);
+
+            let code = codegen::stringify(&ctx, &Vec::new());
+            code
+        }
+        Err(err) => {
+            diagnostic::status(format, "Failed to generate code.");
+            diagnostic::emit(&[Diagnostic::from(&err)], format, &src);
+            return false;
+        }
+    };
+
+
+    timer.phase("codegen", String::new());
+
+    {
+        let mut path = std::path::Path::new(path).join("target").join(profile.0);
+        path = path.join("out.rdbin");
+        std::fs::write(path, executable).unwrap();
+    }
+
+    // TODO: uncomment for prod
+    sum::write_sums(path, profile.0, &sum::sum(path, profile.0));
+    timer.phase("write", String::new());
+    if timings {
+        timer.print(format);
+    }
+
+
+    true
+}
+
+/// reports warnings of the project, fails if any of them is denied or the project has errors
+pub fn lint(path: &str, profile: (&str, &config::Profile), format: MessageFormat, lints: &lint::Config) -> bool {
+    diagnostic::status(format, format!("Linting... {} {}", path, profile.0));
+    match check(path, profile, format, &mut Timings::new()) {
+        Some((context, src)) => report_lints(&context, lints, format, &src),
+        None => false,
+    }
+}
+
+/// loads and type checks the project, returns it with the directory of its sources
+fn check(
+    path: &str,
+    profile: (&str, &config::Profile),
+    format: MessageFormat,
+    timer: &mut Timings,
+) -> Option<(Context, std::path::PathBuf)> {
     let ruda_path = match std::env::var("RUDA_PATH") {
         Ok(path) => path,
         Err(err) => {
-            println!("RUDA_PATH not found. {}\nProject not compiled.", err);
-            return false;
+            diagnostic::status(format, format!("RUDA_PATH not found. {}\nProject not compiled.", err));
+            return None;
         }
     };
     let main_file = match profile.1.kind {
@@ -45,30 +127,39 @@ pub fn compile(path: &str, profile: (&str, &config::Profile)) -> bool{
     let main_file = match main_file.to_str() {
         Some(file) => file,
         None => {
-            println!("Failed to convert path to string.");
-            return false;
+            diagnostic::status(format, "Failed to convert path to string.");
+            return None;
         }
     };
-    println!("Compiling... {} {}", path, profile.0);
-    use compiler::*;
+    // modules are named relative to the directory of the main file
+    let src = std::path::Path::new(path).join("src");
     let (ast, params, registry) = match generate_ast(&ruda_path) {
         Ok(ast) => (ast.ast, ast.params, ast.registry),
         Err(err) => {
-            println!("{}", err);
-            println!("Close all programs that use Ruda and try again.");
-            println!("If that doesn't help, try to reinstall Ruda.");
-            return false;
+            diagnostic::status(format, err);
+            diagnostic::status(format, "Close all programs that use Ruda and try again.");
+            diagnostic::status(format, "If that doesn't help, try to reinstall Ruda.");
+            return None;
         }
     };
+    let mut cache = Cache::open(
+        std::path::Path::new(path).join("target").join(profile.0).join("cache"),
+        &cache_salt(&ruda_path),
+    );
+    timer.phase("grammar", String::new());
     //println!("AST generated.");
-    let dictionaries = match build_dictionaries(&main_file, &mut (ast, params)) {
+    let (dictionaries, import_graph) = match build_import_graph_cached(&main_file, &mut (ast, params), &mut cache) {
         Ok(dictionaries) => dictionaries,
         Err(err) => {
-            println!("Failed to load dictionaries.");
-            println!("Err: '{}':{}", err.1, err.0);
-            return false;
+            diagnostic::status(format, "Failed to load dictionaries.");
+            diagnostic::emit(&err.0.diagnostics(&err.1), format, &src);
+            return None;
         }
     };
+    timer.phase(
+        "modules",
+        format!("{} parsed, {} cached", cache.stats.modules_parsed, cache.stats.modules_cached),
+    );
     //println!("Dictionary generated.");
     // println!("{:?}", dictionaries);
     // BEWARE: this part is what you call a technical debt
@@ -77,14 +168,14 @@ pub fn compile(path: &str, profile: (&str, &config::Profile)) -> bool{
     for (lib_name, lib_path) in &profile.1.binaries {
         let lib_path = std::path::Path::new(path).join(lib_path);
         if !lib_path.exists() {
-            println!("{} does not exist.", lib_path.to_str().unwrap());
-            return false;
+            diagnostic::status(format, format!("{} does not exist.", lib_path.to_str().unwrap()));
+            return None;
         }
         let lib_path = match lib_path.to_str() {
             Some(path) => path,
             None => {
-                println!("Failed to convert path to string.");
-                return false;
+                diagnostic::status(format, "Failed to convert path to string.");
+                return None;
             }
         };
         bin_paths.push(lib_path.to_string());
@@ -92,12 +183,12 @@ pub fn compile(path: &str, profile: (&str, &config::Profile)) -> bool{
     }
     let mut temp_ast = (registry, Vec::new());
     let mut binaries = HashMap::new();
-    let mut std_lib = match build_std_lib(&mut temp_ast) {
+    let mut std_lib = match build_std_lib_cached(&mut temp_ast, &mut cache) {
         Ok(std_lib) => std_lib,
         Err(err) => {
-            println!("Failed to load std lib.");
-            println!("{}", err);
-            return false;
+            diagnostic::status(format, "Failed to load std lib.");
+            diagnostic::status(format, err);
+            return None;
         }
     };
     let mut dicts = Vec::new();
@@ -108,12 +199,12 @@ pub fn compile(path: &str, profile: (&str, &config::Profile)) -> bool{
         names.push(take.1);
     }
     drop(std_lib);
-    match build_binaries(&bin_paths, &mut temp_ast, &mut dicts) {
+    match build_binaries_cached(&bin_paths, &mut temp_ast, &mut dicts, &mut cache) {
         Ok(()) => {},
         Err(err) => {
-            println!("Failed to load binaries.");
-            println!("{}", err);
-            return false;
+            diagnostic::status(format, "Failed to load binaries.");
+            diagnostic::status(format, err);
+            return None;
         }
     };
     for _ in 0..names.len() {
@@ -137,46 +228,108 @@ pub fn compile(path: &str, profile: (&str, &config::Profile)) -> bool{
         }
         
     }
+    timer.phase(
+        "libraries",
+        format!("{} loaded, {} cached", cache.stats.libraries_loaded, cache.stats.libraries_cached),
+    );
     //println!("Binaries generated.");
     // println!("{:?}", dicts);
+    if let Err(err) = import_graph.check_libraries(|lib| binaries.contains_key(lib)) {
+        diagnostic::status(format, "Failed to load dictionaries.");
+        diagnostic::emit(&[Diagnostic::from(&err)], format, &src);
+        return None;
+    }
     let mut context = Context::new(dictionaries, binaries);
     match prep_objects::prep(&mut context) {
         Ok(_) => {
         }
         Err(err) => {
-            println!("Failed to prepare objects.");
+            diagnostic::status(format, "Failed to prepare objects.");
             // TODO: println!("{}", err);
-            return false;
+            return None;
         }
     }
-    let executable = match codegen::gen(&mut context, "main.rd") {
-        Ok(ctx) => {
-            // println!("{:?}", ctx.code.data);
-            // println!("{:?}", ctx.code.entry_point);
-            // println!("{:?}", ctx.memory.heap.data);
-            // println!("{:?}", ctx.memory.stack.data);
-            // println!("{:?}", ctx.memory.strings.pool);
-            // println!("{:?}", ctx.memory.non_primitives);
+    timer.phase("prep", String::new());
+    // modules whose sources and imported interfaces did not change passed the check before
+    let keys = cache.module_keys(&context.0, &import_graph);
+    let mut changed = context
+        .0
+        .keys()
+        .filter(|module| keys.get(*module).map_or(true, |key| !cache.is_checked(key)))
+        .cloned()
+        .collect::<Vec<_>>();
+    changed.sort();
+    let type_errors = typecheck::check_files(&context, &changed);
+    timer.phase(
+        "typecheck",
+        format!("{} checked, {} unchanged", changed.len(), context.0.len() - changed.len()),
+    );
+    if type_errors.len() > 0 {
+        diagnostic::status(format, "Type check failed.");
+        let diagnostics: Vec<Diagnostic> = type_errors
+            .iter()
+            .map(|(err, file)| Diagnostic::from(err).in_file(file))
+            .collect();
+        diagnostic::emit(&diagnostics, format, &src);
+        return None;
+    }
+    cache.save_checked(keys.values());
+    Some((context, src))
+}
 
-            let code = codegen::stringify(&ctx, &Vec::new());
-            code
-        }
-        Err(err) => {
-            println!("Failed to generate code: {:?}", err);
-            return false;
-        }
-    };
+/// entries of the cache made by a different grammar or compiler are not used
+fn cache_salt(ruda_path: &str) -> String {
+    let mut salt = String::new();
+    for ast in ["ruda.ast", "registry.ast"] {
+        salt.push_str(&std::fs::read_to_string(std::path::Path::new(ruda_path).join(ast)).unwrap_or_default());
+    }
+    let modified = std::env::current_exe()
+        .and_then(|exe| exe.metadata())
+        .and_then(|metadata| metadata.modified());
+    if let Ok(modified) = modified {
+        salt.push_str(&format!("{:?}", modified));
+    }
+    salt
+}
 
+/// how long each phase of the build took
+pub struct Timings {
+    start: std::time::Instant,
+    last: std::time::Instant,
+    /// name of the phase, its duration and what it did
+    phases: Vec<(&'static str, std::time::Duration, String)>,
+}
 
-    {
-        let mut path = std::path::Path::new(path).join("target").join(profile.0);
-        path = path.join("out.rdbin");
-        std::fs::write(path, executable).unwrap();
+impl Timings {
+    pub fn new() -> Self {
+        let now = std::time::Instant::now();
+        Self {
+            start: now,
+            last: now,
+            phases: Vec::new(),
+        }
     }
 
-    // TODO: uncomment for prod
-    sum::write_sums(path, profile.0, &sum::sum(path, profile.0));
+    /// ends the phase that started when the previous one ended
+    pub fn phase(&mut self, name: &'static str, detail: String) {
+        let now = std::time::Instant::now();
+        self.phases.push((name, now - self.last, detail));
+        self.last = now;
+    }
 
+    pub fn print(&self, format: MessageFormat) {
+        diagnostic::status(format, "Timings:");
+        for (name, duration, detail) in &self.phases {
+            let line = format!("  {:<10} {:>10.2?}  {}", name, duration, detail);
+            diagnostic::status(format, line.trim_end());
+        }
+        diagnostic::status(format, format!("  {:<10} {:>10.2?}", "total", self.last - self.start));
+    }
+}
 
-    true
+/// emits the warnings of the program, returns false if any of them is denied
+fn report_lints(context: &Context, lints: &lint::Config, format: MessageFormat, src: &std::path::Path) -> bool {
+    let (diagnostics, denied) = lint::diagnostics(&lint::lint(context), lints);
+    diagnostic::emit(&diagnostics, format, src);
+    !denied
 }
@@ -1,6 +1,8 @@
 use serde_either::StringOrStruct;
 use std::collections::HashMap;
 
+use compiler::lint;
+
 use crate::remote;
 
 #[derive(serde::Deserialize, Debug)]
@@ -23,6 +25,10 @@ struct TempConfig {
 
     #[serde(default = "HashMap::new")]
     profile: HashMap<String, TempProfile>,
+
+    /// levels of the lints, `unused_variables = "deny"`
+    #[serde(default = "HashMap::new")]
+    lints: HashMap<String, String>,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -33,6 +39,8 @@ struct TempProfile {
     dependencies: HashMap<String, StringOrStruct<TempDependencyTable>>,
     #[serde(default = "HashMap::new")]
     binaries: HashMap<String, String>,
+    /// allows inlining of small functions
+    inline: Option<bool>,
 }
 
 /// describes a dependency
@@ -106,6 +114,8 @@ pub struct Config {
     pub binaries: HashMap<String, String>,
 
     pub profile: HashMap<String, Profile>,
+
+    pub lints: lint::Config,
 }
 
 #[derive(Debug)]
@@ -154,6 +164,8 @@ pub struct Profile {
     pub dependencies: HashMap<String, Dependency>,
     pub binaries: HashMap<String, String>,
     pub kind: ProjectKind,
+    pub inline: bool,
+    pub lints: lint::Config,
 }
 
 fn temp_into_config(path: &str, temp: TempConfig) -> Config {
@@ -169,6 +181,7 @@ fn temp_into_config(path: &str, temp: TempConfig) -> Config {
         dependencies: HashMap::new(),
         binaries: HashMap::new(),
         profile: HashMap::new(),
+        lints: read_lints(path, &temp.lints),
     };
 
     config.dependencies = canonicalize_dependencies(path, &temp.dependencies);
@@ -185,6 +198,8 @@ fn temp_into_config(path: &str, temp: TempConfig) -> Config {
                     dependencies: canonicalize_dependencies(&path, &profile.dependencies),
                     binaries: profile.binaries,
                     kind: config.kind,
+                    inline: profile.inline.unwrap_or(true),
+                    lints: config.lints.clone(),
                 },
             )
         })
@@ -192,6 +207,24 @@ fn temp_into_config(path: &str, temp: TempConfig) -> Config {
 
     config
 }
+/// lints are set in the order of their names, `warnings` first so single lints can override it
+fn read_lints(path: &str, temp: &HashMap<String, String>) -> lint::Config {
+    let mut names = temp.keys().collect::<Vec<_>>();
+    names.sort_by_key(|name| (name.as_str() != lint::ALL, name.as_str()));
+    let mut lints = lint::Config::new();
+    for name in names {
+        let set = temp[name]
+            .parse()
+            .and_then(|level| lints.set(name, level));
+        if let Err(err) = set {
+            println!("{}", err);
+            println!("Failed to parse config file at {}", path);
+            std::process::exit(1);
+        }
+    }
+    lints
+}
+
 fn fix_path(project: &str, path: &str) -> String {
     if remote::is_remote(path) {
         return path.to_string();
@@ -0,0 +1,167 @@
+use std::path::Path;
+
+use compiler::docgen::{self, Module, Package};
+use compiler::*;
+
+use crate::{config, remote};
+
+/// Generate documentation of a project, its dependencies and the standard library
+///
+/// HTML is written to `target/doc/html` and Markdown to `target/doc/markdown`,
+/// `format` picks one of them, both are written without it.
+pub fn doc(path: &str, profile: &str, format: Option<crate::args::DocFormat>, private: bool, no_deps: bool) -> bool {
+    let ruda_path = match std::env::var("RUDA_PATH") {
+        Ok(path) => path,
+        Err(err) => {
+            println!("RUDA_PATH not found. {}", err);
+            return false;
+        }
+    };
+    let config = config::read(path);
+    let profile = match config.profile.get(profile) {
+        Some(prof) => prof,
+        None => {
+            println!("Profile \"{}\" not found", profile);
+            return false;
+        }
+    };
+    let mut registry = match generate_ast(&ruda_path) {
+        Ok(asts) => (asts.registry, Vec::new()),
+        Err(err) => {
+            println!("{}", err);
+            return false;
+        }
+    };
+    println!("Documenting... {} {}", config.name, path);
+    let mut project = match package(&config.name, path, &profile.kind, &ruda_path, private) {
+        Some(project) => project,
+        None => return false,
+    };
+    // binaries of the project are documented with its modules
+    let mut names = profile.binaries.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+    let bin_paths = names
+        .iter()
+        .map(|name| Path::new(path).join(&profile.binaries[name]).to_string_lossy().to_string())
+        .collect();
+    let mut binaries = Vec::new();
+    if let Err(err) = build_binaries(&bin_paths, &mut registry, &mut binaries) {
+        println!("Failed to load binaries.");
+        println!("{}", err);
+        return false;
+    }
+    for (name, binary) in names.iter().zip(binaries.iter()) {
+        project.modules.push(Module::from_library(name, binary));
+    }
+    let mut packages = vec![project];
+    if !no_deps {
+        let mut deps = profile.dependencies.iter().collect::<Vec<_>>();
+        deps.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, dep) in deps {
+            let dep_path = remote::path(&dep.path, "latest");
+            if !config::contains(&dep_path) {
+                println!("Dependency {} not found, it is not documented", dep.path);
+                continue;
+            }
+            let kind = match config::read(&dep_path).profile.get(&dep.profile) {
+                Some(profile) => profile.kind,
+                None => config::ProjectKind::Lib,
+            };
+            match package(name, &dep_path, &kind, &ruda_path, false) {
+                Some(dep) => packages.push(dep),
+                None => return false,
+            }
+        }
+        let mut std_lib = match build_std_lib(&mut registry) {
+            Ok(std_lib) => std_lib,
+            Err(err) => {
+                println!("Failed to load std lib.");
+                println!("{}", err);
+                return false;
+            }
+        };
+        std_lib.sort_by(|a, b| a.1.cmp(&b.1));
+        packages.push(Package {
+            name: "std".to_string(),
+            modules: std_lib
+                .iter()
+                .map(|(lib, name)| Module::from_library(name, lib))
+                .collect(),
+        });
+    }
+    let target = Path::new(path).join("target").join("doc");
+    let outputs = match format {
+        Some(crate::args::DocFormat::Html) => vec![("html", docgen::html(&packages))],
+        Some(crate::args::DocFormat::Markdown) => vec![("markdown", docgen::markdown(&packages))],
+        None => vec![
+            ("html", docgen::html(&packages)),
+            ("markdown", docgen::markdown(&packages)),
+        ],
+    };
+    for (dir, files) in outputs {
+        let dir = target.join(dir);
+        // pages of items that no longer exist are removed
+        if dir.exists() {
+            if let Err(err) = std::fs::remove_dir_all(&dir) {
+                println!("Failed to clear {}. {}", dir.display(), err);
+                return false;
+            }
+        }
+        for (file, contents) in files {
+            let file = dir.join(file);
+            let written = match file.parent() {
+                Some(parent) => std::fs::create_dir_all(parent).and_then(|_| std::fs::write(&file, contents)),
+                None => std::fs::write(&file, contents),
+            };
+            if let Err(err) = written {
+                println!("Failed to write {}. {}", file.display(), err);
+                return false;
+            }
+        }
+        println!("Documentation written to {}", dir.display());
+    }
+    true
+}
+
+/// modules of the project at `path` in the order they are imported
+fn package(
+    name: &str,
+    path: &str,
+    kind: &config::ProjectKind,
+    ruda_path: &str,
+    private: bool,
+) -> Option<Package> {
+    let asts = match generate_ast(ruda_path) {
+        Ok(asts) => asts,
+        Err(err) => {
+            println!("{}", err);
+            return None;
+        }
+    };
+    let src = Path::new(path).join("src");
+    let main_file = match kind {
+        config::ProjectKind::Bin => src.join("main.rd"),
+        config::ProjectKind::Lib => src.join("lib.rd"),
+    };
+    let (dictionaries, graph) = match build_import_graph(&main_file.to_string_lossy(), &mut (asts.ast, asts.params)) {
+        Ok(loaded) => loaded,
+        Err((err, file)) => {
+            println!("Failed to load dictionaries of {}.", name);
+            diagnostic::emit(&err.diagnostics(&file), diagnostic::MessageFormat::Human, &src);
+            return None;
+        }
+    };
+    let modules = graph
+        .modules
+        .iter()
+        .filter_map(|module| {
+            let dictionary = dictionaries.get(module)?;
+            let source = std::fs::read_to_string(src.join(module)).ok();
+            Some(Module::from_dictionary(module, dictionary, source, private))
+        })
+        .collect();
+    Some(Package {
+        name: name.to_string(),
+        modules,
+    })
+}
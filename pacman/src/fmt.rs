@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+
+use compiler::formatter;
+
+/// Format every source of a project, or a single file
+///
+/// With `check` the files are left as they are and the process fails
+/// if any of them is not formatted.
+pub fn fmt(path: &str, check: bool) {
+    let path = Path::new(path);
+    let files = match path.is_file() {
+        true => vec![path.to_path_buf()],
+        false => {
+            // only the sources of a project, dependencies are formatted by their authors
+            let src = path.join("src");
+            let mut files = Vec::new();
+            collect(if src.is_dir() { &src } else { path }, &mut files);
+            files.sort();
+            files
+        }
+    };
+    let mut unformatted = 0;
+    let mut failed = 0;
+    for file in &files {
+        let source = match std::fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(err) => {
+                println!("Could not read {}: {}", file.display(), err);
+                failed += 1;
+                continue;
+            }
+        };
+        let formatted = match formatter::format(&source) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                println!("Could not format {}: {}", file.display(), err);
+                failed += 1;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        unformatted += 1;
+        if check {
+            println!("Not formatted: {}", file.display());
+            continue;
+        }
+        if let Err(err) = std::fs::write(file, formatted) {
+            println!("Could not write {}: {}", file.display(), err);
+            failed += 1;
+            continue;
+        }
+        println!("Formatted: {}", file.display());
+    }
+    if failed > 0 || check && unformatted > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            println!("Could not read {}: {}", dir.display(), err);
+            std::process::exit(1);
+        }
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rd") {
+            files.push(path);
+        }
+    }
+}
@@ -2,8 +2,11 @@ mod args;
 mod build;
 mod compile;
 mod config;
+mod doc;
+mod fmt;
 mod init;
 mod remote;
+mod repl;
 mod sum;
 mod run;
 mod lens;
@@ -35,11 +38,32 @@ fn main() {
             args,
             path,
             debug,
+            message_format,
+            deny,
+            timings,
         } => {
-            build::run(&path, profile, args.clone(), *debug);
+            build::run(&path, profile, args.clone(), *debug, (*message_format).into(), deny, *timings);
         }
-        Task::Build { profile, path } => {
-            build::build(&path, profile);
+        Task::Build {
+            profile,
+            path,
+            message_format,
+            deny,
+            timings,
+        } => {
+            if !build::build(&path, profile, (*message_format).into(), deny, *timings) {
+                std::process::exit(1);
+            }
+        }
+        Task::Lint {
+            profile,
+            path,
+            message_format,
+            deny,
+        } => {
+            if !build::lint(&path, profile, (*message_format).into(), deny) {
+                std::process::exit(1);
+            }
         }
         Task::Install { source, version } => {
             remote::install(source, version);
@@ -61,6 +85,21 @@ fn main() {
         } => {
             build::restore(&path, profile, *compile, *run, args.clone(), *debug);
         }
+        Task::Fmt { path, check } => {
+            fmt::fmt(&path, *check);
+        }
+        Task::Doc {
+            profile,
+            path,
+            format,
+            private,
+            no_deps,
+        } => {
+            if !doc::doc(&path, profile, *format, *private, *no_deps) {
+                std::process::exit(1);
+            }
+        }
+        Task::Repl => repl::repl(),
         Task::Lens { path, target, profile } => {
             let target = match target {
                 Some(target) => target.clone(),
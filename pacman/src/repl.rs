@@ -0,0 +1,182 @@
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+
+use compiler::repl::{Failed, Repl};
+use compiler::session::STD_LIBS;
+use runtime::runtime_types::{Context, Lib, Types};
+use runtime::runtime_error::ErrTypes;
+
+/// Read inputs from stdin, compile and run them one by one
+///
+/// The program and the VM are kept between the inputs, only the new statement is run.
+pub fn repl() {
+    let ruda_path = match std::env::var("RUDA_PATH") {
+        Ok(path) => path,
+        Err(err) => {
+            println!("RUDA_PATH not found. {}", err);
+            return;
+        }
+    };
+    let mut libraries = Vec::new();
+    let mut libs = Vec::new();
+    for name in STD_LIBS {
+        let path = Path::new(&ruda_path)
+            .join("stdlib")
+            .join(&name[1..])
+            .with_extension(std::env::consts::DLL_EXTENSION);
+        match load_lib(&path) {
+            Some((declarations, lib)) => {
+                libraries.push((name.to_string(), declarations));
+                libs.push(lib);
+            }
+            None => libs.push(missing),
+        }
+    }
+    let mut session = match Repl::new(&libraries, libs) {
+        Ok(session) => session,
+        Err(failed) => {
+            report(&failed);
+            return;
+        }
+    };
+    let interactive = std::io::stdin().is_terminal();
+    let mut buffer = String::new();
+    loop {
+        if interactive {
+            print!("{}", if buffer.is_empty() { ">> " } else { ".. " });
+            let _ = std::io::stdout().flush();
+        }
+        let line = match read_line() {
+            Some(line) => line,
+            None => break,
+        };
+        buffer.push_str(&line);
+        buffer.push('\n');
+        if depth(&buffer) > 0 {
+            continue;
+        }
+        let input = std::mem::take(&mut buffer);
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        let (command, rest) = match input.strip_prefix(':') {
+            Some(command) => command.split_once(char::is_whitespace).unwrap_or((command, "")),
+            None => ("", input),
+        };
+        let rest = rest.trim();
+        match command {
+            "" => match session.eval(rest) {
+                Ok(Some(value)) => println!("{value}"),
+                Ok(None) => (),
+                Err(failed) => report(&failed),
+            },
+            "type" => match session.type_of(rest) {
+                Ok(Some(kind)) => println!("{kind}"),
+                Ok(None) => println!("no value"),
+                Err(failed) => report(&failed),
+            },
+            "dis" => match session.disassemble(rest) {
+                Ok(instructions) => {
+                    for instruction in &instructions {
+                        println!("{instruction}");
+                    }
+                }
+                Err(failed) => report(&failed),
+            },
+            "reset" => {
+                if let Err(failed) = session.reset() {
+                    report(&failed);
+                }
+            }
+            "quit" => break,
+            _ => println!("Unknown command :{command}, expected :type, :dis, :reset or :quit"),
+        }
+        let _ = std::io::stdout().flush();
+    }
+}
+
+fn report(failed: &Failed) {
+    for diagnostic in &failed.diagnostics {
+        println!("{}", diagnostic.render(Some(&failed.source)));
+    }
+}
+
+/// reads a line of stdin, None at the end of the input
+///
+/// the libraries have their own buffer of stdin, so the lines after this one
+/// are left unread for the programs that read the input
+fn read_line() -> Option<String> {
+    #[cfg(unix)]
+    {
+        use std::io::Read;
+        use std::os::fd::FromRawFd;
+        // stdin must stay open after the line is read
+        let mut stdin = std::mem::ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(0) });
+        let mut line = Vec::new();
+        let mut byte = [0];
+        loop {
+            match stdin.read(&mut byte) {
+                Ok(0) if line.is_empty() => return None,
+                Ok(0) => break,
+                Ok(_) if byte[0] == b'\n' => break,
+                Ok(_) => line.push(byte[0]),
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => (),
+                Err(_) => return None,
+            }
+        }
+        let line = String::from_utf8_lossy(&line);
+        Some(line.strip_suffix('\r').unwrap_or(&line).to_string())
+    }
+    #[cfg(not(unix))]
+    {
+        let mut line = String::new();
+        match std::io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim_end_matches(['\n', '\r']).to_string()),
+        }
+    }
+}
+
+/// number of brackets left open, strings and comments are skipped
+fn depth(input: &str) -> i32 {
+    let mut depth = 0;
+    for line in input.lines() {
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth -= 1,
+                '"' | '\'' => {
+                    while let Some(next) = chars.next() {
+                        match next {
+                            '\\' => {
+                                chars.next();
+                            }
+                            _ if next == c => break,
+                            _ => (),
+                        }
+                    }
+                }
+                '/' if chars.as_str().starts_with('/') => break,
+                _ => (),
+            }
+        }
+    }
+    depth
+}
+
+/// loads a library of the VM, returns its declarations and the function the VM calls
+fn load_lib(path: &Path) -> Option<(String, Lib)> {
+    let lib = unsafe { libloading::Library::new(path) }.ok()?;
+    let register = unsafe { lib.get::<fn() -> String>(b"register\0") }.ok()?();
+    let init = unsafe { lib.get::<fn(&(), usize) -> Lib>(b"init\0") }.ok()?;
+    let lib_fn = init(&(), 0);
+    std::mem::forget(lib);
+    Some((register, lib_fn))
+}
+
+/// libraries that are not installed fail when they are called
+fn missing(_: &mut Context, _: usize) -> Result<Types, ErrTypes> {
+    Err(ErrTypes::Message("library is not installed".to_string()))
+}
@@ -0,0 +1,32 @@
+import re
+import subprocess
+
+# Pipes the scripts into `ruda repl`, RUDA_PATH has to be set
+# Every line of a script that prints something ends with a comment with the printed line, like `x + 1 // 6`
+
+scripts = ["tests/repl/input.txt"]
+expectation = re.compile(r"// *(.*)$")
+
+failed = []
+
+for name in scripts:
+    with open(name) as script:
+        source = script.read()
+    expected = []
+    for line in source.splitlines():
+        match = expectation.search(line)
+        if match and not line.startswith("//"):
+            expected.append(match.group(1).strip())
+    result = subprocess.run(["ruda", "repl"], input=source, capture_output=True, text=True, timeout=60)
+    printed = result.stdout.splitlines()
+    if printed != expected:
+        print("Error: Unexpected output of", name)
+        for line in printed:
+            print("    " + line)
+        failed.append(name)
+
+if failed:
+    print("Failed", len(failed), "of", len(scripts))
+    exit(1)
+
+print("Success,", len(scripts), "scripts run")
@@ -1,6 +1,7 @@
 import os
 import shutil
 import platform
+import subprocess
 
 # User-defined variables (change to your liking)
 current_dir = os.getcwd()  # Current directory (don't change this)
@@ -36,11 +37,17 @@ pacman_dir = os.path.join(current_dir, "pacman")
 os.chdir(pacman_dir)
 os.system("cargo build --release")
 
+# Build language server
+lsp_dir = os.path.join(current_dir, "lsp")
+os.chdir(lsp_dir)
+os.system("cargo build --release")
+
 # Paths to executables and binaries
 path_to_vm = os.path.join(vm_dir, "target", "release", "rusty_vm")
 path_to_compiler = os.path.join(compiler_dir, "target", "release", "compiler_cli")
 path_to_binaries = bin_dir
 path_to_package_manager = os.path.join(pacman_dir, "target", "release", "ruda")
+path_to_lsp = os.path.join(lsp_dir, "target", "release", "ruda-lsp")
 
 # Determine the file extension based on the platform
 if platform.system() == "Windows":
@@ -84,6 +91,18 @@ else:
     # Copy package manager
     shutil.copy(path_to_package_manager + executable_ext, os.path.join(bin_dir, "ruda" + executable_ext))
 
+# Check if language server binary exists
+if not os.path.exists(path_to_lsp + executable_ext):
+    print("Error: Language server binary not found at", path_to_lsp + executable_ext)
+else:
+    # Remove old language server
+    old_lsp_path = os.path.join(bin_dir, "ruda-lsp" + executable_ext)
+    if os.path.exists(old_lsp_path):
+        os.remove(old_lsp_path)
+
+    # Copy language server
+    shutil.copy(path_to_lsp + executable_ext, os.path.join(bin_dir, "ruda-lsp" + executable_ext))
+
 # Define a dictionary to map file extensions for different platforms
 platform_extensions = {
     "Windows": ".dll",
@@ -139,6 +158,12 @@ path_to_ast = os.path.join(current_dir, "compiler", "ast")
 ruda_ast = os.path.join(path_to_ast, "ruda.ast")
 registry_ast = os.path.join(path_to_ast, "registry.ast")
 
+# Check the grammars before they replace the installed ones, grammar-check exits with 1 if one has errors
+rudac = os.path.join(bin_dir, "rudac.exe" if platform.system() == "Windows" else "rudac")
+if subprocess.run([rudac, "grammar-check", ruda_ast, registry_ast]).returncode == 1:
+    print("Error: The grammars have errors, they were not copied")
+    exit(1)
+
 # Remove old AST files
 old_ruda_ast_path = os.path.join(root_dir, "ruda.ast")
 if os.path.exists(old_ruda_ast_path):
@@ -0,0 +1,48 @@
+import os
+import re
+import subprocess
+import sys
+
+# Compiles every test project in memory with `rudac session` and runs it in the same process.
+# RUDA_PATH is removed from the environment, the standard library is loaded from the directory
+# given as the first argument (build/stdlib by default).
+# The output has to match the comments after the io.println calls in src/main.rd
+
+stdlib = sys.argv[1] if len(sys.argv) > 1 else os.path.join("build", "stdlib")
+env = {key: value for key, value in os.environ.items() if key != "RUDA_PATH"}
+# projects that wait for input
+skip = ["number-guessing-game"]
+expectation = re.compile(r"io\.println\(.*\) *// *(.*)$")
+
+failed = []
+count = 0
+
+for name in sorted(os.listdir("tests")):
+    project = os.path.join("tests", name)
+    main = os.path.join(project, "src", "main.rd")
+    if name in skip or not os.path.isfile(main):
+        continue
+    with open(main) as file:
+        expected = [match.group(1) for match in map(expectation.search, file) if match]
+    # projects without expectations are checked by hand
+    if not expected:
+        continue
+    count += 1
+
+    try:
+        result = subprocess.run(["rudac", "session", project, stdlib], env=env, stdin=subprocess.DEVNULL, capture_output=True, text=True, timeout=10)
+    except subprocess.TimeoutExpired:
+        print("Error: Timed out", name)
+        failed.append(name)
+        continue
+    output = result.stdout.splitlines()
+    if result.returncode != 0 or output != expected:
+        print("Error: Unexpected output of", name)
+        print(result.stdout)
+        failed.append(name)
+
+if failed:
+    print("Failed", len(failed), "of", count)
+    exit(1)
+
+print("Success,", count, "projects compiled in memory")
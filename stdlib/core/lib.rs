@@ -153,6 +153,22 @@ fn call(ctx: &mut Context, id: usize, lib_id: usize) -> Result<Types, runtime_er
             }
             return Err(runtime_error::ErrTypes::Message("Invalid swap indices".to_string()));
         }
+        // core::str_format
+        12 => {
+            if let Types::Pointer(u_size, PointerTypes::String) = m.registers[GENERAL_REG2] {
+                let spec = match format_spec::FormatSpec::parse(m.strings.to_str(u_size)) {
+                    Ok(spec) => spec,
+                    Err(err) => return Err(runtime_error::ErrTypes::Message(err)),
+                };
+                let str = spec.format(&m.registers[GENERAL_REG1], m);
+                let pos = m.strings.from_str(&str);
+                return Ok(Types::Pointer(pos, PointerTypes::String));
+            } else {
+                return Err(runtime_error::ErrTypes::Message(format!(
+                    "Invalid string pointer"
+                )));
+            }
+        }
         id => unreachable!("Invalid function id: {id}"),
     }
 }
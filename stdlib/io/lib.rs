@@ -172,7 +172,8 @@ fn register() -> String {
     fun print(msg=reg.ptr: string) > 0i
 
     /// Prints the provided string to the standard output with a newline.
-    fun println(msg=reg.ptr: string) > 1i
+    /// Without a message it prints an empty line.
+    fun println(msg=reg.ptr: string = "") > 1i
 
     /// Reads a line of input from the standard input and returns it as a string.
     fun input(): string > 2i
@@ -0,0 +1,43 @@
+import json
+import os
+import re
+import subprocess
+
+# Compiles the broken test projects with `rudac build --message-format=json`, RUDA_PATH has to be set
+# Every error has to be announced by a comment with its message at the end of the line it is reported at,
+# like `// error: expected `:`, found `int``, labels pointing elsewhere follow the message after `; `
+
+projects = ["syntax-errors", "unclosed-string"]
+expectation = re.compile(r"// *error: (.*)$")
+
+failed = []
+
+for name in projects:
+    project = os.path.join("tests", name)
+    src = os.path.join(project, "src")
+    expected = set()
+    with open(os.path.join(src, "main.rd")) as source:
+        for number, line in enumerate(source, 1):
+            match = expectation.search(line)
+            if match:
+                expected.add((number, match.group(1).strip()))
+    result = subprocess.run(["rudac", "build", "main.rd", "--message-format=json"], cwd=src, stdin=subprocess.DEVNULL, capture_output=True, text=True, timeout=60)
+    reported = set()
+    for line in result.stdout.splitlines():
+        diagnostic = json.loads(line)
+        primary = diagnostic["labels"][0]
+        secondary = [label["message"] for label in diagnostic["labels"][1:]]
+        reported.add((primary["line"], "; ".join([diagnostic["message"]] + secondary)))
+    if reported != expected:
+        print("Error: Unexpected errors of", name)
+        for error in sorted(expected - reported):
+            print("    missing", *error)
+        for error in sorted(reported - expected):
+            print("    unexpected", *error)
+        failed.append(name)
+
+if failed:
+    print("Failed", len(failed), "of", len(projects))
+    exit(1)
+
+print("Success,", len(projects), "projects checked")
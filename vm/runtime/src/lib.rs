@@ -76,6 +76,7 @@ impl Context {
                     code_ptr: 0,
                     id: None,
                     cs_ptr: 0,
+                    args_ptr: 0,
                 }; CALL_STACK_SIZE],
             },
             exit_code: ExitCodes::End,
@@ -463,6 +464,7 @@ impl Context {
             }
             GotoPtr => {
                 if let Types::Function(u_size) = self.memory.registers[CODE_PTR_REG] {
+                    self.memory.stack.call_stack[self.memory.stack.ptr].code_ptr = self.code.ptr;
                     self.code.ptr = self.memory.fun_table[u_size].loc;
                 } else {
                     return self.panic_rt(ErrTypes::InvalidType(
@@ -997,6 +999,7 @@ impl Context {
                         self.memory.registers[np_reg],
                     ));
                 }
+                self.next_line();
             }
             Cast(reg1, ttype) => {
                 match Self::cast(&mut self.memory.registers, reg1, ttype) {
@@ -1016,23 +1019,26 @@ impl Context {
                     code_ptr: self.code.ptr,
                     id: None,
                     cs_ptr: self.memory.stack.ptr,
+                    args_ptr: self.memory.args.ptr,
                 }) {
                     return self.panic_rt(err);
                 }
-                self.next_line()
+                // the next instruction is the entry of the handler
+                self.code.ptr += 2;
             }
             CatchId(id) => {
                 if let Err(err) = self.catches.push(runtime_types::Catch {
                     code_ptr: self.code.ptr,
                     id: Some(id),
                     cs_ptr: self.memory.stack.ptr,
+                    args_ptr: self.memory.args.ptr,
                 }) {
                     return self.panic_rt(err);
                 }
-                self.next_line()
+                self.code.ptr += 2;
             }
             DeleteCatch(n) => {
-                self.catches.truncate(self.catches.cache.len() - n);
+                self.catches.truncate(self.catches.catches_ptr - n);
                 self.next_line()
             }
             /*StrCpy(reg) => {
@@ -1062,8 +1068,15 @@ impl Context {
                 self.next_line();
             }
             Panic => {
-                self.enter_panic();
-                self.next_line();
+                if !self.enter_panic() {
+                    self.break_code = Some(self.code.ptr);
+                    let msg = self.error_message(RETURN_REG);
+                    println!(
+                        "{}",
+                        get_message(&ErrTypes::Message(msg), self.find_debug(self.code.ptr))
+                    );
+                    return false;
+                }
             }
             Neg(reg) => {
                 match self.memory.registers[reg] {
@@ -1102,6 +1115,16 @@ impl Context {
                 }
                 self.next_line();
             }
+            GotoOffset(reg) => match self.memory.registers[reg] {
+                Types::Uint(offset) => self.code.ptr += offset + 1,
+                Types::Int(offset) if offset >= 0 => self.code.ptr += offset as usize + 1,
+                _ => {
+                    return self.panic_rt(ErrTypes::WrongTypeOperation(
+                        self.memory.registers[reg],
+                        self.code.data[self.code.ptr],
+                    ));
+                }
+            },
         }
         return true;
     }
@@ -1202,6 +1225,14 @@ impl Context {
         }
     }
     fn panic_rt(&mut self, kind: ErrTypes) -> bool {
+        // runtime errors are catchable as errors with id 0
+        let (msg, code) = get_data(&kind);
+        let obj = self.memory.allocate_obj(3);
+        let msg = self.memory.strings.from_str(&msg);
+        self.memory.heap.data[obj][0] = Types::Uint(0);
+        self.memory.heap.data[obj][1] = Types::Pointer(msg, PointerTypes::String);
+        self.memory.heap.data[obj][2] = Types::Int(code as i64);
+        self.memory.registers[RETURN_REG] = Types::Pointer(obj, PointerTypes::Object);
         if self.enter_panic() {
             return true;
         }
@@ -1244,11 +1275,13 @@ impl Context {
             None => None,
         }
     }
-    /// This function is called when an exception is thrown. It will search for a catch block
-    /// that matches the exception type. If it finds one, it will set the code pointer to the
-    /// catch block and set the stack pointer to the catch block's stack pointer. If it doesn't
-    /// find one, it will set the exit code to exception and return false. (indicating that the VM should exit)
+    /// This function is called when an exception is thrown. The error object is expected in
+    /// the return register. It will search for a catch block that matches the error id.
+    /// If it finds one, it will set the code pointer to the instruction following the catch
+    /// and restore the stack and argument pointers from the time the catch was created.
+    /// If it doesn't find one, it will set the exit code to exception and return false. (indicating that the VM should exit)
     fn enter_panic(&mut self) -> bool {
+        let e_type = self.error_id(RETURN_REG);
         let mut i = self.catches.catches_ptr;
         loop {
             if i == 0 {
@@ -1256,23 +1289,36 @@ impl Context {
                 return false;
             }
             i -= 1;
-            if let Some(n) = self.catches.cache[i].id {
-                if let Types::NonPrimitive(e_type) = self.memory.registers[RETURN_REG] {
-                    if n == e_type {
-                        self.code.ptr = self.catches.cache[i].code_ptr;
-                        self.memory.stack.ptr = self.catches.cache[i].cs_ptr;
-                    }
-                }
-                break;
-            } else {
-                self.code.ptr = self.catches.cache[i].code_ptr;
-                self.memory.stack.ptr = self.catches.cache[i].cs_ptr;
-                break;
+            let catch = self.catches.cache[i];
+            if catch.id.is_some() && catch.id != e_type {
+                continue;
             }
+            self.code.ptr = catch.code_ptr + 1;
+            self.memory.stack.ptr = catch.cs_ptr;
+            self.memory.args.ptr = catch.args_ptr;
+            break;
         }
         self.catches.truncate(i);
         true
     }
+    /// reads the id of the error object stored in the given register
+    fn error_id(&self, reg: usize) -> Option<usize> {
+        if let Types::Pointer(obj, PointerTypes::Object) = self.memory.registers[reg] {
+            if let Some(Types::Uint(id)) = self.memory.heap.data[obj].first() {
+                return Some(*id);
+            }
+        }
+        None
+    }
+    /// reads the message of the error object stored in the given register
+    fn error_message(&self, reg: usize) -> String {
+        if let Types::Pointer(obj, PointerTypes::Object) = self.memory.registers[reg] {
+            if let Some(Types::Pointer(msg, PointerTypes::String)) = self.memory.heap.data[obj].get(1) {
+                return self.memory.strings.pool[*msg].clone();
+            }
+        }
+        String::from("Uncaught error")
+    }
     pub fn size(&self) -> usize {
         self.memory.size()
             + std::mem::size_of_val(&self.break_code)
@@ -1453,6 +1499,9 @@ impl Context {
                 format!("args: {:?}", self.memory.args.data[self.memory.args.ptr])
             }
             Instructions::NullCheck => format!("register0: {}", self.memory.registers[0]),
+            Instructions::GotoOffset(reg) => {
+                format!("offset: {}", self.memory.registers[*reg])
+            }
         };
         prepend.push_str(&val);
         prepend
@@ -2240,8 +2289,8 @@ pub mod runtime_types {
             if self.catches_ptr == CALL_STACK_SIZE {
                 return Err(ErrTypes::CatchOwerflow);
             }
-            self.catches_ptr += 1;
             self.cache[self.catches_ptr] = catch;
+            self.catches_ptr += 1;
             Ok(())
         }
         /// pops the last catch from the stack
@@ -2266,6 +2315,8 @@ pub mod runtime_types {
         pub code_ptr: usize,
         /// location of the stack the catch was created in
         pub cs_ptr: usize,
+        /// location of the arguments the catch was created in
+        pub args_ptr: usize,
         /// Describes the type of the catch
         ///
         /// If the catch is a catch all, then the type is None
@@ -2550,7 +2601,7 @@ pub mod runtime_types {
         Deallocate,
         /// Goto: pos | moves code_pointer to <pos>
         Goto(usize),
-        /// GotoCodePtr: pos_reg | moves code pointer to reg(<reg>)
+        /// GotoCodePtr: | moves code pointer to the function in reg(CODE_PTR_REG) and saves current code ptr (like jump)
         GotoPtr,
         /// Branch: pos1 pos2 | if reg(0), goto <pos1> else goto <pos2>
         Branch(usize, usize),
@@ -2619,9 +2670,9 @@ pub mod runtime_types {
         Break(usize),
         /// Method: struct trait method | takes struct and calls method on it, assuming it implements trait  
         DynMethod(usize, usize, usize),
-        /// Panic | program enters panic mode, returning from all stacks until exception is caught
+        /// Panic | program enters panic mode with error object in return register, returning from all stacks until exception is caught
         Panic,
-        /// Catch | catches an error and returns program to normal mode, cached if read in normal mode
+        /// Catch | registers a catch, skips the next instruction which is executed when an exception is caught
         Catch,
         /// Catch ID: id | same as normal catch but responds only to exception with same id
         CatchId(usize),
@@ -2649,6 +2700,8 @@ pub mod runtime_types {
         CloseArgs,
         /// Null | sets reg(0) to reg(0) != null (used for null checks)
         NullCheck,
+        /// Goto offset: reg | skips reg(reg) instructions following this one (used for jump tables)
+        GotoOffset(usize),
     }
     impl fmt::Display for Instructions {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -2714,6 +2767,7 @@ pub mod runtime_types {
                 Instructions::OpenArgs => "OpenArguments",
                 Instructions::CloseArgs => "CloseArguments",
                 Instructions::NullCheck => "NullCheck",
+                Instructions::GotoOffset(_) => "GotoOffset",
             };
             write!(f, "{str}")
         }
@@ -2763,7 +2817,13 @@ pub mod runtime_error {
         };
     }
     pub fn get_message(kind: &ErrTypes, line: Option<(Line, String, String)>) -> String {
-        let data = match &kind {
+        let data = get_data(kind);
+        let message = gen_message(data.0, line, data.1);
+        message
+    }
+    /// returns the message and the code of the error
+    pub fn get_data(kind: &ErrTypes) -> (String, u8) {
+        match &kind {
             ErrTypes::CrossTypeOperation(var1, var2, instr) => (
                 format!("Operation '{instr}' failed: Cross-type operation {var1:+}, {var2:+}"),
                 0,
@@ -2788,9 +2848,165 @@ pub mod runtime_error {
             ErrTypes::MethodNotFound => (format!("Method not found"), 7),
             ErrTypes::Message(msg) => (msg.clone(), 8),
             ErrTypes::CannotReadUserdata => (format!("Cannot use userdata"), 9),
-        };
-        let message = gen_message(data.0, line, data.1);
-        message
+        }
+    }
+}
+
+/// Format specifiers of interpolated strings
+///
+/// `"{value:>8.2}"` formats value with the specifier `>8.2`,
+/// the compiler checks the specifier and the runtime applies it
+pub mod format_spec {
+    use super::runtime_types::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Align {
+        Left,
+        Right,
+        Center,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Radix {
+        Decimal,
+        /// x
+        Hex,
+        /// X
+        UpperHex,
+        /// b
+        Binary,
+    }
+
+    /// `[[fill]align][0][width][.precision][x|X|b]`
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct FormatSpec {
+        pub fill: char,
+        /// numbers are aligned to the right and everything else to the left by default
+        pub align: Option<Align>,
+        /// pads numbers with zeros after the sign
+        pub zero: bool,
+        pub width: Option<usize>,
+        /// digits after the decimal point of floats, characters of strings
+        pub precision: Option<usize>,
+        pub radix: Radix,
+    }
+
+    impl FormatSpec {
+        pub fn parse(spec: &str) -> Result<FormatSpec, String> {
+            let mut result = FormatSpec {
+                fill: ' ',
+                align: None,
+                zero: false,
+                width: None,
+                precision: None,
+                radix: Radix::Decimal,
+            };
+            let chars = spec.chars().collect::<Vec<_>>();
+            let align = |chr: char| match chr {
+                '<' => Some(Align::Left),
+                '>' => Some(Align::Right),
+                '^' => Some(Align::Center),
+                _ => None,
+            };
+            let mut i = 0;
+            if chars.len() > 1 && align(chars[1]).is_some() {
+                result.fill = chars[0];
+                result.align = align(chars[1]);
+                i = 2;
+            } else if !chars.is_empty() && align(chars[0]).is_some() {
+                result.align = align(chars[0]);
+                i = 1;
+            }
+            if chars.get(i) == Some(&'0') {
+                result.zero = true;
+                i += 1;
+            }
+            let number = |i: &mut usize| {
+                let start = *i;
+                while *i < chars.len() && chars[*i].is_ascii_digit() {
+                    *i += 1;
+                }
+                chars[start..*i].iter().collect::<String>().parse::<usize>().ok()
+            };
+            result.width = number(&mut i);
+            if chars.get(i) == Some(&'.') {
+                i += 1;
+                result.precision = match number(&mut i) {
+                    Some(precision) => Some(precision),
+                    None => return Err(format!("expected precision after '.' in '{spec}'")),
+                };
+            }
+            result.radix = match chars.get(i) {
+                Some('x') => Radix::Hex,
+                Some('X') => Radix::UpperHex,
+                Some('b') => Radix::Binary,
+                _ => Radix::Decimal,
+            };
+            if result.radix != Radix::Decimal {
+                i += 1;
+            }
+            if i != chars.len() {
+                return Err(format!("unexpected '{}' in '{spec}'", chars[i]));
+            }
+            Ok(result)
+        }
+
+        pub fn format(&self, value: &Types, mem: &Memory) -> String {
+            let (text, is_number) = match *value {
+                Types::Int(n) => (self.integer(n.is_negative(), n.unsigned_abs()), true),
+                Types::Uint(n) => (self.integer(false, n as u64), true),
+                Types::Float(f) => match self.precision {
+                    Some(precision) => (format!("{f:.precision$}"), true),
+                    None => (f.to_string(), true),
+                },
+                Types::Pointer(loc, PointerTypes::String) => {
+                    let text = mem.strings.to_str(loc);
+                    match self.precision {
+                        Some(precision) => (text.chars().take(precision).collect(), false),
+                        None => (text.to_string(), false),
+                    }
+                }
+                _ => (value.to_str(mem), false),
+            };
+            let width = self.width.unwrap_or(0);
+            let len = text.chars().count();
+            if len >= width {
+                return text;
+            }
+            if self.zero && is_number {
+                let (sign, digits) = match text.strip_prefix('-') {
+                    Some(digits) => ("-", digits),
+                    None => ("", text.as_str()),
+                };
+                return format!("{sign}{}{digits}", "0".repeat(width - len));
+            }
+            let pad = width - len;
+            let align = match self.align {
+                Some(align) => align,
+                None if is_number => Align::Right,
+                None => Align::Left,
+            };
+            let (left, right) = match align {
+                Align::Left => (0, pad),
+                Align::Right => (pad, 0),
+                Align::Center => (pad / 2, pad - pad / 2),
+            };
+            let fill = |n: usize| self.fill.to_string().repeat(n);
+            format!("{}{text}{}", fill(left), fill(right))
+        }
+
+        fn integer(&self, negative: bool, n: u64) -> String {
+            let digits = match self.radix {
+                Radix::Decimal => n.to_string(),
+                Radix::Hex => format!("{n:x}"),
+                Radix::UpperHex => format!("{n:X}"),
+                Radix::Binary => format!("{n:b}"),
+            };
+            match negative {
+                true => format!("-{digits}"),
+                false => digits,
+            }
+        }
     }
 }
 
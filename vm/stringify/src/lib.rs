@@ -635,6 +635,7 @@ pub fn byte_into_string(byte: Instructions, str: &mut String) {
         Instructions::OpenArgs => s(58),
         Instructions::CloseArgs => s(59),
         Instructions::NullCheck => s(60),
+        Instructions::GotoOffset(n) => s(61) + &b256str(n, 1),
     };
     str.push_str(&append);
 }
@@ -755,6 +756,7 @@ pub fn str_into_byte(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> In
         58 => Instructions::OpenArgs,
         59 => Instructions::CloseArgs,
         60 => Instructions::NullCheck,
+        61 => Instructions::GotoOffset(read_number(chars, 1)),
         _ => panic!("Unknown instruction"),
     };
     byte